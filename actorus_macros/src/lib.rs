@@ -1,6 +1,8 @@
 //! Procedural macros for Actorus tools
 //!
-//! Provides the #[tool] and #[tool_fn] attribute macros for auto-generating tool metadata
+//! Provides the #[tool] and #[tool_fn] attribute macros for auto-generating tool metadata,
+//! #[derive(Tool)] for implementing the Tool trait on argument structs, and
+//! #[derive(ToolEnum)] for listing an enum's values as a parameter's allowed values
 
 use proc_macro::TokenStream;
 use quote::quote;
@@ -37,16 +39,451 @@ impl Parse for ToolArgs {
             }
         }
 
+        if name.is_empty() {
+            return Err(syn::Error::new(
+                proc_macro2::Span::call_site(),
+                "missing required argument `name = \"...\"`",
+            ));
+        }
+
         Ok(ToolArgs { name, description })
     }
 }
 
+/// Validation constraints declared in a `#[param(...)]` attribute, plus
+/// element types inferred from the Rust type
+#[derive(Default)]
+struct ParamConstraints {
+    allowed_values: Option<Vec<String>>,
+    minimum: Option<f64>,
+    maximum: Option<f64>,
+    pattern: Option<String>,
+    item_types: Option<Vec<&'static str>>,
+}
+
+impl ParamConstraints {
+    /// Consume one constraint key, returning false if `meta` is not a constraint
+    fn parse(&mut self, meta: &syn::meta::ParseNestedMeta) -> Result<bool> {
+        if meta.path.is_ident("allowed_values") {
+            let array: syn::ExprArray = meta.value()?.parse()?;
+            let values = array
+                .elems
+                .iter()
+                .map(|elem| match elem {
+                    syn::Expr::Lit(syn::ExprLit {
+                        lit: syn::Lit::Str(lit),
+                        ..
+                    }) => Ok(lit.value()),
+                    other => Err(syn::Error::new_spanned(
+                        other,
+                        "allowed_values must be string literals",
+                    )),
+                })
+                .collect::<Result<_>>()?;
+            self.allowed_values = Some(values);
+        } else if meta.path.is_ident("minimum") {
+            self.minimum = Some(parse_number(meta)?);
+        } else if meta.path.is_ident("maximum") {
+            self.maximum = Some(parse_number(meta)?);
+        } else if meta.path.is_ident("pattern") {
+            let lit: LitStr = meta.value()?.parse()?;
+            self.pattern = Some(lit.value());
+        } else {
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    /// Constraint fields of the generated ToolParameter, except `allowed_values`
+    fn to_fields(&self) -> proc_macro2::TokenStream {
+        let minimum = option_tokens(self.minimum);
+        let maximum = option_tokens(self.maximum);
+        let pattern = match &self.pattern {
+            Some(pattern) => quote! { Some(#pattern.to_string()) },
+            None => quote! { None },
+        };
+        let item_types = match &self.item_types {
+            Some(types) => quote! { Some(vec![#(#types.to_string()),*]) },
+            None => quote! { None },
+        };
+        quote! {
+            minimum: #minimum,
+            maximum: #maximum,
+            pattern: #pattern,
+            item_types: #item_types,
+        }
+    }
+}
+
+/// `ToolParameter` literal for one parameter
+///
+/// `enum_type` is the Rust type of a parameter converted with serde. When it
+/// implements `ToolEnum` and no `allowed_values` were given, the parameter is
+/// described as a string limited to the enum's values.
+fn parameter_definition(
+    name: &str,
+    param_type: &str,
+    description: &str,
+    required: bool,
+    constraints: &ParamConstraints,
+    enum_type: Option<&Type>,
+) -> proc_macro2::TokenStream {
+    let constraint_fields = constraints.to_fields();
+    match (&constraints.allowed_values, enum_type) {
+        (None, Some(ty)) => quote! {{
+            #[allow(unused_imports)]
+            use actorus::tools::__private::{ViaAnyType as _, ViaToolEnum as _};
+            let allowed_values =
+                (&actorus::tools::__private::EnumProbe::<#ty>::new()).allowed_values();
+            actorus::tools::ToolParameter {
+                name: #name.to_string(),
+                param_type: if allowed_values.is_some() { "string" } else { #param_type }
+                    .to_string(),
+                description: #description.to_string(),
+                required: #required,
+                allowed_values,
+                #constraint_fields
+            }
+        }},
+        (allowed_values, _) => {
+            let allowed_values = match allowed_values {
+                Some(values) => quote! { Some(vec![#(#values.to_string()),*]) },
+                None => quote! { None },
+            };
+            quote! {
+                actorus::tools::ToolParameter {
+                    name: #name.to_string(),
+                    param_type: #param_type.to_string(),
+                    description: #description.to_string(),
+                    required: #required,
+                    allowed_values: #allowed_values,
+                    #constraint_fields
+                }
+            }
+        }
+    }
+}
+
+/// Error for a `#[param]` key that is not recognized
+fn unknown_param_key(meta: &syn::meta::ParseNestedMeta, extra: &str) -> syn::Error {
+    meta.error(format!(
+        "unknown #[param] key; expected description{}, allowed_values, minimum, maximum or pattern",
+        extra
+    ))
+}
+
+/// Parse an integer or float literal, optionally negative
+fn parse_number(meta: &syn::meta::ParseNestedMeta) -> Result<f64> {
+    let expr: syn::Expr = meta.value()?.parse()?;
+    let (negative, lit) = match &expr {
+        syn::Expr::Unary(syn::ExprUnary {
+            op: syn::UnOp::Neg(_),
+            expr,
+            ..
+        }) => (true, &**expr),
+        other => (false, other),
+    };
+    let value = match lit {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Int(int),
+            ..
+        }) => int.base10_parse::<f64>()?,
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Float(float),
+            ..
+        }) => float.base10_parse::<f64>()?,
+        other => return Err(syn::Error::new_spanned(other, "expected a numeric literal")),
+    };
+    Ok(if negative { -value } else { value })
+}
+
+fn option_tokens(value: Option<f64>) -> proc_macro2::TokenStream {
+    match value {
+        Some(value) => quote! { Some(#value) },
+        None => quote! { None },
+    }
+}
+
+/// How a parameter's JSON value is converted into its Rust type
+#[derive(Clone, Copy, PartialEq)]
+enum ValueKind {
+    String,
+    Signed,
+    Unsigned,
+    NonZero,
+    Float,
+    Boolean,
+    /// `HashMap`/`BTreeMap`, sent as a JSON object
+    Map,
+    /// Tuples, sent as a fixed-length JSON array
+    Tuple,
+    /// Anything else is deserialized with serde
+    Deserialize,
+}
+
+impl ValueKind {
+    /// Classify by the last path segment, so `std::primitive::u32` and `u32` agree
+    ///
+    /// Type aliases cannot be resolved from a macro and fall back to `Deserialize`.
+    fn of(ty: &Type) -> Self {
+        let ident = match ty {
+            Type::Reference(reference) => return Self::of(&reference.elem),
+            Type::Group(group) => return Self::of(&group.elem),
+            Type::Tuple(tuple) if !tuple.elems.is_empty() => return ValueKind::Tuple,
+            Type::Path(type_path) => match type_path.path.segments.last() {
+                Some(segment) => segment.ident.to_string(),
+                None => return ValueKind::Deserialize,
+            },
+            _ => return ValueKind::Deserialize,
+        };
+
+        match ident.as_str() {
+            "String" | "str" => ValueKind::String,
+            "i8" | "i16" | "i32" | "i64" | "i128" | "isize" => ValueKind::Signed,
+            "u8" | "u16" | "u32" | "u64" | "u128" | "usize" => ValueKind::Unsigned,
+            "f32" | "f64" => ValueKind::Float,
+            "bool" => ValueKind::Boolean,
+            "HashMap" | "BTreeMap" => ValueKind::Map,
+            name if name.starts_with("NonZero") => ValueKind::NonZero,
+            _ => ValueKind::Deserialize,
+        }
+    }
+
+    fn schema_type(self) -> &'static str {
+        match self {
+            ValueKind::String => "string",
+            ValueKind::Signed | ValueKind::Unsigned | ValueKind::NonZero | ValueKind::Float => {
+                "number"
+            }
+            ValueKind::Boolean => "boolean",
+            ValueKind::Map | ValueKind::Deserialize => "object",
+            ValueKind::Tuple => "array",
+        }
+    }
+
+    /// Whether values are converted with serde rather than a `Value` accessor
+    fn is_deserialized(self) -> bool {
+        matches!(
+            self,
+            ValueKind::NonZero | ValueKind::Map | ValueKind::Tuple | ValueKind::Deserialize
+        )
+    }
+
+    /// Schema types of a map's values or of each tuple position
+    fn item_types(ty: &Type) -> Option<Vec<&'static str>> {
+        let element_type = |ty: &Type| ValueKind::of(ty).schema_type();
+        match ty {
+            Type::Reference(reference) => Self::item_types(&reference.elem),
+            Type::Tuple(tuple) if !tuple.elems.is_empty() => {
+                Some(tuple.elems.iter().map(element_type).collect())
+            }
+            Type::Path(type_path) if ValueKind::of(ty) == ValueKind::Map => {
+                let segment = type_path.path.segments.last()?;
+                let syn::PathArguments::AngleBracketed(args) = &segment.arguments else {
+                    return None;
+                };
+                match args.args.iter().nth(1)? {
+                    syn::GenericArgument::Type(value) => Some(vec![element_type(value)]),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Phrase used in "must be ..." errors for missing or mistyped arguments
+    fn expected(self) -> &'static str {
+        match self {
+            ValueKind::String => "a string",
+            ValueKind::Signed => "an integer in range",
+            ValueKind::Unsigned => "a non-negative integer in range",
+            ValueKind::NonZero => "a non-zero integer in range",
+            ValueKind::Float => "a number",
+            ValueKind::Boolean => "a boolean",
+            ValueKind::Map => "an object",
+            ValueKind::Tuple => "an array",
+            ValueKind::Deserialize => "valid JSON for its type",
+        }
+    }
+
+    /// Expression turning `v: &serde_json::Value` into `Option<ty>`
+    fn extract(self, ty: &Type) -> proc_macro2::TokenStream {
+        match self {
+            ValueKind::String => quote! { v.as_str().map(|s| s.to_string()) },
+            ValueKind::Signed => quote! { v.as_i64().and_then(|n| <#ty>::try_from(n).ok()) },
+            ValueKind::Unsigned => quote! { v.as_u64().and_then(|n| <#ty>::try_from(n).ok()) },
+            ValueKind::Float => quote! { v.as_f64().map(|n| n as #ty) },
+            ValueKind::Boolean => quote! { v.as_bool() },
+            ValueKind::NonZero | ValueKind::Map | ValueKind::Tuple | ValueKind::Deserialize => {
+                quote! { serde_json::from_value::<#ty>(v.clone()).ok() }
+            }
+        }
+    }
+}
+
+/// Inner type of `Option<T>`, including path-qualified `std::option::Option<T>`
+fn option_inner(ty: &Type) -> Option<&Type> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) => match args.args.first()? {
+            syn::GenericArgument::Type(inner) => Some(inner),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Read `description` and constraints from `#[param(...)]` attributes
+fn parse_param_attrs(attrs: &[syn::Attribute]) -> Result<(Option<String>, ParamConstraints)> {
+    let mut description = None;
+    let mut constraints = ParamConstraints::default();
+    for attr in attrs.iter().filter(|a| a.path().is_ident("param")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("description") {
+                let lit: LitStr = meta.value()?.parse()?;
+                description = Some(lit.value());
+            } else if !constraints.parse(&meta)? {
+                return Err(unknown_param_key(&meta, ""));
+            }
+            Ok(())
+        })?;
+    }
+    Ok((description, constraints))
+}
+
+/// ToolParameter definition and `let #name = ...;` extraction from `args`
+///
+/// `Option<T>` parameters are optional; everything else is required.
+/// `param_name_str` is the key the LLM uses, which may differ from the binding.
+fn param_codegen(
+    param_name: &Ident,
+    param_name_str: &str,
+    param_type: &Type,
+    param_desc: Option<String>,
+    mut constraints: ParamConstraints,
+) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
+    // Determine if optional and base type
+    let (is_optional, base_type) = match option_inner(param_type) {
+        Some(inner) => (true, inner),
+        None => (false, param_type),
+    };
+    let kind = ValueKind::of(base_type);
+    let param_type_name = kind.schema_type();
+    constraints.item_types = ValueKind::item_types(base_type);
+
+    // Generate parameter metadata
+    let is_required = !is_optional;
+    let param_desc = param_desc.unwrap_or_else(|| format!("Parameter: {}", param_name_str));
+    let definition = parameter_definition(
+        param_name_str,
+        param_type_name,
+        &param_desc,
+        is_required,
+        &constraints,
+        (kind == ValueKind::Deserialize).then_some(base_type),
+    );
+
+    // Generate parameter extraction logic
+    let extract = kind.extract(base_type);
+    let extraction = if is_optional {
+        quote! {
+            let #param_name = args.get(#param_name_str).and_then(|v| #extract);
+        }
+    } else if kind.is_deserialized() {
+        // For required struct, deserialize from JSON
+        quote! {
+            let #param_name = serde_json::from_value::<#param_type>(
+                args.get(#param_name_str)
+                    .ok_or_else(|| anyhow::anyhow!("Missing required parameter: {}", #param_name_str))?
+                    .clone()
+            )?;
+        }
+    } else {
+        let expected = kind.expected();
+        quote! {
+            let #param_name = args.get(#param_name_str)
+                .and_then(|v| #extract)
+                .ok_or_else(|| anyhow::anyhow!(
+                    "'{}' parameter is required and must be {}",
+                    #param_name_str,
+                    #expected
+                ))?;
+        }
+    };
+
+    (definition, extraction)
+}
+
+/// The generated `execute` calls `#fn_name(...).await?`, so reject anything
+/// else here rather than leave the user with a confusing downstream error
+fn check_tool_fn_signature(sig: &syn::Signature) -> Result<()> {
+    if sig.asyncness.is_none() {
+        return Err(syn::Error::new_spanned(
+            sig.fn_token,
+            "#[tool_fn] requires an async fn",
+        ));
+    }
+
+    let returns_result = match &sig.output {
+        syn::ReturnType::Type(_, ty) => match &**ty {
+            Type::Path(type_path) => type_path
+                .path
+                .segments
+                .last()
+                .is_some_and(|segment| segment.ident == "Result"),
+            _ => false,
+        },
+        syn::ReturnType::Default => false,
+    };
+    if !returns_result {
+        let message = "#[tool_fn] functions must return a Result, e.g. anyhow::Result<String>";
+        return Err(match &sig.output {
+            syn::ReturnType::Type(_, ty) => syn::Error::new_spanned(ty, message),
+            syn::ReturnType::Default => syn::Error::new_spanned(&sig.ident, message),
+        });
+    }
+
+    Ok(())
+}
+
+/// Binding and exposed parameter name of a `#[tool_fn]` argument
+///
+/// A single leading underscore is dropped from the exposed name, so
+/// `_query` (unused in the body) still reaches the LLM as `query`.
+fn tool_fn_param_name(pat: &syn::Pat) -> Result<(&Ident, String)> {
+    match pat {
+        syn::Pat::Ident(pat_ident) if pat_ident.subpat.is_none() => {
+            let binding = &pat_ident.ident;
+            let name = binding.to_string();
+            let exposed = name.strip_prefix('_').unwrap_or(&name).to_string();
+            Ok((binding, exposed))
+        }
+        syn::Pat::Wild(_) => Err(syn::Error::new_spanned(
+            pat,
+            "#[tool_fn] arguments need a name the LLM can use; write `_name` instead of `_`",
+        )),
+        _ => Err(syn::Error::new_spanned(
+            pat,
+            "#[tool_fn] arguments must be plain identifiers; destructure inside the function body",
+        )),
+    }
+}
+
 /// Attribute macro for simple tool metadata generation
 ///
 /// Usage:
 /// ```ignore
 /// #[tool!(name = "greet", description = "Greets a person")]
-/// pub struct GreetTool;
+/// pub struct GreetTool {
+///     #[param(description = "Tone of the greeting", allowed_values = ["formal", "casual"])]
+///     tone: String,
+/// }
 /// ```
 #[proc_macro_attribute]
 pub fn tool(args: TokenStream, input: TokenStream) -> TokenStream {
@@ -66,9 +503,12 @@ pub fn tool(args: TokenStream, input: TokenStream) -> TokenStream {
             }
         }
         _ => {
-            return syn::Error::new_spanned(input_item, "#[tool] can only be applied to structs or impl blocks")
-                .to_compile_error()
-                .into();
+            return syn::Error::new_spanned(
+                input_item,
+                "#[tool] can only be applied to structs or impl blocks",
+            )
+            .to_compile_error()
+            .into();
         }
     };
     let tool_name = &tool_args.name;
@@ -80,64 +520,64 @@ pub fn tool(args: TokenStream, input: TokenStream) -> TokenStream {
     if let syn::Item::Struct(struct_item) = &input_item {
         if let syn::Fields::Named(fields) = &struct_item.fields {
             for field in &fields.named {
-            let field_name = field.ident.as_ref().unwrap();
-            let mut is_param = false;
-            let mut param_desc = String::new();
-            let mut required = true;
-
-            // Check for #[param] attribute
-            for attr in &field.attrs {
-                if attr.path().is_ident("param") {
+                let field_name = field.ident.as_ref().unwrap();
+                let mut is_param = false;
+                let mut param_desc = String::new();
+                let mut required = true;
+                let mut constraints = ParamConstraints::default();
+
+                // Check for #[param] attribute; a bare #[param] takes the defaults
+                for attr in &field.attrs {
+                    if !attr.path().is_ident("param") {
+                        continue;
+                    }
                     is_param = true;
+                    if matches!(attr.meta, syn::Meta::Path(_)) {
+                        continue;
+                    }
 
                     // Parse the attribute meta for description and required
-                    if let Ok(meta_list) = attr.meta.require_list() {
-                        let _ = meta_list.parse_nested_meta(|meta| {
-                            if meta.path.is_ident("description") {
-                                let lit: LitStr = meta.value()?.parse()?;
-                                param_desc = lit.value();
-                            } else if meta.path.is_ident("required") {
-                                let lit: LitBool = meta.value()?.parse()?;
-                                required = lit.value;
-                            }
-                            Ok(())
-                        });
+                    let parsed = attr.parse_nested_meta(|meta| {
+                        if meta.path.is_ident("description") {
+                            let lit: LitStr = meta.value()?.parse()?;
+                            param_desc = lit.value();
+                        } else if meta.path.is_ident("required") {
+                            let lit: LitBool = meta.value()?.parse()?;
+                            required = lit.value;
+                        } else if !constraints.parse(&meta)? {
+                            return Err(unknown_param_key(&meta, ", required"));
+                        }
+                        Ok(())
+                    });
+                    if let Err(e) = parsed {
+                        return e.to_compile_error().into();
                     }
                 }
-            }
 
-            if is_param {
-                let field_name_str = field_name.to_string();
-                let field_type = &field.ty;
-
-                // Determine type based on Rust type
-                let type_str = quote!(#field_type).to_string();
-                let param_type = if type_str.contains("String") || type_str.contains("str") {
-                    "string"
-                } else if type_str.contains("i64")
-                    || type_str.contains("i32")
-                    || type_str.contains("usize")
-                    || type_str.contains("f64")
-                    || type_str.contains("f32")
-                {
-                    "number"
-                } else if type_str.contains("bool") {
-                    "boolean"
-                } else {
-                    "string" // default
-                };
+                if is_param {
+                    let field_name_str = field_name.to_string();
+                    let field_type = &field.ty;
 
-                param_definitions.push(quote! {
-                    actorus::tools::ToolParameter {
-                        name: #field_name_str.to_string(),
-                        param_type: #param_type.to_string(),
-                        description: #param_desc.to_string(),
-                        required: #required,
-                    }
-                });
+                    // Determine type based on Rust type
+                    let base_type = option_inner(field_type).unwrap_or(field_type);
+                    let kind = ValueKind::of(base_type);
+                    let param_type = match kind {
+                        ValueKind::Deserialize => "string", // default
+                        kind => kind.schema_type(),
+                    };
+                    constraints.item_types = ValueKind::item_types(base_type);
+
+                    param_definitions.push(parameter_definition(
+                        &field_name_str,
+                        param_type,
+                        &param_desc,
+                        required,
+                        &constraints,
+                        (kind == ValueKind::Deserialize).then_some(base_type),
+                    ));
+                }
             }
         }
-        }
     }
 
     // Generate the output - add metadata method to the impl block or create new one
@@ -195,10 +635,23 @@ pub fn tool(args: TokenStream, input: TokenStream) -> TokenStream {
 /// ```
 ///
 /// This generates a struct and Tool implementation from a simple function.
+///
+/// Arguments may carry `#[param(...)]` with a `description` and the constraints
+/// `allowed_values = ["a", "b"]`, `minimum = 1`, `maximum = 65535` or
+/// `pattern = "^[a-z]+$"`. The generated `validate` rejects arguments that
+/// break them before the function is called.
+///
+/// Arguments must be plain identifiers. A leading underscore is left out of
+/// the parameter name, so `_query: String` is exposed as `query`; two
+/// arguments that end up with the same exposed name are rejected.
 #[proc_macro_attribute]
 pub fn tool_fn(args: TokenStream, input: TokenStream) -> TokenStream {
     let tool_args = parse_macro_input!(args as ToolArgs);
-    let input_fn = parse_macro_input!(input as syn::ItemFn);
+    let mut input_fn = parse_macro_input!(input as syn::ItemFn);
+
+    if let Err(e) = check_tool_fn_signature(&input_fn.sig) {
+        return e.to_compile_error().into();
+    }
 
     let fn_name = &input_fn.sig.ident;
     let tool_name = &tool_args.name;
@@ -225,126 +678,44 @@ pub fn tool_fn(args: TokenStream, input: TokenStream) -> TokenStream {
     let mut param_definitions = Vec::new();
     let mut param_extractions = Vec::new();
     let mut fn_args = Vec::new();
+    let mut exposed_names = std::collections::HashMap::new();
 
-    for arg in &input_fn.sig.inputs {
+    for arg in &mut input_fn.sig.inputs {
         if let FnArg::Typed(pat_type) = arg {
-            if let syn::Pat::Ident(pat_ident) = &*pat_type.pat {
-                let param_name = &pat_ident.ident;
-                let param_name_str = param_name.to_string();
-                let param_type = &pat_type.ty;
-
-                // Determine if optional and base type
-                let (is_optional, base_type_str) = match &**param_type {
-                    Type::Path(type_path) => {
-                        let type_str = quote!(#type_path).to_string();
-                        if type_str.starts_with("Option") {
-                            // Extract inner type from Option<T>
-                            if let Some(seg) = type_path.path.segments.first() {
-                                if let syn::PathArguments::AngleBracketed(args) = &seg.arguments {
-                                    if let Some(syn::GenericArgument::Type(inner_type)) =
-                                        args.args.first()
-                                    {
-                                        (true, quote!(#inner_type).to_string())
-                                    } else {
-                                        (true, type_str)
-                                    }
-                                } else {
-                                    (false, type_str)
-                                }
-                            } else {
-                                (true, type_str)
-                            }
-                        } else {
-                            (false, type_str)
-                        }
-                    }
-                    _ => (false, quote!(#param_type).to_string()),
-                };
-
-                // Map Rust type to tool parameter type
-                let (param_type_name, is_struct) = if base_type_str.contains("String") || base_type_str.contains("str") {
-                    ("string", false)
-                } else if base_type_str.contains("i64")
-                    || base_type_str.contains("i32")
-                    || base_type_str.contains("f64")
-                    || base_type_str.contains("f32")
-                    || base_type_str.contains("usize")
-                {
-                    ("number", false)
-                } else if base_type_str.contains("bool") {
-                    ("boolean", false)
-                } else {
-                    // Assume it's a custom struct/type that needs JSON deserialization
-                    ("object", true)
-                };
+            // Consume #[param] attributes; they are not valid on the emitted function
+            let (param_desc, constraints) = match parse_param_attrs(&pat_type.attrs) {
+                Ok(parsed) => parsed,
+                Err(e) => return e.to_compile_error().into(),
+            };
+            pat_type.attrs.retain(|a| !a.path().is_ident("param"));
 
-                // Generate parameter metadata
-                let is_required = !is_optional;
-                param_definitions.push(quote! {
-                    actorus::tools::ToolParameter {
-                        name: #param_name_str.to_string(),
-                        param_type: #param_type_name.to_string(),
-                        description: format!("Parameter: {}", #param_name_str),
-                        required: #is_required,
-                    }
-                });
-
-                // Generate parameter extraction logic
-                if is_optional {
-                    if param_type_name == "string" {
-                        param_extractions.push(quote! {
-                            let #param_name = args.get(#param_name_str)
-                                .and_then(|v| v.as_str())
-                                .map(|s| s.to_string());
-                        });
-                    } else if param_type_name == "number" {
-                        // For Option<number>, we extract as the original Rust type
-                        param_extractions.push(quote! {
-                            let #param_name = args.get(#param_name_str)
-                                .and_then(|v| v.as_i64())
-                                .map(|n| n as #param_type);
-                        });
-                    } else if param_type_name == "boolean" {
-                        param_extractions.push(quote! {
-                            let #param_name = args.get(#param_name_str)
-                                .and_then(|v| v.as_bool());
-                        });
-                    } else if is_struct {
-                        // For Option<Struct>, deserialize from JSON
-                        param_extractions.push(quote! {
-                            let #param_name = args.get(#param_name_str)
-                                .and_then(|v| serde_json::from_value::<#param_type>(v.clone()).ok());
-                        });
-                    }
-                    fn_args.push(quote! { #param_name });
-                } else {
-                    // Required parameter
-                    if param_type_name == "string" {
-                        param_extractions.push(quote! {
-                            let #param_name = actorus::validate_required_string!(args, #param_name_str).to_string();
-                        });
-                    } else if param_type_name == "number" {
-                        // For required numbers, cast to the exact type
-                        param_extractions.push(quote! {
-                            let #param_name = actorus::validate_required_number!(args, #param_name_str) as #param_type;
-                        });
-                    } else if is_struct {
-                        // For required struct, deserialize from JSON
-                        param_extractions.push(quote! {
-                            let #param_name = serde_json::from_value::<#param_type>(
-                                args.get(#param_name_str)
-                                    .ok_or_else(|| anyhow::anyhow!("Missing required parameter: {}", #param_name_str))?
-                                    .clone()
-                            )?;
-                        });
-                    } else {
-                        param_extractions.push(quote! {
-                            let #param_name = actorus::validate_required_string!(args, #param_name_str).to_string();
-                        });
-                    }
-                    fn_args.push(quote! { #param_name });
-                }
+            let (param_name, param_name_str) = match tool_fn_param_name(&pat_type.pat) {
+                Ok(names) => names,
+                Err(e) => return e.to_compile_error().into(),
+            };
+            if let Some(earlier) =
+                exposed_names.insert(param_name_str.clone(), param_name.to_string())
+            {
+                return syn::Error::new_spanned(
+                    &pat_type.pat,
+                    format!(
+                        "#[tool_fn] arguments `{}` and `{}` are both exposed as `{}`",
+                        earlier, param_name, param_name_str
+                    ),
+                )
+                .to_compile_error()
+                .into();
             }
+            let (definition, extraction) = param_codegen(
+                param_name,
+                &param_name_str,
+                &pat_type.ty,
+                param_desc,
+                constraints,
+            );
+            param_definitions.push(definition);
+            param_extractions.push(extraction);
+            fn_args.push(quote! { #param_name });
         }
     }
 
@@ -387,9 +758,12 @@ pub fn tool_fn(args: TokenStream, input: TokenStream) -> TokenStream {
                 Self::tool_metadata()
             }
 
+            // Extracted values are only checked here, not used
+            #[allow(unused_variables)]
             fn validate(&self, args: &serde_json::Value) -> anyhow::Result<()> {
                 // Auto-generated validation
                 #(#param_extractions)*
+                actorus::tools::validate_constraints(&Self::tool_metadata().parameters, args)?;
                 Ok(())
             }
 
@@ -409,3 +783,250 @@ pub fn tool_fn(args: TokenStream, input: TokenStream) -> TokenStream {
 
     TokenStream::from(expanded)
 }
+
+/// Derive the `Tool` trait for a struct whose `#[param]` fields are its arguments
+///
+/// Usage:
+/// ```ignore
+/// #[derive(Clone, Default, Tool)]
+/// #[tool_meta(name = "greet", description = "Greet a person")]
+/// pub struct GreetTool {
+///     #[param(description = "Who to greet")]
+///     name: String,
+///     /// Not a parameter: kept from the registered instance
+///     greeting: String,
+/// }
+///
+/// impl GreetTool {
+///     async fn run(&self) -> anyhow::Result<String> {
+///         Ok(format!("{}, {}!", self.greeting, self.name))
+///     }
+/// }
+/// ```
+///
+/// `execute` fills the `#[param]` fields from the arguments and calls `run` on
+/// the result. Any remaining fields are copied from the registered instance,
+/// so structs that have them must implement `Clone`.
+#[proc_macro_derive(Tool, attributes(tool_meta, param))]
+pub fn derive_tool(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as syn::DeriveInput);
+    match expand_derive_tool(&input) {
+        Ok(expanded) => TokenStream::from(expanded),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn expand_derive_tool(input: &syn::DeriveInput) -> Result<proc_macro2::TokenStream> {
+    let struct_name = &input.ident;
+    let fields = match &input.data {
+        syn::Data::Struct(syn::DataStruct {
+            fields: syn::Fields::Named(fields),
+            ..
+        }) => &fields.named,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                struct_name,
+                "#[derive(Tool)] requires a struct with named fields",
+            ))
+        }
+    };
+
+    let tool_args = input
+        .attrs
+        .iter()
+        .find(|a| a.path().is_ident("tool_meta"))
+        .ok_or_else(|| {
+            syn::Error::new_spanned(
+                struct_name,
+                "#[derive(Tool)] requires #[tool_meta(name = \"...\", description = \"...\")]",
+            )
+        })?
+        .parse_args::<ToolArgs>()?;
+    let tool_name = &tool_args.name;
+    let tool_desc = &tool_args.description;
+
+    let mut param_definitions = Vec::new();
+    let mut param_extractions = Vec::new();
+    let mut param_fields = Vec::new();
+    let mut has_other_fields = false;
+
+    for field in fields {
+        if !field.attrs.iter().any(|a| a.path().is_ident("param")) {
+            has_other_fields = true;
+            continue;
+        }
+        let field_name = field.ident.as_ref().unwrap();
+        let (param_desc, constraints) = parse_param_attrs(&field.attrs)?;
+        let (definition, extraction) = param_codegen(
+            field_name,
+            &field_name.to_string(),
+            &field.ty,
+            param_desc,
+            constraints,
+        );
+        param_definitions.push(definition);
+        param_extractions.push(extraction);
+        param_fields.push(field_name);
+    }
+
+    let rest = if has_other_fields {
+        quote! { ..::std::clone::Clone::clone(self) }
+    } else {
+        quote! {}
+    };
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        #[async_trait::async_trait]
+        impl #impl_generics actorus::tools::Tool for #struct_name #ty_generics #where_clause {
+            fn metadata(&self) -> actorus::tools::ToolMetadata {
+                actorus::tools::ToolMetadata {
+                    name: #tool_name.to_string(),
+                    description: #tool_desc.to_string(),
+                    parameters: vec![
+                        #(#param_definitions),*
+                    ],
+                }
+            }
+
+            // Extracted values are only checked here, not used
+            #[allow(unused_variables)]
+            fn validate(&self, args: &serde_json::Value) -> anyhow::Result<()> {
+                #(#param_extractions)*
+                actorus::tools::validate_constraints(&self.metadata().parameters, args)?;
+                Ok(())
+            }
+
+            async fn execute(&self, args: serde_json::Value) -> anyhow::Result<actorus::tools::ToolResult> {
+                self.validate(&args)?;
+
+                // Fill the parameter fields from the arguments
+                #(#param_extractions)*
+                let call = Self {
+                    #(#param_fields,)*
+                    #rest
+                };
+
+                let result = call.run().await?;
+                actorus::tool_result!(success: result)
+            }
+        }
+    })
+}
+
+/// Derive `ToolEnum` for an enum of unit variants
+///
+/// Usage:
+/// ```ignore
+/// #[derive(Deserialize, ToolEnum)]
+/// #[serde(rename_all = "lowercase")]
+/// enum Unit {
+///     Celsius,
+///     Fahrenheit,
+/// }
+/// ```
+///
+/// A `#[tool_fn]`, `#[tool]` or `#[derive(Tool)]` parameter of the enum's type
+/// then lists `celsius` and `fahrenheit` as its allowed values. Names follow
+/// serde's `rename` and `rename_all` attributes, so they match what
+/// deserialization accepts.
+#[proc_macro_derive(ToolEnum, attributes(serde))]
+pub fn derive_tool_enum(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as syn::DeriveInput);
+    match expand_derive_tool_enum(&input) {
+        Ok(expanded) => TokenStream::from(expanded),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn expand_derive_tool_enum(input: &syn::DeriveInput) -> Result<proc_macro2::TokenStream> {
+    let enum_name = &input.ident;
+    let syn::Data::Enum(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            enum_name,
+            "#[derive(ToolEnum)] requires an enum",
+        ));
+    };
+
+    let rename_all = serde_rename(&input.attrs, "rename_all")?;
+    let mut values = Vec::new();
+    for variant in &data.variants {
+        if !matches!(variant.fields, syn::Fields::Unit) {
+            return Err(syn::Error::new_spanned(
+                variant,
+                "#[derive(ToolEnum)] requires unit variants",
+            ));
+        }
+        let value = match serde_rename(&variant.attrs, "rename")? {
+            Some(renamed) => renamed,
+            None => rename_variant(&variant.ident.to_string(), rename_all.as_deref())
+                .map_err(|message| syn::Error::new_spanned(enum_name, message))?,
+        };
+        values.push(value);
+    }
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics actorus::tools::ToolEnum for #enum_name #ty_generics #where_clause {
+            fn allowed_values() -> Vec<String> {
+                vec![#(#values.to_string()),*]
+            }
+        }
+    })
+}
+
+/// Value of `#[serde(key = "...")]`, ignoring other serde keys
+fn serde_rename(attrs: &[syn::Attribute], key: &str) -> Result<Option<String>> {
+    let mut value = None;
+    for attr in attrs.iter().filter(|a| a.path().is_ident("serde")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident(key) {
+                let lit: LitStr = meta.value()?.parse()?;
+                value = Some(lit.value());
+            } else if meta.input.peek(Token![=]) {
+                // Skip the value of keys this derive does not need
+                meta.value()?.parse::<syn::Expr>()?;
+            } else if meta.input.peek(syn::token::Paren) {
+                meta.parse_nested_meta(|nested| {
+                    if nested.input.peek(Token![=]) {
+                        nested.value()?.parse::<syn::Expr>()?;
+                    }
+                    Ok(())
+                })?;
+            }
+            Ok(())
+        })?;
+    }
+    Ok(value)
+}
+
+/// Variant name as serde writes it under `rename_all`
+fn rename_variant(variant: &str, rename_all: Option<&str>) -> std::result::Result<String, String> {
+    // Variants are PascalCase, so split before each uppercase letter
+    let mut words: Vec<String> = Vec::new();
+    for c in variant.chars() {
+        if c.is_uppercase() || words.is_empty() {
+            words.push(String::new());
+        }
+        words.last_mut().unwrap().push(c);
+    }
+    let lower: Vec<String> = words.iter().map(|w| w.to_lowercase()).collect();
+
+    Ok(match rename_all {
+        None | Some("PascalCase") => variant.to_string(),
+        Some("lowercase") => variant.to_lowercase(),
+        Some("UPPERCASE") => variant.to_uppercase(),
+        Some("camelCase") => {
+            let mut chars = variant.chars();
+            chars
+                .next()
+                .map(|first| first.to_lowercase().chain(chars).collect())
+                .unwrap_or_default()
+        }
+        Some("snake_case") => lower.join("_"),
+        Some("SCREAMING_SNAKE_CASE") => lower.join("_").to_uppercase(),
+        Some("kebab-case") => lower.join("-"),
+        Some("SCREAMING-KEBAB-CASE") => lower.join("-").to_uppercase(),
+        Some(other) => return Err(format!("unsupported rename_all rule \"{}\"", other)),
+    })
+}
@@ -0,0 +1,7 @@
+//! Diagnostics emitted by the tool macros for unsupported input
+
+#[test]
+fn test_compile_fail_diagnostics() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/*.rs");
+}
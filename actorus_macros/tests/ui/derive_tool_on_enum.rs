@@ -0,0 +1,10 @@
+use actorus_macros::Tool;
+
+#[derive(Tool)]
+#[tool_meta(name = "pick_color", description = "Pick a color")]
+pub enum Color {
+    Red,
+    Green,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use actorus_macros::ToolEnum;
+
+#[derive(ToolEnum)]
+enum Shape {
+    Circle,
+    Square(u32),
+}
+
+fn main() {}
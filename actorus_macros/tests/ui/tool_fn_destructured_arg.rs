@@ -0,0 +1,8 @@
+use actorus_macros::tool_fn;
+
+#[tool_fn(name = "distance", description = "Distance from the origin")]
+async fn distance((x, y): (f64, f64)) -> Result<String, String> {
+    Ok(format!("{}", (x * x + y * y).sqrt()))
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use actorus_macros::tool_fn;
+
+#[tool_fn(name = "search", description = "Search for a term")]
+async fn search(_query: String, query: String) -> Result<String, String> {
+    Ok(query)
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use actorus_macros::tool_fn;
+
+#[tool_fn(description = "Greet a person")]
+async fn greet(name: String) -> Result<String, String> {
+    Ok(format!("Hello, {}!", name))
+}
+
+fn main() {}
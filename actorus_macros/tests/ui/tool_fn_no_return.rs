@@ -0,0 +1,8 @@
+use actorus_macros::tool_fn;
+
+#[tool_fn(name = "log", description = "Log a message")]
+async fn log(message: String) {
+    println!("{}", message);
+}
+
+fn main() {}
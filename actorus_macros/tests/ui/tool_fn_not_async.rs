@@ -0,0 +1,8 @@
+use actorus_macros::tool_fn;
+
+#[tool_fn(name = "greet", description = "Greet a person")]
+fn greet(name: String) -> Result<String, String> {
+    Ok(format!("Hello, {}!", name))
+}
+
+fn main() {}
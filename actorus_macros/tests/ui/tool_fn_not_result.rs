@@ -0,0 +1,8 @@
+use actorus_macros::tool_fn;
+
+#[tool_fn(name = "greet", description = "Greet a person")]
+async fn greet(name: String) -> String {
+    format!("Hello, {}!", name)
+}
+
+fn main() {}
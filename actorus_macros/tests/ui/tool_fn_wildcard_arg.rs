@@ -0,0 +1,8 @@
+use actorus_macros::tool_fn;
+
+#[tool_fn(name = "ping", description = "Reply with pong")]
+async fn ping(_: String) -> Result<String, String> {
+    Ok("pong".to_string())
+}
+
+fn main() {}
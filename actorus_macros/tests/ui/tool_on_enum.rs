@@ -0,0 +1,9 @@
+use actorus_macros::tool;
+
+#[tool(name = "pick_color", description = "Pick a color")]
+pub enum Color {
+    Red,
+    Green,
+}
+
+fn main() {}
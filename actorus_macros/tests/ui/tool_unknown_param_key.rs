@@ -0,0 +1,9 @@
+use actorus_macros::tool;
+
+#[tool(name = "search", description = "Search for a term")]
+pub struct SearchTool {
+    #[param(description = "Term to find", optional = true)]
+    query: String,
+}
+
+fn main() {}
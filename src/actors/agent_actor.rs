@@ -7,6 +7,8 @@
 //! - LLM interaction details abstracted
 
 use crate::actors::messages::*;
+use crate::config::guardrails::{guardrail_failure, sanitize_observation, GuardrailPolicy};
+use crate::config::prompts::{self, PromptVars};
 use crate::config::Settings;
 use crate::core::llm::{ChatMessage, LLMClient};
 use crate::tools::{executor::ToolExecutor, registry::ToolRegistry, ToolConfig};
@@ -16,6 +18,7 @@ use serde_json::Value;
 use std::sync::Arc;
 use tokio::sync::mpsc::{channel, Receiver, Sender};
 use tokio::time::{interval, Duration};
+use tracing::Instrument;
 
 static ROUTER_SENDER: OnceCell<Sender<RoutingMessage>> = OnceCell::new();
 
@@ -66,8 +69,8 @@ async fn agent_actor(mut receiver: Receiver<AgentMessage>, settings: Settings, a
     tracing::info!("Agent actor started");
 
     let llm_client = LLMClient::new(api_key, settings.clone());
-    let tool_registry = Arc::new(ToolRegistry::with_defaults());
-    let tool_executor = ToolExecutor::new(ToolConfig::default());
+    let tool_registry = Arc::new(ToolRegistry::with_defaults_for(&settings.tools));
+    let tool_executor = ToolExecutor::new(ToolConfig::for_settings(&settings));
 
     let heartbeat_interval = Duration::from_millis(settings.system.heartbeat_interval_ms);
     let mut heartbeat_timer = interval(heartbeat_interval);
@@ -80,15 +83,25 @@ async fn agent_actor(mut receiver: Receiver<AgentMessage>, settings: Settings, a
             Some(message) = receiver.recv() => {
                 match message {
                     AgentMessage::RunTask(task) => {
-                        tracing::info!("Agent received task: {}", task.task_description);
-
-                        let result = run_react_loop(
-                            &llm_client,
-                            &tool_registry,
-                            &tool_executor,
-                            &task.task_description,
-                            task.max_iterations.unwrap_or(default_max_iterations),
-                        ).await;
+                        let span = task.request_id.span();
+                        tracing::info!(parent: &span, "Agent received task: {}", task.task_description);
+
+                        let planned_before = tool_executor.planned_tool_calls().len();
+                        let result = task
+                            .context
+                            .scope(run_react_loop(
+                                &llm_client,
+                                &tool_registry,
+                                &tool_executor,
+                                &task.task_description,
+                                task.max_iterations.unwrap_or(default_max_iterations),
+                                task.steps.as_ref(),
+                            ))
+                            .instrument(span)
+                            .await
+                            .with_planned_tool_calls(
+                                tool_executor.planned_tool_calls().split_off(planned_before),
+                            );
 
                         let _ = task.response.send(result);
                     }
@@ -124,63 +137,92 @@ async fn run_react_loop(
     tool_executor: &ToolExecutor,
     task: &str,
     max_iterations: usize,
+    progress: Option<&Sender<AgentStep>>,
 ) -> AgentResponse {
     let mut steps = Vec::new();
     let mut conversation_history = Vec::new();
 
+    let guardrails = match GuardrailPolicy::from_config(&llm_client.settings().guardrails) {
+        Ok(policy) => policy,
+        Err(e) => return guardrail_failure("Agent", e.into(), steps),
+    };
+
     // System prompt for the agent
-    let system_prompt = format!(
-        "You are an autonomous agent that can use tools to accomplish tasks.\n\n\
-         Available Tools:\n{}\n\n\
-         IMPORTANT: You MUST respond in this EXACT JSON format:\n\
-         {{\n  \
-           \"thought\": \"your reasoning about what to do next\",\n  \
-           \"action\": {{\"tool\": \"tool_name\", \"input\": {{\"param\": \"value\"}}}},\n  \
-           \"is_final\": false,\n  \
-           \"final_answer\": null\n\
-         }}\n\n\
-         When the task is COMPLETE:\n\
-         - Set \"is_final\": true\n\
-         - Set \"action\": null\n\
-         - Provide a clear \"final_answer\" summarizing what you accomplished\n\n\
-         CRITICAL: A task is COMPLETE when:\n\
-         1. You have successfully executed all required tools AND received their results\n\
-         2. You have the information/result requested by the user\n\
-         3. No further actions are needed to satisfy the user's request\n\n\
-         After each tool execution, check: Does the observation contain what the user asked for?\n\
-         If YES, immediately set is_final=true and provide the final_answer.\n\
-         Do NOT repeat the same action if you already have the result.\n\n\
-         Always respond with valid JSON only. No extra text.",
-        tool_registry.tools_description()
+    let system_prompt = prompts::render(
+        &llm_client.settings().prompts.react_template,
+        &PromptVars {
+            system_prompt: "You are an autonomous agent that can use tools to accomplish tasks.",
+            tools: &tool_registry.tools_description(),
+            context: "",
+            max_iterations,
+        },
     );
 
-    conversation_history.push(ChatMessage {
-        role: "system".to_string(),
-        content: system_prompt,
-    });
+    conversation_history.push(ChatMessage::system(system_prompt));
+    if let Some(note) = prompts::current_time_note(&llm_client.settings().prompts) {
+        conversation_history.push(ChatMessage::system(note));
+    }
 
-    conversation_history.push(ChatMessage {
-        role: "user".to_string(),
-        content: format!("Task: {}", task),
-    });
+    conversation_history.push(ChatMessage::user(format!("Task: {}", task)));
 
     for iteration in 0..max_iterations {
         tracing::info!("Agent iteration {}/{}", iteration + 1, max_iterations);
 
         // Think: Ask LLM for next action
-        let decision = match think(llm_client, &conversation_history).await {
-            Ok(d) => d,
-            Err(e) => {
-                tracing::error!("Failed to get decision from LLM: {}", e);
-                return AgentResponse::Failure {
-                    error: format!("Failed to reason: {}", e),
-                    steps,
-                    metadata: None,
-                    completion_status: Some(CompletionStatus::Failed {
-                        error: format!("LLM reasoning failed: {}", e),
-                        recoverable: true,
-                    }),
-                };
+        let mut unknown_tool_retries = 0;
+        let decision = loop {
+            let mut decision = match think(llm_client, &conversation_history).await {
+                Ok(d) => d,
+                Err(e) => {
+                    tracing::error!("Failed to get decision from LLM: {}", e);
+                    return AgentResponse::Failure {
+                        error: format!("Failed to reason: {}", e),
+                        steps,
+                        metadata: None,
+                        completion_status: Some(CompletionStatus::Failed {
+                            error: format!("LLM reasoning failed: {}", e),
+                            recoverable: true,
+                        }),
+                    };
+                }
+            };
+
+            // Record and echo the registered name, not the model's near miss
+            if let Some(action) = decision.action.as_mut() {
+                if let Some(name) = tool_registry
+                    .resolve(&action.tool)
+                    .filter(|name| *name != action.tool)
+                {
+                    tracing::warn!(
+                        "Agent resolved unknown tool '{}' to '{}'",
+                        action.tool,
+                        name
+                    );
+                    action.tool = name;
+                }
+            }
+
+            let max_retries = llm_client.settings().agent.unknown_tool_retries;
+            match decision.action.as_ref() {
+                Some(action)
+                    if !decision.is_final
+                        && unknown_tool_retries < max_retries
+                        && !tool_registry.has_tool(&action.tool) =>
+                {
+                    unknown_tool_retries += 1;
+                    tracing::warn!(
+                        "Agent picked unknown tool '{}', asking again ({}/{})",
+                        action.tool,
+                        unknown_tool_retries,
+                        max_retries
+                    );
+                    conversation_history.extend(prompts::unknown_tool_reask(
+                        &decision,
+                        &action.tool,
+                        &tool_registry.tool_names(),
+                    ));
+                }
+                _ => break decision,
             }
         };
 
@@ -192,12 +234,21 @@ async fn run_react_loop(
                 .final_answer
                 .unwrap_or_else(|| "Task completed without explicit answer".to_string());
 
-            steps.push(AgentStep {
-                iteration,
-                thought: decision.thought.clone(),
-                action: None,
-                observation: Some(final_answer.clone()),
-            });
+            push_step(
+                &mut steps,
+                progress,
+                AgentStep {
+                    iteration,
+                    thought: decision.thought.clone(),
+                    action: None,
+                    observation: Some(final_answer.clone()),
+                },
+            )
+            .await;
+
+            if let Err(violation) = guardrails.check("final answer", &final_answer) {
+                return guardrail_failure("Agent", violation.into(), steps);
+            }
 
             return AgentResponse::Success {
                 result: final_answer,
@@ -211,21 +262,41 @@ async fn run_react_loop(
         if let Some(action) = decision.action {
             tracing::info!("Agent executing tool: {}", action.tool);
 
+            let input = serde_json::to_string(&action.input).unwrap_or_default();
+            let location = format!("input for tool '{}'", action.tool);
+            if let Err(violation) = guardrails.check(&location, &input) {
+                push_step(
+                    &mut steps,
+                    progress,
+                    AgentStep {
+                        iteration,
+                        thought: decision.thought,
+                        action: Some(action.tool.clone()),
+                        observation: None,
+                    },
+                )
+                .await;
+                return guardrail_failure("Agent", violation.into(), steps);
+            }
+
             let tool = match tool_registry.get(&action.tool) {
                 Some(t) => t,
                 None => {
                     let error_msg = format!("Tool '{}' not found", action.tool);
-                    conversation_history.push(ChatMessage {
-                        role: "assistant".to_string(),
-                        content: format!("Error: {}", error_msg),
-                    });
-
-                    steps.push(AgentStep {
-                        iteration,
-                        thought: decision.thought,
-                        action: Some(action.tool.clone()),
-                        observation: Some(error_msg),
-                    });
+                    conversation_history
+                        .push(ChatMessage::assistant(format!("Error: {}", error_msg)));
+
+                    push_step(
+                        &mut steps,
+                        progress,
+                        AgentStep {
+                            iteration,
+                            thought: decision.thought,
+                            action: Some(action.tool.clone()),
+                            observation: Some(error_msg),
+                        },
+                    )
+                    .await;
                     continue;
                 }
             };
@@ -236,17 +307,19 @@ async fn run_react_loop(
                 Err(e) => {
                     tracing::error!("Tool execution error: {}", e);
                     let error_msg = format!("Tool execution failed: {}", e);
-                    conversation_history.push(ChatMessage {
-                        role: "assistant".to_string(),
-                        content: error_msg.clone(),
-                    });
-
-                    steps.push(AgentStep {
-                        iteration,
-                        thought: decision.thought,
-                        action: Some(action.tool.clone()),
-                        observation: Some(error_msg),
-                    });
+                    conversation_history.push(ChatMessage::assistant(error_msg.clone()));
+
+                    push_step(
+                        &mut steps,
+                        progress,
+                        AgentStep {
+                            iteration,
+                            thought: decision.thought,
+                            action: Some(action.tool.clone()),
+                            observation: Some(error_msg),
+                        },
+                    )
+                    .await;
                     continue;
                 }
             };
@@ -259,35 +332,33 @@ async fn run_react_loop(
 
             tracing::debug!("Tool observation: {}", observation);
 
-            // Add the agent's action to conversation history
-            conversation_history.push(ChatMessage {
-                role: "assistant".to_string(),
-                content: serde_json::to_string(&AgentDecision {
-                    thought: decision.thought.clone(),
-                    action: Some(action.clone()),
-                    is_final: false,
-                    final_answer: None,
-                })
-                .unwrap_or_else(|_| format!("Action: {}", action.tool)),
-            });
-
-            // Add observation to conversation with prompt to check completion
-            conversation_history.push(ChatMessage {
-                role: "user".to_string(),
-                content: format!(
-                    "Observation: {}\n\nDoes this observation contain the answer to the original task? \
-                     If yes, set is_final=true and provide final_answer. \
-                     If no, what is the next action needed?",
-                    observation
-                ),
-            });
-
-            steps.push(AgentStep {
-                iteration,
-                thought: decision.thought,
-                action: Some(action.tool.clone()),
-                observation: Some(observation),
-            });
+            // Record the call and its observation, then ask whether the task is done
+            let history_observation = if llm_client.settings().guardrails.sanitize_observations {
+                sanitize_observation(&action.tool, &observation)
+            } else {
+                observation.clone()
+            };
+            conversation_history.extend(prompts::tool_call_turns(
+                &decision.thought,
+                &action.tool,
+                &action.input,
+                history_observation,
+                "Does this observation contain the answer to the original task? \
+                 If yes, set is_final=true and provide final_answer. \
+                 If no, what is the next action needed?",
+            ));
+
+            push_step(
+                &mut steps,
+                progress,
+                AgentStep {
+                    iteration,
+                    thought: decision.thought,
+                    action: Some(action.tool.clone()),
+                    observation: Some(observation),
+                },
+            )
+            .await;
         } else {
             // No action specified - check if this is actually a completion
             // If we have previous observations and no action, treat as complete
@@ -305,12 +376,17 @@ async fn run_react_loop(
                         .unwrap_or_else(|| "Task completed".to_string())
                 };
 
-                steps.push(AgentStep {
-                    iteration,
-                    thought: "Task completed based on previous observations".to_string(),
-                    action: None,
-                    observation: Some(result.clone()),
-                });
+                push_step(
+                    &mut steps,
+                    progress,
+                    AgentStep {
+                        iteration,
+                        thought: "Task completed based on previous observations".to_string(),
+                        action: None,
+                        observation: Some(result.clone()),
+                    },
+                )
+                .await;
 
                 return AgentResponse::Success {
                     result,
@@ -324,17 +400,19 @@ async fn run_react_loop(
             let error_msg = "No action specified and no prior progress".to_string();
             tracing::warn!("{}", error_msg);
 
-            conversation_history.push(ChatMessage {
-                role: "assistant".to_string(),
-                content: error_msg.clone(),
-            });
+            conversation_history.push(ChatMessage::assistant(error_msg.clone()));
 
-            steps.push(AgentStep {
-                iteration,
-                thought: decision.thought,
-                action: None,
-                observation: Some(error_msg),
-            });
+            push_step(
+                &mut steps,
+                progress,
+                AgentStep {
+                    iteration,
+                    thought: decision.thought,
+                    action: None,
+                    observation: Some(error_msg),
+                },
+            )
+            .await;
         }
     }
 
@@ -357,6 +435,18 @@ async fn run_react_loop(
     }
 }
 
+/// Record a step and forward it to the progress channel, if any
+async fn push_step(
+    steps: &mut Vec<AgentStep>,
+    progress: Option<&Sender<AgentStep>>,
+    step: AgentStep,
+) {
+    if let Some(tx) = progress {
+        let _ = tx.send(step.clone()).await;
+    }
+    steps.push(step);
+}
+
 /// Think step - Ask LLM to reason about next action
 async fn think(
     llm_client: &LLMClient,
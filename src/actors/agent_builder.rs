@@ -8,17 +8,33 @@
 
 use crate::tools::Tool;
 use std::sync::Arc;
+use thiserror::Error;
 
 /// Type alias for agent configuration tuple
-/// Format: (name, description, system_prompt, tools, response_schema)
+/// Format: (name, description, system_prompt, tools, response_schema, return_tool_output, examples)
 pub type AgentConfig = (
     String,
     String,
     String,
     Vec<Arc<dyn Tool>>,
     Option<serde_json::Value>,
+    bool,
+    Vec<(String, String)>,
 );
 
+/// Reasons an agent configuration is rejected by `AgentBuilder::try_build`
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum AgentBuildError {
+    #[error("agent name must not be empty")]
+    EmptyName,
+    #[error("agent '{0}' needs a description so routers and supervisors can select it")]
+    MissingDescription(String),
+    #[error("agent '{0}' has an empty system prompt")]
+    EmptySystemPrompt(String),
+    #[error("an agent named '{0}' is already in the collection")]
+    DuplicateName(String),
+}
+
 /// Builder for creating specialized agent configurations
 ///
 /// Provides a fluent API for constructing agents with custom tools
@@ -43,6 +59,7 @@ pub struct AgentBuilder {
     tools: Vec<Arc<dyn Tool>>,
     response_schema: Option<serde_json::Value>,
     return_tool_output: bool,
+    examples: Vec<(String, String)>,
 }
 
 impl AgentBuilder {
@@ -55,6 +72,7 @@ impl AgentBuilder {
             tools: Vec::new(),
             response_schema: None,
             return_tool_output: false,
+            examples: Vec::new(),
         }
     }
 
@@ -117,24 +135,69 @@ impl AgentBuilder {
         self
     }
 
+    /// Add a few-shot example exchange
+    ///
+    /// Examples are shown to the agent in the order they were added, after
+    /// the system prompt and before the task. The assistant reply should be
+    /// in the same JSON decision format the agent is expected to produce.
+    pub fn example(mut self, user: impl Into<String>, assistant: impl Into<String>) -> Self {
+        self.examples.push((user.into(), assistant.into()));
+        self
+    }
+
     /// Build the agent configuration
     ///
     /// Returns a tuple suitable for use with `supervisor::orchestrate_custom_agents`
     /// or for creating SpecializedAgent instances.
     ///
-    /// Format: (name, description, system_prompt, tools, response_schema)
+    /// Format: (name, description, system_prompt, tools, response_schema, return_tool_output, examples)
+    ///
+    /// Missing description and system prompt fall back to generic defaults.
+    /// Incomplete configurations are logged as warnings; use `try_build` to
+    /// reject them instead.
+    pub fn build(self) -> AgentConfig {
+        if let Err(e) = self.validate() {
+            tracing::warn!("[AgentBuilder] {}", e);
+        }
+        self.warn_if_no_tools();
+        self.into_config()
+    }
+
+    /// Build the agent configuration, failing fast on an incomplete setup
     ///
-    /// Note: return_tool_output is automatically enabled when response_schema is set
-    pub fn build(
-        self,
-    ) -> (
-        String,
-        String,
-        String,
-        Vec<Arc<dyn Tool>>,
-        Option<serde_json::Value>,
-        bool,
-    ) {
+    /// Requires a non-empty name and description and rejects an explicitly
+    /// empty system prompt. An agent without tools is allowed but logged.
+    pub fn try_build(self) -> Result<AgentConfig, AgentBuildError> {
+        self.validate()?;
+        self.warn_if_no_tools();
+        Ok(self.into_config())
+    }
+
+    fn validate(&self) -> Result<(), AgentBuildError> {
+        if self.name.trim().is_empty() {
+            return Err(AgentBuildError::EmptyName);
+        }
+        let description = self.description.as_deref().unwrap_or_default();
+        if description.trim().is_empty() {
+            return Err(AgentBuildError::MissingDescription(self.name.clone()));
+        }
+        if self
+            .system_prompt
+            .as_deref()
+            .is_some_and(|p| p.trim().is_empty())
+        {
+            return Err(AgentBuildError::EmptySystemPrompt(self.name.clone()));
+        }
+        Ok(())
+    }
+
+    fn warn_if_no_tools(&self) {
+        if self.tools.is_empty() {
+            tracing::warn!("[AgentBuilder] Agent '{}' has no tools", self.name);
+        }
+    }
+
+    fn into_config(self) -> AgentConfig {
         let description = self
             .description
             .unwrap_or_else(|| format!("Specialized agent: {}", self.name));
@@ -153,6 +216,7 @@ impl AgentBuilder {
             self.tools,
             self.response_schema,
             self.return_tool_output,
+            self.examples,
         )
     }
 
@@ -172,14 +236,7 @@ impl AgentBuilder {
 /// Provides utility methods for working with multiple agents
 /// as a group, making it easier to pass to supervisor APIs.
 pub struct AgentCollection {
-    agents: Vec<(
-        String,
-        String,
-        String,
-        Vec<Arc<dyn Tool>>,
-        Option<serde_json::Value>,
-        bool,
-    )>,
+    agents: Vec<AgentConfig>,
 }
 
 impl AgentCollection {
@@ -194,36 +251,46 @@ impl AgentCollection {
         self
     }
 
+    /// Add an agent from a builder, rejecting a name already in the collection
+    ///
+    /// Supervisors key agents by name, so a duplicate would silently replace
+    /// the earlier agent. On error the collection is left unchanged.
+    pub fn try_add(&mut self, builder: AgentBuilder) -> Result<(), AgentBuildError> {
+        if self.contains(builder.name()) {
+            return Err(AgentBuildError::DuplicateName(builder.name().to_string()));
+        }
+        self.agents.push(builder.build());
+        Ok(())
+    }
+
     /// Add a pre-built agent configuration
-    pub fn add_config(
-        mut self,
-        config: (
-            String,
-            String,
-            String,
-            Vec<Arc<dyn Tool>>,
-            Option<serde_json::Value>,
-            bool,
-        ),
-    ) -> Self {
+    pub fn add_config(mut self, config: AgentConfig) -> Self {
         self.agents.push(config);
         self
     }
 
     /// Build into a vector of agent configurations
-    pub fn build(
-        self,
-    ) -> Vec<(
-        String,
-        String,
-        String,
-        Vec<Arc<dyn Tool>>,
-        Option<serde_json::Value>,
-        bool,
-    )> {
+    ///
+    /// Duplicate names are logged as warnings; only one of them will be
+    /// reachable once the agents are keyed by name.
+    pub fn build(self) -> Vec<AgentConfig> {
+        let mut seen = std::collections::HashSet::new();
+        for (name, _, _, _, _, _, _) in &self.agents {
+            if !seen.insert(name.as_str()) {
+                tracing::warn!(
+                    "[AgentCollection] Duplicate agent name '{}'; only one will be available",
+                    name
+                );
+            }
+        }
         self.agents
     }
 
+    /// Check whether an agent with the given name is in the collection
+    pub fn contains(&self, name: &str) -> bool {
+        self.agents.iter().any(|(n, _, _, _, _, _, _)| n == name)
+    }
+
     /// Get the number of agents in the collection
     pub fn len(&self) -> usize {
         self.agents.len()
@@ -238,7 +305,7 @@ impl AgentCollection {
     pub fn list_agents(&self) -> Vec<(&str, &str)> {
         self.agents
             .iter()
-            .map(|(name, desc, _, _, _, _)| (name.as_str(), desc.as_str()))
+            .map(|(name, desc, _, _, _, _, _)| (name.as_str(), desc.as_str()))
             .collect()
     }
 }
@@ -329,20 +396,21 @@ mod tests {
         assert_eq!(builder.name(), "test_agent");
         assert_eq!(builder.tool_count(), 1);
 
-        let (name, desc, prompt, tools, schema, return_tool_output) = builder.build();
+        let (name, desc, prompt, tools, schema, return_tool_output, examples) = builder.build();
         assert_eq!(name, "test_agent");
         assert_eq!(desc, "Test agent");
         assert_eq!(prompt, "Test prompt");
         assert_eq!(tools.len(), 1);
         assert!(schema.is_none());
         assert_eq!(return_tool_output, false);
+        assert!(examples.is_empty());
     }
 
     #[test]
     fn test_agent_builder_defaults() {
         let builder = AgentBuilder::new("test_agent").tool(DummyTool);
 
-        let (name, desc, prompt, _tools, _schema, _return_tool_output) = builder.build();
+        let (name, desc, prompt, _tools, _schema, _return_tool_output, _examples) = builder.build();
         assert_eq!(name, "test_agent");
         assert!(desc.contains("test_agent"));
         assert!(prompt.contains("test_agent"));
@@ -378,4 +446,59 @@ mod tests {
         assert_eq!(list[0].0, "agent1");
         assert_eq!(list[1].0, "agent2");
     }
+
+    #[test]
+    fn test_try_build_rejects_empty_name() {
+        let result = AgentBuilder::new("  ")
+            .description("Nameless agent")
+            .tool(DummyTool)
+            .try_build();
+        assert_eq!(result.err(), Some(AgentBuildError::EmptyName));
+
+        let result = AgentBuilder::new("agent").tool(DummyTool).try_build();
+        assert_eq!(
+            result.err(),
+            Some(AgentBuildError::MissingDescription("agent".to_string()))
+        );
+
+        let result = AgentBuilder::new("agent")
+            .description("Has a description")
+            .system_prompt("")
+            .try_build();
+        assert_eq!(
+            result.err(),
+            Some(AgentBuildError::EmptySystemPrompt("agent".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_try_build_accepts_complete_config() {
+        let (name, desc, prompt, tools, _schema, _return_tool_output, _examples) =
+            AgentBuilder::new("data_agent")
+                .description("Manages inventory data")
+                .tool(DummyTool)
+                .try_build()
+                .unwrap();
+
+        assert_eq!(name, "data_agent");
+        assert_eq!(desc, "Manages inventory data");
+        assert!(prompt.contains("data_agent"));
+        assert_eq!(tools.len(), 1);
+    }
+
+    #[test]
+    fn test_agent_collection_rejects_duplicate_names() {
+        let mut collection = AgentCollection::new();
+        collection
+            .try_add(AgentBuilder::new("data_agent").tool(DummyTool))
+            .unwrap();
+        assert!(collection.contains("data_agent"));
+
+        let result = collection.try_add(AgentBuilder::new("data_agent").tool(DummyTool));
+        assert_eq!(
+            result.err(),
+            Some(AgentBuildError::DuplicateName("data_agent".to_string()))
+        );
+        assert_eq!(collection.build().len(), 1);
+    }
 }
@@ -5,14 +5,20 @@
 //! - Conversation history management internalized
 //! - Session lifecycle management hidden
 
+use crate::config::guardrails::{sanitize_observation, GuardrailError, GuardrailPolicy};
+use crate::config::prompts::{self, PromptVars};
 use crate::config::Settings;
-use crate::core::llm::{ChatMessage, LLMClient};
+use crate::core::llm::{self, ChatMessage, LLMClient, Role};
+use crate::storage::kv::{InMemoryKvStore, MemoryStore, ScopedMemoryStore};
 use crate::storage::ConversationStorage;
+use crate::tools::filesystem::FileUndo;
+use crate::tools::scratchpad::{RecallTool, RememberTool};
 use crate::tools::{executor::ToolExecutor, registry::ToolRegistry, ToolConfig};
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::sync::Arc;
+use tokio::sync::mpsc;
 
 /// Agent session with persistent conversation history
 pub struct AgentSession {
@@ -22,7 +28,13 @@ pub struct AgentSession {
     tool_registry: Arc<ToolRegistry>,
     tool_executor: ToolExecutor,
     storage: Arc<dyn ConversationStorage>,
+    memory_store: Arc<dyn MemoryStore>,
+    memory: Arc<dyn MemoryStore>,
     pub(crate) max_iterations: usize,
+    max_history: Option<usize>,
+    /// File changes made by tools, keyed by where their exchange starts in the history
+    file_changes: Vec<(usize, FileUndo)>,
+    tool_output: Option<mpsc::Sender<String>>,
 }
 
 /// Decision structure returned by LLM
@@ -50,11 +62,32 @@ pub struct SessionStep {
 
 impl AgentSession {
     /// Create a new agent session
+    ///
+    /// Session memory lives in the storage's own memory store, so it is
+    /// kept wherever the history is; storages without one keep it in
+    /// process only.
     pub async fn new(
         session_id: impl Into<String>,
         storage: Arc<dyn ConversationStorage>,
         settings: Settings,
         api_key: String,
+    ) -> Result<Self> {
+        let memory_store = storage
+            .memory_store()
+            .unwrap_or_else(|| Arc::new(InMemoryKvStore::new()));
+        Self::with_memory_store(session_id, storage, memory_store, settings, api_key).await
+    }
+
+    /// Create a session whose long-term memory lives in `memory_store`
+    ///
+    /// The store may be shared between sessions; each session only sees
+    /// the keys it wrote through the `remember` and `recall` tools.
+    pub async fn with_memory_store(
+        session_id: impl Into<String>,
+        storage: Arc<dyn ConversationStorage>,
+        memory_store: Arc<dyn MemoryStore>,
+        settings: Settings,
+        api_key: String,
     ) -> Result<Self> {
         let session_id = session_id.into();
 
@@ -64,21 +97,57 @@ impl AgentSession {
             .await
             .unwrap_or_else(|_| Vec::new());
 
+        let memory: Arc<dyn MemoryStore> = Arc::new(ScopedMemoryStore::new(
+            Arc::clone(&memory_store),
+            &session_id,
+        ));
+
         let llm_client = LLMClient::new(api_key, settings.clone());
-        let tool_registry = Arc::new(ToolRegistry::with_defaults());
-        let tool_executor = ToolExecutor::new(ToolConfig::default());
+        let mut tool_registry = ToolRegistry::with_defaults_for(&settings.tools);
+        tool_registry.register(Arc::new(RememberTool::new(Arc::clone(&memory))));
+        tool_registry.register(Arc::new(RecallTool::new(Arc::clone(&memory))));
+        let tool_executor = ToolExecutor::new(ToolConfig::for_settings(&settings));
 
         Ok(Self {
             session_id,
             conversation_history,
             llm_client,
-            tool_registry,
+            tool_registry: Arc::new(tool_registry),
             tool_executor,
             storage,
+            memory_store,
+            memory,
             max_iterations: settings.agent.max_iterations,
+            max_history: None,
+            file_changes: Vec::new(),
+            tool_output: None,
         })
     }
 
+    /// Send tools' partial output, such as shell output lines, to `output` as it is produced
+    ///
+    /// Only tools that support streaming send anything; a dropped receiver
+    /// does not interrupt them.
+    pub fn with_tool_output(mut self, output: mpsc::Sender<String>) -> Self {
+        self.tool_output = Some(output);
+        self
+    }
+
+    /// Reason through an already configured LLM client instead of the default one
+    pub fn with_llm_client(mut self, llm_client: LLMClient) -> Self {
+        self.llm_client = llm_client;
+        self
+    }
+
+    /// Send at most `max_messages` messages of history to the LLM
+    ///
+    /// The oldest messages are dropped first; system messages and messages
+    /// marked `pinned` are always kept.
+    pub fn with_max_history(mut self, max_messages: usize) -> Self {
+        self.max_history = Some(max_messages);
+        self
+    }
+
     /// Set maximum iterations (mutable version)
     pub fn set_max_iterations(&mut self, max_iterations: usize) {
         self.max_iterations = max_iterations;
@@ -89,50 +158,75 @@ impl AgentSession {
         self.max_iterations
     }
 
+    /// Give the session a system prompt, stored and persisted as its first message
+    ///
+    /// The prompt is placed ahead of the tool-use instructions. A resumed
+    /// session has its existing system message replaced.
+    pub async fn with_system_prompt(mut self, prompt: impl Into<String>) -> Result<Self> {
+        let system_message =
+            ChatMessage::system(format!("{}\n\n{}", prompt.into(), self.instructions()));
+
+        match self.conversation_history.first_mut() {
+            Some(first) if first.role == Role::System => *first = system_message,
+            _ => self.conversation_history.insert(0, system_message),
+        }
+
+        self.storage
+            .save(&self.session_id, &self.conversation_history)
+            .await?;
+        Ok(self)
+    }
+
+    /// Tool-use instructions rendered from the session prompt template
+    fn instructions(&self) -> String {
+        prompts::render(
+            &self.llm_client.settings().prompts.session_template,
+            &PromptVars {
+                tools: &self.tool_registry.tools_description(),
+                max_iterations: self.max_iterations,
+                ..Default::default()
+            },
+        )
+    }
+
     /// Send a message and get response (maintains conversation context)
     pub async fn send_message(&mut self, message: &str) -> Result<SessionResponse> {
+        self.run_message(message, None).await
+    }
+
+    /// Like `send_message`, but also sends each step to `progress` as it happens
+    ///
+    /// History is persisted once the loop finishes, as with `send_message`.
+    /// A dropped receiver does not interrupt the loop.
+    pub async fn send_message_streaming(
+        &mut self,
+        message: &str,
+        progress: mpsc::Sender<SessionStep>,
+    ) -> Result<SessionResponse> {
+        self.run_message(message, Some(&progress)).await
+    }
+
+    async fn run_message(
+        &mut self,
+        message: &str,
+        progress: Option<&mpsc::Sender<SessionStep>>,
+    ) -> Result<SessionResponse> {
         // If this is the first message, add system prompt
         if self.conversation_history.is_empty() {
-            let system_prompt = format!(
-                "You are an autonomous agent that can use tools OR respond directly to accomplish tasks.\n\n\
-                 Available Tools:\n{}\n\n\
-                 IMPORTANT: You MUST respond in this EXACT JSON format:\n\
-                 {{\n  \
-                   \"thought\": \"your reasoning about what to do next\",\n  \
-                   \"action\": {{\"tool\": \"tool_name\", \"input\": {{\"param\": \"value\"}}}},\n  \
-                   \"is_final\": false,\n  \
-                   \"final_answer\": null\n\
-                 }}\n\n\
-                 DECISION GUIDELINES:\n\
-                 1. For conversational messages (greetings, questions about context, general chat):\n\
-                    - Set \"is_final\": true immediately\n\
-                    - Set \"action\": null (no tool needed)\n\
-                    - Provide your answer in \"final_answer\"\n\
-                 2. For tasks requiring tools (file operations, shell commands, web requests):\n\
-                    - Choose appropriate tool\n\
-                    - Execute action\n\
-                    - After getting the observation, set \"is_final\": true with \"final_answer\"\n\n\
-                 EXAMPLES:\n\
-                 User: \"hi\" → {{\"thought\": \"greeting\", \"action\": null, \"is_final\": true, \"final_answer\": \"Hello! How can I help you?\"}}\n\
-                 User: \"list files\" → {{\"thought\": \"need shell tool\", \"action\": {{\"tool\": \"execute_shell\", \"input\": {{\"command\": \"ls\"}}}}, \"is_final\": false, \"final_answer\": null}}\n\n\
-                 Always respond with valid JSON only. No extra text.",
-                self.tool_registry.tools_description()
-            );
-
-            self.conversation_history.push(ChatMessage {
-                role: "system".to_string(),
-                content: system_prompt,
-            });
+            let system_prompt = self.instructions();
+            self.conversation_history
+                .push(ChatMessage::system(system_prompt));
+            if let Some(note) = prompts::current_time_note(&self.llm_client.settings().prompts) {
+                self.conversation_history.push(ChatMessage::system(note));
+            }
         }
 
         // Add user message
-        self.conversation_history.push(ChatMessage {
-            role: "user".to_string(),
-            content: message.to_string(),
-        });
+        self.conversation_history
+            .push(ChatMessage::user(message.to_string()));
 
         // Execute ReAct loop with existing conversation context
-        let response = self.execute_react_loop().await?;
+        let response = self.execute_react_loop(progress).await?;
 
         // Persist updated history
         self.storage
@@ -145,6 +239,7 @@ impl AgentSession {
     /// Clear conversation history
     pub async fn clear_history(&mut self) -> Result<()> {
         self.conversation_history.clear();
+        self.file_changes.clear();
         self.storage.delete(&self.session_id).await?;
         Ok(())
     }
@@ -154,15 +249,147 @@ impl AgentSession {
         &self.conversation_history
     }
 
+    /// Take back the most recent exchange
+    ///
+    /// Removes the last message the user sent together with every reply,
+    /// tool call and observation that followed it, persists the result and
+    /// returns the removed messages. Files written or edited by tools during
+    /// the exchange are restored first, from the undo records the file tools
+    /// attach to their results; changes made before the session was loaded
+    /// are not tracked. Returns an empty list when there is no exchange to
+    /// undo.
+    pub async fn undo_last_interaction(&mut self) -> Result<Vec<ChatMessage>> {
+        let Some(start) = self.last_exchange_start() else {
+            return Ok(Vec::new());
+        };
+
+        // Newest change first, so a file changed twice ends up as it began
+        let first = self.file_changes.partition_point(|(at, _)| *at < start);
+        for (_, undo) in self.file_changes[first..].iter().rev() {
+            undo.restore()
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to restore {}: {}", undo.path.display(), e))?;
+        }
+        self.file_changes.truncate(first);
+
+        let removed = self.conversation_history.split_off(start);
+        self.storage
+            .save(&self.session_id, &self.conversation_history)
+            .await?;
+        tracing::info!(
+            "[Session {}] Undid last interaction ({} messages)",
+            self.session_id,
+            removed.len()
+        );
+        Ok(removed)
+    }
+
+    /// Index of the message that started the latest exchange
+    ///
+    /// User turns right after a tool result are the loop's own follow-ups.
+    fn last_exchange_start(&self) -> Option<usize> {
+        let history = &self.conversation_history;
+        (0..history.len()).rev().find(|&i| {
+            history[i].role == Role::User && (i == 0 || history[i - 1].role != Role::Tool)
+        })
+    }
+
+    /// Remove the message at `index` and persist the shortened history
+    pub async fn remove_message(&mut self, index: usize) -> Result<ChatMessage> {
+        if index >= self.conversation_history.len() {
+            anyhow::bail!(
+                "Message index {} out of range for {} messages",
+                index,
+                self.conversation_history.len()
+            );
+        }
+        let removed = self.conversation_history.remove(index);
+        self.storage
+            .save(&self.session_id, &self.conversation_history)
+            .await?;
+        Ok(removed)
+    }
+
+    /// Mark the message at `index` as pinned, or unpin it, and persist the change
+    ///
+    /// Pinned messages survive history trimming.
+    pub async fn pin_message(&mut self, index: usize, pinned: bool) -> Result<()> {
+        let len = self.conversation_history.len();
+        let message = self.conversation_history.get_mut(index).ok_or_else(|| {
+            anyhow::anyhow!("Message index {} out of range for {} messages", index, len)
+        })?;
+        message.pinned = pinned;
+        self.storage
+            .save(&self.session_id, &self.conversation_history)
+            .await
+    }
+
+    /// Replace the content of the most recent message and persist it
+    pub async fn edit_last(&mut self, content: impl Into<String>) -> Result<()> {
+        let last = self
+            .conversation_history
+            .last_mut()
+            .ok_or_else(|| anyhow::anyhow!("Session '{}' has no messages", self.session_id))?;
+        last.content = content.into();
+        self.storage
+            .save(&self.session_id, &self.conversation_history)
+            .await
+    }
+
+    /// Long-term memory scoped to this session
+    pub fn memory(&self) -> &Arc<dyn MemoryStore> {
+        &self.memory
+    }
+
     /// Get session ID
     pub fn session_id(&self) -> &str {
         &self.session_id
     }
 
+    /// Branch this conversation into a new session sharing the same storage
+    ///
+    /// The new session starts with a copy of the persisted history; later
+    /// messages in either session do not affect the other.
+    pub async fn fork(
+        &self,
+        new_session_id: impl Into<String>,
+        settings: Settings,
+        api_key: String,
+    ) -> Result<AgentSession> {
+        let new_session_id = new_session_id.into();
+        self.storage.fork(&self.session_id, &new_session_id).await?;
+
+        let mut forked = AgentSession::with_memory_store(
+            new_session_id,
+            Arc::clone(&self.storage),
+            Arc::clone(&self.memory_store),
+            settings,
+            api_key,
+        )
+        .await?;
+        forked.max_iterations = self.max_iterations;
+
+        for key in self.memory.keys().await? {
+            if let Some(value) = self.memory.get(&key).await? {
+                forked.memory.put(&key, &value).await?;
+            }
+        }
+        Ok(forked)
+    }
+
     /// Execute ReAct loop with existing conversation history
-    async fn execute_react_loop(&mut self) -> Result<SessionResponse> {
+    async fn execute_react_loop(
+        &mut self,
+        progress: Option<&mpsc::Sender<SessionStep>>,
+    ) -> Result<SessionResponse> {
         let mut steps = Vec::new();
 
+        let guardrails = match GuardrailPolicy::from_config(&self.llm_client.settings().guardrails)
+        {
+            Ok(policy) => policy,
+            Err(e) => return Ok(self.guardrail_stop(e.into(), steps)),
+        };
+
         for iteration in 0..self.max_iterations {
             tracing::debug!(
                 "[Session {}] Iteration {}/{}",
@@ -172,7 +399,52 @@ impl AgentSession {
             );
 
             // Think: Ask LLM for next action
-            let decision = self.think().await?;
+            let mut unknown_tool_retries = 0;
+            let decision = loop {
+                let mut decision = self.think().await?;
+
+                // Record and echo the registered name, not the model's near miss
+                if let Some(action) = decision.action.as_mut() {
+                    if let Some(name) = self
+                        .tool_registry
+                        .resolve(&action.tool)
+                        .filter(|name| *name != action.tool)
+                    {
+                        tracing::warn!(
+                            "[Session {}] Resolved unknown tool '{}' to '{}'",
+                            self.session_id,
+                            action.tool,
+                            name
+                        );
+                        action.tool = name;
+                    }
+                }
+
+                let max_retries = self.llm_client.settings().agent.unknown_tool_retries;
+                match decision.action.as_ref() {
+                    Some(action)
+                        if !decision.is_final
+                            && unknown_tool_retries < max_retries
+                            && !self.tool_registry.has_tool(&action.tool) =>
+                    {
+                        unknown_tool_retries += 1;
+                        tracing::warn!(
+                            "[Session {}] Model picked unknown tool '{}', asking again ({}/{})",
+                            self.session_id,
+                            action.tool,
+                            unknown_tool_retries,
+                            max_retries
+                        );
+                        let reask = prompts::unknown_tool_reask(
+                            &decision,
+                            &action.tool,
+                            &self.tool_registry.tool_names(),
+                        );
+                        self.conversation_history.extend(reask);
+                    }
+                    _ => break decision,
+                }
+            };
 
             tracing::debug!(
                 "[Session {}] Thought: {}",
@@ -186,11 +458,24 @@ impl AgentSession {
                     .final_answer
                     .unwrap_or_else(|| "Task completed".to_string());
 
-                steps.push(SessionStep {
-                    thought: decision.thought,
-                    action: None,
-                    observation: Some(final_answer.clone()),
-                });
+                if let Err(violation) = guardrails.check("final answer", &final_answer) {
+                    return Ok(self.guardrail_stop(violation.into(), steps));
+                }
+
+                // Keep the answer so later turns and undo see the full exchange
+                self.conversation_history
+                    .push(ChatMessage::assistant(final_answer.clone()));
+
+                push_step(
+                    &mut steps,
+                    progress,
+                    SessionStep {
+                        thought: decision.thought,
+                        action: None,
+                        observation: Some(final_answer.clone()),
+                    },
+                )
+                .await;
 
                 return Ok(SessionResponse {
                     message: final_answer,
@@ -207,20 +492,39 @@ impl AgentSession {
                     action.tool
                 );
 
+                let input = serde_json::to_string(&action.input).unwrap_or_default();
+                let location = format!("input for tool '{}'", action.tool);
+                if let Err(violation) = guardrails.check(&location, &input) {
+                    push_step(
+                        &mut steps,
+                        progress,
+                        SessionStep {
+                            thought: decision.thought,
+                            action: Some(action.tool.clone()),
+                            observation: None,
+                        },
+                    )
+                    .await;
+                    return Ok(self.guardrail_stop(violation.into(), steps));
+                }
+
                 let tool = match self.tool_registry.get(&action.tool) {
                     Some(t) => t,
                     None => {
                         let error_msg = format!("Tool '{}' not found", action.tool);
-                        self.conversation_history.push(ChatMessage {
-                            role: "assistant".to_string(),
-                            content: format!("Error: {}", error_msg),
-                        });
+                        self.conversation_history
+                            .push(ChatMessage::assistant(format!("Error: {}", error_msg)));
 
-                        steps.push(SessionStep {
-                            thought: decision.thought,
-                            action: Some(action.tool.clone()),
-                            observation: Some(error_msg.clone()),
-                        });
+                        push_step(
+                            &mut steps,
+                            progress,
+                            SessionStep {
+                                thought: decision.thought,
+                                action: Some(action.tool.clone()),
+                                observation: Some(error_msg.clone()),
+                            },
+                        )
+                        .await;
 
                         return Ok(SessionResponse {
                             message: error_msg,
@@ -231,10 +535,22 @@ impl AgentSession {
                 };
 
                 // Observe: Get tool result
-                let tool_result = self
-                    .tool_executor
-                    .execute(tool, action.input.clone())
-                    .await?;
+                let tool_result = match &self.tool_output {
+                    Some(output) => {
+                        self.tool_executor
+                            .execute_streaming(tool, action.input.clone(), output.clone())
+                            .await?
+                    }
+                    None => {
+                        self.tool_executor
+                            .execute(tool, action.input.clone())
+                            .await?
+                    }
+                };
+                if let Some(undo) = FileUndo::from_result(&tool_result) {
+                    let start = self.last_exchange_start().unwrap_or_default();
+                    self.file_changes.push((start, undo));
+                }
 
                 let observation = if tool_result.success {
                     tool_result.output.clone()
@@ -244,34 +560,33 @@ impl AgentSession {
 
                 tracing::debug!("[Session {}] Observation: {}", self.session_id, observation);
 
-                // Add agent's action to conversation history
-                self.conversation_history.push(ChatMessage {
-                    role: "assistant".to_string(),
-                    content: serde_json::to_string(&AgentDecision {
-                        thought: decision.thought.clone(),
-                        action: Some(action.clone()),
-                        is_final: false,
-                        final_answer: None,
-                    })
-                    .unwrap_or_else(|_| format!("Action: {}", action.tool)),
-                });
-
-                // Add observation to conversation
-                self.conversation_history.push(ChatMessage {
-                    role: "user".to_string(),
-                    content: format!(
-                        "Observation: {}\n\nDoes this observation contain the answer? \
-                         If yes, set is_final=true and provide final_answer. \
-                         If no, what is the next action needed?",
-                        observation
-                    ),
-                });
+                // Record the call and its observation, then ask whether the task is done
+                let history_observation =
+                    if self.llm_client.settings().guardrails.sanitize_observations {
+                        sanitize_observation(&action.tool, &observation)
+                    } else {
+                        observation.clone()
+                    };
+                self.conversation_history.extend(prompts::tool_call_turns(
+                    &decision.thought,
+                    &action.tool,
+                    &action.input,
+                    history_observation,
+                    "Does this observation contain the answer? \
+                     If yes, set is_final=true and provide final_answer. \
+                     If no, what is the next action needed?",
+                ));
 
-                steps.push(SessionStep {
-                    thought: decision.thought,
-                    action: Some(action.tool.clone()),
-                    observation: Some(observation),
-                });
+                push_step(
+                    &mut steps,
+                    progress,
+                    SessionStep {
+                        thought: decision.thought,
+                        action: Some(action.tool.clone()),
+                        observation: Some(observation),
+                    },
+                )
+                .await;
             } else {
                 // No action but also not marked as final - this is likely a conversational response
                 // Treat the thought as the final answer
@@ -282,18 +597,24 @@ impl AgentSession {
                     );
 
                     let final_answer = decision.thought.clone();
+                    if let Err(violation) = guardrails.check("final answer", &final_answer) {
+                        return Ok(self.guardrail_stop(violation.into(), steps));
+                    }
 
                     // Add assistant's response to conversation history
-                    self.conversation_history.push(ChatMessage {
-                        role: "assistant".to_string(),
-                        content: final_answer.clone(),
-                    });
+                    self.conversation_history
+                        .push(ChatMessage::assistant(final_answer.clone()));
 
-                    steps.push(SessionStep {
-                        thought: decision.thought,
-                        action: None,
-                        observation: Some(final_answer.clone()),
-                    });
+                    push_step(
+                        &mut steps,
+                        progress,
+                        SessionStep {
+                            thought: decision.thought,
+                            action: None,
+                            observation: Some(final_answer.clone()),
+                        },
+                    )
+                    .await;
 
                     return Ok(SessionResponse {
                         message: final_answer,
@@ -303,11 +624,16 @@ impl AgentSession {
                 }
 
                 let error_msg = "No action specified and no response provided".to_string();
-                steps.push(SessionStep {
-                    thought: decision.thought,
-                    action: None,
-                    observation: Some(error_msg.clone()),
-                });
+                push_step(
+                    &mut steps,
+                    progress,
+                    SessionStep {
+                        thought: decision.thought,
+                        action: None,
+                        observation: Some(error_msg.clone()),
+                    },
+                )
+                .await;
 
                 return Ok(SessionResponse {
                     message: error_msg,
@@ -325,12 +651,36 @@ impl AgentSession {
         })
     }
 
+    /// End the turn because the guardrail policy was broken or invalid
+    ///
+    /// The offending text is not added to the conversation history.
+    fn guardrail_stop(&self, error: GuardrailError, steps: Vec<SessionStep>) -> SessionResponse {
+        tracing::warn!("[Session {}] {}", self.session_id, error);
+        SessionResponse {
+            message: error.to_string(),
+            steps,
+            completed: false,
+        }
+    }
+
     /// Think step - Ask LLM to reason about next action
+    ///
+    /// With `max_history` set, only a trimmed copy is sent; the session keeps
+    /// and persists its full history.
     async fn think(&self) -> Result<AgentDecision> {
-        let response = self
-            .llm_client
-            .chat(self.conversation_history.clone())
-            .await?;
+        let mut messages = self.conversation_history.clone();
+        if let Some(max_history) = self.max_history {
+            let dropped = llm::trim_history(&mut messages, max_history);
+            if !dropped.is_empty() {
+                tracing::debug!(
+                    "[Session {}] Left {} old message(s) out of the request",
+                    self.session_id,
+                    dropped.len()
+                );
+            }
+        }
+
+        let response = self.llm_client.chat(messages).await?;
 
         // Try to parse JSON response
         match serde_json::from_str::<AgentDecision>(&response) {
@@ -369,6 +719,18 @@ impl AgentSession {
     }
 }
 
+/// Record a step and forward it to the progress channel, if any
+async fn push_step(
+    steps: &mut Vec<SessionStep>,
+    progress: Option<&mpsc::Sender<SessionStep>>,
+    step: SessionStep,
+) {
+    if let Some(tx) = progress {
+        let _ = tx.send(step.clone()).await;
+    }
+    steps.push(step);
+}
+
 /// Response from a session message
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SessionResponse {
@@ -376,3 +738,408 @@ pub struct SessionResponse {
     pub steps: Vec<SessionStep>,
     pub completed: bool,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::mock_llm::MockLLM;
+    use crate::storage::memory::InMemoryStorage;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_system_prompt_is_first_message_after_reload() {
+        let storage: Arc<dyn ConversationStorage> = Arc::new(InMemoryStorage::new());
+        let open = || {
+            AgentSession::new(
+                "pirate",
+                Arc::clone(&storage),
+                Settings::new().unwrap(),
+                "test-key".to_string(),
+            )
+        };
+
+        open()
+            .await
+            .unwrap()
+            .with_system_prompt("Always answer like a pirate")
+            .await
+            .unwrap();
+
+        let reloaded = open().await.unwrap();
+        let first = &reloaded.history()[0];
+        assert_eq!(reloaded.history().len(), 1);
+        assert_eq!(first.role, Role::System);
+        assert!(first.content.starts_with("Always answer like a pirate\n\n"));
+        assert!(first.content.contains("Available Tools:"));
+    }
+
+    #[tokio::test]
+    async fn test_edit_and_remove_messages_persist() {
+        let storage: Arc<dyn ConversationStorage> = Arc::new(InMemoryStorage::new());
+        storage
+            .save(
+                "chat",
+                &[
+                    ChatMessage::system("instructions"),
+                    ChatMessage::user("What is 2 + 2?"),
+                    ChatMessage::assistant("5"),
+                ],
+            )
+            .await
+            .unwrap();
+        let open = || {
+            AgentSession::new(
+                "chat",
+                Arc::clone(&storage),
+                Settings::new().unwrap(),
+                "test-key".to_string(),
+            )
+        };
+
+        let mut session = open().await.unwrap();
+        assert_eq!(session.history()[1].content, "What is 2 + 2?");
+
+        session.edit_last("4").await.unwrap();
+        let removed = session.remove_message(0).await.unwrap();
+        assert_eq!(removed.content, "instructions");
+        assert!(session.remove_message(5).await.is_err());
+
+        let reloaded = open().await.unwrap();
+        let contents: Vec<&str> = reloaded
+            .history()
+            .iter()
+            .map(|m| m.content.as_str())
+            .collect();
+        assert_eq!(contents, vec!["What is 2 + 2?", "4"]);
+    }
+
+    #[tokio::test]
+    async fn test_streamed_steps_match_response() {
+        let llm = Arc::new(MockLLM::new([
+            json!({
+                "thought": "check what I know",
+                "action": {"tool": "recall", "input": {}},
+                "is_final": false,
+                "final_answer": null
+            })
+            .to_string(),
+            json!({
+                "thought": "nothing stored",
+                "action": null,
+                "is_final": true,
+                "final_answer": "I don't know anything about you yet"
+            })
+            .to_string(),
+        ]));
+        let shared: Arc<dyn MemoryStore> = Arc::new(InMemoryKvStore::new());
+        let mut session = session("stream", &shared)
+            .await
+            .with_llm_client(LLMClient::with_backend(llm, Settings::new().unwrap()));
+
+        let (tx, mut rx) = mpsc::channel(8);
+        let response = session
+            .send_message_streaming("What do you know about me?", tx)
+            .await
+            .unwrap();
+
+        let mut streamed = Vec::new();
+        while let Some(step) = rx.recv().await {
+            streamed.push(step);
+        }
+
+        assert!(response.completed);
+        assert_eq!(streamed.len(), 2);
+        assert_eq!(
+            serde_json::to_value(&streamed).unwrap(),
+            serde_json::to_value(&response.steps).unwrap()
+        );
+        assert_eq!(streamed[0].action.as_deref(), Some("recall"));
+    }
+
+    #[tokio::test]
+    async fn test_undo_removes_only_the_last_exchange() {
+        let final_answer = |answer: &str| {
+            json!({"thought": "answer", "action": null, "is_final": true, "final_answer": answer})
+                .to_string()
+        };
+        let llm = Arc::new(MockLLM::new([
+            final_answer("Hello Ada"),
+            json!({
+                "thought": "look it up",
+                "action": {"tool": "recall", "input": {}},
+                "is_final": false,
+                "final_answer": null
+            })
+            .to_string(),
+            final_answer("You like blue"),
+        ]));
+        let shared: Arc<dyn MemoryStore> = Arc::new(InMemoryKvStore::new());
+        let mut session = session("undo", &shared)
+            .await
+            .with_llm_client(LLMClient::with_backend(llm, Settings::new().unwrap()));
+
+        session.send_message("Hi, I'm Ada").await.unwrap();
+        session
+            .send_message("What is my favorite color?")
+            .await
+            .unwrap();
+
+        let removed = session.undo_last_interaction().await.unwrap();
+        let roles: Vec<Role> = removed.iter().map(|m| m.role).collect();
+        assert_eq!(
+            roles,
+            [
+                Role::User,
+                Role::Assistant,
+                Role::Tool,
+                Role::User,
+                Role::Assistant
+            ]
+        );
+        assert_eq!(removed[0].content, "What is my favorite color?");
+        assert_eq!(
+            removed[1].tool_calls.as_ref().unwrap()[0].function.name,
+            "recall"
+        );
+        assert_eq!(
+            removed[2].tool_call_id,
+            Some(removed[1].tool_calls.as_ref().unwrap()[0].id.clone())
+        );
+
+        let history: Vec<(Role, &str)> = session.history()[1..]
+            .iter()
+            .map(|m| (m.role, m.content.as_str()))
+            .collect();
+        assert_eq!(
+            history,
+            vec![(Role::User, "Hi, I'm Ada"), (Role::Assistant, "Hello Ada")]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_max_history_trims_only_the_request() {
+        let final_answer = |answer: &str| {
+            json!({"thought": "answer", "action": null, "is_final": true, "final_answer": answer})
+                .to_string()
+        };
+        let llm = Arc::new(MockLLM::new([final_answer("one"), final_answer("two")]));
+        let storage: Arc<dyn ConversationStorage> = Arc::new(InMemoryStorage::new());
+        let mut session = AgentSession::new(
+            "trimmed",
+            Arc::clone(&storage),
+            Settings::new().unwrap(),
+            "test-key".to_string(),
+        )
+        .await
+        .unwrap()
+        .with_llm_client(LLMClient::with_backend(
+            llm.clone(),
+            Settings::new().unwrap(),
+        ))
+        .with_max_history(3);
+
+        session.send_message("first").await.unwrap();
+        session.send_message("second").await.unwrap();
+
+        // System prompt plus the latest two messages reach the model
+        let sent = &llm.requests()[1].messages;
+        assert_eq!(sent.len(), 3);
+        assert_eq!(sent[0].role, Role::System);
+        assert_eq!(sent[2].content, "second");
+
+        // Nothing is lost from the session or its storage
+        assert_eq!(session.history().len(), 5);
+        assert_eq!(storage.load("trimmed").await.unwrap().len(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_banned_tool_input_stops_the_turn() {
+        let mut settings = Settings::new().unwrap();
+        settings.guardrails.banned_patterns = vec![r"rm\s+-rf".to_string()];
+        let llm = Arc::new(MockLLM::new([json!({
+            "thought": "clean up",
+            "action": {"tool": "execute_shell", "input": {"command": "rm -rf /"}},
+            "is_final": false,
+            "final_answer": null
+        })
+        .to_string()]));
+        let shared: Arc<dyn MemoryStore> = Arc::new(InMemoryKvStore::new());
+        let mut session = session("guarded", &shared)
+            .await
+            .with_llm_client(LLMClient::with_backend(llm, settings));
+
+        let response = session.send_message("Free some disk space").await.unwrap();
+
+        assert!(!response.completed);
+        assert!(response
+            .message
+            .starts_with("Guardrail violation: input for tool 'execute_shell'"));
+        assert_eq!(response.steps.len(), 1);
+        assert_eq!(response.steps[0].observation, None);
+    }
+
+    #[tokio::test]
+    async fn test_undo_restores_files_written_during_the_exchange() {
+        // The default sandbox keeps writes inside the working directory
+        let dir = tempfile::tempdir_in(".").unwrap();
+        let notes = dir.path().join("notes.txt");
+        std::fs::write(&notes, "original").unwrap();
+        let write = |content: &str| {
+            json!({
+                "thought": "save it",
+                "action": {
+                    "tool": "write_file",
+                    "input": {"path": notes.to_str().unwrap(), "content": content}
+                },
+                "is_final": false,
+                "final_answer": null
+            })
+            .to_string()
+        };
+        let done =
+            json!({"thought": "done", "action": null, "is_final": true, "final_answer": "Saved"})
+                .to_string();
+        let llm = Arc::new(MockLLM::new([write("first"), write("second"), done]));
+        let shared: Arc<dyn MemoryStore> = Arc::new(InMemoryKvStore::new());
+        let mut session = session("undo-files", &shared)
+            .await
+            .with_llm_client(LLMClient::with_backend(llm, Settings::new().unwrap()));
+
+        session.send_message("Update my notes").await.unwrap();
+        assert_eq!(std::fs::read_to_string(&notes).unwrap(), "second");
+
+        session.undo_last_interaction().await.unwrap();
+        assert_eq!(std::fs::read_to_string(&notes).unwrap(), "original");
+    }
+
+    async fn session(id: &str, memory_store: &Arc<dyn MemoryStore>) -> AgentSession {
+        AgentSession::with_memory_store(
+            id,
+            Arc::new(InMemoryStorage::new()),
+            Arc::clone(memory_store),
+            Settings::new().unwrap(),
+            "test-key".to_string(),
+        )
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_session_memory_is_isolated_per_session() {
+        let shared: Arc<dyn MemoryStore> = Arc::new(InMemoryKvStore::new());
+        let alice = session("alice", &shared).await;
+        let bob = session("bob", &shared).await;
+
+        alice.memory().put("favorite_color", "blue").await.unwrap();
+
+        assert_eq!(
+            alice
+                .memory()
+                .get("favorite_color")
+                .await
+                .unwrap()
+                .as_deref(),
+            Some("blue")
+        );
+        assert_eq!(bob.memory().get("favorite_color").await.unwrap(), None);
+        assert!(alice.tool_registry.has_tool("remember"));
+        assert!(alice.tool_registry.has_tool("recall"));
+
+        // A new session with the same id picks the memory back up
+        let alice_again = session("alice", &shared).await;
+        assert_eq!(
+            alice_again.memory().keys().await.unwrap(),
+            vec!["favorite_color"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_session_memory_persists_with_file_storage() {
+        use crate::storage::filesystem::FileSystemStorage;
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let open = || async {
+            let storage = FileSystemStorage::new(temp_dir.path().to_path_buf())
+                .await
+                .unwrap();
+            AgentSession::new(
+                "alice",
+                Arc::new(storage),
+                Settings::new().unwrap(),
+                "test-key".to_string(),
+            )
+            .await
+            .unwrap()
+        };
+
+        open()
+            .await
+            .memory()
+            .put("favorite_color", "blue")
+            .await
+            .unwrap();
+
+        let reopened = open().await;
+        assert_eq!(
+            reopened
+                .memory()
+                .get("favorite_color")
+                .await
+                .unwrap()
+                .as_deref(),
+            Some("blue")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_custom_default_tool_reaches_new_sessions() {
+        use crate::tools::registry::{builtin_tools, replace_default_tools, set_default_tools};
+        use crate::tools::{Tool, ToolMetadata, ToolResult};
+        use async_trait::async_trait;
+
+        struct OrgDirectoryTool;
+
+        #[async_trait]
+        impl Tool for OrgDirectoryTool {
+            fn metadata(&self) -> ToolMetadata {
+                ToolMetadata {
+                    name: "org_directory".to_string(),
+                    description: "Look up a colleague".to_string(),
+                    parameters: vec![],
+                }
+            }
+
+            async fn execute(&self, _args: Value) -> anyhow::Result<ToolResult> {
+                Ok(ToolResult::success("Ada, platform team"))
+            }
+        }
+
+        /// Puts the process-wide default tools back when the test ends
+        struct RestoreDefaultTools(Option<Vec<Arc<dyn Tool>>>);
+
+        impl Drop for RestoreDefaultTools {
+            fn drop(&mut self) {
+                replace_default_tools(self.0.take());
+            }
+        }
+
+        // Keep the built-ins so concurrently running tests still find them
+        let mut tools = builtin_tools();
+        tools.push(Arc::new(OrgDirectoryTool));
+        let _restore = RestoreDefaultTools(replace_default_tools(None));
+        set_default_tools(tools);
+
+        let session = AgentSession::new(
+            "directory",
+            Arc::new(InMemoryStorage::new()),
+            Settings::new().unwrap(),
+            "test-key".to_string(),
+        )
+        .await
+        .unwrap();
+
+        assert!(session.tool_registry.has_tool("org_directory"));
+        assert!(session.tool_registry.has_tool("execute_shell"));
+        assert!(session.instructions().contains("Tool: org_directory"));
+    }
+}
@@ -96,6 +96,16 @@ impl HandoffCoordinator {
                 });
                 return ValidationResult::failure(errors);
             }
+            AgentResponse::NeedsInput { prompt, .. } => {
+                errors.push(ValidationError {
+                    field: "response".to_string(),
+                    error_type: "AgentNeedsInput".to_string(),
+                    message: format!("Agent is waiting for input: {}", prompt),
+                    expected: Some("Success".to_string()),
+                    actual: Some("NeedsInput".to_string()),
+                });
+                return ValidationResult::failure(errors);
+            }
         };
 
         // Validate metadata if present
@@ -1,12 +1,17 @@
 use crate::actors::messages::*;
 use crate::config::Settings;
 use std::collections::HashMap;
+use std::time::SystemTime;
 use tokio::sync::mpsc::{Receiver, Sender};
+use tokio::sync::watch;
 use tokio::time::{timeout, Duration, Instant};
 
+/// Track actor heartbeats, request resets for silent actors and publish
+/// every change in health to `health_tx`
 pub async fn health_monitor_actor(
     mut receiver: Receiver<RoutingMessage>,
     router_sender: Sender<RoutingMessage>,
+    health_tx: watch::Sender<StateSnapshot>,
     settings: Settings,
 ) {
     let mut heartbeats: HashMap<ActorType, Instant> = HashMap::new();
@@ -21,6 +26,7 @@ pub async fn health_monitor_actor(
                 RoutingMessage::Heartbeat(actor_type) => {
                     heartbeats.insert(actor_type, Instant::now());
                     tracing::debug!("Heartbeat received from {:?}", actor_type);
+                    health_tx.send_replace(create_snapshot(&heartbeats, check_interval));
                 }
                 // ✅ Handle GetState requests
                 RoutingMessage::GetState(response_tx) => {
@@ -39,6 +45,8 @@ pub async fn health_monitor_actor(
             }
             Err(_) => {
                 check_actor_health(&heartbeats, check_interval, &router_sender).await;
+                // Actors may have gone inactive without any new message
+                health_tx.send_replace(create_snapshot(&heartbeats, check_interval));
             }
         }
     }
@@ -50,6 +58,7 @@ fn create_snapshot(
     check_interval: Duration,
 ) -> StateSnapshot {
     let now = Instant::now();
+    let wall_now = SystemTime::now();
     let cutoff = now - check_interval;
 
     let mut active_actors = HashMap::new();
@@ -58,7 +67,8 @@ fn create_snapshot(
     for (actor_type, heartbeat_time) in heartbeats.iter() {
         let is_active = *heartbeat_time >= cutoff;
         active_actors.insert(*actor_type, is_active);
-        last_heartbeat.insert(*actor_type, *heartbeat_time);
+        // Monotonic instants can't leave the process; report wall-clock time instead
+        last_heartbeat.insert(*actor_type, wall_now - now.duration_since(*heartbeat_time));
     }
 
     StateSnapshot {
@@ -90,3 +100,53 @@ async fn check_actor_health(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::mpsc::channel;
+
+    #[tokio::test]
+    async fn test_heartbeat_is_published_to_subscribers() {
+        let (sender, receiver) = channel(8);
+        let (router_tx, _router_rx) = channel(8);
+        let (health_tx, mut health_rx) = watch::channel(StateSnapshot::default());
+        tokio::spawn(health_monitor_actor(
+            receiver,
+            router_tx,
+            health_tx,
+            Settings::new().unwrap(),
+        ));
+
+        sender
+            .send(RoutingMessage::Heartbeat(ActorType::LLM))
+            .await
+            .unwrap();
+
+        timeout(Duration::from_secs(1), health_rx.changed())
+            .await
+            .expect("no health update published")
+            .unwrap();
+        assert_eq!(
+            health_rx.borrow().active_actors.get(&ActorType::LLM),
+            Some(&true)
+        );
+
+        sender.send(RoutingMessage::Shutdown).await.unwrap();
+    }
+
+    #[test]
+    fn test_snapshot_round_trips_through_serde() {
+        let heartbeats = HashMap::from([
+            (ActorType::LLM, Instant::now()),
+            (ActorType::Router, Instant::now()),
+        ]);
+        let snapshot = create_snapshot(&heartbeats, Duration::from_secs(5));
+
+        let json = serde_json::to_string(&snapshot).unwrap();
+        let restored: StateSnapshot = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(restored, snapshot);
+        assert_eq!(restored.active_actors.get(&ActorType::LLM), Some(&true));
+    }
+}
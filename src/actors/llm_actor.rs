@@ -1,10 +1,11 @@
 use crate::actors::messages::*;
 use crate::config::Settings;
-use crate::core::llm::LLMClient;
+use crate::core::llm::{LLMClient, ProviderError};
 use std::sync::OnceLock;
 use tokio::sync::mpsc::{channel, Receiver, Sender};
 use tokio::sync::oneshot;
 use tokio::time::{timeout, Duration};
+use tracing::Instrument;
 
 static ROUTER_SENDER: OnceLock<Sender<RoutingMessage>> = OnceLock::new();
 
@@ -53,24 +54,31 @@ async fn llm_actor(mut receiver: Receiver<LLMMessage>, settings: Settings, api_k
 async fn handle_llm_message(message: LLMMessage, client: &LLMClient) {
     match message {
         LLMMessage::Chat(chat_request) => {
-            let messages: Vec<_> = chat_request
-                .messages
-                .iter()
-                .map(|m| crate::core::llm::ChatMessage {
-                    role: m.role.clone(),
-                    content: m.content.clone(),
-                })
-                .collect();
-
-            if chat_request.stream {
-                handle_stream_chat(messages, client, chat_request.response).await;
-            } else {
-                handle_regular_chat(messages, client, chat_request.response).await;
-            }
+            let span = chat_request.request_id.span();
+            handle_chat(chat_request, client).instrument(span).await;
         }
     }
 }
 
+async fn handle_chat(chat_request: ChatRequest, client: &LLMClient) {
+    let messages: Vec<_> = chat_request
+        .messages
+        .iter()
+        .map(crate::core::llm::ChatMessage::from)
+        .collect();
+    tracing::debug!(
+        "LLM actor handling chat request ({} messages, stream: {})",
+        messages.len(),
+        chat_request.stream
+    );
+
+    if chat_request.stream {
+        handle_stream_chat(messages, client, chat_request.response).await;
+    } else {
+        handle_regular_chat(messages, client, chat_request.response).await;
+    }
+}
+
 async fn handle_regular_chat(
     messages: Vec<crate::core::llm::ChatMessage>,
     client: &LLMClient,
@@ -82,17 +90,26 @@ async fn handle_regular_chat(
         }
         Err(e) => {
             tracing::error!("LLM chat error: {}", e);
-            let _ = response_channel.send(ChatResponse::Error(e.to_string()));
+            let response = match e.downcast::<ProviderError>() {
+                Ok(provider_error) => ChatResponse::ProviderError(provider_error),
+                Err(e) => ChatResponse::Error(e.to_string()),
+            };
+            let _ = response_channel.send(response);
         }
     }
 }
 
+/// Stream reply tokens through a channel of `system.channel_buffer_size`
+///
+/// The channel is bounded: once the consumer falls that many tokens behind,
+/// sending waits, which pauses reading from the provider. Tokens are never
+/// dropped and memory stays bounded however slow the consumer is.
 async fn handle_stream_chat(
     messages: Vec<crate::core::llm::ChatMessage>,
     client: &LLMClient,
     response_channel: oneshot::Sender<ChatResponse>,
 ) {
-    let (tx, rx) = channel(100);
+    let (tx, rx) = channel(client.settings().system.channel_buffer_size);
 
     // Send receiver back immediately
     let _ = response_channel.send(ChatResponse::StreamTokens(rx));
@@ -112,3 +129,72 @@ fn send_heartbeat() {
 pub fn set_router_sender(sender: Sender<RoutingMessage>) {
     let _ = ROUTER_SENDER.set(sender);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::llm::{ChatMessage, Completion, CompletionRequest, LLMBackend};
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    const TOKENS: usize = 30;
+
+    /// Streams numbered tokens, counting how many the channel has accepted
+    struct CountingStream {
+        sent: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl LLMBackend for CountingStream {
+        async fn complete(&self, _request: &CompletionRequest) -> anyhow::Result<Completion> {
+            anyhow::bail!("streaming only")
+        }
+
+        async fn stream(
+            &self,
+            _request: &CompletionRequest,
+            tx: Sender<String>,
+        ) -> anyhow::Result<()> {
+            for i in 0..TOKENS {
+                tx.send(format!("t{} ", i)).await?;
+                self.sent.fetch_add(1, Ordering::SeqCst);
+            }
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_slow_consumer_applies_backpressure_without_losing_tokens() {
+        let mut settings = Settings::new().unwrap();
+        settings.system.channel_buffer_size = 2;
+        let sent = Arc::new(AtomicUsize::new(0));
+        let client = LLMClient::with_backend(
+            Arc::new(CountingStream {
+                sent: Arc::clone(&sent),
+            }),
+            settings,
+        );
+
+        let (response_tx, response_rx) = oneshot::channel();
+        let producer = tokio::spawn(async move {
+            handle_stream_chat(vec![ChatMessage::user("count")], &client, response_tx).await;
+        });
+
+        let Ok(ChatResponse::StreamTokens(mut tokens)) = response_rx.await else {
+            panic!("expected a token stream");
+        };
+        let mut received = Vec::new();
+        while let Some(token) = tokens.recv().await {
+            received.push(token);
+            // The producer can only run as far ahead as the channel holds,
+            // plus the one chunk the client's fallback relay may hold
+            assert!(sent.load(Ordering::SeqCst) <= received.len() + 3);
+            tokio::time::sleep(Duration::from_millis(2)).await;
+        }
+        producer.await.unwrap();
+
+        let expected: Vec<String> = (0..TOKENS).map(|i| format!("t{} ", i)).collect();
+        assert_eq!(received, expected);
+    }
+}
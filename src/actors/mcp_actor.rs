@@ -4,6 +4,7 @@ use crate::core::mcp::MCPClient;
 use std::sync::OnceLock;
 use tokio::sync::mpsc::{channel, Receiver, Sender};
 use tokio::time::{timeout, Duration};
+use tracing::Instrument;
 
 static ROUTER_SENDER: OnceLock<Sender<RoutingMessage>> = OnceLock::new();
 
@@ -49,8 +50,17 @@ async fn mcp_actor(mut receiver: Receiver<MCPMessage>, settings: Settings) {
 }
 
 async fn handle_mcp_message(message: MCPMessage) {
+    let span = match &message {
+        MCPMessage::ListTools(request) => request.request_id.span(),
+        MCPMessage::CallTool(request) => request.request_id.span(),
+    };
+    handle_mcp_request(message).instrument(span).await;
+}
+
+async fn handle_mcp_request(message: MCPMessage) {
     match message {
         MCPMessage::ListTools(request) => {
+            tracing::debug!("MCP actor listing tools of '{}'", request.server_command);
             let args_refs: Vec<&str> = request.server_args.iter().map(|s| s.as_str()).collect();
 
             match MCPClient::new(&request.server_command, args_refs).await {
@@ -72,6 +82,11 @@ async fn handle_mcp_message(message: MCPMessage) {
             }
         }
         MCPMessage::CallTool(request) => {
+            tracing::debug!(
+                "MCP actor calling tool '{}' on '{}'",
+                request.tool_name,
+                request.server_command
+            );
             let args_refs: Vec<&str> = request.server_args.iter().map(|s| s.as_str()).collect();
 
             match MCPClient::new(&request.server_command, args_refs).await {
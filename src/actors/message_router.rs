@@ -4,34 +4,159 @@ use crate::actors::llm_actor::LLMActorHandle;
 use crate::actors::mcp_actor::MCPActorHandle;
 use crate::actors::messages::*;
 use crate::config::Settings;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use tokio::sync::mpsc::{channel, Receiver, Sender};
+use tokio::sync::{oneshot, watch};
 use tokio::time::{sleep, Duration};
 
+/// Outcome of a graceful shutdown
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ShutdownSummary {
+    /// Requests that completed during the drain phase
+    pub drained: usize,
+    /// Requests still outstanding when the drain timeout expired
+    pub aborted: usize,
+}
+
 pub struct MessageRouterHandle {
     sender: Sender<RoutingMessage>,
+    accepting: Arc<AtomicBool>,
+    in_flight: Arc<watch::Sender<usize>>,
+    drain_timeout: Duration,
+    health: watch::Receiver<StateSnapshot>,
 }
 
 impl MessageRouterHandle {
     pub fn new(settings: Settings, api_key: String) -> Self {
         let buffer_size = settings.system.channel_buffer_size;
+        let drain_timeout = Duration::from_millis(settings.system.shutdown_timeout_ms);
         let (sender, receiver) = channel(buffer_size);
-        tokio::spawn(router_actor(receiver, settings, api_key));
-        Self { sender }
+        let (health_tx, health_rx) = watch::channel(StateSnapshot::default());
+        tokio::spawn(router_actor(receiver, health_tx, settings, api_key));
+
+        let mut handle = Self::with_sender(sender, drain_timeout);
+        handle.health = health_rx;
+        handle
+    }
+
+    pub(crate) fn with_sender(sender: Sender<RoutingMessage>, drain_timeout: Duration) -> Self {
+        Self {
+            sender,
+            accepting: Arc::new(AtomicBool::new(true)),
+            in_flight: Arc::new(watch::channel(0).0),
+            drain_timeout,
+            health: watch::channel(StateSnapshot::default()).1,
+        }
+    }
+
+    /// Actor health, republished by the health monitor on every heartbeat
+    pub fn subscribe_health(&self) -> watch::Receiver<StateSnapshot> {
+        self.health.clone()
     }
 
     pub async fn send_message(&self, message: RoutingMessage) -> anyhow::Result<()> {
+        let message = match message {
+            RoutingMessage::Shutdown | RoutingMessage::GetState(_) => message,
+            work => {
+                if !self.accepting.load(Ordering::SeqCst) {
+                    anyhow::bail!("Router is shutting down and no longer accepts requests");
+                }
+                self.track(work)
+            }
+        };
+
         self.sender
             .send(message)
             .await
             .map_err(|e| anyhow::anyhow!("Failed to send message to Router: {}", e))
     }
 
-    pub async fn shutdown(&self) -> anyhow::Result<()> {
-        self.send_message(RoutingMessage::Shutdown).await
+    /// Stop accepting work, drain outstanding requests, then stop the actors
+    ///
+    /// Waits up to `system.shutdown_timeout_ms` for every in-flight request
+    /// to be answered. Requests still running after that are abandoned when
+    /// the actors stop.
+    pub async fn shutdown(&self) -> anyhow::Result<ShutdownSummary> {
+        self.accepting.store(false, Ordering::SeqCst);
+
+        let outstanding = *self.in_flight.borrow();
+        if outstanding > 0 {
+            tracing::info!(
+                "[Router] Draining {} in-flight request(s) before shutdown",
+                outstanding
+            );
+        }
+
+        let mut in_flight = self.in_flight.subscribe();
+        let _ = tokio::time::timeout(self.drain_timeout, in_flight.wait_for(|n| *n == 0)).await;
+
+        let aborted = (*self.in_flight.borrow()).min(outstanding);
+        let summary = ShutdownSummary {
+            drained: outstanding - aborted,
+            aborted,
+        };
+        if summary.aborted > 0 {
+            tracing::warn!(
+                "[Router] Drain timed out; aborting {} request(s)",
+                summary.aborted
+            );
+        }
+
+        self.sender
+            .send(RoutingMessage::Shutdown)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to send message to Router: {}", e))?;
+        Ok(summary)
+    }
+
+    /// Swap the request's reply channel for one that counts it as in flight
+    fn track(&self, message: RoutingMessage) -> RoutingMessage {
+        match message {
+            RoutingMessage::LLM(LLMMessage::Chat(mut request)) => {
+                request.response = self.track_reply(request.response);
+                RoutingMessage::LLM(LLMMessage::Chat(request))
+            }
+            RoutingMessage::MCP(MCPMessage::ListTools(mut request)) => {
+                request.response = self.track_reply(request.response);
+                RoutingMessage::MCP(MCPMessage::ListTools(request))
+            }
+            RoutingMessage::MCP(MCPMessage::CallTool(mut request)) => {
+                request.response = self.track_reply(request.response);
+                RoutingMessage::MCP(MCPMessage::CallTool(request))
+            }
+            RoutingMessage::Agent(AgentMessage::RunTask(mut task)) => {
+                task.response = self.track_reply(task.response);
+                RoutingMessage::Agent(AgentMessage::RunTask(task))
+            }
+            other => other,
+        }
+    }
+
+    /// Forward replies to the caller, keeping the request counted until the
+    /// actor answers or drops the channel
+    fn track_reply<T: Send + 'static>(&self, caller: oneshot::Sender<T>) -> oneshot::Sender<T> {
+        let (tx, rx) = oneshot::channel();
+        self.in_flight.send_modify(|n| *n += 1);
+
+        let in_flight = Arc::clone(&self.in_flight);
+        tokio::spawn(async move {
+            if let Ok(reply) = rx.await {
+                let _ = caller.send(reply);
+            }
+            in_flight.send_modify(|n| *n -= 1);
+        });
+
+        tx
     }
 }
 
-async fn router_actor(mut receiver: Receiver<RoutingMessage>, settings: Settings, api_key: String) {
+async fn router_actor(
+    mut receiver: Receiver<RoutingMessage>,
+    health_tx: watch::Sender<StateSnapshot>,
+    settings: Settings,
+    api_key: String,
+) {
     tracing::info!("Router actor started");
 
     let mut llm_handle = LLMActorHandle::new(settings.clone(), api_key.clone());
@@ -48,6 +173,7 @@ async fn router_actor(mut receiver: Receiver<RoutingMessage>, settings: Settings
     tokio::spawn(health_monitor_actor(
         supervisor_receiver,
         router_tx,
+        health_tx,
         settings.clone(),
     ));
 
@@ -63,20 +189,27 @@ async fn router_actor(mut receiver: Receiver<RoutingMessage>, settings: Settings
         tokio::select! {
             // Handle incoming messages from external API
             Some(message) = receiver.recv() => {
+                let span = message
+                    .request_id()
+                    .map(RequestId::span)
+                    .unwrap_or_else(tracing::Span::none);
                 match message {
                     RoutingMessage::LLM(llm_message) => {
+                        tracing::debug!(parent: &span, "Routing request to LLM actor");
                         if let Err(e) = llm_handle.send_message(llm_message).await {
-                            tracing::error!("Failed to send to LLM actor: {}", e);
+                            tracing::error!(parent: &span, "Failed to send to LLM actor: {}", e);
                         }
                     }
                     RoutingMessage::MCP(mcp_message) => {
+                        tracing::debug!(parent: &span, "Routing request to MCP actor");
                         if let Err(e) = mcp_handle.send_message(mcp_message).await {
-                            tracing::error!("Failed to send to MCP actor: {}", e);
+                            tracing::error!(parent: &span, "Failed to send to MCP actor: {}", e);
                         }
                     }
                     RoutingMessage::Agent(agent_message) => {
+                        tracing::debug!(parent: &span, "Routing request to Agent actor");
                         if let Err(e) = agent_handle.send_message(agent_message).await {
-                            tracing::error!("Failed to send to Agent actor: {}", e);
+                            tracing::error!(parent: &span, "Failed to send to Agent actor: {}", e);
                         }
                     }
                     // Handle GetState from external API
@@ -155,3 +288,187 @@ async fn router_actor(mut receiver: Receiver<RoutingMessage>, settings: Settings
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    fn chat(response: oneshot::Sender<ChatResponse>) -> RoutingMessage {
+        RoutingMessage::LLM(LLMMessage::Chat(ChatRequest {
+            request_id: RequestId::new(),
+            messages: vec![],
+            stream: false,
+            response,
+        }))
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_waits_for_slow_request() {
+        let (sender, mut receiver) = channel(8);
+        let handle = MessageRouterHandle::with_sender(sender, Duration::from_secs(5));
+
+        // Stand-in actor that answers after a delay
+        let actor = tokio::spawn(async move {
+            let mut saw_shutdown = false;
+            while let Some(message) = receiver.recv().await {
+                match message {
+                    RoutingMessage::LLM(LLMMessage::Chat(request)) => {
+                        tokio::spawn(async move {
+                            sleep(Duration::from_millis(200)).await;
+                            let _ = request.response.send(ChatResponse::Complete("done".into()));
+                        });
+                    }
+                    RoutingMessage::Shutdown => {
+                        saw_shutdown = true;
+                        break;
+                    }
+                    _ => {}
+                }
+            }
+            saw_shutdown
+        });
+
+        let (tx, rx) = oneshot::channel();
+        handle.send_message(chat(tx)).await.unwrap();
+
+        let start = Instant::now();
+        let summary = handle.shutdown().await.unwrap();
+
+        assert!(start.elapsed() >= Duration::from_millis(150));
+        assert_eq!(
+            summary,
+            ShutdownSummary {
+                drained: 1,
+                aborted: 0
+            }
+        );
+        assert!(matches!(rx.await, Ok(ChatResponse::Complete(ref s)) if s == "done"));
+        assert!(actor.await.unwrap());
+
+        let (tx, _rx) = oneshot::channel();
+        assert!(handle.send_message(chat(tx)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_aborts_requests_past_timeout() {
+        let (sender, mut receiver) = channel(8);
+        let handle = MessageRouterHandle::with_sender(sender, Duration::from_millis(50));
+
+        let (tx, _rx) = oneshot::channel();
+        handle.send_message(chat(tx)).await.unwrap();
+        // Hold the request without answering it
+        let _pending = receiver.recv().await;
+
+        let summary = handle.shutdown().await.unwrap();
+        assert_eq!(
+            summary,
+            ShutdownSummary {
+                drained: 0,
+                aborted: 1
+            }
+        );
+    }
+
+    /// Records the target of every event logged inside a `request` span,
+    /// paired with that span's request id
+    #[derive(Clone, Default)]
+    struct RequestIdCapture(Arc<std::sync::Mutex<Vec<(String, String)>>>);
+
+    struct CapturedId(String);
+
+    #[derive(Default)]
+    struct RequestIdVisitor(Option<String>);
+
+    impl tracing::field::Visit for RequestIdVisitor {
+        fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+            if field.name() == "request_id" {
+                self.0 = Some(format!("{:?}", value));
+            }
+        }
+    }
+
+    impl<S> tracing_subscriber::Layer<S> for RequestIdCapture
+    where
+        S: tracing::Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a>,
+    {
+        fn on_new_span(
+            &self,
+            attrs: &tracing::span::Attributes<'_>,
+            id: &tracing::span::Id,
+            ctx: tracing_subscriber::layer::Context<'_, S>,
+        ) {
+            let mut visitor = RequestIdVisitor::default();
+            attrs.record(&mut visitor);
+            if let (Some(request_id), Some(span)) = (visitor.0, ctx.span(id)) {
+                span.extensions_mut().insert(CapturedId(request_id));
+            }
+        }
+
+        fn on_event(
+            &self,
+            event: &tracing::Event<'_>,
+            ctx: tracing_subscriber::layer::Context<'_, S>,
+        ) {
+            let request_id = ctx.event_scope(event).and_then(|scope| {
+                scope
+                    .from_root()
+                    .find_map(|span| span.extensions().get::<CapturedId>().map(|id| id.0.clone()))
+            });
+            if let Some(request_id) = request_id {
+                self.0
+                    .lock()
+                    .unwrap()
+                    .push((event.metadata().target().to_string(), request_id));
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_request_id_correlates_router_and_llm_actor_logs() {
+        use tracing_subscriber::layer::SubscriberExt;
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/chat/completions"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "choices": [{"message": {"role": "assistant", "content": "traced"}}]
+            })))
+            .mount(&server)
+            .await;
+
+        let capture = RequestIdCapture::default();
+        let _guard =
+            tracing::subscriber::set_default(tracing_subscriber::registry().with(capture.clone()));
+
+        let mut settings = Settings::new().unwrap();
+        settings.llm.base_url = server.uri();
+        let router = MessageRouterHandle::new(settings, "test-key".to_string());
+
+        let request_id = RequestId::new();
+        let (tx, rx) = oneshot::channel();
+        router
+            .send_message(RoutingMessage::LLM(LLMMessage::Chat(ChatRequest {
+                request_id: request_id.clone(),
+                messages: vec![],
+                stream: false,
+                response: tx,
+            })))
+            .await
+            .unwrap();
+        assert!(matches!(rx.await, Ok(ChatResponse::Complete(ref s)) if s == "traced"));
+        router.shutdown().await.unwrap();
+
+        let events = capture.0.lock().unwrap().clone();
+        let logged_by = |module: &str| {
+            events
+                .iter()
+                .any(|(target, id)| target.ends_with(module) && id == request_id.as_str())
+        };
+        assert!(logged_by("message_router"), "{:?}", events);
+        assert!(logged_by("llm_actor"), "{:?}", events);
+        assert!(events.iter().all(|(_, id)| id == request_id.as_str()));
+    }
+}
@@ -1,10 +1,17 @@
+use crate::actors::specialized_agent::PendingInput;
+use crate::core::llm::{ChatMessage, ContentPart, ProviderError, Role};
+use crate::tools::context::ExecutionContext;
+use crate::tools::executor::PlannedToolCall;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
 use tokio::sync::{mpsc, oneshot};
-use tokio::time::Instant;
+use tokio::time::{Duration, Instant};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ActorType {
     LLM,
     MCP,
@@ -13,8 +20,50 @@ pub enum ActorType {
     Supervisor,
 }
 
+/// Correlates every log line produced while serving one request
+///
+/// Generated at the API boundary and carried through the router into the
+/// actor that does the work, where it is attached to a `request` tracing span.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct RequestId(String);
+
+impl RequestId {
+    /// A fresh id, unique within the process and unlikely to repeat across runs
+    pub fn new() -> Self {
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+        let started = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or_default();
+        let sequence = NEXT.fetch_add(1, Ordering::Relaxed);
+        Self(format!("req-{:x}-{:x}", started, sequence))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Span that tags everything logged inside it with this id
+    pub fn span(&self) -> tracing::Span {
+        tracing::info_span!("request", request_id = %self.0)
+    }
+}
+
+impl Default for RequestId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Display for RequestId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
 #[derive(Debug)]
 pub struct ChatRequest {
+    pub request_id: RequestId,
     pub messages: Vec<ChatMessageData>,
     pub stream: bool,
     pub response: oneshot::Sender<ChatResponse>,
@@ -22,8 +71,44 @@ pub struct ChatRequest {
 
 #[derive(Debug, Clone)]
 pub struct ChatMessageData {
-    pub role: String,
+    pub role: Role,
     pub content: String,
+    /// Content blocks such as images that follow the text
+    pub parts: Vec<ContentPart>,
+}
+
+impl ChatMessageData {
+    pub fn new(role: Role, content: impl Into<String>) -> Self {
+        Self {
+            role,
+            content: content.into(),
+            parts: Vec::new(),
+        }
+    }
+
+    pub fn system(content: impl Into<String>) -> Self {
+        Self::new(Role::System, content)
+    }
+
+    pub fn user(content: impl Into<String>) -> Self {
+        Self::new(Role::User, content)
+    }
+
+    pub fn assistant(content: impl Into<String>) -> Self {
+        Self::new(Role::Assistant, content)
+    }
+
+    /// Append content blocks, e.g. images, after the text
+    pub fn with_parts(mut self, parts: Vec<ContentPart>) -> Self {
+        self.parts.extend(parts);
+        self
+    }
+}
+
+impl From<&ChatMessageData> for ChatMessage {
+    fn from(message: &ChatMessageData) -> Self {
+        ChatMessage::new(message.role, message.content.clone()).with_parts(message.parts.clone())
+    }
 }
 
 #[derive(Debug)]
@@ -31,10 +116,13 @@ pub enum ChatResponse {
     Complete(String),
     StreamTokens(mpsc::Receiver<String>),
     Error(String),
+    /// Failure reported by the provider itself, with its HTTP status
+    ProviderError(ProviderError),
 }
 
 #[derive(Debug)]
 pub struct MCPToolCall {
+    pub request_id: RequestId,
     pub server_command: String,
     pub server_args: Vec<String>,
     pub tool_name: String,
@@ -44,6 +132,7 @@ pub struct MCPToolCall {
 
 #[derive(Debug)]
 pub struct MCPListTools {
+    pub request_id: RequestId,
     pub server_command: String,
     pub server_args: Vec<String>,
     pub response: oneshot::Sender<MCPResponse>,
@@ -70,12 +159,17 @@ pub enum MCPMessage {
 // Agent-related messages
 #[derive(Debug)]
 pub struct AgentTask {
+    pub request_id: RequestId,
+    /// Caller's context, made current while the agent runs its tools
+    pub context: ExecutionContext,
     pub task_description: String,
     pub max_iterations: Option<usize>,
+    /// Receives each step as the agent takes it, ahead of the response
+    pub steps: Option<mpsc::Sender<AgentStep>>,
     pub response: oneshot::Sender<AgentResponse>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AgentStep {
     pub iteration: usize,
     pub thought: String,
@@ -154,6 +248,15 @@ impl ValidationResult {
         self.warnings = warnings;
         self
     }
+
+    /// Errors as one line, e.g. "score: Required field 'score' is missing"
+    pub fn error_summary(&self) -> String {
+        self.errors
+            .iter()
+            .map(|e| format!("{}: {}", e.field, e.message))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
 }
 
 /// Metadata about agent execution and output
@@ -162,11 +265,34 @@ pub struct OutputMetadata {
     pub confidence: f32,
     pub execution_time_ms: u64,
     pub tokens_used: Option<u32>,
+    /// Intermediate results by name, e.g. each completed sub-goal's result by
+    /// id when a supervisor runs with `agent.structured_results`
     pub partial_results: HashMap<String, String>,
     pub schema_version: Option<String>,
     pub validation_result: Option<ValidationResult>,
     pub agent_name: Option<String>,
     pub tool_calls: Vec<ToolCallMetadata>,
+    /// LLM provider that served the last call; the model name unless a
+    /// fallback chain gives the provider its own name
+    #[serde(default)]
+    pub provider: Option<String>,
+    /// Why the router picked this agent, for routed tasks
+    #[serde(default)]
+    pub routing: Option<RoutingDecision>,
+    /// Sub-goals a supervisor dropped from its plan because it declared more
+    /// than `agent.max_sub_goals`
+    #[serde(default)]
+    pub dropped_sub_goals: Vec<String>,
+    /// Tool calls recorded instead of made because of a dry run
+    #[serde(default)]
+    pub planned_tool_calls: Vec<PlannedToolCall>,
+}
+
+/// Agent chosen by the router and the reasoning behind the choice
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoutingDecision {
+    pub agent_name: String,
+    pub reasoning: String,
 }
 
 /// Metadata about tool calls made during execution
@@ -190,10 +316,36 @@ impl Default for OutputMetadata {
             validation_result: None,
             agent_name: None,
             tool_calls: Vec::new(),
+            provider: None,
+            routing: None,
+            dropped_sub_goals: Vec::new(),
+            planned_tool_calls: Vec::new(),
         }
     }
 }
 
+/// Wall-clock limit for one request, passed from the supervisor to the agents it invokes
+///
+/// Checked before each agent invocation and ReAct iteration starts; work
+/// already underway is allowed to finish.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Deadline(Option<Instant>);
+
+impl Deadline {
+    /// No time limit
+    pub fn none() -> Self {
+        Self(None)
+    }
+
+    pub fn after(duration: Duration) -> Self {
+        Self(Some(Instant::now() + duration))
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.0.is_some_and(|at| Instant::now() >= at)
+    }
+}
+
 /// Completion status with additional context
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum CompletionStatus {
@@ -234,6 +386,55 @@ pub enum AgentResponse {
         metadata: Option<OutputMetadata>,
         completion_status: Option<CompletionStatus>,
     },
+    /// Paused until the caller answers `prompt`; continue with `SpecializedAgent::resume`
+    NeedsInput {
+        prompt: String,
+        steps: Vec<AgentStep>,
+        metadata: Option<OutputMetadata>,
+        completion_status: Option<CompletionStatus>,
+        pending: Box<PendingInput>,
+    },
+}
+
+impl AgentResponse {
+    /// Steps taken while producing this response, regardless of outcome
+    pub fn steps(&self) -> &[AgentStep] {
+        match self {
+            AgentResponse::Success { steps, .. }
+            | AgentResponse::Failure { steps, .. }
+            | AgentResponse::Timeout { steps, .. }
+            | AgentResponse::NeedsInput { steps, .. } => steps,
+        }
+    }
+
+    /// Execution metadata attached to this response, if any
+    pub fn metadata(&self) -> Option<&OutputMetadata> {
+        match self {
+            AgentResponse::Success { metadata, .. }
+            | AgentResponse::Failure { metadata, .. }
+            | AgentResponse::Timeout { metadata, .. }
+            | AgentResponse::NeedsInput { metadata, .. } => metadata.as_ref(),
+        }
+    }
+
+    pub fn metadata_mut(&mut self) -> &mut Option<OutputMetadata> {
+        match self {
+            AgentResponse::Success { metadata, .. }
+            | AgentResponse::Failure { metadata, .. }
+            | AgentResponse::Timeout { metadata, .. }
+            | AgentResponse::NeedsInput { metadata, .. } => metadata,
+        }
+    }
+
+    /// Attach the tool calls a dry run recorded, adding metadata if there is none
+    pub(crate) fn with_planned_tool_calls(mut self, calls: Vec<PlannedToolCall>) -> Self {
+        if !calls.is_empty() {
+            self.metadata_mut()
+                .get_or_insert_with(OutputMetadata::default)
+                .planned_tool_calls = calls;
+        }
+        self
+    }
 }
 
 #[derive(Debug)]
@@ -253,8 +454,22 @@ pub enum RoutingMessage {
     Shutdown,
 }
 
-#[derive(Debug, Clone)]
+impl RoutingMessage {
+    /// Id of the request carried by this message, if it carries one
+    pub fn request_id(&self) -> Option<&RequestId> {
+        match self {
+            RoutingMessage::LLM(LLMMessage::Chat(request)) => Some(&request.request_id),
+            RoutingMessage::MCP(MCPMessage::ListTools(request)) => Some(&request.request_id),
+            RoutingMessage::MCP(MCPMessage::CallTool(request)) => Some(&request.request_id),
+            RoutingMessage::Agent(AgentMessage::RunTask(task)) => Some(&task.request_id),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct StateSnapshot {
     pub active_actors: HashMap<ActorType, bool>,
-    pub last_heartbeat: HashMap<ActorType, Instant>,
+    /// Wall-clock time of each actor's latest heartbeat, comparable across processes
+    pub last_heartbeat: HashMap<ActorType, SystemTime>,
 }
@@ -13,5 +13,5 @@ pub mod specialized_agents_factory;
 pub mod supervisor_agent;
 pub mod validation;
 
-pub use agent_builder::{AgentBuilder, AgentCollection};
-pub use message_router::MessageRouterHandle;
+pub use agent_builder::{AgentBuildError, AgentBuilder, AgentCollection};
+pub use message_router::{MessageRouterHandle, ShutdownSummary};
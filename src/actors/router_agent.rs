@@ -8,19 +8,42 @@
 //!
 //! Information Hiding:
 //! - Hides intent classification logic
-//! - Hides agent selection strategy
+//! - Hides agent selection strategy (rules first, then the LLM)
 //! - Exposes simple routing interface
 
-use crate::actors::messages::{AgentResponse, CompletionStatus};
+use crate::actors::messages::{AgentResponse, CompletionStatus, OutputMetadata, RoutingDecision};
 use crate::actors::specialized_agent::SpecializedAgent;
 use crate::core::llm::{ChatMessage, LLMClient};
-use serde::{Deserialize, Serialize};
+use futures::future::join_all;
+use regex::{Regex, RegexBuilder};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
 use std::collections::HashMap;
 
-/// Routing decision returned by LLM
-#[derive(Debug, Deserialize, Serialize)]
-struct RoutingDecision {
-    agent_name: String,
+/// Deterministic route checked before the LLM is asked
+///
+/// Useful for known request shapes that must always reach the same agent,
+/// e.g. anything starting with `run ` goes to `shell_agent`.
+#[derive(Debug, Clone)]
+pub struct RouterRule {
+    pattern: Regex,
+    agent: String,
+}
+
+impl RouterRule {
+    /// Route tasks matching the case-insensitive regex `pattern` to `agent`
+    pub fn new(pattern: &str, agent: impl Into<String>) -> Result<Self, regex::Error> {
+        Ok(Self {
+            pattern: RegexBuilder::new(pattern).case_insensitive(true).build()?,
+            agent: agent.into(),
+        })
+    }
+}
+
+/// Classifier reply when several agents may be chosen
+#[derive(Debug, Deserialize)]
+struct MultiRoutingDecision {
+    agent_names: Vec<String>,
     reasoning: String,
 }
 
@@ -28,6 +51,7 @@ struct RoutingDecision {
 pub struct RouterAgent {
     agents: HashMap<String, SpecializedAgent>,
     llm_client: LLMClient,
+    rules: Vec<RouterRule>,
 }
 
 impl RouterAgent {
@@ -40,15 +64,39 @@ impl RouterAgent {
         Self {
             agents: agent_map,
             llm_client,
+            rules: Vec::new(),
         }
     }
 
+    /// Check these rules, in order, before classifying with the LLM
+    ///
+    /// The first matching rule decides the agent and no LLM call is made.
+    pub fn with_rules(mut self, rules: Vec<RouterRule>) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    /// Decision from the first rule matching the task, if any
+    fn match_rule(&self, task: &str) -> Option<RoutingDecision> {
+        self.rules
+            .iter()
+            .find(|rule| rule.pattern.is_match(task))
+            .map(|rule| RoutingDecision {
+                agent_name: rule.agent.clone(),
+                reasoning: format!("Matched routing rule '{}'", rule.pattern.as_str()),
+            })
+    }
+
     /// Route a task to the appropriate specialized agent
     pub async fn route_task(&self, task: &str, max_iterations: usize) -> AgentResponse {
         tracing::info!("[RouterAgent] Routing task: {}", task);
 
-        // Step 1: Classify intent using LLM
-        let routing_decision = match self.classify_intent(task).await {
+        // Step 1: Apply routing rules, falling back to LLM classification
+        let classified = match self.match_rule(task) {
+            Some(decision) => Ok(decision),
+            None => self.classify_intent(task).await,
+        };
+        let routing_decision = match classified {
             Ok(decision) => decision,
             Err(e) => {
                 tracing::error!("[RouterAgent] Failed to classify intent: {}", e);
@@ -70,7 +118,191 @@ impl RouterAgent {
             routing_decision.reasoning
         );
 
-        // Step 2: Route to selected agent
+        // Step 2: Route to selected agent, recording why it was chosen
+        let mut response = self.dispatch(task, &routing_decision, max_iterations).await;
+        response
+            .metadata_mut()
+            .get_or_insert_with(Default::default)
+            .routing = Some(routing_decision);
+        response
+    }
+
+    /// Route a task to every agent it needs and merge their results
+    ///
+    /// For requests spanning domains, e.g. "download this file and summarize
+    /// it". The chosen agents run concurrently on the full task; the result
+    /// has one section per agent. A routing rule match selects its agent alone.
+    pub async fn route_task_multi(&self, task: &str, max_iterations: usize) -> AgentResponse {
+        tracing::info!("[RouterAgent] Routing task to multiple agents: {}", task);
+
+        let classified = match self.match_rule(task) {
+            Some(decision) => Ok(MultiRoutingDecision {
+                agent_names: vec![decision.agent_name],
+                reasoning: decision.reasoning,
+            }),
+            None => self.classify_intent_multi(task).await,
+        };
+        let decision = match classified {
+            Ok(decision) => decision,
+            Err(e) => {
+                tracing::error!("[RouterAgent] Failed to classify intent: {}", e);
+                return AgentResponse::Failure {
+                    error: format!("Failed to classify intent: {}", e),
+                    steps: vec![],
+                    metadata: None,
+                    completion_status: Some(CompletionStatus::Failed {
+                        error: format!("Intent classification failed: {}", e),
+                        recoverable: true,
+                    }),
+                };
+            }
+        };
+
+        let mut selected: Vec<&SpecializedAgent> = Vec::new();
+        for name in &decision.agent_names {
+            if selected.iter().any(|agent| agent.name() == name) {
+                continue;
+            }
+            match self.agents.get(name) {
+                Some(agent) => selected.push(agent),
+                None => tracing::warn!("[RouterAgent] Agent '{}' not found, skipping", name),
+            }
+        }
+        if selected.is_empty() {
+            if let Some(general_agent) = self.agents.get("general_agent") {
+                tracing::info!("[RouterAgent] Falling back to general_agent");
+                selected.push(general_agent);
+            }
+        }
+
+        let agent_names: Vec<&str> = selected.iter().map(|agent| agent.name()).collect();
+        tracing::info!(
+            "[RouterAgent] Routing to {:?} - Reason: {}",
+            agent_names,
+            decision.reasoning
+        );
+        let routing = RoutingDecision {
+            agent_name: agent_names.join(", "),
+            reasoning: decision.reasoning,
+        };
+
+        let responses = join_all(
+            selected
+                .iter()
+                .map(|agent| agent.execute_task(task, max_iterations)),
+        )
+        .await;
+
+        let mut response = Self::merge_responses(&agent_names, responses);
+        response
+            .metadata_mut()
+            .get_or_insert_with(Default::default)
+            .routing = Some(routing);
+        response
+    }
+
+    /// Combine per-agent responses into one, succeeding if any agent succeeded
+    fn merge_responses(agent_names: &[&str], responses: Vec<AgentResponse>) -> AgentResponse {
+        let mut sections = Vec::new();
+        let mut failures = Vec::new();
+        let mut steps = Vec::new();
+        let mut metadata = OutputMetadata {
+            agent_name: Some(agent_names.join(", ")),
+            ..Default::default()
+        };
+        let mut tokens_used = 0;
+
+        for (name, response) in agent_names.iter().zip(responses) {
+            if let Some(agent_metadata) = response.metadata() {
+                tokens_used += agent_metadata.tokens_used.unwrap_or(0);
+                metadata.execution_time_ms = metadata
+                    .execution_time_ms
+                    .max(agent_metadata.execution_time_ms);
+                metadata
+                    .tool_calls
+                    .extend(agent_metadata.tool_calls.iter().cloned());
+            }
+            match response {
+                AgentResponse::Success {
+                    result,
+                    steps: agent_steps,
+                    ..
+                } => {
+                    sections.push(format!("[{}]\n{}", name, result));
+                    steps.extend(agent_steps);
+                }
+                AgentResponse::Failure {
+                    error,
+                    steps: agent_steps,
+                    ..
+                } => {
+                    failures.push(format!("{}: {}", name, error));
+                    steps.extend(agent_steps);
+                }
+                AgentResponse::Timeout {
+                    partial_result,
+                    steps: agent_steps,
+                    ..
+                } => {
+                    sections.push(format!("[{}] (incomplete)\n{}", name, partial_result));
+                    failures.push(format!("{}: did not finish", name));
+                    steps.extend(agent_steps);
+                }
+                AgentResponse::NeedsInput {
+                    prompt,
+                    steps: agent_steps,
+                    ..
+                } => {
+                    failures.push(format!("{}: waiting for input: {}", name, prompt));
+                    steps.extend(agent_steps);
+                }
+            }
+        }
+        metadata.tokens_used = Some(tokens_used);
+
+        if sections.is_empty() {
+            let error = if failures.is_empty() {
+                "No suitable agent found for routing".to_string()
+            } else {
+                failures.join("; ")
+            };
+            return AgentResponse::Failure {
+                error: error.clone(),
+                steps,
+                metadata: Some(metadata),
+                completion_status: Some(CompletionStatus::Failed {
+                    error,
+                    recoverable: true,
+                }),
+            };
+        }
+
+        let completion_status = if failures.is_empty() {
+            CompletionStatus::Complete { confidence: 1.0 }
+        } else {
+            let progress = (agent_names.len() - failures.len()) as f32 / agent_names.len() as f32;
+            metadata.confidence = progress;
+            CompletionStatus::Partial {
+                progress,
+                next_steps: failures,
+            }
+        };
+
+        AgentResponse::Success {
+            result: sections.join("\n\n"),
+            steps,
+            metadata: Some(metadata),
+            completion_status: Some(completion_status),
+        }
+    }
+
+    /// Run the task on the chosen agent, falling back to `general_agent` if it is unknown
+    async fn dispatch(
+        &self,
+        task: &str,
+        routing_decision: &RoutingDecision,
+        max_iterations: usize,
+    ) -> AgentResponse {
         match self.agents.get(&routing_decision.agent_name) {
             Some(agent) => agent.execute_task(task, max_iterations).await,
             None => {
@@ -103,13 +335,6 @@ impl RouterAgent {
 
     /// Classify user intent using LLM to determine which agent should handle the task
     async fn classify_intent(&self, task: &str) -> anyhow::Result<RoutingDecision> {
-        // Build agent descriptions for the router prompt
-        let agent_descriptions: Vec<String> = self
-            .agents
-            .values()
-            .map(|agent| format!("- {}: {}", agent.name(), agent.description()))
-            .collect();
-
         let router_system_prompt = format!(
             "You are a router that classifies user requests and determines which specialized agent should handle them.\n\n\
              Available Agents:\n{}\n\n\
@@ -125,47 +350,206 @@ impl RouterAgent {
              - If the task involves web requests or fetching online data, choose 'web_agent'\n\
              - If the task requires multiple tool types or is unclear, choose 'general_agent'\n\n\
              Respond with valid JSON only. No extra text.",
-            agent_descriptions.join("\n")
+            self.agent_catalog()
         );
 
         let messages = vec![
-            ChatMessage {
-                role: "system".to_string(),
-                content: router_system_prompt,
-            },
-            ChatMessage {
-                role: "user".to_string(),
-                content: format!("Task: {}", task),
-            },
+            ChatMessage::system(router_system_prompt),
+            ChatMessage::user(format!("Task: {}", task)),
         ];
 
         let response = self.llm_client.chat(messages).await?;
 
-        // Try to parse JSON response
-        match serde_json::from_str::<RoutingDecision>(&response) {
-            Ok(decision) => Ok(decision),
-            Err(e) => {
-                // LLM might return text instead of JSON, try to extract JSON
-                tracing::warn!("[RouterAgent] Failed to parse decision as JSON: {}", e);
-
-                // Try to find JSON in the response
-                if let Some(start) = response.find('{') {
-                    if let Some(end) = response.rfind('}') {
-                        let json_str = &response[start..=end];
-                        match serde_json::from_str::<RoutingDecision>(json_str) {
-                            Ok(decision) => return Ok(decision),
-                            Err(_) => {}
-                        }
-                    }
-                }
+        // If all parsing fails, default to general_agent
+        Ok(extract_json(&response).unwrap_or_else(|| RoutingDecision {
+            agent_name: "general_agent".to_string(),
+            reasoning: "Failed to parse router response, using general agent as fallback"
+                .to_string(),
+        }))
+    }
 
-                // If all parsing fails, default to general_agent
-                Ok(RoutingDecision {
-                    agent_name: "general_agent".to_string(),
-                    reasoning: "Failed to parse router response, using general agent as fallback"
-                        .to_string(),
-                })
+    /// Classify user intent into every agent needed for the task
+    async fn classify_intent_multi(&self, task: &str) -> anyhow::Result<MultiRoutingDecision> {
+        let router_system_prompt = format!(
+            "You are a router that decides which specialized agents should handle a user request.\n\n\
+             Available Agents:\n{}\n\n\
+             A request may span several domains (e.g. download a file and summarize it). \
+             Choose every agent needed, but no more; most requests need only one.\n\n\
+             IMPORTANT: You MUST respond in this EXACT JSON format:\n\
+             {{\n  \
+               \"agent_names\": [\"first_agent\", \"second_agent\"],\n  \
+               \"reasoning\": \"why these agents are needed\"\n\
+             }}\n\n\
+             Respond with valid JSON only. No extra text.",
+            self.agent_catalog()
+        );
+
+        let messages = vec![
+            ChatMessage::system(router_system_prompt),
+            ChatMessage::user(format!("Task: {}", task)),
+        ];
+
+        let response = self.llm_client.chat(messages).await?;
+
+        Ok(
+            extract_json(&response).unwrap_or_else(|| MultiRoutingDecision {
+                agent_names: vec!["general_agent".to_string()],
+                reasoning: "Failed to parse router response, using general agent as fallback"
+                    .to_string(),
+            }),
+        )
+    }
+
+    /// One line per agent, sorted so the prompt is identical across runs
+    fn agent_catalog(&self) -> String {
+        let mut agent_descriptions: Vec<String> = self
+            .agents
+            .values()
+            .map(|agent| format!("- {}: {}", agent.name(), agent.description()))
+            .collect();
+        agent_descriptions.sort();
+        agent_descriptions.join("\n")
+    }
+}
+
+/// Parse a JSON reply, also accepting JSON wrapped in surrounding text
+fn extract_json<T: DeserializeOwned>(response: &str) -> Option<T> {
+    match serde_json::from_str(response) {
+        Ok(decision) => Some(decision),
+        Err(e) => {
+            // LLM might return text instead of JSON, try to extract JSON
+            tracing::warn!("[RouterAgent] Failed to parse decision as JSON: {}", e);
+            let start = response.find('{')?;
+            let end = response.rfind('}')?;
+            serde_json::from_str(response.get(start..=end)?).ok()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actors::specialized_agents_factory::default_agent_configs;
+    use crate::config::Settings;
+    use crate::core::mock_llm::MockLLM;
+    use crate::core::replay::ReplayBackend;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_replay_routes_file_task_to_file_ops_agent() {
+        let settings = Settings::new().unwrap();
+        let transcript = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/router_file_task.json"
+        );
+        let replay = Arc::new(ReplayBackend::from_file(transcript).unwrap());
+
+        // Whichever agent is chosen finishes immediately
+        let agent_llm = Arc::new(MockLLM::new([
+            r#"{"thought": "done", "action": null, "is_final": true, "final_answer": "read"}"#,
+        ]));
+        let agents = default_agent_configs()
+            .into_iter()
+            .map(|config| {
+                SpecializedAgent::with_llm_client(
+                    config,
+                    LLMClient::with_backend(agent_llm.clone(), settings.clone()),
+                )
+            })
+            .collect();
+
+        let router = RouterAgent::new(
+            agents,
+            LLMClient::with_backend(replay.clone(), settings.clone()),
+        );
+        let response = router
+            .route_task("Read the contents of Cargo.toml", 3)
+            .await;
+
+        assert!(replay.is_exhausted());
+        assert_eq!(
+            response.metadata().and_then(|m| m.agent_name.as_deref()),
+            Some("file_ops_agent")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_matching_rule_bypasses_llm() {
+        let settings = Settings::new().unwrap();
+        let router_llm = Arc::new(MockLLM::new(Vec::<String>::new()));
+        let agent_llm = Arc::new(MockLLM::new([
+            r#"{"thought": "done", "action": null, "is_final": true, "final_answer": "ran"}"#,
+        ]));
+        let agents = default_agent_configs()
+            .into_iter()
+            .map(|config| {
+                SpecializedAgent::with_llm_client(
+                    config,
+                    LLMClient::with_backend(agent_llm.clone(), settings.clone()),
+                )
+            })
+            .collect();
+
+        let router = RouterAgent::new(
+            agents,
+            LLMClient::with_backend(router_llm.clone(), settings.clone()),
+        )
+        .with_rules(vec![
+            RouterRule::new(r"^run\s", "shell_agent").unwrap(),
+            RouterRule::new(r"\bfile\b", "file_ops_agent").unwrap(),
+        ]);
+        let response = router.route_task("Run ls on the file tree", 3).await;
+
+        assert!(router_llm.requests().is_empty());
+        assert_eq!(
+            response.metadata().and_then(|m| m.agent_name.as_deref()),
+            Some("shell_agent")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_multi_routing_runs_every_selected_agent() {
+        let settings = Settings::new().unwrap();
+        let classifier = Arc::new(MockLLM::new([
+            r#"{"agent_names": ["web_agent", "file_ops_agent"], "reasoning": "download then save"}"#,
+        ]));
+        // Each agent gets its own backend so concurrent runs cannot swap replies
+        let agents = default_agent_configs()
+            .into_iter()
+            .map(|config| {
+                let answer = format!("{} done", config.name);
+                let reply = serde_json::json!({
+                    "thought": "done",
+                    "action": null,
+                    "is_final": true,
+                    "final_answer": answer
+                });
+                SpecializedAgent::with_llm_client(
+                    config,
+                    LLMClient::with_backend(
+                        Arc::new(MockLLM::new([reply.to_string()])),
+                        settings.clone(),
+                    ),
+                )
+            })
+            .collect();
+
+        let router = RouterAgent::new(agents, LLMClient::with_backend(classifier, settings));
+        let response = router
+            .route_task_multi("Download the report and save it to disk", 3)
+            .await;
+
+        match &response {
+            AgentResponse::Success { result, .. } => {
+                assert_eq!(
+                    result,
+                    "[web_agent]\nweb_agent done\n\n[file_ops_agent]\nfile_ops_agent done"
+                );
             }
+            other => panic!("expected success, got {:?}", other),
         }
+        let routing = response.metadata().unwrap().routing.as_ref().unwrap();
+        assert_eq!(routing.agent_name, "web_agent, file_ops_agent");
+        assert_eq!(routing.reasoning, "download then save");
     }
 }
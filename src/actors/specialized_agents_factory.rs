@@ -14,9 +14,9 @@ use crate::actors::specialized_agent::{SpecializedAgent, SpecializedAgentConfig}
 use crate::config::Settings;
 use crate::tools::*;
 
-/// Create a file operations specialized agent
-pub fn create_file_ops_agent(settings: Settings, api_key: String) -> SpecializedAgent {
-    let (name, description, system_prompt, tools, response_schema, return_tool_output) =
+/// Configuration of the file operations specialized agent
+pub fn file_ops_agent_config() -> SpecializedAgentConfig {
+    let (name, description, system_prompt, tools, response_schema, return_tool_output, examples) =
         AgentBuilder::new("file_ops_agent")
             .description(
                 "Handles file system operations including reading and writing files. \
@@ -29,23 +29,25 @@ pub fn create_file_ops_agent(settings: Settings, api_key: String) -> Specialized
             )
             .tool(filesystem::ReadFileTool::new(1024 * 1024 * 10)) // 10MB limit
             .tool(filesystem::WriteFileTool::new(1024 * 1024 * 10)) // 10MB limit
+            .tool(filesystem::EditFileTool::new(1024 * 1024 * 10))
+            .tool(filesystem::FindFilesTool::new(500))
             .build();
 
-    let config = SpecializedAgentConfig {
+    SpecializedAgentConfig {
         name,
         description,
         system_prompt,
         tools,
         response_schema,
         return_tool_output,
-    };
-
-    SpecializedAgent::new(config, settings, api_key)
+        examples,
+        ..Default::default()
+    }
 }
 
-/// Create a shell command specialized agent
-pub fn create_shell_agent(settings: Settings, api_key: String) -> SpecializedAgent {
-    let (name, description, system_prompt, tools, response_schema, return_tool_output) = AgentBuilder::new("shell_agent")
+/// Configuration of the shell command specialized agent
+pub fn shell_agent_config() -> SpecializedAgentConfig {
+    let (name, description, system_prompt, tools, response_schema, return_tool_output, examples) = AgentBuilder::new("shell_agent")
         .description(
             "Executes shell commands and system operations. \
              Use this agent for tasks involving command-line operations, \
@@ -60,21 +62,21 @@ pub fn create_shell_agent(settings: Settings, api_key: String) -> SpecializedAge
         .tool(shell::ShellTool::new(30)) // 30 second timeout
         .build();
 
-    let config = SpecializedAgentConfig {
+    SpecializedAgentConfig {
         name,
         description,
         system_prompt,
         tools,
         response_schema,
         return_tool_output,
-    };
-
-    SpecializedAgent::new(config, settings, api_key)
+        examples,
+        ..Default::default()
+    }
 }
 
-/// Create a web/HTTP specialized agent
-pub fn create_web_agent(settings: Settings, api_key: String) -> SpecializedAgent {
-    let (name, description, system_prompt, tools, response_schema, return_tool_output) =
+/// Configuration of the web/HTTP specialized agent
+pub fn web_agent_config() -> SpecializedAgentConfig {
+    let (name, description, system_prompt, tools, response_schema, return_tool_output, examples) =
         AgentBuilder::new("web_agent")
             .description(
                 "Handles HTTP requests and web-based operations. \
@@ -89,21 +91,21 @@ pub fn create_web_agent(settings: Settings, api_key: String) -> SpecializedAgent
             .tool(http::HttpTool::new(30)) // 30 second timeout
             .build();
 
-    let config = SpecializedAgentConfig {
+    SpecializedAgentConfig {
         name,
         description,
         system_prompt,
         tools,
         response_schema,
         return_tool_output,
-    };
-
-    SpecializedAgent::new(config, settings, api_key)
+        examples,
+        ..Default::default()
+    }
 }
 
-/// Create a general-purpose agent with all tools (for backwards compatibility)
-pub fn create_general_agent(settings: Settings, api_key: String) -> SpecializedAgent {
-    let (name, description, system_prompt, tools, response_schema, return_tool_output) =
+/// Configuration of the general-purpose agent with all tools
+pub fn general_agent_config() -> SpecializedAgentConfig {
+    let (name, description, system_prompt, tools, response_schema, return_tool_output, examples) =
         AgentBuilder::new("general_agent")
             .description(
                 "General-purpose agent with access to all tools. \
@@ -118,19 +120,51 @@ pub fn create_general_agent(settings: Settings, api_key: String) -> SpecializedA
             .tool(shell::ShellTool::new(30))
             .tool(filesystem::ReadFileTool::new(1024 * 1024 * 10))
             .tool(filesystem::WriteFileTool::new(1024 * 1024 * 10))
+            .tool(filesystem::EditFileTool::new(1024 * 1024 * 10))
+            .tool(filesystem::FindFilesTool::new(500))
             .tool(http::HttpTool::new(30))
             .build();
 
-    let config = SpecializedAgentConfig {
+    SpecializedAgentConfig {
         name,
         description,
         system_prompt,
         tools,
         response_schema,
         return_tool_output,
-    };
+        examples,
+        ..Default::default()
+    }
+}
+
+/// Create a file operations specialized agent
+pub fn create_file_ops_agent(settings: Settings, api_key: String) -> SpecializedAgent {
+    SpecializedAgent::new(file_ops_agent_config(), settings, api_key)
+}
+
+/// Create a shell command specialized agent
+pub fn create_shell_agent(settings: Settings, api_key: String) -> SpecializedAgent {
+    SpecializedAgent::new(shell_agent_config(), settings, api_key)
+}
 
-    SpecializedAgent::new(config, settings, api_key)
+/// Create a web/HTTP specialized agent
+pub fn create_web_agent(settings: Settings, api_key: String) -> SpecializedAgent {
+    SpecializedAgent::new(web_agent_config(), settings, api_key)
+}
+
+/// Create a general-purpose agent with all tools (for backwards compatibility)
+pub fn create_general_agent(settings: Settings, api_key: String) -> SpecializedAgent {
+    SpecializedAgent::new(general_agent_config(), settings, api_key)
+}
+
+/// Configurations of all default specialized agents
+pub fn default_agent_configs() -> Vec<SpecializedAgentConfig> {
+    vec![
+        file_ops_agent_config(),
+        shell_agent_config(),
+        web_agent_config(),
+        general_agent_config(),
+    ]
 }
 
 /// Create all default specialized agents
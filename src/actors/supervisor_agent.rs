@@ -12,18 +12,30 @@
 //! - Exposes simple orchestration interface
 
 use crate::actors::handoff::HandoffCoordinator;
-use crate::actors::messages::{AgentResponse, AgentStep, CompletionStatus};
-use crate::actors::specialized_agent::SpecializedAgent;
-use crate::config::Settings;
-use crate::core::llm::{ChatMessage, LLMClient};
+use crate::actors::messages::{
+    AgentResponse, AgentStep, CompletionStatus, Deadline, OutputMetadata,
+};
+use crate::actors::specialized_agent::{
+    deserialize_final_answer, summarize_invocation, SpecializedAgent,
+};
+use crate::actors::validation::validate_json_schema;
+use crate::config::{prompts, Settings};
+use crate::core::llm::{ChatMessage, LLMClient, LLMParams};
+use crate::tools::introspect::IntrospectTool;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, Semaphore, SemaphorePermit};
 
 /// Sub-goal declaration for task planning
 #[derive(Debug, Clone, Deserialize, Serialize)]
 struct SubGoalDeclaration {
     id: String,
     description: String,
+    /// Nice-to-have goal the task can complete without
+    #[serde(default)]
+    optional: bool,
 }
 
 /// Supervisor decision returned by LLM
@@ -33,8 +45,10 @@ struct SupervisorDecision {
     sub_goals: Option<Vec<SubGoalDeclaration>>, // Declare sub-goals upfront (first step only)
     agent_to_invoke: Option<String>,
     agent_task: Option<String>,
-    sub_goal_id: Option<String>, // Which sub-goal this task addresses
+    sub_goal_id: Option<String>,     // Which sub-goal this task addresses
+    depends_on: Option<Vec<String>>, // Sub-goals whose outputs the agent receives as JSON
     is_final: bool,
+    #[serde(default, deserialize_with = "deserialize_final_answer")]
     final_answer: Option<String>,
 }
 
@@ -55,6 +69,7 @@ struct SubGoal {
     status: SubGoalStatus,
     assigned_agent: Option<String>,
     result: Option<String>,
+    optional: bool,
 }
 
 /// Task progress tracker for the supervisor
@@ -74,13 +89,14 @@ impl TaskProgress {
         }
     }
 
-    fn add_sub_goal(&mut self, id: String, description: String) {
+    fn add_sub_goal(&mut self, id: String, description: String, optional: bool) {
         self.sub_goals.push(SubGoal {
             id,
             description,
             status: SubGoalStatus::Pending,
             assigned_agent: None,
             result: None,
+            optional,
         });
     }
 
@@ -115,10 +131,50 @@ impl TaskProgress {
         }
     }
 
-    fn is_complete(&self) -> bool {
+    /// Every sub-goal, optional ones included, is completed
+    fn all_completed(&self) -> bool {
         !self.sub_goals.is_empty() && self.completed_count == self.sub_goals.len()
     }
 
+    /// Every required sub-goal is completed; optional ones may still be open
+    ///
+    /// A plan made only of optional sub-goals needs all of them.
+    fn is_complete(&self) -> bool {
+        let mut required = self.sub_goals.iter().filter(|g| !g.optional).peekable();
+        if required.peek().is_none() {
+            return self.all_completed();
+        }
+        required.all(|g| matches!(g.status, SubGoalStatus::Completed))
+    }
+
+    /// Results of completed sub-goals by id
+    fn completed_results(&self) -> HashMap<String, String> {
+        self.sub_goals
+            .iter()
+            .filter(|g| matches!(g.status, SubGoalStatus::Completed))
+            .filter_map(|g| Some((g.id.clone(), g.result.clone()?)))
+            .collect()
+    }
+
+    /// Why a `FailurePolicy::RequireAll` orchestration cannot succeed, if it can't
+    fn unmet_requirement(&self) -> Option<String> {
+        if let Some(goal) = self
+            .sub_goals
+            .iter()
+            .find(|g| matches!(g.status, SubGoalStatus::Failed))
+        {
+            return Some(format!(
+                "Sub-goal '{}' failed: {}",
+                goal.id,
+                goal.result.as_deref().unwrap_or("unknown error")
+            ));
+        }
+        self.sub_goals
+            .iter()
+            .find(|g| !g.optional && !matches!(g.status, SubGoalStatus::Completed))
+            .map(|goal| format!("Sub-goal '{}' was not completed", goal.id))
+    }
+
     fn progress_summary(&self) -> String {
         format!(
             "Progress: {}/{} sub-goals completed ({:.0}%), {} failed",
@@ -143,20 +199,144 @@ impl TaskProgress {
                 SubGoalStatus::Completed => "[✓]",
                 SubGoalStatus::Failed => "[✗]",
             };
-            status.push_str(&format!("  {} {}\n", status_icon, goal.description));
+            let optional = if goal.optional { " (optional)" } else { "" };
+            status.push_str(&format!(
+                "  {} {}{}\n",
+                status_icon, goal.description, optional
+            ));
         }
         status
     }
 }
 
+/// Change in the supervisor's plan, reported through `orchestrate_with_progress`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SupervisorEvent {
+    /// Sub-goal added to the plan
+    SubGoalDeclared {
+        sub_goal_id: String,
+        description: String,
+    },
+    /// Agent invoked to work on a sub-goal
+    SubGoalStarted { sub_goal_id: String, agent: String },
+    /// Agent accomplished the sub-goal
+    SubGoalCompleted {
+        sub_goal_id: String,
+        agent: String,
+        result: String,
+    },
+    /// Agent failed, ran out of time, or its output was rejected
+    SubGoalFailed {
+        sub_goal_id: String,
+        agent: String,
+        error: String,
+    },
+}
+
+/// One progress update: what changed plus the state of the whole plan after it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SupervisorProgress {
+    pub event: SupervisorEvent,
+    /// Share of sub-goals completed, from 0.0 to 1.0
+    pub progress_percentage: f32,
+    /// Checklist of every sub-goal and its status
+    pub detailed_status: String,
+}
+
+/// Send `event` with the current plan state to the progress channel, if any
+///
+/// Never waits on a slow consumer: when the channel is full the event is dropped.
+fn report(
+    progress: Option<&mpsc::Sender<SupervisorProgress>>,
+    task_progress: &TaskProgress,
+    event: SupervisorEvent,
+) {
+    if let Some(tx) = progress {
+        let update = SupervisorProgress {
+            event,
+            progress_percentage: task_progress.progress_percentage(),
+            detailed_status: task_progress.detailed_status(),
+        };
+        if let Err(e) = tx.try_send(update) {
+            tracing::debug!("[SupervisorAgent] Dropped progress event: {}", e);
+        }
+    }
+}
+
+/// Global ceiling on the work a supervisor may spend across all agent invocations
+///
+/// `max_orchestration_steps` only bounds the supervisor's own loop; each agent it
+/// invokes runs a full ReAct loop of its own. The budget caps the combined cost.
+/// `None` leaves a dimension unbounded.
+#[derive(Debug, Clone, Default)]
+pub struct SupervisorBudget {
+    /// Maximum ReAct iterations summed over every agent invocation
+    pub max_total_agent_iterations: Option<usize>,
+    /// Maximum tokens consumed by the supervisor and all agents combined
+    pub max_total_tokens: Option<u64>,
+    /// Maximum times any single agent may be invoked; further requests for
+    /// that agent are refused so the supervisor must change course
+    pub max_invocations_per_agent: Option<usize>,
+}
+
+impl SupervisorBudget {
+    /// Describe which limit the given usage exceeds, if any
+    fn exceeded_by(&self, agent_iterations: usize, tokens: u64) -> Option<String> {
+        if let Some(max) = self.max_total_agent_iterations {
+            if agent_iterations >= max {
+                return Some(format!(
+                    "agent iteration budget exhausted ({}/{})",
+                    agent_iterations, max
+                ));
+            }
+        }
+        if let Some(max) = self.max_total_tokens {
+            if tokens >= max {
+                return Some(format!("token budget exhausted ({}/{})", tokens, max));
+            }
+        }
+        None
+    }
+
+    /// Whether an agent already invoked `invocations` times may run again
+    fn allows_invocation(&self, invocations: usize) -> bool {
+        match self.max_invocations_per_agent {
+            Some(max) => invocations < max,
+            None => true,
+        }
+    }
+}
+
+/// How a failed sub-goal affects the outcome of an orchestration
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FailurePolicy {
+    /// Report the failure to the supervisor and let it decide how to proceed
+    #[default]
+    ContinueOnFailure,
+    /// Stop at the first failed sub-goal and return a failure
+    FailFast,
+    /// Keep going, but only succeed if no sub-goal failed and every required
+    /// sub-goal completed
+    RequireAll,
+}
+
 /// Supervisor agent that orchestrates multiple specialized agents
 pub struct SupervisorAgent {
     agents: HashMap<String, SpecializedAgent>,
     llm_client: LLMClient,
     settings: Settings,
     handoff_coordinator: Option<HandoffCoordinator>,
+    budget: SupervisorBudget,
+    failure_policy: FailurePolicy,
+    structured_results: bool,
+    final_schema: Option<serde_json::Value>,
+    planning_params: LLMParams,
+    agent_permits: Arc<Semaphore>,
 }
 
+/// Upper bound on the random delay before waiting for a busy agent slot
+const MAX_PERMIT_JITTER_MS: u64 = 50;
+
 impl SupervisorAgent {
     pub fn new(agents: Vec<SpecializedAgent>, llm_client: LLMClient, settings: Settings) -> Self {
         let mut agent_map = HashMap::new();
@@ -164,14 +344,113 @@ impl SupervisorAgent {
             agent_map.insert(agent.name().to_string(), agent);
         }
 
+        let agent_permits = Arc::new(Semaphore::new(settings.agent.max_concurrent_agents.max(1)));
+        let structured_results = settings.agent.structured_results;
+
         Self {
             agents: agent_map,
             llm_client,
             settings,
             handoff_coordinator: None,
+            budget: SupervisorBudget::default(),
+            failure_policy: FailurePolicy::default(),
+            structured_results,
+            final_schema: None,
+            planning_params: LLMParams::default(),
+            agent_permits,
         }
     }
 
+    /// Override the sampling parameters used when planning the next step
+    ///
+    /// A low temperature keeps agent selection and decomposition deterministic.
+    pub fn with_planning_params(mut self, params: LLMParams) -> Self {
+        self.planning_params = params;
+        self
+    }
+
+    /// Require the final answer to be JSON conforming to the given schema
+    ///
+    /// The final decision is validated before the supervisor reports success.
+    /// A non-conforming answer is sent back once with the validation errors;
+    /// a second mismatch fails the orchestration. Automatic completion from
+    /// sub-goal results is disabled so the supervisor always writes the answer.
+    pub fn with_final_schema(mut self, schema: serde_json::Value) -> Self {
+        self.final_schema = Some(schema);
+        self
+    }
+
+    /// Cap the total agent iterations and tokens an orchestration may spend
+    ///
+    /// The budget is checked after every agent invocation. Once exceeded, the
+    /// supervisor stops and returns the results gathered so far.
+    pub fn with_budget(mut self, budget: SupervisorBudget) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    /// Decide whether a failed sub-goal fails the whole orchestration
+    ///
+    /// Defaults to `FailurePolicy::ContinueOnFailure`, where the supervisor may
+    /// still report success after a sub-goal failed.
+    pub fn with_failure_policy(mut self, policy: FailurePolicy) -> Self {
+        self.failure_policy = policy;
+        self
+    }
+
+    /// Attach each completed sub-goal's result, keyed by sub-goal id, to the
+    /// response metadata's `partial_results`
+    ///
+    /// Overrides `agent.structured_results` from settings.
+    pub fn with_structured_results(mut self, enabled: bool) -> Self {
+        self.structured_results = enabled;
+        self
+    }
+
+    /// Limit how many agents may execute at once across concurrent orchestrations
+    ///
+    /// Overrides `agent.max_concurrent_agents` from settings. Zero is treated as one.
+    pub fn with_max_concurrent_agents(mut self, limit: usize) -> Self {
+        self.agent_permits = Arc::new(Semaphore::new(limit.max(1)));
+        self
+    }
+
+    /// Wait for a free agent slot
+    ///
+    /// When every slot is taken, callers sleep a random few milliseconds before
+    /// queueing so that waiters released together do not hit the LLM in lockstep.
+    async fn acquire_agent_permit(&self) -> SemaphorePermit<'_> {
+        if let Ok(permit) = self.agent_permits.try_acquire() {
+            return permit;
+        }
+
+        let jitter_ms = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|elapsed| elapsed.subsec_nanos() as u64 % MAX_PERMIT_JITTER_MS)
+            .unwrap_or(0);
+        tracing::debug!(
+            "[SupervisorAgent] All agent slots busy, waiting (jitter {}ms)",
+            jitter_ms
+        );
+        tokio::time::sleep(Duration::from_millis(jitter_ms)).await;
+
+        self.agent_permits
+            .acquire()
+            .await
+            .expect("agent semaphore is never closed")
+    }
+
+    /// Tool reporting this supervisor's agents and their tools, for agents
+    /// that plan which of their peers to involve
+    pub fn introspect_tool(&self) -> IntrospectTool {
+        IntrospectTool::new(
+            self.agents
+                .values()
+                .map(SpecializedAgent::profile)
+                .collect(),
+        )
+    }
+
     /// Enable handoff validation with a configured coordinator
     pub fn with_handoff_validation(mut self, coordinator: HandoffCoordinator) -> Self {
         self.handoff_coordinator = Some(coordinator);
@@ -180,6 +459,103 @@ impl SupervisorAgent {
 
     /// Orchestrate a complex task across multiple specialized agents
     pub async fn orchestrate(&self, task: &str, max_orchestration_steps: usize) -> AgentResponse {
+        self.orchestrate_with_deadline(task, max_orchestration_steps, Deadline::none())
+            .await
+    }
+
+    /// Orchestrate a task, starting no new step or agent once `deadline` has passed
+    ///
+    /// The deadline is passed on to every invoked agent. When it expires the
+    /// supervisor returns the completed sub-goal results as a partial answer.
+    ///
+    /// Sub-goals declared beyond `agent.max_sub_goals` are dropped and listed
+    /// in the response metadata's `dropped_sub_goals`.
+    pub async fn orchestrate_with_deadline(
+        &self,
+        task: &str,
+        max_orchestration_steps: usize,
+        deadline: Deadline,
+    ) -> AgentResponse {
+        self.orchestrate_reporting(task, max_orchestration_steps, deadline, None)
+            .await
+    }
+
+    /// Like `orchestrate`, but sends each sub-goal status change to `progress`
+    /// as it happens, e.g. to render a live task board
+    ///
+    /// A dropped receiver does not interrupt the orchestration.
+    pub async fn orchestrate_with_progress(
+        &self,
+        task: &str,
+        max_orchestration_steps: usize,
+        progress: mpsc::Sender<SupervisorProgress>,
+    ) -> AgentResponse {
+        self.orchestrate_reporting(
+            task,
+            max_orchestration_steps,
+            Deadline::none(),
+            Some(&progress),
+        )
+        .await
+    }
+
+    async fn orchestrate_reporting(
+        &self,
+        task: &str,
+        max_orchestration_steps: usize,
+        deadline: Deadline,
+        progress: Option<&mpsc::Sender<SupervisorProgress>>,
+    ) -> AgentResponse {
+        let mut task_progress = TaskProgress::new();
+        let mut dropped_sub_goals = Vec::new();
+        let mut response = self
+            .run_orchestration(
+                task,
+                max_orchestration_steps,
+                deadline,
+                progress,
+                &mut task_progress,
+                &mut dropped_sub_goals,
+            )
+            .await;
+
+        if self.failure_policy == FailurePolicy::RequireAll {
+            if let Some(error) = task_progress.unmet_requirement() {
+                if let AgentResponse::Success { steps, .. } = response {
+                    tracing::warn!("[SupervisorAgent] Failing orchestration: {}", error);
+                    response = Self::failure(error, steps, false);
+                }
+            }
+        }
+
+        if self.structured_results {
+            let sub_goal_results = task_progress.completed_results();
+            if !sub_goal_results.is_empty() {
+                response
+                    .metadata_mut()
+                    .get_or_insert_with(OutputMetadata::default)
+                    .partial_results = sub_goal_results;
+            }
+        }
+
+        if !dropped_sub_goals.is_empty() {
+            response
+                .metadata_mut()
+                .get_or_insert_with(OutputMetadata::default)
+                .dropped_sub_goals = dropped_sub_goals;
+        }
+        response
+    }
+
+    async fn run_orchestration(
+        &self,
+        task: &str,
+        max_orchestration_steps: usize,
+        deadline: Deadline,
+        progress: Option<&mpsc::Sender<SupervisorProgress>>,
+        task_progress: &mut TaskProgress,
+        dropped_sub_goals: &mut Vec<String>,
+    ) -> AgentResponse {
         tracing::info!("[SupervisorAgent] Orchestrating task: {}", task);
 
         let mut conversation_history = Vec::new();
@@ -187,17 +563,35 @@ impl SupervisorAgent {
         let mut agent_results: Vec<(String, String)> = Vec::new(); // (agent_name, result)
         let mut agent_results_context: serde_json::Map<String, serde_json::Value> =
             serde_json::Map::new(); // Structured context
-        let mut task_progress = TaskProgress::new();
+        let supervisor_tokens_at_start = self.llm_client.tokens_used();
+        let mut agent_iterations_used = 0usize;
+        let mut agent_tokens_used = 0u64;
+        let mut agent_invocations: HashMap<String, usize> = HashMap::new();
+        // Summaries of earlier invocations, for agents that keep a scratchpad
+        let mut scratchpads: HashMap<String, Vec<String>> = HashMap::new();
+        let mut sub_goal_outputs: HashMap<String, serde_json::Value> = HashMap::new();
+        let mut final_schema_retried = false;
 
         // Build agent descriptions for the supervisor prompt
-        let agent_descriptions: Vec<String> = self
+        // Sorted so the prompt is identical across runs
+        let mut agent_descriptions: Vec<String> = self
             .agents
             .values()
             .map(|agent| format!("- {}: {}", agent.name(), agent.description()))
             .collect();
+        agent_descriptions.sort();
 
         let max_sub_goals = self.settings.agent.max_sub_goals;
 
+        let final_answer_format = match &self.final_schema {
+            Some(schema) => format!(
+                "FINAL ANSWER FORMAT:\n\
+                 - \"final_answer\" MUST be a JSON object (not a string) matching this JSON schema:\n{}\n\n",
+                serde_json::to_string_pretty(schema).unwrap_or_else(|_| schema.to_string())
+            ),
+            None => String::new(),
+        };
+
         let supervisor_system_prompt = format!(
             "You are a supervisor that coordinates multiple specialized agents to accomplish complex tasks.\n\n\
              Available Agents:\n{}\n\n\
@@ -212,20 +606,24 @@ impl SupervisorAgent {
              - When an agent produces data that the next agent needs, you MUST include the complete data in the agent_task field\n\
              - For example, if agent A returns JSON data and agent B needs to analyze it, set agent_task to: \"Analyze this data: {{the actual JSON here}}\"\n\
              - Do NOT just reference the data (\"use the data from step 1\") - include the actual data!\n\
-             - The agent_task is the ONLY information the agent receives - make it complete\n\n\
+             - The agent_task is the ONLY information the agent receives - make it complete\n\
+             - Alternatively, list the sub-goal ids whose results the agent needs in \"depends_on\"; \
+             their outputs are attached to the agent_task as JSON automatically, so you do not need to copy them\n\n\
              You MUST respond in this EXACT JSON format:\n\
              {{\n  \
                \"thought\": \"your reasoning about what to do next\",\n  \
-               \"sub_goals\": [{{\"id\": \"goal_1\", \"description\": \"...\"}}, ...] or null,\n  \
+               \"sub_goals\": [{{\"id\": \"goal_1\", \"description\": \"...\", \"optional\": false}}, ...] or null,\n  \
                \"agent_to_invoke\": \"agent_name or null\",\n  \
                \"agent_task\": \"specific task for the agent or null\",\n  \
                \"sub_goal_id\": \"which sub-goal this addresses or null\",\n  \
+               \"depends_on\": [\"goal_1\"] or null,\n  \
                \"is_final\": false,\n  \
                \"final_answer\": null\n\
              }}\n\n\
              FIRST STEP (Planning):\n\
              - Declare AT MOST {} sub-goals (prioritize the most important)\n\
              - Set \"sub_goals\" to an array with ids like 'goal_1', 'goal_2', etc.\n\
+             - Mark nice-to-have sub-goals with \"optional\": true; they may be skipped when steps run short\n\
              - Set \"agent_to_invoke\" to the first agent you'll use\n\
              - Set \"agent_task\" to the specific task for that agent\n\
              - Set \"sub_goal_id\" to 'goal_1' (the first sub-goal)\n\
@@ -247,24 +645,23 @@ impl SupervisorAgent {
              CRITICAL: If the task is complex, prioritize the {} most important sub-goals.\n\
              You can invoke the same agent multiple times if needed.\n\
              Always consider previous agent results when deciding next steps.\n\n\
+             {}\
              Respond with valid JSON only. No extra text.",
             agent_descriptions.join("\n"),
             max_orchestration_steps,
             max_sub_goals,
             max_sub_goals,
             max_sub_goals,
-            max_sub_goals
+            max_sub_goals,
+            final_answer_format
         );
 
-        conversation_history.push(ChatMessage {
-            role: "system".to_string(),
-            content: supervisor_system_prompt,
-        });
+        conversation_history.push(ChatMessage::system(supervisor_system_prompt));
+        if let Some(note) = prompts::current_time_note(&self.llm_client.settings().prompts) {
+            conversation_history.push(ChatMessage::system(note));
+        }
 
-        conversation_history.push(ChatMessage {
-            role: "user".to_string(),
-            content: format!("Task: {}", task),
-        });
+        conversation_history.push(ChatMessage::user(format!("Task: {}", task)));
 
         for step in 0..max_orchestration_steps {
             let remaining_steps = max_orchestration_steps - step;
@@ -275,6 +672,34 @@ impl SupervisorAgent {
                 remaining_steps
             );
 
+            let tokens_used = agent_tokens_used
+                + self
+                    .llm_client
+                    .tokens_used()
+                    .saturating_sub(supervisor_tokens_at_start);
+            if deadline.is_expired() {
+                tracing::warn!("[SupervisorAgent] Deadline exceeded");
+                return Self::finalize_early(
+                    "deadline exceeded".to_string(),
+                    "Allow more time for the request",
+                    step,
+                    task_progress,
+                    all_steps,
+                    tokens_used,
+                );
+            }
+            if let Some(reason) = self.budget.exceeded_by(agent_iterations_used, tokens_used) {
+                tracing::warn!("[SupervisorAgent] Budget exceeded: {}", reason);
+                return Self::finalize_early(
+                    reason,
+                    "Increase the supervisor budget",
+                    step,
+                    task_progress,
+                    all_steps,
+                    tokens_used,
+                );
+            }
+
             // Ask supervisor what to do next
             let decision = match self.decide_next_action(&conversation_history).await {
                 Ok(d) => d,
@@ -295,7 +720,7 @@ impl SupervisorAgent {
             tracing::debug!("[SupervisorAgent] Thought: {}", decision.thought);
 
             // Handle sub-goal declaration (first step only)
-            if let Some(sub_goal_declarations) = decision.sub_goals {
+            if let Some(mut sub_goal_declarations) = decision.sub_goals {
                 let declared_count = sub_goal_declarations.len();
                 let max_allowed = self.settings.agent.max_sub_goals;
 
@@ -306,13 +731,29 @@ impl SupervisorAgent {
                         max_allowed,
                         max_allowed
                     );
+                    dropped_sub_goals.extend(
+                        sub_goal_declarations
+                            .drain(max_allowed..)
+                            .map(|declaration| declaration.description),
+                    );
                 }
 
-                let goals_to_add = sub_goal_declarations.into_iter().take(max_allowed);
-                let added_count = goals_to_add.len();
+                let added_count = sub_goal_declarations.len();
 
-                for declaration in goals_to_add {
-                    task_progress.add_sub_goal(declaration.id, declaration.description);
+                for declaration in sub_goal_declarations {
+                    task_progress.add_sub_goal(
+                        declaration.id.clone(),
+                        declaration.description.clone(),
+                        declaration.optional,
+                    );
+                    report(
+                        progress,
+                        task_progress,
+                        SupervisorEvent::SubGoalDeclared {
+                            sub_goal_id: declaration.id,
+                            description: declaration.description,
+                        },
+                    );
                 }
 
                 tracing::info!(
@@ -325,8 +766,9 @@ impl SupervisorAgent {
             }
 
             // Check if all sub-goals are complete (auto-completion)
-            if !decision.is_final
-                && task_progress.is_complete()
+            if self.final_schema.is_none()
+                && !decision.is_final
+                && task_progress.all_completed()
                 && !task_progress.sub_goals.is_empty()
             {
                 tracing::info!("[SupervisorAgent] All sub-goals completed - auto-completing task");
@@ -363,10 +805,72 @@ impl SupervisorAgent {
 
             // Check if task is complete
             if decision.is_final {
-                let final_answer = decision
+                let mut final_answer = decision
                     .final_answer
+                    .clone()
                     .unwrap_or_else(|| "Task completed without explicit answer".to_string());
 
+                if let Some(schema) = &self.final_schema {
+                    match Self::check_final_answer(&final_answer, schema) {
+                        Ok(structured) => final_answer = structured.to_string(),
+                        Err(errors) if !final_schema_retried => {
+                            final_schema_retried = true;
+                            tracing::warn!(
+                                "[SupervisorAgent] Final answer does not match schema, re-prompting: {}",
+                                errors
+                            );
+
+                            conversation_history.push(ChatMessage::assistant(
+                                serde_json::to_string(&SupervisorDecision {
+                                    thought: decision.thought.clone(),
+                                    sub_goals: None,
+                                    agent_to_invoke: None,
+                                    agent_task: None,
+                                    sub_goal_id: None,
+                                    depends_on: None,
+                                    is_final: true,
+                                    final_answer: Some(final_answer.clone()),
+                                })
+                                .unwrap_or_else(|_| final_answer.clone()),
+                            ));
+                            conversation_history.push(ChatMessage::user(format!(
+                                    "Your final_answer does not match the required schema:\n{}\n\n\
+                                     Respond again with is_final=true and a final_answer JSON object \
+                                     that satisfies the schema.",
+                                    errors
+                                )));
+
+                            all_steps.push(AgentStep {
+                                iteration: step,
+                                thought: decision.thought,
+                                action: None,
+                                observation: Some(format!(
+                                    "Final answer rejected by schema: {}",
+                                    errors
+                                )),
+                            });
+                            continue;
+                        }
+                        Err(errors) => {
+                            tracing::error!(
+                                "[SupervisorAgent] Final answer still does not match schema: {}",
+                                errors
+                            );
+                            all_steps.push(AgentStep {
+                                iteration: step,
+                                thought: decision.thought,
+                                action: None,
+                                observation: Some(final_answer),
+                            });
+                            return Self::failure(
+                                format!("Final answer does not match schema: {}", errors),
+                                all_steps,
+                                true,
+                            );
+                        }
+                    }
+                }
+
                 all_steps.push(AgentStep {
                     iteration: step,
                     thought: decision.thought.clone(),
@@ -389,6 +893,30 @@ impl SupervisorAgent {
                 decision.agent_to_invoke.clone(),
                 decision.agent_task.clone(),
             ) {
+                let invocations = agent_invocations.get(&agent_name).copied().unwrap_or(0);
+                if !self.budget.allows_invocation(invocations) {
+                    let exhausted_msg = format!(
+                        "Agent '{}' is exhausted: it has already been invoked {} times, the maximum allowed",
+                        agent_name, invocations
+                    );
+                    tracing::warn!("[SupervisorAgent] {}", exhausted_msg);
+
+                    conversation_history.push(ChatMessage::user(format!(
+                        "{}.\nDo NOT invoke '{}' again. Either:\n\
+                         1. Invoke a different agent, OR\n\
+                         2. Set is_final=true and provide the final_answer with the results you have",
+                        exhausted_msg, agent_name
+                    )));
+
+                    all_steps.push(AgentStep {
+                        iteration: step,
+                        thought: decision.thought,
+                        action: Some(agent_name),
+                        observation: Some(exhausted_msg),
+                    });
+                    continue;
+                }
+
                 tracing::info!(
                     "[SupervisorAgent] Invoking '{}' with task: {}",
                     agent_name,
@@ -412,11 +940,27 @@ impl SupervisorAgent {
                         "[SupervisorAgent] Sub-goal '{}' not declared upfront, adding now",
                         sub_goal_id
                     );
-                    task_progress.add_sub_goal(sub_goal_id.clone(), agent_task.clone());
+                    task_progress.add_sub_goal(sub_goal_id.clone(), agent_task.clone(), false);
+                    report(
+                        progress,
+                        task_progress,
+                        SupervisorEvent::SubGoalDeclared {
+                            sub_goal_id: sub_goal_id.clone(),
+                            description: agent_task.clone(),
+                        },
+                    );
                 }
 
                 // Mark as in progress
                 task_progress.mark_in_progress(&sub_goal_id, &agent_name);
+                report(
+                    progress,
+                    task_progress,
+                    SupervisorEvent::SubGoalStarted {
+                        sub_goal_id: sub_goal_id.clone(),
+                        agent: agent_name.clone(),
+                    },
+                );
 
                 tracing::info!(
                     "[SupervisorAgent] Working on sub-goal '{}': {}",
@@ -426,9 +970,16 @@ impl SupervisorAgent {
 
                 match self.agents.get(&agent_name) {
                     Some(agent) => {
+                        *agent_invocations.entry(agent_name.clone()).or_insert(0) += 1;
+
                         // Build context from previous agent results
-                        let context = if !agent_results_context.is_empty() {
-                            Some(serde_json::Value::Object(agent_results_context.clone()))
+                        let mut agent_context = agent_results_context.clone();
+                        if let Some(notes) = scratchpads.get(&agent_name) {
+                            agent_context
+                                .insert("scratchpad".to_string(), serde_json::json!(notes));
+                        }
+                        let context = if !agent_context.is_empty() {
+                            Some(serde_json::Value::Object(agent_context))
                         } else {
                             None
                         };
@@ -439,14 +990,41 @@ impl SupervisorAgent {
                             agent_name
                         );
 
-                        // Execute agent task with context
+                        let agent_input = match &decision.depends_on {
+                            Some(dependencies) if !dependencies.is_empty() => {
+                                Self::attach_upstream_outputs(
+                                    &agent_task,
+                                    dependencies,
+                                    &sub_goal_outputs,
+                                )
+                            }
+                            _ => agent_task.clone(),
+                        };
+
+                        // Execute agent task with context, holding a concurrency slot
+                        let permit = self.acquire_agent_permit().await;
                         let agent_response = agent
-                            .execute_task_with_context(
-                                &agent_task,
+                            .execute_task_with_deadline(
+                                &agent_input,
                                 context,
                                 self.settings.agent.max_iterations,
+                                deadline,
                             )
                             .await;
+                        drop(permit);
+
+                        if agent.keeps_scratchpad() {
+                            scratchpads
+                                .entry(agent_name.clone())
+                                .or_default()
+                                .push(summarize_invocation(&agent_input, &agent_response));
+                        }
+
+                        agent_iterations_used += agent_response.steps().len();
+                        agent_tokens_used += agent_response
+                            .metadata()
+                            .and_then(|m| m.tokens_used)
+                            .unwrap_or(0) as u64;
 
                         // Validate handoff if coordinator is configured
                         if let Some(coordinator) = &self.handoff_coordinator {
@@ -479,17 +1057,17 @@ impl SupervisorAgent {
                                 }
 
                                 // Mark sub-goal as failed due to validation
-                                task_progress.mark_failed(
-                                    &sub_goal_id,
-                                    format!(
-                                        "Validation failed: {}",
-                                        validation
-                                            .errors
-                                            .iter()
-                                            .map(|e| format!("{}: {}", e.field, e.message))
-                                            .collect::<Vec<_>>()
-                                            .join(", ")
-                                    ),
+                                let validation_error =
+                                    format!("Validation failed: {}", validation.error_summary());
+                                task_progress.mark_failed(&sub_goal_id, validation_error.clone());
+                                report(
+                                    progress,
+                                    task_progress,
+                                    SupervisorEvent::SubGoalFailed {
+                                        sub_goal_id: sub_goal_id.clone(),
+                                        agent: agent_name.clone(),
+                                        error: validation_error.clone(),
+                                    },
                                 );
 
                                 // Add failure step
@@ -502,19 +1080,23 @@ impl SupervisorAgent {
                                     action: Some(format!("{}:{}", agent_name, agent_task)),
                                     observation: Some(format!(
                                         "VALIDATION FAILED: {}",
-                                        validation
-                                            .errors
-                                            .iter()
-                                            .map(|e| format!("{}: {}", e.field, e.message))
-                                            .collect::<Vec<_>>()
-                                            .join(", ")
+                                        validation.error_summary()
                                     )),
                                 });
 
+                                if self.failure_policy == FailurePolicy::FailFast {
+                                    return Self::failure(
+                                        format!(
+                                            "Sub-goal '{}' failed: {}",
+                                            sub_goal_id, validation_error
+                                        ),
+                                        all_steps,
+                                        false,
+                                    );
+                                }
+
                                 // Continue to next step (supervisor can retry or adjust)
-                                conversation_history.push(ChatMessage {
-                                    role: "user".to_string(),
-                                    content: format!(
+                                conversation_history.push(ChatMessage::user(format!(
                                         "Agent '{}' completed but validation FAILED:\n{}\n\n\
                                          The output does not meet quality standards. You should either:\n\
                                          1. Retry with more specific instructions\n\
@@ -525,8 +1107,7 @@ impl SupervisorAgent {
                                             .map(|e| format!("  ✗ {}: {}", e.field, e.message))
                                             .collect::<Vec<_>>()
                                             .join("\n")
-                                    ),
-                                });
+                                    )));
 
                                 continue;
                             } else {
@@ -551,6 +1132,15 @@ impl SupervisorAgent {
                             } => {
                                 agent_results.push((agent_name.clone(), result.clone()));
                                 task_progress.mark_completed(&sub_goal_id, result.clone());
+                                report(
+                                    progress,
+                                    task_progress,
+                                    SupervisorEvent::SubGoalCompleted {
+                                        sub_goal_id: sub_goal_id.clone(),
+                                        agent: agent_name.clone(),
+                                        result: result.clone(),
+                                    },
+                                );
 
                                 // Store result in context for future agents
                                 // Try to parse as JSON, otherwise store as string
@@ -559,6 +1149,7 @@ impl SupervisorAgent {
                                         .unwrap_or_else(|_| {
                                             serde_json::Value::String(result.clone())
                                         });
+                                sub_goal_outputs.insert(sub_goal_id.clone(), result_value.clone());
                                 agent_results_context
                                     .insert(format!("{}_output", agent_name), result_value);
                                 tracing::debug!(
@@ -567,7 +1158,8 @@ impl SupervisorAgent {
                                 );
 
                                 // Check if all sub-goals are now complete
-                                if task_progress.is_complete()
+                                if self.final_schema.is_none()
+                                    && task_progress.all_completed()
                                     && !task_progress.sub_goals.is_empty()
                                 {
                                     tracing::info!("[SupervisorAgent] All sub-goals completed after this success - finalizing");
@@ -651,6 +1243,14 @@ impl SupervisorAgent {
                                     };
                                 format!("TIMEOUT{}: {}", progress_info, partial_result)
                             }
+                            // A supervised run has no one to answer, so the call stays unapproved
+                            AgentResponse::NeedsInput { prompt, .. } => {
+                                task_progress.mark_failed(&sub_goal_id, prompt.clone());
+                                format!(
+                                    "NEEDS INPUT (not available in supervised runs): {}",
+                                    prompt
+                                )
+                            }
                         };
 
                         tracing::info!(
@@ -658,25 +1258,57 @@ impl SupervisorAgent {
                             agent_name,
                             result_summary
                         );
+                        if !matches!(agent_response, AgentResponse::Success { .. }) {
+                            report(
+                                progress,
+                                task_progress,
+                                SupervisorEvent::SubGoalFailed {
+                                    sub_goal_id: sub_goal_id.clone(),
+                                    agent: agent_name.clone(),
+                                    error: result_summary.clone(),
+                                },
+                            );
+
+                            if self.failure_policy == FailurePolicy::FailFast {
+                                all_steps.push(AgentStep {
+                                    iteration: step,
+                                    thought: decision.thought,
+                                    action: Some(format!("{}:{}", agent_name, agent_task)),
+                                    observation: Some(result_summary.clone()),
+                                });
+                                return Self::failure(
+                                    format!(
+                                        "Sub-goal '{}' failed: {}",
+                                        sub_goal_id, result_summary
+                                    ),
+                                    all_steps,
+                                    false,
+                                );
+                            }
+                        }
 
                         // Add supervisor's decision to conversation
-                        conversation_history.push(ChatMessage {
-                            role: "assistant".to_string(),
-                            content: serde_json::to_string(&SupervisorDecision {
+                        conversation_history.push(ChatMessage::assistant(
+                            serde_json::to_string(&SupervisorDecision {
                                 thought: decision.thought.clone(),
                                 sub_goals: None, // Already declared, don't repeat
                                 agent_to_invoke: Some(agent_name.clone()),
                                 agent_task: Some(agent_task.clone()),
                                 sub_goal_id: Some(sub_goal_id.clone()),
+                                depends_on: decision.depends_on.clone(),
                                 is_final: false,
                                 final_answer: None,
                             })
                             .unwrap_or_else(|_| format!("Invoking {}", agent_name)),
-                        });
+                        ));
 
                         // Add agent result to conversation with progress tracking
                         let remaining_after_this = max_orchestration_steps - step - 1;
-                        let urgency_msg = if remaining_after_this <= 2 {
+                        let urgency_msg = if remaining_after_this <= 2
+                            && task_progress.is_complete()
+                        {
+                            format!("\n\nWARNING: Only {} orchestration steps remaining! All required sub-goals are done; skip the optional ones and provide the final answer.", remaining_after_this)
+                        } else if remaining_after_this <= 2 {
                             format!("\n\nWARNING: Only {} orchestration steps remaining! You must finalize the task soon or provide a final answer with the results you have.", remaining_after_this)
                         } else {
                             format!(
@@ -687,16 +1319,13 @@ impl SupervisorAgent {
 
                         let progress_status = task_progress.detailed_status();
 
-                        conversation_history.push(ChatMessage {
-                            role: "user".to_string(),
-                            content: format!(
+                        conversation_history.push(ChatMessage::user(format!(
                                 "Agent '{}' completed the task.\nResult: {}{}\n{}\n\n\
                                  Based on this result and progress, what should happen next?\n\
                                  IMPORTANT: If the next agent needs this result as input, you MUST copy the complete result data into the agent_task field!\n\
                                  If all sub-goals are complete, set is_final=true and provide the final_answer.",
                                 agent_name, result_summary, urgency_msg, progress_status
-                            ),
-                        });
+                            )));
 
                         all_steps.push(AgentStep {
                             iteration: step,
@@ -709,10 +1338,8 @@ impl SupervisorAgent {
                         let error_msg = format!("Agent '{}' not found", agent_name);
                         tracing::error!("[SupervisorAgent] {}", error_msg);
 
-                        conversation_history.push(ChatMessage {
-                            role: "user".to_string(),
-                            content: format!("Error: {}", error_msg),
-                        });
+                        conversation_history
+                            .push(ChatMessage::user(format!("Error: {}", error_msg)));
 
                         all_steps.push(AgentStep {
                             iteration: step,
@@ -728,15 +1355,12 @@ impl SupervisorAgent {
                     "Supervisor must either invoke an agent or mark task as final".to_string();
                 tracing::warn!("[SupervisorAgent] {}", warning);
 
-                conversation_history.push(ChatMessage {
-                    role: "user".to_string(),
-                    content: format!(
-                        "{}\nPlease either:\n\
+                conversation_history.push(ChatMessage::user(format!(
+                    "{}\nPlease either:\n\
                          1. Invoke an agent with a specific task, OR\n\
                          2. Set is_final=true if the task is complete",
-                        warning
-                    ),
-                });
+                    warning
+                )));
 
                 all_steps.push(AgentStep {
                     iteration: step,
@@ -754,6 +1378,39 @@ impl SupervisorAgent {
             task_progress.progress_summary()
         );
 
+        // Out of steps with only optional sub-goals left: the task is done
+        if self.final_schema.is_none() && task_progress.is_complete() {
+            let combined_results: Vec<String> = task_progress
+                .sub_goals
+                .iter()
+                .filter(|g| matches!(g.status, SubGoalStatus::Completed))
+                .filter_map(|g| g.result.clone())
+                .collect();
+            let skipped: Vec<&str> = task_progress
+                .sub_goals
+                .iter()
+                .filter(|g| !matches!(g.status, SubGoalStatus::Completed))
+                .map(|g| g.description.as_str())
+                .collect();
+
+            let final_answer = format!(
+                "Task completed successfully. All required sub-goals accomplished (skipped optional: {}):\n\n{}",
+                skipped.join(", "),
+                combined_results.join("\n\n")
+            );
+            tracing::info!(
+                "[SupervisorAgent] Required sub-goals complete, skipping {} optional",
+                skipped.len()
+            );
+
+            return AgentResponse::Success {
+                result: final_answer,
+                steps: all_steps,
+                metadata: None,
+                completion_status: Some(CompletionStatus::Complete { confidence: 0.9 }),
+            };
+        }
+
         let progress = task_progress.progress_percentage();
 
         AgentResponse::Timeout {
@@ -774,12 +1431,140 @@ impl SupervisorAgent {
         }
     }
 
+    /// Parse a final answer as JSON and validate it against the final schema
+    ///
+    /// Returns the parsed value, or a readable list of violations.
+    fn check_final_answer(
+        final_answer: &str,
+        schema: &serde_json::Value,
+    ) -> Result<serde_json::Value, String> {
+        let parsed = serde_json::from_str::<serde_json::Value>(final_answer)
+            .ok()
+            .or_else(|| {
+                let start = final_answer.find('{')?;
+                let end = final_answer.rfind('}')?;
+                serde_json::from_str(final_answer.get(start..=end)?).ok()
+            })
+            .ok_or_else(|| "final_answer is not valid JSON".to_string())?;
+
+        let validation = validate_json_schema(&parsed, schema);
+        if validation.valid {
+            Ok(parsed)
+        } else {
+            Err(validation.error_summary())
+        }
+    }
+
+    /// Failure response ending the orchestration, e.g. one rejected by the
+    /// failure policy or a final answer that never matched the schema
+    fn failure(error: String, steps: Vec<AgentStep>, recoverable: bool) -> AgentResponse {
+        AgentResponse::Failure {
+            error: error.clone(),
+            steps,
+            metadata: None,
+            completion_status: Some(CompletionStatus::Failed { error, recoverable }),
+        }
+    }
+
+    /// Stop orchestrating and return whatever the completed sub-goals produced
+    fn finalize_early(
+        reason: String,
+        next_step: &str,
+        step: usize,
+        task_progress: &TaskProgress,
+        mut all_steps: Vec<AgentStep>,
+        tokens_used: u64,
+    ) -> AgentResponse {
+        let completed_results: Vec<String> = task_progress
+            .sub_goals
+            .iter()
+            .filter(|g| matches!(g.status, SubGoalStatus::Completed))
+            .filter_map(|g| g.result.clone())
+            .collect();
+
+        let partial_result = if completed_results.is_empty() {
+            format!(
+                "Supervisor stopped early: {}. No sub-goals completed.",
+                reason
+            )
+        } else {
+            format!(
+                "Supervisor stopped early: {}. {}\n\n{}",
+                reason,
+                task_progress.progress_summary(),
+                completed_results.join("\n\n")
+            )
+        };
+
+        all_steps.push(AgentStep {
+            iteration: step,
+            thought: format!("Stopping early: {}", reason),
+            action: None,
+            observation: Some(partial_result.clone()),
+        });
+
+        AgentResponse::Timeout {
+            partial_result,
+            steps: all_steps,
+            metadata: Some(OutputMetadata {
+                confidence: task_progress.progress_percentage(),
+                tokens_used: Some(tokens_used.min(u32::MAX as u64) as u32),
+                ..Default::default()
+            }),
+            completion_status: Some(CompletionStatus::Partial {
+                progress: task_progress.progress_percentage(),
+                next_steps: vec![
+                    next_step.to_string(),
+                    format!("Resume from: {}", task_progress.detailed_status()),
+                ],
+            }),
+        }
+    }
+
+    /// Append the outputs of completed upstream sub-goals to an agent task
+    ///
+    /// Outputs are embedded as JSON under the `upstream` key, keyed by sub-goal
+    /// id, so structured results reach the agent intact instead of being
+    /// re-typed by the supervisor. Dependencies without a result are skipped.
+    fn attach_upstream_outputs(
+        agent_task: &str,
+        dependencies: &[String],
+        sub_goal_outputs: &HashMap<String, serde_json::Value>,
+    ) -> String {
+        let mut upstream = serde_json::Map::new();
+        for id in dependencies {
+            match sub_goal_outputs.get(id) {
+                Some(output) => {
+                    upstream.insert(id.clone(), output.clone());
+                }
+                None => tracing::warn!(
+                    "[SupervisorAgent] Dependency '{}' has no completed output, skipping",
+                    id
+                ),
+            }
+        }
+
+        if upstream.is_empty() {
+            return agent_task.to_string();
+        }
+
+        let payload = serde_json::json!({ "upstream": upstream });
+        format!(
+            "{}\n\nUPSTREAM DATA (outputs of earlier sub-goals, use these values as-is):\n```json\n{}\n```",
+            agent_task,
+            serde_json::to_string_pretty(&payload).unwrap_or_else(|_| payload.to_string())
+        )
+    }
+
     /// Ask supervisor LLM to decide next action
     async fn decide_next_action(
         &self,
         conversation: &[ChatMessage],
     ) -> anyhow::Result<SupervisorDecision> {
-        let response = self.llm_client.chat(conversation.to_vec()).await?;
+        let response = self
+            .llm_client
+            .chat_with_params(conversation.to_vec(), &self.planning_params)
+            .await?;
 
         // Try to parse JSON response
         match serde_json::from_str::<SupervisorDecision>(&response) {
@@ -814,6 +1599,7 @@ impl SupervisorAgent {
                     agent_to_invoke: None,
                     agent_task: None,
                     sub_goal_id: None,
+                    depends_on: None,
                     is_final: false,
                     final_answer: None,
                 })
@@ -821,3 +1607,839 @@ impl SupervisorAgent {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actors::specialized_agent::SpecializedAgentConfig;
+    use crate::core::mock_llm::MockLLM;
+    use serde_json::json;
+    use std::sync::Arc;
+
+    /// Scripted backend replying in order, each reply reporting 50 tokens
+    fn scripted_llm(replies: Vec<serde_json::Value>) -> Arc<MockLLM> {
+        Arc::new(MockLLM::new(replies.iter().map(|r| r.to_string())).with_tokens_per_reply(50))
+    }
+
+    fn client_for(llm: &Arc<MockLLM>, settings: &Settings) -> LLMClient {
+        LLMClient::with_backend(llm.clone(), settings.clone())
+    }
+
+    fn mock_config(name: &str) -> SpecializedAgentConfig {
+        SpecializedAgentConfig {
+            name: name.to_string(),
+            description: format!("Mock agent {}", name),
+            system_prompt: "You are a mock agent".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn mock_agent(name: &str, llm: &Arc<MockLLM>, settings: &Settings) -> SpecializedAgent {
+        SpecializedAgent::with_llm_client(mock_config(name), client_for(llm, settings))
+    }
+
+    #[tokio::test]
+    async fn test_token_budget_finalizes_early_with_partial_result() {
+        let settings = Settings::new().unwrap();
+        let llm = scripted_llm(vec![
+            // Supervisor plans two sub-goals and invokes the first agent
+            json!({
+                "thought": "plan",
+                "sub_goals": [
+                    {"id": "goal_1", "description": "fetch"},
+                    {"id": "goal_2", "description": "report"}
+                ],
+                "agent_to_invoke": "fetch_agent",
+                "agent_task": "fetch the data",
+                "sub_goal_id": "goal_1",
+                "is_final": false,
+                "final_answer": null
+            }),
+            // The agent finishes in a single iteration
+            json!({
+                "thought": "done",
+                "action": null,
+                "is_final": true,
+                "final_answer": "fetched 42 rows"
+            }),
+        ]);
+
+        let supervisor = SupervisorAgent::new(
+            vec![
+                mock_agent("fetch_agent", &llm, &settings),
+                mock_agent("report_agent", &llm, &settings),
+            ],
+            client_for(&llm, &settings),
+            settings.clone(),
+        )
+        .with_budget(SupervisorBudget {
+            max_total_agent_iterations: None,
+            max_total_tokens: Some(10),
+            ..Default::default()
+        });
+
+        let response = supervisor.orchestrate("fetch and report", 5).await;
+
+        match response {
+            AgentResponse::Timeout {
+                partial_result,
+                completion_status,
+                metadata,
+                ..
+            } => {
+                assert!(partial_result.contains("token budget exhausted"));
+                assert!(partial_result.contains("fetched 42 rows"));
+                assert!(matches!(
+                    completion_status,
+                    Some(CompletionStatus::Partial { progress, .. }) if progress == 0.5
+                ));
+                assert_eq!(metadata.unwrap().tokens_used, Some(100));
+            }
+            other => panic!("expected partial result, got {:?}", other),
+        }
+
+        // The supervisor must not have asked the LLM for another decision
+        assert_eq!(llm.requests().len(), 2);
+    }
+
+    #[test]
+    fn test_budget_limits() {
+        let unbounded = SupervisorBudget::default();
+        assert!(unbounded.exceeded_by(1_000, 1_000_000).is_none());
+
+        let budget = SupervisorBudget {
+            max_total_agent_iterations: Some(3),
+            max_total_tokens: Some(500),
+            max_invocations_per_agent: Some(2),
+        };
+        assert!(budget.exceeded_by(2, 499).is_none());
+        assert!(budget.exceeded_by(3, 0).unwrap().contains("iteration"));
+        assert!(budget.exceeded_by(0, 500).unwrap().contains("token"));
+        assert!(unbounded.allows_invocation(usize::MAX));
+        assert!(budget.allows_invocation(1));
+        assert!(!budget.allows_invocation(2));
+    }
+
+    #[tokio::test]
+    async fn test_final_schema_reprompts_once_on_mismatch() {
+        let settings = Settings::new().unwrap();
+        let llm = scripted_llm(vec![
+            json!({
+                "thought": "answer directly",
+                "is_final": true,
+                "final_answer": {"summary": "all good"}
+            }),
+            json!({
+                "thought": "add the score",
+                "is_final": true,
+                "final_answer": {"summary": "all good", "score": 0.8}
+            }),
+        ]);
+
+        let supervisor =
+            SupervisorAgent::new(vec![], client_for(&llm, &settings), settings.clone())
+                .with_final_schema(json!({
+                    "type": "object",
+                    "properties": {
+                        "summary": {"type": "string"},
+                        "score": {"type": "number"}
+                    },
+                    "required": ["summary", "score"]
+                }));
+
+        let response = supervisor.orchestrate("summarize", 5).await;
+
+        match response {
+            AgentResponse::Success { result, steps, .. } => {
+                let value: serde_json::Value = serde_json::from_str(&result).unwrap();
+                assert_eq!(value["summary"], "all good");
+                assert_eq!(value["score"], 0.8);
+                assert!(steps[0].observation.as_deref().unwrap().contains("score"));
+            }
+            other => panic!("expected success, got {:?}", other),
+        }
+
+        let requests = llm.requests();
+        assert_eq!(requests.len(), 2);
+        let retry_prompt = &requests[1].messages.last().unwrap().content;
+        assert!(retry_prompt.contains("does not match the required schema"));
+    }
+
+    #[tokio::test]
+    async fn test_sub_goals_past_limit_are_reported_as_dropped() {
+        let mut settings = Settings::new().unwrap();
+        settings.agent.max_sub_goals = 2;
+        let llm = scripted_llm(vec![json!({
+            "thought": "plan, then answer",
+            "sub_goals": [
+                {"id": "fetch", "description": "Fetch the data"},
+                {"id": "clean", "description": "Clean the data"},
+                {"id": "chart", "description": "Chart the data"},
+                {"id": "report", "description": "Write the report"}
+            ],
+            "is_final": true,
+            "final_answer": "done"
+        })]);
+
+        let supervisor =
+            SupervisorAgent::new(vec![], client_for(&llm, &settings), settings.clone());
+        let response = supervisor.orchestrate("analyze", 3).await;
+
+        let result = crate::api::agent::AgentResult::from_response(response);
+        assert!(result.success);
+        assert!(result.plan_truncated());
+        assert_eq!(
+            result.dropped_sub_goals,
+            ["Chart the data", "Write the report"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_progress_events_follow_sub_goal_lifecycle() {
+        let settings = Settings::new().unwrap();
+        let done = |answer: &str| json!({"thought": "done", "action": null, "is_final": true, "final_answer": answer});
+        let llm = scripted_llm(vec![
+            json!({
+                "thought": "plan",
+                "sub_goals": [
+                    {"id": "goal_1", "description": "fetch"},
+                    {"id": "goal_2", "description": "report"}
+                ],
+                "agent_to_invoke": "fetch_agent",
+                "agent_task": "fetch the rows",
+                "sub_goal_id": "goal_1",
+                "is_final": false,
+                "final_answer": null
+            }),
+            done("42 rows"),
+            json!({
+                "thought": "report",
+                "sub_goals": null,
+                "agent_to_invoke": "report_agent",
+                "agent_task": "summarize the rows",
+                "sub_goal_id": "goal_2",
+                "is_final": false,
+                "final_answer": null
+            }),
+            done("report written"),
+        ]);
+
+        let supervisor = SupervisorAgent::new(
+            vec![
+                mock_agent("fetch_agent", &llm, &settings),
+                mock_agent("report_agent", &llm, &settings),
+            ],
+            client_for(&llm, &settings),
+            settings.clone(),
+        );
+
+        let (tx, mut rx) = mpsc::channel(32);
+        let response = supervisor
+            .orchestrate_with_progress("fetch and report", 5, tx)
+            .await;
+        assert!(matches!(response, AgentResponse::Success { .. }));
+
+        let mut updates = Vec::new();
+        while let Some(update) = rx.recv().await {
+            updates.push(update);
+        }
+
+        let lifecycle: Vec<(&str, &str)> = updates
+            .iter()
+            .map(|update| match &update.event {
+                SupervisorEvent::SubGoalDeclared { sub_goal_id, .. } => {
+                    ("declared", sub_goal_id.as_str())
+                }
+                SupervisorEvent::SubGoalStarted { sub_goal_id, .. } => {
+                    ("started", sub_goal_id.as_str())
+                }
+                SupervisorEvent::SubGoalCompleted { sub_goal_id, .. } => {
+                    ("completed", sub_goal_id.as_str())
+                }
+                SupervisorEvent::SubGoalFailed { sub_goal_id, .. } => {
+                    ("failed", sub_goal_id.as_str())
+                }
+            })
+            .collect();
+        assert_eq!(
+            lifecycle,
+            [
+                ("declared", "goal_1"),
+                ("declared", "goal_2"),
+                ("started", "goal_1"),
+                ("completed", "goal_1"),
+                ("started", "goal_2"),
+                ("completed", "goal_2"),
+            ]
+        );
+
+        assert_eq!(updates[3].progress_percentage, 0.5);
+        assert_eq!(updates[5].progress_percentage, 1.0);
+        assert!(updates[5].detailed_status.contains("[✓] report"));
+        assert!(matches!(
+            &updates[3].event,
+            SupervisorEvent::SubGoalCompleted { agent, result, .. }
+                if agent == "fetch_agent" && result == "42 rows"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_full_progress_channel_does_not_stall_orchestration() {
+        let settings = Settings::new().unwrap();
+        let llm = scripted_llm(vec![
+            json!({
+                "thought": "plan",
+                "sub_goals": [{"id": "goal_1", "description": "fetch"}],
+                "agent_to_invoke": "fetch_agent",
+                "agent_task": "fetch the rows",
+                "sub_goal_id": "goal_1",
+                "is_final": false,
+                "final_answer": null
+            }),
+            json!({"thought": "done", "action": null, "is_final": true, "final_answer": "42 rows"}),
+        ]);
+        let supervisor = SupervisorAgent::new(
+            vec![mock_agent("fetch_agent", &llm, &settings)],
+            client_for(&llm, &settings),
+            settings.clone(),
+        );
+
+        // Nobody drains the channel while the supervisor runs
+        let (tx, mut rx) = mpsc::channel(1);
+        let response = tokio::time::timeout(
+            Duration::from_secs(5),
+            supervisor.orchestrate_with_progress("fetch", 5, tx),
+        )
+        .await
+        .expect("orchestration blocked on the progress channel");
+        assert!(matches!(response, AgentResponse::Success { .. }));
+
+        // Only the first event fit; the rest were dropped
+        assert!(matches!(
+            rx.recv().await.unwrap().event,
+            SupervisorEvent::SubGoalDeclared { .. }
+        ));
+        assert!(rx.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_pending_optional_sub_goal_does_not_block_completion() {
+        let settings = Settings::new().unwrap();
+        let llm = scripted_llm(vec![
+            json!({
+                "thought": "plan",
+                "sub_goals": [
+                    {"id": "goal_1", "description": "fetch"},
+                    {"id": "goal_2", "description": "chart", "optional": true}
+                ],
+                "agent_to_invoke": "fetch_agent",
+                "agent_task": "fetch the rows",
+                "sub_goal_id": "goal_1",
+                "is_final": false,
+                "final_answer": null
+            }),
+            json!({"thought": "done", "action": null, "is_final": true, "final_answer": "42 rows"}),
+            // The supervisor stalls on its last step without touching the chart
+            json!({"thought": "hmm", "is_final": false}),
+        ]);
+
+        let supervisor = SupervisorAgent::new(
+            vec![mock_agent("fetch_agent", &llm, &settings)],
+            client_for(&llm, &settings),
+            settings.clone(),
+        );
+        let response = supervisor.orchestrate("fetch and chart", 2).await;
+
+        match response {
+            AgentResponse::Success {
+                result,
+                completion_status,
+                ..
+            } => {
+                assert!(result.contains("42 rows"));
+                assert!(result.contains("skipped optional: chart"));
+                assert!(matches!(
+                    completion_status,
+                    Some(CompletionStatus::Complete { .. })
+                ));
+            }
+            other => panic!("expected success, got {:?}", other),
+        }
+
+        // The follow-up prompt told the supervisor the chart could be skipped
+        let requests = llm.requests();
+        let follow_up = &requests[2].messages.last().unwrap().content;
+        assert!(follow_up.contains("chart (optional)"));
+        assert!(follow_up.contains("skip the optional ones"));
+    }
+
+    #[tokio::test]
+    async fn test_fail_fast_stops_at_first_failed_sub_goal() {
+        let settings = Settings::new().unwrap();
+        let llm = scripted_llm(vec![
+            json!({
+                "thought": "plan",
+                "sub_goals": [
+                    {"id": "goal_1", "description": "fetch"},
+                    {"id": "goal_2", "description": "report"}
+                ],
+                "agent_to_invoke": "fetch_agent",
+                "agent_task": "fetch the rows",
+                "sub_goal_id": "goal_1",
+                "is_final": false,
+                "final_answer": null
+            }),
+            // Never reached: the supervisor would otherwise carry on
+            json!({"thought": "ignore it", "is_final": true, "final_answer": "all good"}),
+        ]);
+
+        // The agent's backend has nothing scripted, so its first call fails
+        let fetch_agent = mock_agent("fetch_agent", &scripted_llm(vec![]), &settings);
+        let supervisor = SupervisorAgent::new(
+            vec![fetch_agent],
+            client_for(&llm, &settings),
+            settings.clone(),
+        )
+        .with_failure_policy(FailurePolicy::FailFast);
+
+        let response = supervisor.orchestrate("fetch and report", 5).await;
+
+        match response {
+            AgentResponse::Failure {
+                error,
+                steps,
+                completion_status,
+                ..
+            } => {
+                assert!(error.starts_with("Sub-goal 'goal_1' failed"), "{}", error);
+                assert_eq!(steps.len(), 1);
+                assert!(matches!(
+                    completion_status,
+                    Some(CompletionStatus::Failed {
+                        recoverable: false,
+                        ..
+                    })
+                ));
+            }
+            other => panic!("expected failure, got {:?}", other),
+        }
+        assert_eq!(llm.remaining(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_structured_results_expose_each_sub_goal_by_id() {
+        let settings = Settings::new().unwrap();
+        let rows = json!({"rows": [{"id": 1}, {"id": 2}]});
+        let done = |answer: serde_json::Value| json!({"thought": "done", "action": null, "is_final": true, "final_answer": answer});
+        let llm = scripted_llm(vec![
+            json!({
+                "thought": "plan",
+                "sub_goals": [
+                    {"id": "goal_1", "description": "fetch"},
+                    {"id": "goal_2", "description": "report"}
+                ],
+                "agent_to_invoke": "fetch_agent",
+                "agent_task": "fetch the rows",
+                "sub_goal_id": "goal_1",
+                "is_final": false,
+                "final_answer": null
+            }),
+            done(rows.clone()),
+            json!({
+                "thought": "report",
+                "sub_goals": null,
+                "agent_to_invoke": "report_agent",
+                "agent_task": "count the rows",
+                "sub_goal_id": "goal_2",
+                "is_final": false,
+                "final_answer": null
+            }),
+            done(json!("2 rows")),
+        ]);
+
+        let supervisor = SupervisorAgent::new(
+            vec![
+                mock_agent("fetch_agent", &llm, &settings),
+                mock_agent("report_agent", &llm, &settings),
+            ],
+            client_for(&llm, &settings),
+            settings.clone(),
+        )
+        .with_structured_results(true);
+        let response = supervisor.orchestrate("fetch and report", 5).await;
+
+        let result = crate::api::agent::AgentResult::from_response(response);
+        assert!(result.success);
+        assert!(result.result.contains("2 rows"));
+        assert_eq!(result.sub_goal_result("goal_1"), Some(&rows));
+        assert_eq!(result.sub_goal_result("goal_2"), Some(&json!("2 rows")));
+        assert_eq!(result.sub_goal_results.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_invocation_cap_refuses_repeated_agent() {
+        let settings = Settings::new().unwrap();
+        let invoke_fetch = json!({
+            "thought": "fetch again",
+            "sub_goals": null,
+            "agent_to_invoke": "fetch_agent",
+            "agent_task": "fetch the data",
+            "sub_goal_id": "goal_1",
+            "is_final": false,
+            "final_answer": null
+        });
+        let llm = scripted_llm(vec![
+            json!({
+                "thought": "plan",
+                "sub_goals": [
+                    {"id": "goal_1", "description": "fetch"},
+                    {"id": "goal_2", "description": "report"}
+                ],
+                "agent_to_invoke": "fetch_agent",
+                "agent_task": "fetch the data",
+                "sub_goal_id": "goal_1",
+                "is_final": false,
+                "final_answer": null
+            }),
+            json!({
+                "thought": "done",
+                "action": null,
+                "is_final": true,
+                "final_answer": "fetched 42 rows"
+            }),
+            // The supervisor insists on the same agent and is refused without a call
+            invoke_fetch,
+            json!({
+                "thought": "finalize with what we have",
+                "is_final": true,
+                "final_answer": "42 rows"
+            }),
+        ]);
+
+        let supervisor = SupervisorAgent::new(
+            vec![mock_agent("fetch_agent", &llm, &settings)],
+            client_for(&llm, &settings),
+            settings.clone(),
+        )
+        .with_budget(SupervisorBudget {
+            max_invocations_per_agent: Some(1),
+            ..Default::default()
+        });
+
+        let response = supervisor.orchestrate("fetch and report", 5).await;
+
+        match response {
+            AgentResponse::Success { result, steps, .. } => {
+                assert_eq!(result, "42 rows");
+                assert!(steps[1]
+                    .observation
+                    .as_deref()
+                    .unwrap()
+                    .contains("'fetch_agent' is exhausted"));
+            }
+            other => panic!("expected success, got {:?}", other),
+        }
+
+        let requests = llm.requests();
+        assert_eq!(requests.len(), 4);
+        let refusal = &requests[3].messages.last().unwrap().content;
+        assert!(refusal.contains("Do NOT invoke 'fetch_agent' again"));
+    }
+
+    #[tokio::test]
+    async fn test_depends_on_injects_upstream_json_into_agent_task() {
+        let settings = Settings::new().unwrap();
+        let rows = json!({"rows": [{"id": 1, "name": "widget"}, {"id": 2, "name": "gadget"}]});
+        let llm = scripted_llm(vec![
+            json!({
+                "thought": "plan",
+                "sub_goals": [
+                    {"id": "goal_1", "description": "fetch"},
+                    {"id": "goal_2", "description": "report"}
+                ],
+                "agent_to_invoke": "fetch_agent",
+                "agent_task": "fetch the rows",
+                "sub_goal_id": "goal_1",
+                "is_final": false,
+                "final_answer": null
+            }),
+            json!({
+                "thought": "fetched",
+                "action": null,
+                "is_final": true,
+                "final_answer": rows.clone()
+            }),
+            // The supervisor references the upstream result instead of copying it
+            json!({
+                "thought": "report on the rows",
+                "sub_goals": null,
+                "agent_to_invoke": "report_agent",
+                "agent_task": "summarize the rows",
+                "sub_goal_id": "goal_2",
+                "depends_on": ["goal_1"],
+                "is_final": false,
+                "final_answer": null
+            }),
+            json!({
+                "thought": "reported",
+                "action": null,
+                "is_final": true,
+                "final_answer": "2 products"
+            }),
+        ]);
+
+        let supervisor = SupervisorAgent::new(
+            vec![
+                mock_agent("fetch_agent", &llm, &settings),
+                mock_agent("report_agent", &llm, &settings),
+            ],
+            client_for(&llm, &settings),
+            settings.clone(),
+        );
+
+        let response = supervisor.orchestrate("fetch and report", 5).await;
+        assert!(matches!(response, AgentResponse::Success { .. }));
+
+        let requests = llm.requests();
+        let task_turn = &requests[3].messages[1].content;
+        assert!(task_turn.starts_with("Task: summarize the rows"));
+
+        let block = task_turn
+            .split("```json\n")
+            .nth(1)
+            .and_then(|rest| rest.split("\n```").next())
+            .unwrap();
+        let payload: serde_json::Value = serde_json::from_str(block).unwrap();
+        assert_eq!(payload["upstream"]["goal_1"], rows);
+    }
+
+    #[tokio::test]
+    async fn test_reinvoked_agent_sees_scratchpad_of_earlier_invocation() {
+        let settings = Settings::new().unwrap();
+        let llm = scripted_llm(vec![
+            json!({
+                "thought": "plan",
+                "sub_goals": [
+                    {"id": "goal_1", "description": "draft"},
+                    {"id": "goal_2", "description": "revise"}
+                ],
+                "agent_to_invoke": "writer",
+                "agent_task": "draft a haiku about rust",
+                "sub_goal_id": "goal_1",
+                "is_final": false,
+                "final_answer": null
+            }),
+            json!({
+                "thought": "wrote a first draft",
+                "action": null,
+                "is_final": true,
+                "final_answer": "iron oxide blooms"
+            }),
+            json!({
+                "thought": "needs polish",
+                "sub_goals": null,
+                "agent_to_invoke": "writer",
+                "agent_task": "revise your haiku",
+                "sub_goal_id": "goal_2",
+                "is_final": false,
+                "final_answer": null
+            }),
+            json!({
+                "thought": "revised",
+                "action": null,
+                "is_final": true,
+                "final_answer": "iron oxide blooms, softly"
+            }),
+        ]);
+
+        let writer = SpecializedAgent::with_llm_client(
+            SpecializedAgentConfig {
+                keep_scratchpad: true,
+                ..mock_config("writer")
+            },
+            client_for(&llm, &settings),
+        );
+        let supervisor =
+            SupervisorAgent::new(vec![writer], client_for(&llm, &settings), settings.clone());
+
+        let response = supervisor.orchestrate("write a haiku", 5).await;
+        assert!(matches!(response, AgentResponse::Success { .. }));
+
+        let requests = llm.requests();
+        assert!(!requests[1].messages[0].content.contains("scratchpad"));
+
+        let second_invocation = &requests[3].messages[0].content;
+        assert!(second_invocation.contains("\"scratchpad\""));
+        assert!(second_invocation.contains("Task: draft a haiku about rust"));
+        assert!(second_invocation.contains("Result: iron oxide blooms"));
+    }
+
+    /// Scripted backend that takes `delay` to answer each call
+    struct SlowLLM {
+        inner: Arc<MockLLM>,
+        delay: Duration,
+    }
+
+    #[async_trait::async_trait]
+    impl crate::core::llm::LLMBackend for SlowLLM {
+        async fn complete(
+            &self,
+            request: &crate::core::llm::CompletionRequest,
+        ) -> anyhow::Result<crate::core::llm::Completion> {
+            tokio::time::sleep(self.delay).await;
+            self.inner.complete(request).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_deadline_stops_orchestration_with_partial_result() {
+        let settings = Settings::new().unwrap();
+        let invoke = |agent: &str, goal: &str| {
+            json!({
+                "thought": "next",
+                "sub_goals": null,
+                "agent_to_invoke": agent,
+                "agent_task": format!("work on {}", goal),
+                "sub_goal_id": goal,
+                "is_final": false,
+                "final_answer": null
+            })
+        };
+        let done = |answer: &str| json!({"thought": "done", "action": null, "is_final": true, "final_answer": answer});
+        let script = scripted_llm(vec![
+            json!({
+                "thought": "plan",
+                "sub_goals": [
+                    {"id": "goal_1", "description": "fetch"},
+                    {"id": "goal_2", "description": "report"}
+                ],
+                "agent_to_invoke": "fetch_agent",
+                "agent_task": "fetch the rows",
+                "sub_goal_id": "goal_1",
+                "is_final": false,
+                "final_answer": null
+            }),
+            done("42 rows"),
+            invoke("report_agent", "goal_2"),
+            done("report written"),
+        ]);
+        let slow = Arc::new(SlowLLM {
+            inner: script.clone(),
+            delay: Duration::from_millis(100),
+        });
+        let agent = |name: &str| {
+            SpecializedAgent::with_llm_client(
+                mock_config(name),
+                LLMClient::with_backend(slow.clone(), settings.clone()),
+            )
+        };
+        let supervisor = SupervisorAgent::new(
+            vec![agent("fetch_agent"), agent("report_agent")],
+            LLMClient::with_backend(slow.clone(), settings.clone()),
+            settings.clone(),
+        );
+
+        // Expires while the first agent is working, so no second agent starts
+        let started = std::time::Instant::now();
+        let response = supervisor
+            .orchestrate_with_deadline(
+                "fetch and report",
+                5,
+                Deadline::after(Duration::from_millis(150)),
+            )
+            .await;
+
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert_eq!(script.remaining(), 2);
+        match response {
+            AgentResponse::Timeout {
+                partial_result,
+                completion_status,
+                ..
+            } => {
+                assert!(partial_result.contains("deadline exceeded"));
+                assert!(partial_result.contains("42 rows"));
+                assert!(matches!(
+                    completion_status,
+                    Some(CompletionStatus::Partial { .. })
+                ));
+            }
+            other => panic!("expected partial result, got {:?}", other),
+        }
+    }
+
+    /// Backend answering by request content so concurrent runs cannot interleave a script
+    ///
+    /// Agent calls (system prompt "You are a mock agent") sleep briefly and
+    /// record how many were in flight at once.
+    #[derive(Default)]
+    struct ConcurrencyProbeLLM {
+        active: std::sync::atomic::AtomicUsize,
+        peak: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl crate::core::llm::LLMBackend for ConcurrencyProbeLLM {
+        async fn complete(
+            &self,
+            request: &crate::core::llm::CompletionRequest,
+        ) -> anyhow::Result<crate::core::llm::Completion> {
+            use std::sync::atomic::Ordering;
+
+            let is_agent = request.messages[0].content.contains("You are a mock agent");
+            let reply = if is_agent {
+                let now = self.active.fetch_add(1, Ordering::SeqCst) + 1;
+                self.peak.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(30)).await;
+                self.active.fetch_sub(1, Ordering::SeqCst);
+                json!({"thought": "done", "is_final": true, "final_answer": "ok"})
+            } else if request
+                .messages
+                .last()
+                .is_some_and(|m| m.content.contains("Agent 'worker' completed"))
+            {
+                json!({"thought": "done", "is_final": true, "final_answer": "ok"})
+            } else {
+                json!({
+                    "thought": "delegate",
+                    "agent_to_invoke": "worker",
+                    "agent_task": "do the work",
+                    "is_final": false
+                })
+            };
+
+            Ok(crate::core::llm::Completion {
+                content: reply.to_string(),
+                tokens_used: 0,
+                tool_calls: Vec::new(),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_orchestrations_respect_agent_limit() {
+        let settings = Settings::new().unwrap();
+        let llm = Arc::new(ConcurrencyProbeLLM::default());
+        let agent_config = mock_config("worker");
+        let supervisor = SupervisorAgent::new(
+            vec![SpecializedAgent::with_llm_client(
+                agent_config,
+                LLMClient::with_backend(llm.clone(), settings.clone()),
+            )],
+            LLMClient::with_backend(llm.clone(), settings.clone()),
+            settings,
+        )
+        .with_max_concurrent_agents(2);
+
+        let tasks: Vec<String> = (0..6).map(|i| format!("task {}", i)).collect();
+        let responses =
+            futures::future::join_all(tasks.iter().map(|task| supervisor.orchestrate(task, 3)))
+                .await;
+
+        assert!(responses
+            .iter()
+            .all(|r| matches!(r, AgentResponse::Success { .. })));
+        let peak = llm.peak.load(std::sync::atomic::Ordering::SeqCst);
+        assert!((1..=2).contains(&peak), "peak concurrency was {}", peak);
+    }
+}
@@ -270,6 +270,118 @@ impl Default for OutputValidator {
     }
 }
 
+/// Validate a JSON value against a JSON Schema document
+///
+/// Supports the subset of JSON Schema used for structured LLM outputs:
+/// `type`, `required`, `properties`, `items` and `enum`. Unknown keywords
+/// are ignored.
+pub fn validate_json_schema(output: &Value, schema: &Value) -> ValidationResult {
+    let validator = OutputValidator::new();
+    let mut errors = Vec::new();
+    validator.check_json_schema(output, schema, "", &mut errors);
+
+    if errors.is_empty() {
+        ValidationResult::success()
+    } else {
+        ValidationResult::failure(errors)
+    }
+}
+
+impl OutputValidator {
+    fn check_json_schema(
+        &self,
+        value: &Value,
+        schema: &Value,
+        path: &str,
+        errors: &mut Vec<ValidationError>,
+    ) {
+        let field = if path.is_empty() { "$" } else { path };
+
+        if let Some(expected) = schema.get("type") {
+            let allowed: Vec<&str> = match expected {
+                Value::String(t) => vec![t.as_str()],
+                Value::Array(ts) => ts.iter().filter_map(|t| t.as_str()).collect(),
+                _ => vec![],
+            };
+            let matches = allowed.iter().any(|t| match *t {
+                "integer" => value.is_i64() || value.is_u64(),
+                other => self.check_type(value, other),
+            });
+            if !allowed.is_empty() && !matches {
+                errors.push(ValidationError {
+                    field: field.to_string(),
+                    error_type: "TypeMismatch".to_string(),
+                    message: format!(
+                        "Field '{}' has wrong type. Expected: {}, Actual: {}",
+                        field,
+                        allowed.join(" | "),
+                        self.get_value_type(value)
+                    ),
+                    expected: Some(allowed.join(" | ")),
+                    actual: Some(self.get_value_type(value)),
+                });
+                return;
+            }
+        }
+
+        if let Some(Value::Array(options)) = schema.get("enum") {
+            if !options.contains(value) {
+                errors.push(ValidationError {
+                    field: field.to_string(),
+                    error_type: "Enum".to_string(),
+                    message: format!("Field '{}' has invalid value: {}", field, value),
+                    expected: Some(Value::Array(options.clone()).to_string()),
+                    actual: Some(value.to_string()),
+                });
+            }
+        }
+
+        if let Some(object) = value.as_object() {
+            if let Some(Value::Array(required)) = schema.get("required") {
+                for name in required.iter().filter_map(|r| r.as_str()) {
+                    if !object.contains_key(name) {
+                        let child = join_path(path, name);
+                        errors.push(ValidationError {
+                            field: child.clone(),
+                            error_type: "MissingRequired".to_string(),
+                            message: format!("Required field '{}' is missing", child),
+                            expected: Some("present".to_string()),
+                            actual: Some("missing".to_string()),
+                        });
+                    }
+                }
+            }
+
+            if let Some(Value::Object(properties)) = schema.get("properties") {
+                for (name, property_schema) in properties {
+                    if let Some(child_value) = object.get(name) {
+                        self.check_json_schema(
+                            child_value,
+                            property_schema,
+                            &join_path(path, name),
+                            errors,
+                        );
+                    }
+                }
+            }
+        }
+
+        if let (Some(items), Some(item_schema)) = (value.as_array(), schema.get("items")) {
+            for (index, item) in items.iter().enumerate() {
+                self.check_json_schema(item, item_schema, &format!("{}[{}]", field, index), errors);
+            }
+        }
+    }
+}
+
+fn join_path(parent: &str, child: &str) -> String {
+    if parent.is_empty() {
+        child.to_string()
+    } else {
+        format!("{}.{}", parent, child)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -358,4 +470,31 @@ mod tests {
         assert!(!result.valid);
         assert_eq!(result.errors[0].error_type, "MinLength");
     }
+
+    #[test]
+    fn test_json_schema_validation() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "summary": {"type": "string"},
+                "score": {"type": "number"},
+                "tags": {"type": "array", "items": {"type": "string"}}
+            },
+            "required": ["summary", "score"]
+        });
+
+        let valid = json!({"summary": "ok", "score": 0.9, "tags": ["a"]});
+        assert!(validate_json_schema(&valid, &schema).valid);
+
+        let missing = json!({"summary": "ok"});
+        let result = validate_json_schema(&missing, &schema);
+        assert!(!result.valid);
+        assert_eq!(result.errors[0].field, "score");
+        assert_eq!(result.errors[0].error_type, "MissingRequired");
+
+        let wrong_item = json!({"summary": "ok", "score": 1, "tags": [3]});
+        let result = validate_json_schema(&wrong_item, &schema);
+        assert!(!result.valid);
+        assert_eq!(result.errors[0].field, "tags[0]");
+    }
 }
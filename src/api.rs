@@ -4,8 +4,10 @@
 //! that hides the complexity of the actor system underneath.
 
 use crate::actors::messages::*;
+use crate::actors::MessageRouterHandle;
+use crate::core::llm::{ImageSource, LLMParams, Role};
+use crate::error::Result;
 use crate::System;
-use anyhow::Result;
 use tokio::sync::oneshot;
 
 /// Simple chat function - just send a prompt and get a response
@@ -31,76 +33,106 @@ pub async fn chat_with_system(
     prompt: impl Into<String>,
     system_prompt: Option<String>,
 ) -> Result<String> {
-    let global_system = System::global();
-
     let mut messages = vec![];
 
     if let Some(sys) = system_prompt {
-        messages.push(ChatMessageData {
-            role: "system".to_string(),
-            content: sys,
-        });
+        messages.push(ChatMessageData::system(sys));
     }
 
-    messages.push(ChatMessageData {
-        role: "user".to_string(),
-        content: prompt.into(),
-    });
+    messages.push(ChatMessageData::user(prompt));
+
+    chat_with_messages(messages).await
+}
+
+/// Chat with a prebuilt message history
+///
+/// Messages are sent to the LLM exactly as given, so callers keep full
+/// control over roles and ordering. The other chat helpers build on this.
+pub async fn chat_with_messages(messages: Vec<ChatMessageData>) -> Result<String> {
+    send_chat(&System::global()?.router, messages).await
+}
 
+async fn send_chat(router: &MessageRouterHandle, messages: Vec<ChatMessageData>) -> Result<String> {
     let (tx, rx) = oneshot::channel();
     let request = ChatRequest {
+        request_id: RequestId::new(),
         messages,
         stream: false,
         response: tx,
     };
 
-    global_system
-        .router
+    router
         .send_message(RoutingMessage::LLM(LLMMessage::Chat(request)))
         .await?;
 
     match rx.await? {
         ChatResponse::Complete(content) => Ok(content),
-        ChatResponse::Error(e) => Err(anyhow::anyhow!(e)),
-        _ => Err(anyhow::anyhow!("Unexpected response")),
+        ChatResponse::Error(e) => Err(anyhow::anyhow!(e).into()),
+        ChatResponse::ProviderError(e) => Err(e.into()),
+        _ => Err(anyhow::anyhow!("Unexpected response").into()),
     }
 }
 
+/// Embed texts with the configured embedding model
+///
+/// Calls the provider directly rather than through the actor system, so it
+/// does not require `init()`. Vectors are returned in input order.
+pub async fn embed(texts: Vec<String>) -> Result<Vec<Vec<f32>>> {
+    use crate::config::Settings;
+    use crate::core::llm::LLMClient;
+
+    let settings = Settings::new()?;
+    let client = LLMClient::new(System::api_key(&settings)?, settings);
+    Ok(client.embed(texts).await?)
+}
+
 /// Stream chat responses token by token
+///
+/// Tokens are buffered up to `system.channel_buffer_size`; a slow callback
+/// holds back reading from the provider rather than losing tokens.
 pub async fn chat_stream(
     prompt: impl Into<String>,
     mut callback: impl FnMut(String),
 ) -> Result<String> {
-    let system = System::global();
+    let messages = vec![ChatMessageData::user(prompt)];
 
-    let messages = vec![ChatMessageData {
-        role: "user".to_string(),
-        content: prompt.into(),
-    }];
+    let mut tokens = stream_tokens(&System::global()?.router, messages).await?;
+    let mut full_response = String::new();
+    while let Some(token) = tokens.recv().await {
+        callback(token.clone());
+        full_response.push_str(&token);
+    }
+    Ok(full_response)
+}
 
+/// Ask the LLM actor for a streamed reply
+///
+/// A reply that arrives whole is delivered as a single token.
+pub(crate) async fn stream_tokens(
+    router: &MessageRouterHandle,
+    messages: Vec<ChatMessageData>,
+) -> Result<tokio::sync::mpsc::Receiver<String>> {
     let (tx, rx) = oneshot::channel();
     let request = ChatRequest {
+        request_id: RequestId::new(),
         messages,
         stream: true,
         response: tx,
     };
 
-    system
-        .router
+    router
         .send_message(RoutingMessage::LLM(LLMMessage::Chat(request)))
         .await?;
 
     match rx.await? {
-        ChatResponse::StreamTokens(mut stream_rx) => {
-            let mut full_response = String::new();
-            while let Some(token) = stream_rx.recv().await {
-                callback(token.clone());
-                full_response.push_str(&token);
-            }
-            Ok(full_response)
+        ChatResponse::StreamTokens(stream_rx) => Ok(stream_rx),
+        ChatResponse::Complete(content) => {
+            let (tx, rx) = tokio::sync::mpsc::channel(1);
+            let _ = tx.send(content).await;
+            Ok(rx)
         }
-        ChatResponse::Complete(content) => Ok(content),
-        ChatResponse::Error(e) => Err(anyhow::anyhow!(e)),
+        ChatResponse::Error(e) => Err(anyhow::anyhow!(e).into()),
+        ChatResponse::ProviderError(e) => Err(e.into()),
     }
 }
 
@@ -116,49 +148,41 @@ impl Conversation {
     }
 
     pub fn with_system(mut self, system: impl Into<String>) -> Self {
-        self.messages.push(ChatMessageData {
-            role: "system".to_string(),
-            content: system.into(),
-        });
+        self.messages.push(ChatMessageData::system(system));
         self
     }
 
     pub fn user(mut self, message: impl Into<String>) -> Self {
-        self.messages.push(ChatMessageData {
-            role: "user".to_string(),
-            content: message.into(),
-        });
+        self.messages.push(ChatMessageData::user(message));
         self
     }
 
     pub fn assistant(mut self, message: impl Into<String>) -> Self {
-        self.messages.push(ChatMessageData {
-            role: "assistant".to_string(),
-            content: message.into(),
-        });
+        self.messages.push(ChatMessageData::assistant(message));
         self
     }
 
-    pub async fn send(self) -> Result<String> {
-        let system = System::global();
-
-        let (tx, rx) = oneshot::channel();
-        let request = ChatRequest {
-            messages: self.messages,
-            stream: false,
-            response: tx,
-        };
+    /// Append a user message with images, for vision-capable models
+    pub fn user_with_images(
+        mut self,
+        message: impl Into<String>,
+        images: Vec<ImageSource>,
+    ) -> Self {
+        self.messages.push(
+            ChatMessageData::user(message)
+                .with_parts(images.iter().map(ImageSource::to_content_part).collect()),
+        );
+        self
+    }
 
-        system
-            .router
-            .send_message(RoutingMessage::LLM(LLMMessage::Chat(request)))
-            .await?;
+    /// Append a message with an explicit role
+    pub fn message(mut self, role: Role, content: impl Into<String>) -> Self {
+        self.messages.push(ChatMessageData::new(role, content));
+        self
+    }
 
-        match rx.await? {
-            ChatResponse::Complete(content) => Ok(content),
-            ChatResponse::Error(e) => Err(anyhow::anyhow!(e)),
-            _ => Err(anyhow::anyhow!("Unexpected response")),
-        }
+    pub async fn send(self) -> Result<String> {
+        chat_with_messages(self.messages).await
     }
 }
 
@@ -173,10 +197,11 @@ pub mod mcp {
     use super::*;
 
     pub async fn list_tools(server_command: &str, server_args: Vec<String>) -> Result<Vec<String>> {
-        let system = System::global();
+        let system = System::global()?;
 
         let (tx, rx) = oneshot::channel();
         let request = MCPListTools {
+            request_id: RequestId::new(),
             server_command: server_command.to_string(),
             server_args,
             response: tx,
@@ -189,8 +214,8 @@ pub mod mcp {
 
         match rx.await? {
             MCPResponse::Tools(tools) => Ok(tools),
-            MCPResponse::Error(e) => Err(anyhow::anyhow!(e)),
-            _ => Err(anyhow::anyhow!("Unexpected response")),
+            MCPResponse::Error(e) => Err(anyhow::anyhow!(e).into()),
+            _ => Err(anyhow::anyhow!("Unexpected response").into()),
         }
     }
 
@@ -200,10 +225,11 @@ pub mod mcp {
         tool_name: &str,
         arguments: serde_json::Value,
     ) -> Result<String> {
-        let system = System::global();
+        let system = System::global()?;
 
         let (tx, rx) = oneshot::channel();
         let request = MCPToolCall {
+            request_id: RequestId::new(),
             server_command: server_command.to_string(),
             server_args,
             tool_name: tool_name.to_string(),
@@ -218,8 +244,8 @@ pub mod mcp {
 
         match rx.await? {
             MCPResponse::Content(content) => Ok(content),
-            MCPResponse::Error(e) => Err(anyhow::anyhow!(e)),
-            _ => Err(anyhow::anyhow!("Unexpected response")),
+            MCPResponse::Error(e) => Err(anyhow::anyhow!(e).into()),
+            _ => Err(anyhow::anyhow!("Unexpected response").into()),
         }
     }
 }
@@ -227,7 +253,102 @@ pub mod mcp {
 /// Batch processing utilities
 pub mod batch {
     use super::*;
+    use crate::error::ActorusError;
     use futures::stream::{self, StreamExt};
+    use serde::Deserialize;
+    use std::time::{Duration, Instant};
+
+    /// One row of a structured batch job, e.g. an eval dataset entry
+    #[derive(Debug, Clone, PartialEq, Deserialize)]
+    pub struct BatchRecord {
+        pub id: String,
+        pub prompt: String,
+        /// Optional system prompt for this row only
+        #[serde(default, deserialize_with = "empty_as_none")]
+        pub system: Option<String>,
+    }
+
+    /// CSV leaves missing cells as empty strings rather than absent fields
+    fn empty_as_none<'de, D>(deserializer: D) -> std::result::Result<Option<String>, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let value = Option::<String>::deserialize(deserializer)?;
+        Ok(value.filter(|s| !s.trim().is_empty()))
+    }
+
+    /// Parse one JSON object per line, skipping blank lines
+    pub fn parse_jsonl(input: &str) -> Result<Vec<BatchRecord>> {
+        input
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| {
+                serde_json::from_str(line)
+                    .map_err(|e| ActorusError::Validation(format!("line {}: {}", i + 1, e)))
+            })
+            .collect()
+    }
+
+    /// Parse CSV with an `id,prompt,system` header row (`system` may be omitted)
+    pub fn parse_csv(input: &str) -> Result<Vec<BatchRecord>> {
+        csv::Reader::from_reader(input.as_bytes())
+            .deserialize()
+            .enumerate()
+            .map(|(i, record)| {
+                // Row 1 is the header
+                record.map_err(|e| ActorusError::Validation(format!("row {}: {}", i + 2, e)))
+            })
+            .collect()
+    }
+
+    /// Result of one batch record
+    #[derive(Debug)]
+    pub struct BatchOutcome {
+        /// Position of the record in the input
+        pub index: usize,
+        pub id: String,
+        pub result: Result<String>,
+        pub duration: Duration,
+    }
+
+    /// Run every record, pairing each result with its record's id
+    ///
+    /// Outcomes are returned in input order, whatever order they finish in.
+    pub async fn process_records(
+        records: Vec<BatchRecord>,
+        concurrency: usize,
+    ) -> Result<Vec<BatchOutcome>> {
+        Ok(process_records_with(&System::global()?.router, records, concurrency).await)
+    }
+
+    async fn process_records_with(
+        router: &MessageRouterHandle,
+        records: Vec<BatchRecord>,
+        concurrency: usize,
+    ) -> Vec<BatchOutcome> {
+        let mut outcomes: Vec<BatchOutcome> = stream::iter(records.into_iter().enumerate())
+            .map(|(index, record)| async move {
+                let started = Instant::now();
+                let mut messages = vec![];
+                if let Some(system) = record.system {
+                    messages.push(ChatMessageData::system(system));
+                }
+                messages.push(ChatMessageData::user(record.prompt));
+                let result = send_chat(router, messages).await;
+                BatchOutcome {
+                    index,
+                    id: record.id,
+                    result,
+                    duration: started.elapsed(),
+                }
+            })
+            .buffer_unordered(concurrency)
+            .collect()
+            .await;
+        outcomes.sort_by_key(|outcome| outcome.index);
+        outcomes
+    }
 
     pub async fn process_prompts(prompts: Vec<String>, concurrency: usize) -> Vec<Result<String>> {
         stream::iter(prompts)
@@ -247,13 +368,95 @@ pub mod batch {
             .collect()
             .await
     }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use std::collections::HashMap;
+        use std::io::Write;
+        use tokio::sync::mpsc::channel;
+
+        #[tokio::test]
+        async fn test_jsonl_records_map_ids_to_results() {
+            let mut file = tempfile::NamedTempFile::new().unwrap();
+            writeln!(file, r#"{{"id": "q1", "prompt": "2+2?"}}"#).unwrap();
+            writeln!(file).unwrap();
+            writeln!(
+                file,
+                r#"{{"id": "q2", "prompt": "Capital of France?", "system": "Be terse"}}"#
+            )
+            .unwrap();
+            let records = parse_jsonl(&std::fs::read_to_string(file.path()).unwrap()).unwrap();
+            assert_eq!(records.len(), 2);
+            assert_eq!(records[1].system.as_deref(), Some("Be terse"));
+
+            // Answer each chat with its system prompt (if any) and user prompt
+            let (sender, mut receiver) = channel(8);
+            let router = MessageRouterHandle::with_sender(sender, Duration::from_secs(1));
+            tokio::spawn(async move {
+                while let Some(RoutingMessage::LLM(LLMMessage::Chat(request))) =
+                    receiver.recv().await
+                {
+                    let reply: Vec<String> =
+                        request.messages.iter().map(|m| m.content.clone()).collect();
+                    let _ = request
+                        .response
+                        .send(ChatResponse::Complete(reply.join(" | ")));
+                }
+            });
+
+            let outcomes = process_records_with(&router, records, 2).await;
+            assert_eq!(outcomes[0].index, 0);
+            assert_eq!(outcomes[1].index, 1);
+            let results: HashMap<String, String> = outcomes
+                .into_iter()
+                .map(|outcome| (outcome.id, outcome.result.unwrap()))
+                .collect();
+
+            assert_eq!(results["q1"], "2+2?");
+            assert_eq!(results["q2"], "Be terse | Capital of France?");
+        }
+
+        #[test]
+        fn test_csv_records_and_malformed_input() {
+            let records =
+                parse_csv("id,prompt,system\na,Hello,\nb,\"Hi, there\",Be kind\n").unwrap();
+            assert_eq!(
+                records,
+                vec![
+                    BatchRecord {
+                        id: "a".into(),
+                        prompt: "Hello".into(),
+                        system: None,
+                    },
+                    BatchRecord {
+                        id: "b".into(),
+                        prompt: "Hi, there".into(),
+                        system: Some("Be kind".into()),
+                    },
+                ]
+            );
+
+            assert!(matches!(
+                parse_jsonl("{\"id\": \"a\"}"),
+                Err(ActorusError::Validation(msg)) if msg.starts_with("line 1")
+            ));
+        }
+    }
 }
 
 /// Agent API - Autonomous agent with tool execution capabilities
 pub mod agent {
     use super::*;
-    use crate::actors::messages::{AgentMessage, AgentResponse, AgentStep, AgentTask};
+    use crate::actors::messages::{
+        AgentMessage, AgentResponse, AgentStep, AgentTask, RoutingDecision, ToolCallMetadata,
+    };
+    use crate::storage::artifacts::{Artifact, ArtifactStore};
+    use crate::tools::context::ExecutionContext;
+    use crate::tools::executor::PlannedToolCall;
+    use std::collections::HashMap;
     use std::sync::Arc;
+    use tokio::sync::mpsc;
 
     /// Run an autonomous agent task
     ///
@@ -280,24 +483,35 @@ pub mod agent {
         task: impl Into<String>,
         max_iterations: usize,
     ) -> Result<AgentResult> {
-        let system = System::global();
-        let task_desc = task.into();
+        let response =
+            send_task(&System::global()?.router, task.into(), max_iterations, None).await?;
+        Ok(AgentResult::from_response(response))
+    }
 
+    /// Hand a task to the agent actor behind `router` and wait for its answer
+    ///
+    /// When `steps` is given, each step is sent to it as the agent takes it.
+    pub(crate) async fn send_task(
+        router: &MessageRouterHandle,
+        task: String,
+        max_iterations: usize,
+        steps: Option<mpsc::Sender<AgentStep>>,
+    ) -> Result<AgentResponse> {
         let (tx, rx) = oneshot::channel();
         let agent_task = AgentTask {
-            task_description: task_desc.clone(),
+            request_id: RequestId::new(),
+            context: ExecutionContext::current().unwrap_or_default(),
+            task_description: task,
             max_iterations: Some(max_iterations),
+            steps,
             response: tx,
         };
 
-        system
-            .router
+        router
             .send_message(RoutingMessage::Agent(AgentMessage::RunTask(agent_task)))
             .await?;
 
-        let response = rx.await?;
-
-        Ok(AgentResult::from_response(response))
+        Ok(rx.await?)
     }
 
     /// Run an autonomous agent task with custom tools
@@ -348,15 +562,14 @@ pub mod agent {
         use crate::config::Settings;
 
         let settings = Settings::new()?;
-        let api_key = Settings::api_key()?;
+        let api_key = System::api_key(&settings)?;
 
         let config = SpecializedAgentConfig {
             name: "custom_tools_agent".to_string(),
             description: "Agent with custom user-provided tools".to_string(),
             system_prompt: "You are an agent with access to custom tools. Use them to complete the user's task.".to_string(),
             tools,
-            response_schema: None,
-            return_tool_output: false,
+            ..Default::default()
         };
 
         let agent = SpecializedAgent::new(config, settings, api_key);
@@ -369,7 +582,7 @@ pub mod agent {
     ///
     /// Gracefully stops the agent actor. Useful for cleanup or reconfiguration.
     pub async fn stop() -> Result<()> {
-        let system = System::global();
+        let system = System::global()?;
         system
             .router
             .send_message(RoutingMessage::Agent(AgentMessage::Stop))
@@ -378,12 +591,41 @@ pub mod agent {
     }
 
     /// Result from agent execution
-    #[derive(Debug, Clone)]
+    #[derive(Debug, Clone, Default)]
     pub struct AgentResult {
         pub success: bool,
         pub result: String,
         pub steps: Vec<AgentStepInfo>,
         pub error: Option<String>,
+        /// Every tool call made during the run, in order
+        pub tool_calls: Vec<ToolCallInfo>,
+        /// Agent the router chose and why; only set for routed tasks
+        pub routing_decision: Option<RoutingDecision>,
+        /// Tokens reported by the provider, or estimated in dry-run mode
+        pub tokens_used: Option<u32>,
+        /// Sub-goals the supervisor planned but dropped to stay within
+        /// `agent.max_sub_goals`; empty unless the plan was truncated
+        pub dropped_sub_goals: Vec<String>,
+        /// Result of each completed sub-goal by id; only filled by supervisors
+        /// running with `agent.structured_results`
+        pub sub_goal_results: HashMap<String, serde_json::Value>,
+        /// Tool calls a dry run recorded instead of making, in order; set
+        /// `dry_run` or `tools.dry_run` to see what a task would call
+        pub planned_tool_calls: Vec<PlannedToolCall>,
+        /// Artifact store of the `ExecutionContext` the run was made in
+        pub artifact_store: Option<Arc<dyn ArtifactStore>>,
+        /// Names of the artifacts tools wrote to that store during the run
+        pub artifact_names: Vec<String>,
+    }
+
+    /// Information about a single tool call
+    #[derive(Debug, Clone)]
+    pub struct ToolCallInfo {
+        pub name: String,
+        pub input_size: usize,
+        pub output_size: usize,
+        pub duration_ms: u64,
+        pub success: bool,
     }
 
     /// Information about a single agent step
@@ -396,30 +638,121 @@ pub mod agent {
     }
 
     impl AgentResult {
+        /// Whether the supervisor dropped part of its plan; raise
+        /// `agent.max_sub_goals` to keep every sub-goal
+        pub fn plan_truncated(&self) -> bool {
+            !self.dropped_sub_goals.is_empty()
+        }
+
+        /// Result of the sub-goal with this id, if it completed
+        pub fn sub_goal_result(&self, sub_goal_id: &str) -> Option<&serde_json::Value> {
+            self.sub_goal_results.get(sub_goal_id)
+        }
+
+        /// Artifacts written during the run, in write order; empty when the
+        /// run had no artifact store or wrote nothing
+        pub async fn artifacts(&self) -> Result<Vec<Artifact>> {
+            let Some(store) = &self.artifact_store else {
+                return Ok(Vec::new());
+            };
+            let mut artifacts = Vec::new();
+            for name in &self.artifact_names {
+                if let Some(artifact) = store.get(name).await? {
+                    artifacts.push(artifact);
+                }
+            }
+            Ok(artifacts)
+        }
+
         pub(crate) fn from_response(response: AgentResponse) -> Self {
-            match response {
-                AgentResponse::Success { result, steps, .. } => Self {
-                    success: true,
-                    result,
-                    steps: steps.into_iter().map(AgentStepInfo::from).collect(),
-                    error: None,
-                },
-                AgentResponse::Failure { error, steps, .. } => Self {
-                    success: false,
-                    result: String::new(),
-                    steps: steps.into_iter().map(AgentStepInfo::from).collect(),
-                    error: Some(error),
-                },
+            let tool_calls = response
+                .metadata()
+                .map(|metadata| {
+                    metadata
+                        .tool_calls
+                        .iter()
+                        .cloned()
+                        .map(ToolCallInfo::from)
+                        .collect()
+                })
+                .unwrap_or_default();
+            let routing_decision = response.metadata().and_then(|m| m.routing.clone());
+            let tokens_used = response.metadata().and_then(|m| m.tokens_used);
+            let dropped_sub_goals = response
+                .metadata()
+                .map(|m| m.dropped_sub_goals.clone())
+                .unwrap_or_default();
+            // JSON results stay structured; anything else becomes a string
+            let sub_goal_results = response
+                .metadata()
+                .map(|m| {
+                    m.partial_results
+                        .iter()
+                        .map(|(id, result)| {
+                            let value = serde_json::from_str(result)
+                                .unwrap_or_else(|_| serde_json::Value::String(result.clone()));
+                            (id.clone(), value)
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            let planned_tool_calls = response
+                .metadata()
+                .map(|m| m.planned_tool_calls.clone())
+                .unwrap_or_default();
+            let context = ExecutionContext::current();
+            let artifact_store = context.as_ref().and_then(|c| c.artifact_store());
+            let artifact_names = context
+                .map(|c| c.take_written_artifacts())
+                .unwrap_or_default();
+
+            let (success, result, error, steps) = match response {
+                AgentResponse::Success { result, steps, .. } => (true, result, None, steps),
+                AgentResponse::Failure { error, steps, .. } => {
+                    (false, String::new(), Some(error), steps)
+                }
                 AgentResponse::Timeout {
                     partial_result,
                     steps,
                     ..
-                } => Self {
-                    success: false,
-                    result: partial_result,
-                    steps: steps.into_iter().map(AgentStepInfo::from).collect(),
-                    error: Some("Max iterations reached".to_string()),
-                },
+                } => (
+                    false,
+                    partial_result,
+                    Some("Max iterations reached".to_string()),
+                    steps,
+                ),
+                AgentResponse::NeedsInput { prompt, steps, .. } => (
+                    false,
+                    String::new(),
+                    Some(format!("Waiting for input: {}", prompt)),
+                    steps,
+                ),
+            };
+            Self {
+                success,
+                result,
+                steps: steps.into_iter().map(AgentStepInfo::from).collect(),
+                error,
+                tool_calls,
+                routing_decision,
+                tokens_used,
+                dropped_sub_goals,
+                sub_goal_results,
+                planned_tool_calls,
+                artifact_store,
+                artifact_names,
+            }
+        }
+    }
+
+    impl From<ToolCallMetadata> for ToolCallInfo {
+        fn from(call: ToolCallMetadata) -> Self {
+            Self {
+                name: call.tool_name,
+                input_size: call.input_size,
+                output_size: call.output_size,
+                duration_ms: call.duration_ms,
+                success: call.success,
             }
         }
     }
@@ -439,13 +772,14 @@ pub mod agent {
 /// Router Agent API - Intent classification and routing to specialized agents
 pub mod router {
     use super::*;
+    use crate::actors::agent_builder::AgentConfig;
     use crate::actors::router_agent::RouterAgent;
     use crate::actors::specialized_agents_factory;
     use crate::config::Settings;
     use crate::core::llm::LLMClient;
 
-    pub use crate::actors::messages::{AgentResponse, AgentStep};
-    pub use crate::api::agent::{AgentResult, AgentStepInfo};
+    pub use crate::actors::messages::{AgentResponse, AgentStep, RoutingDecision};
+    pub use crate::api::agent::{AgentResult, AgentStepInfo, ToolCallInfo};
 
     /// Route a task to the appropriate specialized agent
     ///
@@ -475,21 +809,37 @@ pub mod router {
         task: impl Into<String>,
         max_iterations: usize,
     ) -> Result<AgentResult> {
+        let router = default_router()?;
+
+        // Route task
+        let response = router.route_task(&task.into(), max_iterations).await;
+
+        Ok(AgentResult::from_response(response))
+    }
+
+    /// Route a task to every specialized agent it needs and merge their results
+    ///
+    /// Sits between `route_task` and the supervisor: the classifier may pick
+    /// several agents (e.g. web_agent and file_ops_agent for "download this
+    /// file and summarize it"), which run concurrently on the same task. The
+    /// result holds one `[agent_name]` section per agent.
+    pub async fn route_task_multi(task: impl Into<String>) -> Result<AgentResult> {
+        let router = default_router()?;
+        let response = router.route_task_multi(&task.into(), 10).await;
+        Ok(AgentResult::from_response(response))
+    }
+
+    /// Router over the default specialized agents
+    fn default_router() -> Result<RouterAgent> {
         let settings = Settings::new()?;
-        let api_key = Settings::api_key()?;
+        let api_key = System::api_key(&settings)?;
 
         // Create specialized agents
         let agents =
             specialized_agents_factory::create_default_agents(settings.clone(), api_key.clone());
 
-        // Create router
         let llm_client = LLMClient::new(api_key, settings);
-        let router = RouterAgent::new(agents, llm_client);
-
-        // Route task
-        let response = router.route_task(&task.into(), max_iterations).await;
-
-        Ok(AgentResult::from_response(response))
+        Ok(RouterAgent::new(agents, llm_client))
     }
 
     /// List available specialized agents
@@ -552,14 +902,7 @@ pub mod router {
     /// }
     /// ```
     pub async fn route_task_with_custom_agents(
-        agent_configs: Vec<(
-            String,
-            String,
-            String,
-            Vec<std::sync::Arc<dyn crate::tools::Tool>>,
-            Option<serde_json::Value>,
-            bool,
-        )>,
+        agent_configs: Vec<AgentConfig>,
         task: impl Into<String>,
     ) -> Result<AgentResult> {
         route_task_with_custom_agents_and_iterations(agent_configs, task, 10).await
@@ -567,14 +910,7 @@ pub mod router {
 
     /// Route with custom agents and max iterations
     pub async fn route_task_with_custom_agents_and_iterations(
-        agent_configs: Vec<(
-            String,
-            String,
-            String,
-            Vec<std::sync::Arc<dyn crate::tools::Tool>>,
-            Option<serde_json::Value>,
-            bool,
-        )>,
+        agent_configs: Vec<AgentConfig>,
         task: impl Into<String>,
         max_iterations: usize,
     ) -> Result<AgentResult> {
@@ -584,13 +920,21 @@ pub mod router {
         use crate::core::llm::LLMClient;
 
         let settings = Settings::new()?;
-        let api_key = Settings::api_key()?;
+        let api_key = System::api_key(&settings)?;
 
         // Create specialized agents from configs
         let agents: Vec<SpecializedAgent> = agent_configs
             .into_iter()
             .map(
-                |(name, description, system_prompt, tools, response_schema, return_tool_output)| {
+                |(
+                    name,
+                    description,
+                    system_prompt,
+                    tools,
+                    response_schema,
+                    return_tool_output,
+                    examples,
+                )| {
                     let config = SpecializedAgentConfig {
                         name,
                         description,
@@ -598,6 +942,8 @@ pub mod router {
                         tools,
                         response_schema,
                         return_tool_output,
+                        examples,
+                        ..Default::default()
                     };
                     SpecializedAgent::new(config, settings.clone(), api_key.clone())
                 },
@@ -618,15 +964,15 @@ pub mod router {
 /// Supervisor Agent API - Multi-agent orchestration for complex tasks
 pub mod supervisor {
     use super::*;
+    use crate::actors::agent_builder::AgentConfig;
     use crate::actors::handoff::HandoffCoordinator;
     use crate::actors::specialized_agents_factory;
     use crate::actors::supervisor_agent::SupervisorAgent;
     use crate::config::Settings;
     use crate::core::llm::LLMClient;
-    use std::sync::Arc;
 
     pub use crate::actors::messages::{AgentResponse, AgentStep};
-    pub use crate::api::agent::{AgentResult, AgentStepInfo};
+    pub use crate::api::agent::{AgentResult, AgentStepInfo, ToolCallInfo};
 
     /// Orchestrate a complex task across multiple specialized agents
     ///
@@ -663,7 +1009,7 @@ pub mod supervisor {
         max_orchestration_steps: usize,
     ) -> Result<AgentResult> {
         let settings = Settings::new()?;
-        let api_key = Settings::api_key()?;
+        let api_key = System::api_key(&settings)?;
 
         // Create specialized agents
         let agents =
@@ -700,14 +1046,7 @@ pub mod supervisor {
     /// // See supervisor_with_custom_tools.rs for a working example
     /// ```
     pub async fn orchestrate_custom_agents(
-        agent_configs: Vec<(
-            String,
-            String,
-            String,
-            Vec<Arc<dyn crate::tools::Tool>>,
-            Option<serde_json::Value>,
-            bool,
-        )>, // (name, description, system_prompt, tools, response_schema, return_tool_output)
+        agent_configs: Vec<AgentConfig>, // (name, description, system_prompt, tools, response_schema, return_tool_output, examples)
         task: impl Into<String>,
     ) -> Result<AgentResult> {
         let settings = Settings::new()?;
@@ -717,14 +1056,7 @@ pub mod supervisor {
 
     /// Orchestrate with custom agents and max orchestration steps
     pub async fn orchestrate_custom_agents_and_steps(
-        agent_configs: Vec<(
-            String,
-            String,
-            String,
-            Vec<Arc<dyn crate::tools::Tool>>,
-            Option<serde_json::Value>,
-            bool,
-        )>,
+        agent_configs: Vec<AgentConfig>,
         task: impl Into<String>,
         max_orchestration_steps: usize,
     ) -> Result<AgentResult> {
@@ -734,13 +1066,21 @@ pub mod supervisor {
         use crate::core::llm::LLMClient;
 
         let settings = Settings::new()?;
-        let api_key = Settings::api_key()?;
+        let api_key = System::api_key(&settings)?;
 
         // Create specialized agents from configs
         let agents: Vec<SpecializedAgent> = agent_configs
             .into_iter()
             .map(
-                |(name, description, system_prompt, tools, response_schema, return_tool_output)| {
+                |(
+                    name,
+                    description,
+                    system_prompt,
+                    tools,
+                    response_schema,
+                    return_tool_output,
+                    examples,
+                )| {
                     let config = SpecializedAgentConfig {
                         name,
                         description,
@@ -748,6 +1088,8 @@ pub mod supervisor {
                         tools,
                         response_schema,
                         return_tool_output,
+                        examples,
+                        ..Default::default()
                     };
                     SpecializedAgent::new(config, settings.clone(), api_key.clone())
                 },
@@ -821,7 +1163,7 @@ pub mod supervisor {
         max_orchestration_steps: usize,
     ) -> Result<AgentResult> {
         let settings = Settings::new()?;
-        let api_key = Settings::api_key()?;
+        let api_key = System::api_key(&settings)?;
 
         // Create specialized agents
         let agents =
@@ -876,14 +1218,7 @@ pub mod supervisor {
     /// ```
     pub async fn orchestrate_custom_agents_with_validation(
         coordinator: HandoffCoordinator,
-        agent_configs: Vec<(
-            String,
-            String,
-            String,
-            Vec<Arc<dyn crate::tools::Tool>>,
-            Option<serde_json::Value>,
-            bool,
-        )>,
+        agent_configs: Vec<AgentConfig>,
         task: impl Into<String>,
     ) -> Result<AgentResult> {
         let settings = Settings::new()?;
@@ -900,14 +1235,7 @@ pub mod supervisor {
     /// Orchestrate custom agents with validation and custom max orchestration steps
     pub async fn orchestrate_custom_agents_with_validation_and_steps(
         coordinator: HandoffCoordinator,
-        agent_configs: Vec<(
-            String,
-            String,
-            String,
-            Vec<Arc<dyn crate::tools::Tool>>,
-            Option<serde_json::Value>,
-            bool,
-        )>,
+        agent_configs: Vec<AgentConfig>,
         task: impl Into<String>,
         max_orchestration_steps: usize,
     ) -> Result<AgentResult> {
@@ -917,13 +1245,21 @@ pub mod supervisor {
         use crate::core::llm::LLMClient;
 
         let settings = Settings::new()?;
-        let api_key = Settings::api_key()?;
+        let api_key = System::api_key(&settings)?;
 
         // Create specialized agents from configs
         let agents: Vec<SpecializedAgent> = agent_configs
             .into_iter()
             .map(
-                |(name, description, system_prompt, tools, response_schema, return_tool_output)| {
+                |(
+                    name,
+                    description,
+                    system_prompt,
+                    tools,
+                    response_schema,
+                    return_tool_output,
+                    examples,
+                )| {
                     let config = SpecializedAgentConfig {
                         name,
                         description,
@@ -931,6 +1267,8 @@ pub mod supervisor {
                         tools,
                         response_schema,
                         return_tool_output,
+                        examples,
+                        ..Default::default()
                     };
                     SpecializedAgent::new(config, settings.clone(), api_key.clone())
                 },
@@ -954,21 +1292,27 @@ pub mod supervisor {
 /// Session API - Persistent multi-turn conversations with agents
 pub mod session {
     use super::*;
-    use crate::actors::agent_session::AgentSession;
+    use crate::actors::agent_session::{AgentSession, SessionResponse};
     use crate::config::Settings;
+    use crate::core::llm::ChatMessage;
     use crate::storage::{
         filesystem::FileSystemStorage, memory::InMemoryStorage, ConversationStorage,
     };
     use std::path::PathBuf;
     use std::sync::Arc;
+    use tokio::sync::mpsc;
 
-    pub use crate::api::agent::{AgentResult, AgentStepInfo};
+    pub use crate::actors::agent_session::SessionStep;
+    pub use crate::api::agent::{AgentResult, AgentStepInfo, ToolCallInfo};
 
     /// Storage backend type for sessions
+    ///
+    /// Covers both the conversation history and the session's long-term
+    /// memory (facts saved with the `remember` tool).
     pub enum StorageType {
         /// In-memory storage (lost on process termination)
         Memory,
-        /// File system storage (persists to disk)
+        /// File system storage (persists to disk); memory goes to `memory.kv`
         FileSystem(PathBuf),
     }
 
@@ -1024,7 +1368,7 @@ pub mod session {
         storage_type: StorageType,
     ) -> Result<Session> {
         let settings = Settings::new()?;
-        let api_key = Settings::api_key()?;
+        let api_key = System::api_key(&settings)?;
 
         let storage: Arc<dyn ConversationStorage> = match storage_type {
             StorageType::Memory => Arc::new(InMemoryStorage::new()),
@@ -1036,6 +1380,37 @@ pub mod session {
         Ok(Session { inner })
     }
 
+    /// Convert a session response into the common agent result shape
+    fn to_agent_result(session_response: SessionResponse) -> AgentResult {
+        let context = crate::tools::context::ExecutionContext::current();
+        AgentResult {
+            success: session_response.completed,
+            result: session_response.message.clone(),
+            steps: session_response
+                .steps
+                .iter()
+                .enumerate()
+                .map(|(i, step)| AgentStepInfo {
+                    iteration: i,
+                    thought: step.thought.clone(),
+                    action: step.action.clone(),
+                    observation: step.observation.clone(),
+                })
+                .collect(),
+            error: if session_response.completed {
+                None
+            } else {
+                Some(session_response.message)
+            },
+            // Sessions do not record per-call timings
+            artifact_store: context.as_ref().and_then(|c| c.artifact_store()),
+            artifact_names: context
+                .map(|c| c.take_written_artifacts())
+                .unwrap_or_default(),
+            ..Default::default()
+        }
+    }
+
     /// Session handle for multi-turn conversations
     pub struct Session {
         inner: AgentSession,
@@ -1064,32 +1439,25 @@ pub mod session {
             // Restore old max_iterations
             self.inner.set_max_iterations(old_max_iterations);
 
-            // Convert SessionResponse to AgentResult
-            Ok(AgentResult {
-                success: session_response.completed,
-                result: session_response.message.clone(),
-                steps: session_response
-                    .steps
-                    .iter()
-                    .enumerate()
-                    .map(|(i, step)| AgentStepInfo {
-                        iteration: i,
-                        thought: step.thought.clone(),
-                        action: step.action.clone(),
-                        observation: step.observation.clone(),
-                    })
-                    .collect(),
-                error: if session_response.completed {
-                    None
-                } else {
-                    Some(session_response.message)
-                },
-            })
+            Ok(to_agent_result(session_response))
+        }
+
+        /// Send a message, receiving each step on `steps` while the agent works
+        ///
+        /// Useful for showing the agent's progress in an interactive UI; the
+        /// returned result contains the same steps.
+        pub async fn send_message_streaming(
+            &mut self,
+            message: &str,
+            steps: mpsc::Sender<SessionStep>,
+        ) -> Result<AgentResult> {
+            let session_response = self.inner.send_message_streaming(message, steps).await?;
+            Ok(to_agent_result(session_response))
         }
 
         /// Clear conversation history for this session
         pub async fn clear_history(&mut self) -> Result<()> {
-            self.inner.clear_history().await
+            Ok(self.inner.clear_history().await?)
         }
 
         /// Get the session ID
@@ -1097,9 +1465,480 @@ pub mod session {
             self.inner.session_id()
         }
 
+        /// Fork this session into a new one with a copy of the conversation so far
+        ///
+        /// Both sessions share the same storage backend but evolve independently,
+        /// which lets you try a different direction without losing the original.
+        ///
+        /// # Example
+        /// ```no_run
+        /// use actorus::api::session::{self, StorageType};
+        ///
+        /// #[tokio::main]
+        /// async fn main() -> anyhow::Result<()> {
+        ///     let mut session = session::create_session("user-123", StorageType::Memory).await?;
+        ///     session.send_message("Draft a project plan").await?;
+        ///
+        ///     let mut alternative = session.fork("user-123-alt").await?;
+        ///     alternative.send_message("Make the plan more aggressive").await?;
+        ///     Ok(())
+        /// }
+        /// ```
+        pub async fn fork(&self, new_session_id: impl Into<String>) -> Result<Session> {
+            let settings = Settings::new()?;
+            let api_key = System::api_key(&settings)?;
+
+            let inner = self.inner.fork(new_session_id, settings, api_key).await?;
+            Ok(Session { inner })
+        }
+
+        /// Receive tools' partial output, such as shell output lines, while they run
+        ///
+        /// Useful for showing a long command's progress; the final result
+        /// still carries the complete output.
+        pub fn with_tool_output(self, output: mpsc::Sender<String>) -> Self {
+            Self {
+                inner: self.inner.with_tool_output(output),
+            }
+        }
+
+        /// Set the session's system prompt, persisted as its first message
+        pub async fn with_system_prompt(self, prompt: impl Into<String>) -> Result<Self> {
+            Ok(Self {
+                inner: self.inner.with_system_prompt(prompt).await?,
+            })
+        }
+
+        /// Conversation history, oldest first
+        pub fn messages(&self) -> &[ChatMessage] {
+            self.inner.history()
+        }
+
+        /// Remove the last exchange (user message, tool turns and reply)
+        ///
+        /// Files the session's tools wrote or edited during that exchange are
+        /// restored. Returns the removed messages, or an empty list if there
+        /// was nothing to undo.
+        pub async fn undo_last_interaction(&mut self) -> Result<Vec<ChatMessage>> {
+            Ok(self.inner.undo_last_interaction().await?)
+        }
+
+        /// Remove the message at `index`, persisting the change
+        pub async fn remove_message(&mut self, index: usize) -> Result<ChatMessage> {
+            Ok(self.inner.remove_message(index).await?)
+        }
+
+        /// Replace the content of the most recent message, persisting the change
+        ///
+        /// Together with `remove_message` this supports edit-and-regenerate flows.
+        pub async fn edit_last(&mut self, content: impl Into<String>) -> Result<()> {
+            Ok(self.inner.edit_last(content).await?)
+        }
+
         /// Get the number of messages in the conversation history
         pub fn message_count(&self) -> usize {
             self.inner.history().len()
         }
     }
 }
+
+/// Health API - Reachability of the upstream LLM provider
+///
+/// Complements `get_system_state`, which only covers actor heartbeats, so
+/// callers can tell an internal failure apart from a provider outage.
+pub mod health {
+    use super::*;
+    use crate::config::Settings;
+    use crate::core::llm::{ChatMessage, LLMClient};
+    use std::time::{Duration, Instant};
+
+    /// Outcome of a single provider ping
+    #[derive(Debug, Clone)]
+    pub struct LLMHealth {
+        pub reachable: bool,
+        pub latency: Duration,
+        /// Provider that answered, when one did
+        pub provider: Option<String>,
+        pub error: Option<String>,
+    }
+
+    /// Ping the configured LLM provider with a minimal completion
+    ///
+    /// The completion is billed like any other, so pollers should space the
+    /// calls out; the CLI pings at most once a minute and only on request.
+    /// Only configuration problems (such as a missing API key) are returned
+    /// as errors; an unreachable provider is reported in `LLMHealth`.
+    pub async fn check_llm() -> Result<LLMHealth> {
+        let settings = Settings::new()?;
+        let api_key = System::api_key(&settings)?;
+        Ok(check_llm_with(&LLMClient::new(api_key, settings)).await)
+    }
+
+    /// Ping the provider behind an already configured client
+    pub async fn check_llm_with(client: &LLMClient) -> LLMHealth {
+        let start = Instant::now();
+        let result = client
+            .chat_with_params(
+                vec![ChatMessage::user("ping")],
+                &LLMParams::default().max_tokens(1).temperature(0.0),
+            )
+            .await;
+        let latency = start.elapsed();
+
+        match result {
+            Ok(_) => LLMHealth {
+                reachable: true,
+                latency,
+                provider: client.last_provider(),
+                error: None,
+            },
+            Err(e) => {
+                tracing::warn!("[Health] LLM provider unreachable: {}", e);
+                LLMHealth {
+                    reachable: false,
+                    latency,
+                    provider: None,
+                    error: Some(e.to_string()),
+                }
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::core::mock_llm::MockLLM;
+        use std::sync::Arc;
+
+        #[tokio::test]
+        async fn test_check_llm_reports_success_and_failure() {
+            let llm = Arc::new(MockLLM::new(["pong"]));
+            let client = LLMClient::with_backend(llm.clone(), Settings::new().unwrap());
+
+            let healthy = check_llm_with(&client).await;
+            assert!(healthy.reachable);
+            assert!(healthy.error.is_none());
+            assert!(healthy.provider.is_some());
+            assert_eq!(llm.requests()[0].max_tokens, 1);
+
+            // The script is exhausted, so the next ping fails
+            let unhealthy = check_llm_with(&client).await;
+            assert!(!unhealthy.reachable);
+            assert!(unhealthy.provider.is_none());
+            assert!(unhealthy
+                .error
+                .unwrap()
+                .contains("MockLLM called more times than scripted"));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ActorusError;
+    use tokio::sync::mpsc::channel;
+    use tokio::time::Duration;
+
+    #[tokio::test]
+    async fn test_calls_before_init_report_not_initialized() {
+        assert!(matches!(
+            chat("hello").await,
+            Err(ActorusError::NotInitialized)
+        ));
+        assert!(matches!(
+            Conversation::new().user("hello").send().await,
+            Err(ActorusError::NotInitialized)
+        ));
+        assert!(matches!(
+            crate::get_system_state().await,
+            Err(ActorusError::NotInitialized)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_chat_messages_are_routed_unchanged() {
+        let (sender, mut receiver) = channel(8);
+        let router = MessageRouterHandle::with_sender(sender, Duration::from_secs(1));
+
+        let actor = tokio::spawn(async move {
+            match receiver.recv().await {
+                Some(RoutingMessage::LLM(LLMMessage::Chat(request))) => {
+                    let seen: Vec<(Role, String)> = request
+                        .messages
+                        .iter()
+                        .map(|m| (m.role, m.content.clone()))
+                        .collect();
+                    let _ = request.response.send(ChatResponse::Complete("ok".into()));
+                    seen
+                }
+                other => panic!("expected a chat request, got {:?}", other),
+            }
+        });
+
+        let history = vec![
+            ChatMessageData::system("Be brief"),
+            ChatMessageData::user("Name a colour"),
+            ChatMessageData::assistant("Blue"),
+        ];
+        let reply = send_chat(&router, history).await.unwrap();
+
+        assert_eq!(reply, "ok");
+        assert_eq!(
+            actor.await.unwrap(),
+            vec![
+                (Role::System, "Be brief".to_string()),
+                (Role::User, "Name a colour".to_string()),
+                (Role::Assistant, "Blue".to_string()),
+            ]
+        );
+    }
+
+    /// Echoes its input after an optional delay
+    struct EchoTool {
+        name: &'static str,
+        delay: Duration,
+    }
+
+    #[async_trait::async_trait]
+    impl crate::tools::Tool for EchoTool {
+        fn metadata(&self) -> crate::tools::ToolMetadata {
+            crate::tools::ToolMetadata {
+                name: self.name.to_string(),
+                description: "Echo the text".to_string(),
+                parameters: vec![],
+            }
+        }
+
+        async fn execute(
+            &self,
+            args: serde_json::Value,
+        ) -> anyhow::Result<crate::tools::ToolResult> {
+            tokio::time::sleep(self.delay).await;
+            Ok(crate::tools::ToolResult::success(
+                args["text"].as_str().unwrap_or_default(),
+            ))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_user_with_images_sends_image_content_blocks() {
+        use crate::config::Settings;
+        use crate::core::llm::{ChatMessage, LLMClient};
+        use crate::core::mock_llm::MockLLM;
+        use std::sync::Arc;
+
+        let conversation = Conversation::new().user_with_images(
+            "What is in this picture?",
+            vec![ImageSource::bytes(
+                "image/png",
+                vec![0x89, b'P', b'N', b'G'],
+            )],
+        );
+
+        let llm = Arc::new(MockLLM::new(["A tiny PNG"]));
+        let client = LLMClient::with_backend(llm.clone(), Settings::new().unwrap());
+        let messages = conversation
+            .messages
+            .iter()
+            .map(ChatMessage::from)
+            .collect();
+        client.chat(messages).await.unwrap();
+
+        let body = llm.requests()[0].body();
+        assert_eq!(
+            body["messages"][0]["content"],
+            serde_json::json!([
+                {"type": "text", "text": "What is in this picture?"},
+                {"type": "image_url", "image_url": {"url": "data:image/png;base64,iVBORw=="}}
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_agent_result_reports_each_tool_call() {
+        use crate::actors::specialized_agent::{SpecializedAgent, SpecializedAgentConfig};
+        use crate::core::llm::LLMClient;
+        use crate::core::mock_llm::MockLLM;
+        use serde_json::json;
+        use std::sync::Arc;
+
+        let call = |tool: &str, text: &str| {
+            json!({
+                "thought": format!("use {}", tool),
+                "action": {"tool": tool, "input": {"text": text}},
+                "is_final": false,
+                "final_answer": null
+            })
+            .to_string()
+        };
+        let llm = Arc::new(MockLLM::new([
+            call("slow_echo", "first"),
+            call("fast_echo", "second, longer"),
+            json!({"thought": "done", "action": null, "is_final": true, "final_answer": "ok"})
+                .to_string(),
+        ]));
+        let agent = SpecializedAgent::with_llm_client(
+            SpecializedAgentConfig {
+                name: "echo_agent".to_string(),
+                description: "Echoes text".to_string(),
+                system_prompt: "You echo text".to_string(),
+                tools: vec![
+                    Arc::new(EchoTool {
+                        name: "slow_echo",
+                        delay: Duration::from_millis(20),
+                    }),
+                    Arc::new(EchoTool {
+                        name: "fast_echo",
+                        delay: Duration::ZERO,
+                    }),
+                ],
+                ..Default::default()
+            },
+            LLMClient::with_backend(llm, crate::config::Settings::new().unwrap()),
+        );
+
+        let result = agent::AgentResult::from_response(agent.execute_task("Echo twice", 5).await);
+
+        assert!(result.success);
+        let calls: Vec<(&str, usize, bool)> = result
+            .tool_calls
+            .iter()
+            .map(|c| (c.name.as_str(), c.output_size, c.success))
+            .collect();
+        assert_eq!(calls, vec![("slow_echo", 5, true), ("fast_echo", 14, true)]);
+        assert!(result.tool_calls[0].duration_ms >= 20);
+        assert!(result.tool_calls.iter().all(|c| c.input_size > 0));
+    }
+
+    #[tokio::test]
+    async fn test_agent_result_surfaces_routing_reasoning() {
+        use crate::actors::router_agent::RouterAgent;
+        use crate::actors::specialized_agent::SpecializedAgent;
+        use crate::actors::specialized_agents_factory::default_agent_configs;
+        use crate::core::llm::LLMClient;
+        use crate::core::mock_llm::MockLLM;
+        use std::sync::Arc;
+
+        let settings = crate::config::Settings::new().unwrap();
+        let classifier = Arc::new(MockLLM::new([
+            r#"{"agent_name": "web_agent", "reasoning": "The task asks for a web page"}"#,
+        ]));
+        let agent_llm = Arc::new(MockLLM::new([
+            r#"{"thought": "done", "action": null, "is_final": true, "final_answer": "fetched"}"#,
+        ]));
+        let agents = default_agent_configs()
+            .into_iter()
+            .map(|config| {
+                SpecializedAgent::with_llm_client(
+                    config,
+                    LLMClient::with_backend(agent_llm.clone(), settings.clone()),
+                )
+            })
+            .collect();
+        let router = RouterAgent::new(agents, LLMClient::with_backend(classifier, settings));
+
+        let result = agent::AgentResult::from_response(
+            router.route_task("Fetch https://example.com", 3).await,
+        );
+
+        let decision = result.routing_decision.unwrap();
+        assert_eq!(decision.agent_name, "web_agent");
+        assert_eq!(decision.reasoning, "The task asks for a web page");
+    }
+
+    /// Writes its text as a CSV artifact in the current context's store
+    struct ExportTool;
+
+    #[async_trait::async_trait]
+    impl crate::tools::Tool for ExportTool {
+        fn metadata(&self) -> crate::tools::ToolMetadata {
+            crate::tools::ToolMetadata {
+                name: "export_csv".to_string(),
+                description: "Save rows as a CSV artifact".to_string(),
+                parameters: vec![],
+            }
+        }
+
+        async fn execute(
+            &self,
+            args: serde_json::Value,
+        ) -> anyhow::Result<crate::tools::ToolResult> {
+            let store = crate::tools::context::ExecutionContext::current()
+                .and_then(|c| c.artifact_store())
+                .ok_or_else(|| anyhow::anyhow!("no artifact store"))?;
+            let csv = args["csv"].as_str().unwrap_or_default();
+            store
+                .put("rows.csv", csv.as_bytes().to_vec(), "text/csv")
+                .await?;
+            Ok(crate::tools::ToolResult::success("saved rows.csv"))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_artifact_written_by_tool_is_available_after_run() {
+        use crate::actors::specialized_agent::{SpecializedAgent, SpecializedAgentConfig};
+        use crate::core::llm::LLMClient;
+        use crate::core::mock_llm::MockLLM;
+        use crate::storage::artifacts::{ArtifactStore, FileSystemArtifactStore};
+        use crate::tools::context::ExecutionContext;
+        use serde_json::json;
+        use std::sync::Arc;
+
+        let llm = Arc::new(MockLLM::new([
+            json!({
+                "thought": "export",
+                "action": {"tool": "export_csv", "input": {"csv": "id,name\n1,widget\n"}},
+                "is_final": false,
+                "final_answer": null
+            })
+            .to_string(),
+            json!({"thought": "done", "action": null, "is_final": true, "final_answer": "exported"})
+                .to_string(),
+        ]));
+        let agent = SpecializedAgent::with_llm_client(
+            SpecializedAgentConfig {
+                name: "export_agent".to_string(),
+                description: "Exports data".to_string(),
+                system_prompt: "You export data".to_string(),
+                tools: vec![Arc::new(ExportTool)],
+                ..Default::default()
+            },
+            LLMClient::with_backend(llm, crate::config::Settings::new().unwrap()),
+        );
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let store = Arc::new(
+            FileSystemArtifactStore::new(temp_dir.path().to_path_buf())
+                .await
+                .unwrap(),
+        );
+        // Written before the run, so not part of its result
+        store
+            .put("earlier.txt", b"old".to_vec(), "text/plain")
+            .await
+            .unwrap();
+        let result = ExecutionContext::new()
+            .with_artifact_store(store)
+            .scope(async {
+                agent::AgentResult::from_response(agent.execute_task("Export the rows", 3).await)
+            })
+            .await;
+
+        assert!(result.success);
+        let artifacts = result.artifacts().await.unwrap();
+        assert_eq!(artifacts.len(), 1);
+        assert_eq!(artifacts[0].name, "rows.csv");
+        assert_eq!(artifacts[0].mime, "text/csv");
+        assert_eq!(artifacts[0].as_text(), Some("id,name\n1,widget\n"));
+
+        // The artifact outlives the run's store handle
+        let reopened = FileSystemArtifactStore::new(temp_dir.path().to_path_buf())
+            .await
+            .unwrap();
+        assert_eq!(
+            reopened.list().await.unwrap(),
+            vec!["earlier.txt", "rows.csv"]
+        );
+    }
+}
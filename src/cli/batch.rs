@@ -0,0 +1,151 @@
+//! Batch Output - Writing batch results to files
+//!
+//! Information Hiding:
+//! - Row layout shared by every format hidden behind `render_outcomes`
+//! - Format chosen by flag, falling back to the output file's extension
+
+use crate::api::batch::BatchOutcome;
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use serde::Serialize;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum BatchOutputFormat {
+    /// A single JSON array
+    Json,
+    /// One JSON object per line
+    Jsonl,
+    /// Header row followed by one row per result
+    Csv,
+}
+
+impl BatchOutputFormat {
+    /// Format implied by `path`'s extension, defaulting to JSONL
+    pub fn for_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => Self::Json,
+            Some("csv") => Self::Csv,
+            _ => Self::Jsonl,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct OutcomeRow<'a> {
+    index: usize,
+    id: &'a str,
+    success: bool,
+    result: Option<&'a str>,
+    error: Option<String>,
+    duration_ms: u64,
+}
+
+impl<'a> From<&'a BatchOutcome> for OutcomeRow<'a> {
+    fn from(outcome: &'a BatchOutcome) -> Self {
+        Self {
+            index: outcome.index,
+            id: &outcome.id,
+            success: outcome.result.is_ok(),
+            result: outcome.result.as_deref().ok(),
+            error: outcome.result.as_ref().err().map(|e| e.to_string()),
+            duration_ms: outcome.duration.as_millis() as u64,
+        }
+    }
+}
+
+/// Render one record per outcome
+pub fn render_outcomes(outcomes: &[BatchOutcome], format: BatchOutputFormat) -> Result<String> {
+    let rows: Vec<OutcomeRow> = outcomes.iter().map(OutcomeRow::from).collect();
+    match format {
+        BatchOutputFormat::Json => Ok(serde_json::to_string_pretty(&rows)?),
+        BatchOutputFormat::Jsonl => rows
+            .iter()
+            .map(|row| Ok(serde_json::to_string(row)? + "\n"))
+            .collect(),
+        BatchOutputFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(vec![]);
+            for row in &rows {
+                writer.serialize(row)?;
+            }
+            Ok(String::from_utf8(writer.into_inner()?)?)
+        }
+    }
+}
+
+pub async fn write_outcomes(
+    outcomes: &[BatchOutcome],
+    format: BatchOutputFormat,
+    path: &Path,
+) -> Result<()> {
+    let rendered = render_outcomes(outcomes, format)?;
+    tokio::fs::write(path, rendered)
+        .await
+        .context(format!("Failed to write batch results to {:?}", path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    fn outcomes() -> Vec<BatchOutcome> {
+        vec![
+            BatchOutcome {
+                index: 0,
+                id: "1".into(),
+                result: Ok("four".into()),
+                duration: Duration::from_millis(12),
+            },
+            BatchOutcome {
+                index: 1,
+                id: "2".into(),
+                result: Err(anyhow::anyhow!("provider down").into()),
+                duration: Duration::from_millis(3),
+            },
+        ]
+    }
+
+    #[tokio::test]
+    async fn test_writes_one_record_per_prompt_in_each_format() {
+        let temp_dir = TempDir::new().unwrap();
+        let outcomes = outcomes();
+
+        let path = temp_dir.path().join("results.jsonl");
+        write_outcomes(&outcomes, BatchOutputFormat::for_path(&path), &path)
+            .await
+            .unwrap();
+        let lines: Vec<Value> = std::fs::read_to_string(&path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["result"], "four");
+        assert_eq!(lines[0]["duration_ms"], 12);
+        assert_eq!(lines[1]["success"], false);
+        assert_eq!(lines[1]["error"], "provider down");
+
+        let path = temp_dir.path().join("results.json");
+        write_outcomes(&outcomes, BatchOutputFormat::Json, &path)
+            .await
+            .unwrap();
+        let array: Vec<Value> =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(array.len(), 2);
+
+        let path = temp_dir.path().join("results.csv");
+        write_outcomes(&outcomes, BatchOutputFormat::for_path(&path), &path)
+            .await
+            .unwrap();
+        let csv = std::fs::read_to_string(&path).unwrap();
+        let mut rows = csv.lines();
+        assert_eq!(
+            rows.next(),
+            Some("index,id,success,result,error,duration_ms")
+        );
+        assert_eq!(rows.count(), 2);
+    }
+}
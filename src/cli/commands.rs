@@ -1,4 +1,6 @@
+use super::batch::BatchOutputFormat;
 use clap::{Parser, Subcommand};
+use std::path::PathBuf;
 
 #[derive(Parser)]
 #[command(name = "llm-fusion")]
@@ -37,11 +39,23 @@ pub enum Commands {
     },
 
     /// Process prompts from a file in batch
+    ///
+    /// Plain files hold one prompt per line. `.jsonl` and `.csv` files hold
+    /// `id`, `prompt` and optional `system` per record, and print one JSON
+    /// result per record keyed by id.
     Batch {
         file: String,
 
         #[arg(short, long, default_value = "5")]
         concurrency: usize,
+
+        /// Write results (index, id, success, error, duration) to this file
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Output file format; inferred from the --output extension by default
+        #[arg(long, value_enum, requires = "output")]
+        format: Option<BatchOutputFormat>,
     },
 
     /// Check the health status of all actors in the system
@@ -49,5 +63,14 @@ pub enum Commands {
         /// Enable continuous monitoring (refresh every N seconds)
         #[arg(short, long)]
         watch: Option<u64>,
+
+        /// Print the status as JSON instead of human-readable text
+        #[arg(long)]
+        json: bool,
+
+        /// Also ping the LLM provider with a one-token (billed) completion;
+        /// with --watch the ping repeats at most once a minute
+        #[arg(long)]
+        ping_llm: bool,
     },
 }
@@ -0,0 +1,71 @@
+//! Health Output - Machine-readable rendering of system health
+//!
+//! Information Hiding:
+//! - JSON layout of the health report hidden from the command handler
+//! - Heartbeat timestamps converted to "milliseconds since last seen"
+
+use crate::actors::messages::StateSnapshot;
+use crate::api::health::LLMHealth;
+use serde_json::{json, Value};
+
+/// Health report for `health --json`, suitable for jq or alerting
+///
+/// Actors are sorted by name so repeated runs diff cleanly.
+pub fn health_json(state: &StateSnapshot, llm: Option<&LLMHealth>) -> Value {
+    let mut actors: Vec<Value> = state
+        .active_actors
+        .iter()
+        .map(|(actor_type, is_active)| {
+            json!({
+                "actor": format!("{:?}", actor_type),
+                "active": is_active,
+                "last_seen_ms": state
+                    .last_heartbeat
+                    .get(actor_type)
+                    .map(|time| time.elapsed().unwrap_or_default().as_millis() as u64),
+            })
+        })
+        .collect();
+    actors.sort_by(|a, b| a["actor"].as_str().cmp(&b["actor"].as_str()));
+
+    let llm = llm.map(|llm| {
+        json!({
+            "reachable": llm.reachable,
+            "latency_ms": llm.latency.as_millis() as u64,
+            "provider": llm.provider,
+            "error": llm.error,
+        })
+    });
+
+    json!({ "actors": actors, "llm": llm })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actors::messages::ActorType;
+    use std::time::SystemTime;
+
+    #[test]
+    fn test_health_json_is_parseable_with_expected_keys() {
+        let mut state = StateSnapshot::default();
+        state.active_actors.insert(ActorType::Router, true);
+        state.active_actors.insert(ActorType::LLM, false);
+        state
+            .last_heartbeat
+            .insert(ActorType::Router, SystemTime::now());
+
+        let rendered = health_json(&state, None).to_string();
+        let parsed: Value = serde_json::from_str(&rendered).unwrap();
+
+        let actors = parsed["actors"].as_array().unwrap();
+        assert_eq!(actors.len(), 2);
+        assert_eq!(actors[0]["actor"], "LLM");
+        assert_eq!(actors[0]["active"], false);
+        assert!(actors[0]["last_seen_ms"].is_null());
+        assert_eq!(actors[1]["actor"], "Router");
+        assert_eq!(actors[1]["active"], true);
+        assert!(actors[1]["last_seen_ms"].is_u64());
+        assert!(parsed["llm"].is_null());
+    }
+}
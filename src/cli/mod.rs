@@ -1,2 +1,4 @@
+pub mod batch;
 pub mod commands;
+pub mod health;
 pub use commands::*;
@@ -1,2 +1,9 @@
+pub mod guardrails;
+pub mod prompts;
 pub mod settings;
-pub use settings::Settings;
+pub use guardrails::GuardrailConfig;
+pub use prompts::PromptConfig;
+pub use settings::{
+    AgentConfig, LLMConfig, LoggingConfig, Settings, SettingsBuilder, SystemConfig, ToolsConfig,
+    ValidationConfig,
+};
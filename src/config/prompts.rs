@@ -0,0 +1,238 @@
+//! Prompt Templates - Overridable system prompts for ReAct agents
+//!
+//! Information Hiding:
+//! - Hides the default prompt wording behind serde defaults
+//! - Hides placeholder substitution behind `render`
+//! - Exposes templates as plain strings users can override in config
+
+use crate::actors::messages::RequestId;
+use crate::core::llm::{ChatMessage, ToolCall};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::OnceLock;
+
+/// Default ReAct prompt used by specialized agents and the agent actor
+///
+/// Placeholders: `{system_prompt}`, `{tools}`, `{context}`, `{max_iterations}`.
+pub const DEFAULT_REACT_TEMPLATE: &str = r#"{system_prompt}
+
+Available Tools:
+{tools}{context}
+
+IMPORTANT: You have a maximum of {max_iterations} iterations to complete this task.
+You MUST respond in this EXACT JSON format:
+{
+  "thought": "your reasoning about what to do next",
+  "action": {"tool": "tool_name", "input": {"param": "value"}},
+  "is_final": false,
+  "final_answer": null
+}
+
+When the task is COMPLETE:
+- Set "is_final": true
+- Set "action": null
+- Provide a clear "final_answer" summarizing what you accomplished
+
+CRITICAL: A task is COMPLETE when:
+1. You have successfully executed all required tools AND received their results
+2. You have the information/result requested by the user
+3. No further actions are needed to satisfy the user's request
+
+After each tool execution, check: Does the observation contain what the user asked for?
+If YES, immediately set is_final=true and provide the final_answer.
+Do NOT repeat the same action if you already have the result.
+
+Always respond with valid JSON only. No extra text."#;
+
+/// Default prompt for conversational sessions
+///
+/// Placeholders: `{tools}`.
+pub const DEFAULT_SESSION_TEMPLATE: &str = r#"You are an autonomous agent that can use tools OR respond directly to accomplish tasks.
+
+Available Tools:
+{tools}
+
+IMPORTANT: You MUST respond in this EXACT JSON format:
+{
+  "thought": "your reasoning about what to do next",
+  "action": {"tool": "tool_name", "input": {"param": "value"}},
+  "is_final": false,
+  "final_answer": null
+}
+
+DECISION GUIDELINES:
+1. For conversational messages (greetings, questions about context, general chat):
+   - Set "is_final": true immediately
+   - Set "action": null (no tool needed)
+   - Provide your answer in "final_answer"
+2. For tasks requiring tools (file operations, shell commands, web requests):
+   - Choose appropriate tool
+   - Execute action
+   - After getting the observation, set "is_final": true with "final_answer"
+
+EXAMPLES:
+User: "hi" → {"thought": "greeting", "action": null, "is_final": true, "final_answer": "Hello! How can I help you?"}
+User: "list files" → {"thought": "need shell tool", "action": {"tool": "execute_shell", "input": {"command": "ls"}}, "is_final": false, "final_answer": null}
+
+Always respond with valid JSON only. No extra text."#;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptConfig {
+    /// System prompt for task-oriented ReAct loops
+    #[serde(default = "default_react_template")]
+    pub react_template: String,
+    /// System prompt for multi-turn agent sessions
+    #[serde(default = "default_session_template")]
+    pub session_template: String,
+    /// Tell agents and sessions the current UTC date and time when they start
+    #[serde(default)]
+    pub inject_current_time: bool,
+}
+
+impl Default for PromptConfig {
+    fn default() -> Self {
+        Self {
+            react_template: default_react_template(),
+            session_template: default_session_template(),
+            inject_current_time: false,
+        }
+    }
+}
+
+fn default_react_template() -> String {
+    DEFAULT_REACT_TEMPLATE.to_string()
+}
+
+fn default_session_template() -> String {
+    DEFAULT_SESSION_TEMPLATE.to_string()
+}
+
+/// Values substituted into a prompt template
+#[derive(Debug, Clone, Default)]
+pub struct PromptVars<'a> {
+    pub system_prompt: &'a str,
+    pub tools: &'a str,
+    pub context: &'a str,
+    pub max_iterations: usize,
+}
+
+/// Substitute known placeholders in a single pass
+///
+/// Unknown `{...}` sequences (such as the JSON examples in the default
+/// templates) are left untouched, and substituted values are never
+/// re-scanned for placeholders.
+pub fn render(template: &str, vars: &PromptVars) -> String {
+    static PLACEHOLDER: OnceLock<Regex> = OnceLock::new();
+    let pattern = PLACEHOLDER
+        .get_or_init(|| Regex::new(r"\{(system_prompt|tools|context|max_iterations)\}").unwrap());
+
+    pattern
+        .replace_all(template, |caps: &regex::Captures| match &caps[1] {
+            "system_prompt" => vars.system_prompt.to_string(),
+            "tools" => vars.tools.to_string(),
+            "context" => vars.context.to_string(),
+            _ => vars.max_iterations.to_string(),
+        })
+        .into_owned()
+}
+
+/// System message stating the current time, if `inject_current_time` is set
+///
+/// Sent as its own message after the system prompt, so custom templates
+/// need no placeholder for it.
+pub fn current_time_note(config: &PromptConfig) -> Option<String> {
+    config.inject_current_time.then(|| {
+        let now = chrono::Utc::now();
+        format!(
+            "Current date and time: {} (timezone: UTC, {})",
+            now.to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            now.format("%A")
+        )
+    })
+}
+
+/// Corrective message sent when the model picks a tool that does not exist
+pub fn unknown_tool_correction(tool: &str, available: &[String]) -> String {
+    let mut available = available.to_vec();
+    available.sort();
+    let available = if available.is_empty() {
+        "none; answer directly with is_final set to true".to_string()
+    } else {
+        available.join(", ")
+    };
+    format!(
+        "There is no tool named '{}'. Available tools: {}. Pick one of these exact names or give your final answer.",
+        tool, available
+    )
+}
+
+/// Turns asking the model to choose again after its `decision` named the
+/// unknown `tool`
+///
+/// The rejected decision is echoed back first so the correction reads as a
+/// reply to it.
+pub fn unknown_tool_reask(
+    decision: &impl Serialize,
+    tool: &str,
+    available: &[String],
+) -> Vec<ChatMessage> {
+    vec![
+        ChatMessage::assistant(
+            serde_json::to_string(decision).unwrap_or_else(|_| format!("Action: {}", tool)),
+        ),
+        ChatMessage::user(unknown_tool_correction(tool, available)),
+    ]
+}
+
+/// Turns recording one tool call made by a ReAct loop
+///
+/// The call goes out as an assistant turn with `tool_calls`, carrying the
+/// thought as its text, and the observation as the `tool` turn answering it.
+/// `follow_up` is then asked as a user turn.
+pub fn tool_call_turns(
+    thought: &str,
+    tool: &str,
+    input: &Value,
+    observation: impl Into<String>,
+    follow_up: impl Into<String>,
+) -> Vec<ChatMessage> {
+    let call_id = format!("call-{}", RequestId::new().as_str());
+    let call = ChatMessage {
+        content: thought.to_string(),
+        ..ChatMessage::assistant_tool_calls(vec![ToolCall::function(&call_id, tool, input)])
+    };
+    vec![
+        call,
+        ChatMessage::tool(call_id, observation),
+        ChatMessage::user(follow_up),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_leaves_json_braces_and_substituted_values_alone() {
+        let rendered = render(
+            "{system_prompt} | {tools} | {\"is_final\": true} | {max_iterations}",
+            &PromptVars {
+                system_prompt: "Agent",
+                tools: "- {context}",
+                context: "unused",
+                max_iterations: 3,
+            },
+        );
+        assert_eq!(rendered, "Agent | - {context} | {\"is_final\": true} | 3");
+    }
+
+    #[test]
+    fn test_unknown_tool_reask_echoes_decision_then_corrects() {
+        let messages = unknown_tool_reask(&"decision", "delete", &["search".to_string()]);
+
+        assert_eq!(messages[0].content, "\"decision\"");
+        assert!(messages[1].content.contains("no tool named 'delete'"));
+        assert!(messages[1].content.contains("Available tools: search."));
+    }
+}
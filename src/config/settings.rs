@@ -1,7 +1,20 @@
+use crate::config::guardrails::{GuardrailConfig, GuardrailPolicy};
+use crate::config::prompts::PromptConfig;
+use crate::core::circuit_breaker::CircuitBreakerConfig;
+use crate::core::llm::MessageFormat;
+use crate::tools::CacheConfig;
 use anyhow::Result;
-use config::{Config, ConfigError, Environment, File};
+use config::{Config, ConfigError, Environment, File, FileFormat};
 use serde::{Deserialize, Serialize};
 use std::env;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Shipped defaults, compiled in so they don't depend on the working directory
+const DEFAULT_CONFIG: &str = include_str!("../../config/default.toml");
+
+/// Key variable used when no provider-specific one is set
+const GENERIC_API_KEY_ENV: &str = "OPENAI_API_KEY";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Settings {
@@ -10,6 +23,19 @@ pub struct Settings {
     pub validation: ValidationConfig,
     pub system: SystemConfig,
     pub logging: LoggingConfig,
+    #[serde(default)]
+    pub prompts: PromptConfig,
+    #[serde(default)]
+    pub guardrails: GuardrailConfig,
+    /// Applied to each LLM provider and each tool
+    #[serde(default)]
+    pub circuit_breaker: CircuitBreakerConfig,
+    /// Record LLM and tool calls instead of making them, e.g. for cost estimates
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Which built-in tools the default registries include
+    #[serde(default)]
+    pub tools: ToolsConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,6 +43,47 @@ pub struct LLMConfig {
     pub model: String,
     pub max_tokens: u32,
     pub temperature: f32,
+    /// Root of an OpenAI-compatible API; requests go to `{base_url}/chat/completions`
+    #[serde(default = "default_base_url")]
+    pub base_url: String,
+    /// Model used by `LLMClient::embed`
+    #[serde(default = "default_embedding_model")]
+    pub embedding_model: String,
+    /// Providers tried in order when the primary one fails transiently
+    #[serde(default)]
+    pub fallbacks: Vec<LLMFallbackConfig>,
+    /// Provider-specific environment variable checked before `OPENAI_API_KEY`
+    #[serde(default)]
+    pub api_key_env: Option<String>,
+    /// File holding the API key, e.g. one mounted by a secret manager;
+    /// checked last
+    #[serde(default)]
+    pub api_key_file: Option<PathBuf>,
+    /// Message layout in requests; `content_blocks` for multimodal providers
+    #[serde(default)]
+    pub message_format: MessageFormat,
+}
+
+fn default_base_url() -> String {
+    "https://api.openai.com/v1".to_string()
+}
+
+fn default_embedding_model() -> String {
+    "text-embedding-3-small".to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LLMFallbackConfig {
+    /// Label recorded in output metadata; defaults to the model name
+    #[serde(default)]
+    pub name: Option<String>,
+    pub model: String,
+    /// Chat completions URL; defaults to the OpenAI endpoint
+    #[serde(default)]
+    pub endpoint: Option<String>,
+    /// Environment variable holding this provider's API key; defaults to the primary key
+    #[serde(default)]
+    pub api_key_env: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,6 +91,25 @@ pub struct AgentConfig {
     pub max_iterations: usize,
     pub max_orchestration_steps: usize,
     pub max_sub_goals: usize,
+    /// Agents a supervisor lets run at once, to smooth LLM request spikes
+    #[serde(default = "default_max_concurrent_agents")]
+    pub max_concurrent_agents: usize,
+    /// Return each completed sub-goal's result by id alongside a supervisor's
+    /// text answer
+    #[serde(default)]
+    pub structured_results: bool,
+    /// Times an agent is told the valid tool names and asked again after
+    /// picking a tool that does not exist, before the iteration counts
+    #[serde(default = "default_unknown_tool_retries")]
+    pub unknown_tool_retries: usize,
+}
+
+fn default_max_concurrent_agents() -> usize {
+    4
+}
+
+fn default_unknown_tool_retries() -> usize {
+    2
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -38,6 +124,13 @@ pub struct SystemConfig {
     pub heartbeat_interval_ms: u64,
     pub check_interval_ms: u64,
     pub channel_buffer_size: usize,
+    /// How long shutdown waits for in-flight requests before aborting them
+    #[serde(default = "default_shutdown_timeout_ms")]
+    pub shutdown_timeout_ms: u64,
+}
+
+fn default_shutdown_timeout_ms() -> u64 {
+    5000
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -45,6 +138,81 @@ pub struct LoggingConfig {
     pub level: String,
 }
 
+/// Built-in tools that `ToolRegistry::with_defaults` may hand to agents
+///
+/// Every tool is enabled unless turned off here, e.g. to deny agents shell
+/// access in a locked-down deployment.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolsConfig {
+    /// `execute_shell`
+    #[serde(default = "enabled")]
+    pub enable_shell: bool,
+    /// `write_file`, `append_file` and `edit_file`
+    #[serde(default = "enabled")]
+    pub enable_write: bool,
+    /// `http_request` and `download_file`
+    #[serde(default = "enabled")]
+    pub enable_http: bool,
+    /// Let registries map near-miss tool names, such as `read-file` or
+    /// `readFile`, to the one registered tool they clearly mean
+    #[serde(default)]
+    pub fuzzy_names: bool,
+    /// Record tool calls instead of making them while the LLM still decides,
+    /// so a run shows which tools it would call
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Results of cacheable tools kept per agent; 0 turns the cache off
+    #[serde(default)]
+    pub cache_capacity: usize,
+    /// How long a cached tool result stays valid
+    #[serde(default = "default_cache_ttl_ms")]
+    pub cache_ttl_ms: u64,
+}
+
+fn enabled() -> bool {
+    true
+}
+
+fn default_cache_ttl_ms() -> u64 {
+    300_000
+}
+
+impl Default for ToolsConfig {
+    fn default() -> Self {
+        Self {
+            enable_shell: true,
+            enable_write: true,
+            enable_http: true,
+            fuzzy_names: false,
+            dry_run: false,
+            cache_capacity: 0,
+            cache_ttl_ms: default_cache_ttl_ms(),
+        }
+    }
+}
+
+impl ToolsConfig {
+    /// Whether the tool registered as `tool_name` may be given to agents
+    pub fn allows(&self, tool_name: &str) -> bool {
+        match tool_name {
+            "execute_shell" => self.enable_shell,
+            "write_file" | "append_file" | "edit_file" => self.enable_write,
+            "http_request" | "download_file" => self.enable_http,
+            _ => true,
+        }
+    }
+
+    /// Result cache for agent tool executors, if one is configured
+    pub fn cache(&self) -> Option<CacheConfig> {
+        (self.cache_capacity > 0).then(|| {
+            CacheConfig::new(
+                self.cache_capacity,
+                Duration::from_millis(self.cache_ttl_ms),
+            )
+        })
+    }
+}
+
 impl Settings {
     pub fn new() -> Result<Self, ConfigError> {
         let config_env = env::var("CONFIG_ENV").unwrap_or_else(|_| "default".to_string());
@@ -54,11 +222,374 @@ impl Settings {
             .add_source(Environment::with_prefix("APP").separator("__"))
             .build()?;
 
-        config.try_deserialize()
+        Self::from_config(config)
+    }
+
+    /// Load settings from `path`, layered over the built-in defaults
+    ///
+    /// Unlike `new`, neither `CONFIG_ENV` nor `APP__*` variables are consulted,
+    /// so the file alone decides what differs from the defaults.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let config = Config::builder()
+            .add_source(File::from_str(DEFAULT_CONFIG, FileFormat::Toml))
+            .add_source(File::from(path.as_ref()))
+            .build()?;
+
+        Self::from_config(config)
+    }
+
+    /// Built-in defaults, independent of files and environment
+    pub fn defaults() -> Self {
+        Config::builder()
+            .add_source(File::from_str(DEFAULT_CONFIG, FileFormat::Toml))
+            .build()
+            .and_then(Self::from_config)
+            .expect("built-in default config is valid")
+    }
+
+    /// Build settings in code, starting from the built-in defaults
+    pub fn builder() -> SettingsBuilder {
+        SettingsBuilder {
+            settings: Self::defaults(),
+        }
+    }
+
+    fn from_config(config: Config) -> Result<Self, ConfigError> {
+        let settings: Self = config.try_deserialize()?;
+
+        // Fail at load time rather than on the first agent run
+        GuardrailPolicy::from_config(&settings.guardrails)
+            .map_err(|e| ConfigError::Message(format!("Invalid guardrail pattern: {}", e)))?;
+
+        Ok(settings)
+    }
+
+    /// Check that values are in range, reporting every problem at once
+    ///
+    /// Out-of-range values otherwise surface later as confusing runtime
+    /// behavior, e.g. a zero channel buffer panics when the router starts.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let mut problems = Vec::new();
+        let mut require = |ok: bool, problem: String| {
+            if !ok {
+                problems.push(problem);
+            }
+        };
+
+        require(
+            !self.llm.model.trim().is_empty(),
+            "llm.model must not be empty".into(),
+        );
+        require(
+            self.llm.max_tokens > 0,
+            "llm.max_tokens must be greater than 0".into(),
+        );
+        require(
+            (0.0..=2.0).contains(&self.llm.temperature),
+            format!(
+                "llm.temperature must be between 0 and 2 (got {})",
+                self.llm.temperature
+            ),
+        );
+        for (i, fallback) in self.llm.fallbacks.iter().enumerate() {
+            require(
+                !fallback.model.trim().is_empty(),
+                format!("llm.fallbacks[{}].model must not be empty", i),
+            );
+        }
+
+        for (name, value) in [
+            ("agent.max_iterations", self.agent.max_iterations),
+            (
+                "agent.max_orchestration_steps",
+                self.agent.max_orchestration_steps,
+            ),
+            ("agent.max_sub_goals", self.agent.max_sub_goals),
+            (
+                "agent.max_concurrent_agents",
+                self.agent.max_concurrent_agents,
+            ),
+            (
+                "system.channel_buffer_size",
+                self.system.channel_buffer_size,
+            ),
+        ] {
+            require(value > 0, format!("{} must be greater than 0", name));
+        }
+
+        for (name, value) in [
+            (
+                "validation.agent_timeout_ms",
+                self.validation.agent_timeout_ms,
+            ),
+            (
+                "system.heartbeat_interval_ms",
+                self.system.heartbeat_interval_ms,
+            ),
+            (
+                "system.heartbeat_timeout_ms",
+                self.system.heartbeat_timeout_ms,
+            ),
+            ("system.check_interval_ms", self.system.check_interval_ms),
+        ] {
+            require(value > 0, format!("{} must be greater than 0", name));
+        }
+        require(
+            self.system.heartbeat_interval_ms < self.system.heartbeat_timeout_ms,
+            format!(
+                "system.heartbeat_interval_ms ({}) must be less than system.heartbeat_timeout_ms ({}), \
+                 or every actor will be reported dead",
+                self.system.heartbeat_interval_ms, self.system.heartbeat_timeout_ms
+            ),
+        );
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(ConfigError::Message(format!(
+                "Invalid configuration:\n  - {}",
+                problems.join("\n  - ")
+            )))
+        }
+    }
+
+    /// Whether agents should record tool calls instead of making them
+    pub fn tools_dry_run(&self) -> bool {
+        self.dry_run || self.tools.dry_run
+    }
+
+    /// API key for the settings loaded by `new`; see `resolve_api_key`
+    pub fn api_key() -> Result<String, ConfigError> {
+        Self::new()?.resolve_api_key(None)
     }
 
-    pub fn api_key() -> Result<String> {
-        env::var("OPENAI_API_KEY")
-            .map_err(|_| anyhow::anyhow!("OPENAI_API_KEY environment variable not set"))
+    /// Find the API key, checking in order:
+    ///
+    /// 1. `explicit`, when given
+    /// 2. the variable named by `llm.api_key_env`
+    /// 3. `OPENAI_API_KEY`
+    /// 4. the file at `llm.api_key_file`
+    ///
+    /// Blank values count as unset. The error names every place checked.
+    pub fn resolve_api_key(&self, explicit: Option<&str>) -> Result<String, ConfigError> {
+        self.resolve_api_key_with(explicit, |var| env::var(var).ok())
+    }
+
+    fn resolve_api_key_with(
+        &self,
+        explicit: Option<&str>,
+        lookup_env: impl Fn(&str) -> Option<String>,
+    ) -> Result<String, ConfigError> {
+        let present =
+            |key: Option<String>| key.map(|k| k.trim().to_string()).filter(|k| !k.is_empty());
+        let mut checked = vec!["explicit argument".to_string()];
+
+        if let Some(key) = present(explicit.map(str::to_string)) {
+            return Ok(key);
+        }
+
+        let vars = self.llm.api_key_env.iter().map(String::as_str);
+        for var in vars.chain([GENERIC_API_KEY_ENV]) {
+            if let Some(key) = present(lookup_env(var)) {
+                return Ok(key);
+            }
+            checked.push(format!("${}", var));
+        }
+
+        if let Some(path) = &self.llm.api_key_file {
+            if let Some(key) = present(std::fs::read_to_string(path).ok()) {
+                return Ok(key);
+            }
+            checked.push(format!("file {}", path.display()));
+        }
+
+        Err(ConfigError::Message(format!(
+            "No API key found; checked {}",
+            checked.join(", ")
+        )))
+    }
+}
+
+/// Programmatic settings, e.g. for tests or several systems in one process
+///
+/// # Example
+/// ```
+/// use actorus::Settings;
+///
+/// let defaults = Settings::defaults();
+/// let settings = Settings::builder()
+///     .agent(actorus::AgentConfig {
+///         max_iterations: 3,
+///         ..defaults.agent
+///     })
+///     .build()
+///     .unwrap();
+/// assert_eq!(settings.agent.max_iterations, 3);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SettingsBuilder {
+    settings: Settings,
+}
+
+impl SettingsBuilder {
+    pub fn llm(mut self, llm: LLMConfig) -> Self {
+        self.settings.llm = llm;
+        self
+    }
+
+    pub fn agent(mut self, agent: AgentConfig) -> Self {
+        self.settings.agent = agent;
+        self
+    }
+
+    pub fn validation(mut self, validation: ValidationConfig) -> Self {
+        self.settings.validation = validation;
+        self
+    }
+
+    pub fn system(mut self, system: SystemConfig) -> Self {
+        self.settings.system = system;
+        self
+    }
+
+    pub fn logging(mut self, logging: LoggingConfig) -> Self {
+        self.settings.logging = logging;
+        self
+    }
+
+    pub fn prompts(mut self, prompts: PromptConfig) -> Self {
+        self.settings.prompts = prompts;
+        self
+    }
+
+    pub fn guardrails(mut self, guardrails: GuardrailConfig) -> Self {
+        self.settings.guardrails = guardrails;
+        self
+    }
+
+    pub fn circuit_breaker(mut self, circuit_breaker: CircuitBreakerConfig) -> Self {
+        self.settings.circuit_breaker = circuit_breaker;
+        self
+    }
+
+    pub fn tools(mut self, tools: ToolsConfig) -> Self {
+        self.settings.tools = tools;
+        self
+    }
+
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.settings.dry_run = dry_run;
+        self
+    }
+
+    /// Check guardrail patterns and value ranges, then return the settings
+    pub fn build(self) -> Result<Settings, ConfigError> {
+        GuardrailPolicy::from_config(&self.settings.guardrails)
+            .map_err(|e| ConfigError::Message(format!("Invalid guardrail pattern: {}", e)))?;
+        self.settings.validate()?;
+        Ok(self.settings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_settings_are_valid() {
+        Settings::new().unwrap().validate().unwrap();
+    }
+
+    #[test]
+    fn test_validate_reports_every_out_of_range_field() {
+        let mut settings = Settings::new().unwrap();
+        settings.system.channel_buffer_size = 0;
+        settings.llm.temperature = 3.5;
+        settings.system.heartbeat_interval_ms = settings.system.heartbeat_timeout_ms;
+
+        let message = settings.validate().unwrap_err().to_string();
+        assert!(message.contains("system.channel_buffer_size must be greater than 0"));
+        assert!(message.contains("llm.temperature must be between 0 and 2 (got 3.5)"));
+        assert!(message.contains("system.heartbeat_interval_ms"));
+        assert!(!message.contains("agent.max_iterations"));
+    }
+
+    #[test]
+    fn test_builder_overrides_defaults() {
+        let defaults = Settings::defaults();
+        let settings = Settings::builder()
+            .system(SystemConfig {
+                channel_buffer_size: 7,
+                ..defaults.system.clone()
+            })
+            .validation(ValidationConfig {
+                agent_timeout_ms: 1234,
+            })
+            .build()
+            .unwrap();
+
+        assert_eq!(settings.system.channel_buffer_size, 7);
+        assert_eq!(settings.validation.agent_timeout_ms, 1234);
+        assert_eq!(settings.agent.max_iterations, defaults.agent.max_iterations);
+
+        assert!(Settings::builder()
+            .agent(AgentConfig {
+                max_iterations: 0,
+                ..defaults.agent
+            })
+            .build()
+            .is_err());
+    }
+
+    #[test]
+    fn test_api_key_precedence_and_not_found_message() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let key_file = temp_dir.path().join("key");
+        std::fs::write(&key_file, "from-file\n").unwrap();
+
+        let mut settings = Settings::defaults();
+        settings.llm.api_key_env = Some("GROQ_API_KEY".into());
+        settings.llm.api_key_file = Some(key_file);
+
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |var: &str| {
+                vars.iter()
+                    .find(|(name, _)| *name == var)
+                    .map(|(_, value)| value.to_string())
+            }
+        };
+        let both = env(&[("GROQ_API_KEY", "groq"), ("OPENAI_API_KEY", "openai")]);
+
+        let resolve = |explicit, lookup| settings.resolve_api_key_with(explicit, lookup).unwrap();
+        assert_eq!(resolve(Some("explicit"), both), "explicit");
+        assert_eq!(resolve(None, both), "groq");
+        assert_eq!(
+            resolve(Some("  "), env(&[("OPENAI_API_KEY", "openai")])),
+            "openai"
+        );
+        assert_eq!(resolve(None, env(&[("GROQ_API_KEY", "")])), "from-file");
+
+        settings.llm.api_key_file = Some(temp_dir.path().join("missing"));
+        let message = settings
+            .resolve_api_key_with(None, env(&[]))
+            .unwrap_err()
+            .to_string();
+        assert!(message.contains(
+            "No API key found; checked explicit argument, $GROQ_API_KEY, $OPENAI_API_KEY, file "
+        ));
+        assert!(message.ends_with("missing"));
+    }
+
+    #[test]
+    fn test_from_file_layers_over_defaults() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("custom.toml");
+        std::fs::write(&path, "[agent]\nmax_iterations = 9\n").unwrap();
+
+        let settings = Settings::from_file(&path).unwrap();
+        assert_eq!(settings.agent.max_iterations, 9);
+        assert_eq!(settings.llm.model, Settings::defaults().llm.model);
+
+        assert!(Settings::from_file(temp_dir.path().join("missing.toml")).is_err());
     }
 }
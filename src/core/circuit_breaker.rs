@@ -0,0 +1,183 @@
+//! Circuit Breaker - Stop calling dependencies that keep failing
+//!
+//! Information Hiding:
+//! - Failure counting and cooldown timing hidden behind allow/record calls
+//! - Half-open trial bookkeeping hidden from callers
+//! - Callers only see whether a call may proceed and the current state
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CircuitBreakerConfig {
+    /// Consecutive failures that open the breaker; zero disables it
+    #[serde(default = "default_failure_threshold")]
+    pub failure_threshold: u32,
+    /// How long an open breaker rejects calls before letting a trial call through
+    #[serde(default = "default_cooldown_ms")]
+    pub cooldown_ms: u64,
+}
+
+fn default_failure_threshold() -> u32 {
+    5
+}
+
+fn default_cooldown_ms() -> u64 {
+    30_000
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: default_failure_threshold(),
+            cooldown_ms: default_cooldown_ms(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BreakerState {
+    /// Calls flow normally
+    Closed,
+    /// Calls are rejected until the cooldown ends
+    Open,
+    /// Cooldown over; the next call is a trial deciding whether to close again
+    HalfOpen,
+}
+
+#[derive(Debug, Default)]
+struct BreakerInner {
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+    /// When the current half-open trial was let through
+    trial_started: Option<Instant>,
+}
+
+/// Tracks failures of one dependency, such as an LLM provider or a tool
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    config: CircuitBreakerConfig,
+    inner: Mutex<BreakerInner>,
+}
+
+impl CircuitBreaker {
+    pub fn new(config: CircuitBreakerConfig) -> Self {
+        Self {
+            config,
+            inner: Mutex::new(BreakerInner::default()),
+        }
+    }
+
+    pub fn state(&self) -> BreakerState {
+        let inner = self.inner.lock().unwrap();
+        match inner.opened_at {
+            None => BreakerState::Closed,
+            Some(opened_at) if opened_at.elapsed() < self.cooldown() => BreakerState::Open,
+            Some(_) => BreakerState::HalfOpen,
+        }
+    }
+
+    /// Whether a call may proceed now
+    ///
+    /// Once the cooldown has passed, exactly one caller is let through as a
+    /// trial; the others are rejected until that trial is recorded. A trial
+    /// that is never recorded, because its call was cancelled or timed out,
+    /// expires after another cooldown so the next caller can try again.
+    pub fn allow(&self) -> bool {
+        let cooldown = self.cooldown();
+        let mut inner = self.inner.lock().unwrap();
+        match inner.opened_at {
+            None => true,
+            Some(opened_at) if opened_at.elapsed() < cooldown => false,
+            Some(_) if inner.trial_started.is_some_and(|t| t.elapsed() < cooldown) => false,
+            Some(_) => {
+                inner.trial_started = Some(Instant::now());
+                true
+            }
+        }
+    }
+
+    /// The dependency answered; close the breaker
+    pub fn record_success(&self) {
+        *self.inner.lock().unwrap() = BreakerInner::default();
+    }
+
+    /// The dependency failed; open the breaker at the threshold or on a failed trial
+    pub fn record_failure(&self) {
+        if self.config.failure_threshold == 0 {
+            return;
+        }
+        let mut inner = self.inner.lock().unwrap();
+        inner.consecutive_failures += 1;
+        if inner.trial_started.is_some()
+            || inner.consecutive_failures >= self.config.failure_threshold
+        {
+            inner.opened_at = Some(Instant::now());
+            inner.trial_started = None;
+        }
+    }
+
+    fn cooldown(&self) -> Duration {
+        Duration::from_millis(self.config.cooldown_ms)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_opens_after_threshold_then_half_opens_after_cooldown() {
+        let breaker = CircuitBreaker::new(CircuitBreakerConfig {
+            failure_threshold: 2,
+            cooldown_ms: 20,
+        });
+
+        breaker.record_failure();
+        assert_eq!(breaker.state(), BreakerState::Closed);
+        breaker.record_failure();
+        assert_eq!(breaker.state(), BreakerState::Open);
+        assert!(!breaker.allow());
+
+        std::thread::sleep(Duration::from_millis(30));
+        assert_eq!(breaker.state(), BreakerState::HalfOpen);
+        assert!(breaker.allow());
+        assert!(!breaker.allow(), "only one trial call at a time");
+
+        // A failed trial reopens immediately
+        breaker.record_failure();
+        assert_eq!(breaker.state(), BreakerState::Open);
+
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(breaker.allow());
+        breaker.record_success();
+        assert_eq!(breaker.state(), BreakerState::Closed);
+    }
+
+    #[tokio::test]
+    async fn test_dropped_trial_expires_after_cooldown() {
+        let breaker = CircuitBreaker::new(CircuitBreakerConfig {
+            failure_threshold: 1,
+            cooldown_ms: 20,
+        });
+        breaker.record_failure();
+        tokio::time::sleep(Duration::from_millis(30)).await;
+
+        // The trial call is cancelled before it records an outcome
+        let trial = async {
+            assert!(breaker.allow());
+            std::future::pending::<()>().await;
+        };
+        assert!(tokio::time::timeout(Duration::from_millis(5), trial)
+            .await
+            .is_err());
+        assert!(!breaker.allow(), "trial still counts as in flight");
+
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        assert!(breaker.allow(), "abandoned trial no longer blocks callers");
+        breaker.record_success();
+        assert_eq!(breaker.state(), BreakerState::Closed);
+    }
+}
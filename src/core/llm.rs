@@ -1,15 +1,377 @@
 use crate::config::Settings;
+use crate::core::circuit_breaker::{BreakerState, CircuitBreaker};
+use crate::tools::ToolMetadata;
 use anyhow::Result;
+use async_trait::async_trait;
 use futures::StreamExt;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
 use tokio::sync::mpsc;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+const DEFAULT_CHAT_COMPLETIONS_URL: &str = "https://api.openai.com/v1/chat/completions";
+const DEFAULT_EMBEDDINGS_URL: &str = "https://api.openai.com/v1/embeddings";
+
+/// Reply to every dry-run chat, shaped as a final agent decision so ReAct
+/// loops finish after planning a single step
+///
+/// No tool is chosen this way; `tools.dry_run` keeps the LLM deciding and
+/// records the tool calls it asks for instead.
+const DRY_RUN_REPLY: &str = r#"{"thought": "Dry run: no LLM call was made", "is_final": true, "final_answer": "[dry run] LLM call skipped"}"#;
+
+/// Author of a chat message
+///
+/// Serializes to the lowercase role names expected by chat completion APIs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    System,
+    User,
+    Assistant,
+    Tool,
+}
+
+impl Role {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::System => "system",
+            Role::User => "user",
+            Role::Assistant => "assistant",
+            Role::Tool => "tool",
+        }
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<Role> for String {
+    fn from(role: Role) -> Self {
+        role.as_str().to_string()
+    }
+}
+
+/// Error returned when a string does not name a known chat role
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("unknown chat role '{0}' (expected system, user, assistant or tool)")]
+pub struct UnknownRole(pub String);
+
+impl TryFrom<&str> for Role {
+    type Error = UnknownRole;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "system" => Ok(Role::System),
+            "user" => Ok(Role::User),
+            "assistant" => Ok(Role::Assistant),
+            "tool" => Ok(Role::Tool),
+            other => Err(UnknownRole(other.to_string())),
+        }
+    }
+}
+
+impl FromStr for Role {
+    type Err = UnknownRole;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Role::try_from(s)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ChatMessage {
-    pub role: String,
+    pub role: Role,
+    #[serde(default, deserialize_with = "deserialize_nullable_content")]
     pub content: String,
+    /// Tool invocations requested by an assistant turn
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_calls: Option<Vec<ToolCall>>,
+    /// Id of the tool call that a `Role::Tool` message answers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
+    /// Participant name, for providers that distinguish speakers with the same role
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Extra content blocks, such as images, following the text `content`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parts: Option<Vec<ContentPart>>,
+    /// Caller-defined tags, e.g. the producing agent or a token count;
+    /// persisted with the message but never sent to the provider
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Value>,
+    /// Pinned messages are never dropped when history is trimmed
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pinned: bool,
+}
+
+/// One block of multi-part message content
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ContentPart {
+    Text { text: String },
+    ImageUrl { image_url: ImageUrl },
+}
+
+/// Image reference in a content block: an https URL or a `data:` URL
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImageUrl {
+    pub url: String,
+    /// Resolution hint such as "low" or "high"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+/// Image input for a user message
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImageSource {
+    /// Publicly reachable image URL
+    Url(String),
+    /// Raw image bytes, sent inline as a base64 `data:` URL
+    Base64 { media_type: String, data: Vec<u8> },
+}
+
+impl ImageSource {
+    pub fn url(url: impl Into<String>) -> Self {
+        ImageSource::Url(url.into())
+    }
+
+    /// Inline image, e.g. `ImageSource::bytes("image/png", std::fs::read(path)?)`
+    pub fn bytes(media_type: impl Into<String>, data: Vec<u8>) -> Self {
+        ImageSource::Base64 {
+            media_type: media_type.into(),
+            data,
+        }
+    }
+
+    pub fn to_content_part(&self) -> ContentPart {
+        use base64::Engine;
+
+        let url = match self {
+            ImageSource::Url(url) => url.clone(),
+            ImageSource::Base64 { media_type, data } => format!(
+                "data:{};base64,{}",
+                media_type,
+                base64::engine::general_purpose::STANDARD.encode(data)
+            ),
+        };
+        ContentPart::ImageUrl {
+            image_url: ImageUrl { url, detail: None },
+        }
+    }
+}
+
+/// How messages are written in provider requests
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MessageFormat {
+    /// `content` as a plain string, except for messages carrying extra
+    /// parts, which a string cannot hold
+    #[default]
+    Text,
+    /// `content` as an array of typed blocks, as multimodal models expect
+    ContentBlocks,
+}
+
+/// Providers send `"content": null` on assistant turns that only call tools
+fn deserialize_nullable_content<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(Option::<String>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+/// A function call requested by the model in an assistant turn
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolCall {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub call_type: String,
+    pub function: FunctionCall,
+}
+
+/// Name and JSON-encoded arguments of a requested function call
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FunctionCall {
+    pub name: String,
+    /// Arguments as a JSON string, exactly as the provider transmits them
+    pub arguments: String,
+}
+
+impl ToolCall {
+    pub fn function(id: impl Into<String>, name: impl Into<String>, arguments: &Value) -> Self {
+        Self {
+            id: id.into(),
+            call_type: "function".to_string(),
+            function: FunctionCall {
+                name: name.into(),
+                arguments: arguments.to_string(),
+            },
+        }
+    }
+
+    /// Arguments decoded from the JSON string sent by the provider
+    pub fn parsed_arguments(&self) -> Result<Value> {
+        let arguments = self.function.arguments.trim();
+        if arguments.is_empty() {
+            return Ok(Value::Object(Default::default()));
+        }
+        serde_json::from_str(arguments).map_err(|e| {
+            anyhow::anyhow!(
+                "Invalid arguments for tool call '{}': {}",
+                self.function.name,
+                e
+            )
+        })
+    }
+}
+
+/// Reply to `LLMClient::chat_with_tools`
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChatOutcome {
+    /// The model answered in text
+    Text(String),
+    /// The model asked for these tool calls, in order
+    ToolCalls(Vec<ToolCall>),
+}
+
+/// Function definition offered to the provider's native tool calling
+fn function_definition(tool: &ToolMetadata) -> Value {
+    serde_json::json!({
+        "type": "function",
+        "function": {
+            "name": tool.name,
+            "description": tool.description,
+            "parameters": tool.to_json_schema(),
+        }
+    })
+}
+
+impl ChatMessage {
+    pub fn new(role: Role, content: impl Into<String>) -> Self {
+        Self {
+            role,
+            content: content.into(),
+            tool_calls: None,
+            tool_call_id: None,
+            name: None,
+            parts: None,
+            metadata: None,
+            pinned: false,
+        }
+    }
+
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Append content blocks, e.g. `ContentPart::ImageUrl`, after the text
+    pub fn with_parts(mut self, parts: Vec<ContentPart>) -> Self {
+        if !parts.is_empty() {
+            self.parts.get_or_insert_with(Vec::new).extend(parts);
+        }
+        self
+    }
+
+    pub fn with_metadata(mut self, metadata: Value) -> Self {
+        self.metadata = Some(metadata);
+        self
+    }
+
+    /// Keep this message whenever history is trimmed
+    pub fn pinned(mut self) -> Self {
+        self.pinned = true;
+        self
+    }
+
+    /// The message as a provider expects it in `format`
+    pub fn to_provider_json(&self, format: MessageFormat) -> Value {
+        let mut message = serde_json::to_value(self).unwrap_or_default();
+        if let Value::Object(fields) = &mut message {
+            fields.remove("parts");
+            fields.remove("metadata");
+            fields.remove("pinned");
+            if format == MessageFormat::ContentBlocks || self.parts.is_some() {
+                let text = (!self.content.is_empty()).then(|| ContentPart::Text {
+                    text: self.content.clone(),
+                });
+                let blocks: Vec<&ContentPart> =
+                    text.iter().chain(self.parts.iter().flatten()).collect();
+                fields.insert(
+                    "content".to_string(),
+                    serde_json::to_value(blocks).unwrap_or_default(),
+                );
+            }
+        }
+        message
+    }
+
+    pub fn system(content: impl Into<String>) -> Self {
+        Self::new(Role::System, content)
+    }
+
+    pub fn user(content: impl Into<String>) -> Self {
+        Self::new(Role::User, content)
+    }
+
+    pub fn assistant(content: impl Into<String>) -> Self {
+        Self::new(Role::Assistant, content)
+    }
+
+    /// Assistant turn that requests one or more tool calls
+    pub fn assistant_tool_calls(tool_calls: Vec<ToolCall>) -> Self {
+        Self {
+            tool_calls: Some(tool_calls),
+            ..Self::new(Role::Assistant, "")
+        }
+    }
+
+    /// Tool result answering the assistant's call with id `tool_call_id`
+    pub fn tool(tool_call_id: impl Into<String>, content: impl Into<String>) -> Self {
+        Self {
+            tool_call_id: Some(tool_call_id.into()),
+            ..Self::new(Role::Tool, content)
+        }
+    }
+}
+
+/// Drop the oldest messages until at most `max_messages` remain
+///
+/// System and pinned messages are always kept, so the result can still be
+/// longer than `max_messages` when they alone exceed it. A tool result whose
+/// call was dropped goes with it, since providers reject unanswered results.
+/// Returns the dropped messages, oldest first.
+pub fn trim_history(history: &mut Vec<ChatMessage>, max_messages: usize) -> Vec<ChatMessage> {
+    let droppable = |m: &ChatMessage| m.role != Role::System && !m.pinned;
+    let excess = history.len().saturating_sub(max_messages);
+    let mut to_drop = history.iter().filter(|m| droppable(m)).count().min(excess);
+
+    let mut dropped = Vec::new();
+    let mut kept = Vec::with_capacity(history.len() - to_drop);
+    let mut dropped_calls = Vec::new();
+    for message in history.drain(..) {
+        let orphaned = message.role == Role::Tool
+            && message
+                .tool_call_id
+                .as_ref()
+                .is_some_and(|id| dropped_calls.contains(id));
+        if orphaned || (to_drop > 0 && droppable(&message)) {
+            to_drop = to_drop.saturating_sub(1);
+            dropped_calls.extend(message.tool_calls.iter().flatten().map(|c| c.id.clone()));
+            dropped.push(message);
+        } else {
+            kept.push(message);
+        }
+    }
+    *history = kept;
+    dropped
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -34,20 +396,224 @@ fn default_strict() -> bool {
     true
 }
 
-#[derive(Debug, Serialize)]
-struct ChatRequest {
-    model: String,
-    messages: Vec<ChatMessage>,
-    max_tokens: u32,
-    temperature: f32,
-    stream: bool,
+/// Per-request sampling overrides
+///
+/// Any field left as `None` falls back to the value configured in `Settings`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LLMParams {
+    pub temperature: Option<f32>,
+    pub top_p: Option<f32>,
+    pub max_tokens: Option<u32>,
+    pub stop: Option<Vec<String>>,
+    /// Sampling seed for providers that support reproducible outputs
+    pub seed: Option<u64>,
+}
+
+impl LLMParams {
+    pub fn temperature(mut self, temperature: f32) -> Self {
+        self.temperature = Some(temperature);
+        self
+    }
+
+    pub fn top_p(mut self, top_p: f32) -> Self {
+        self.top_p = Some(top_p);
+        self
+    }
+
+    pub fn max_tokens(mut self, max_tokens: u32) -> Self {
+        self.max_tokens = Some(max_tokens);
+        self
+    }
+
+    pub fn stop(mut self, stop: Vec<String>) -> Self {
+        self.stop = Some(stop);
+        self
+    }
+
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+}
+
+/// Chat completion request as sent to the provider
+#[derive(Debug, Clone, Serialize)]
+pub struct CompletionRequest {
+    pub model: String,
+    pub messages: Vec<ChatMessage>,
+    pub max_tokens: u32,
+    pub temperature: f32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    pub stream: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
-    response_format: Option<ResponseFormat>,
+    pub response_format: Option<ResponseFormat>,
+    /// Function definitions the model may call instead of answering in text
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tools: Option<Vec<Value>>,
+    /// Applied to `messages` by `body`
+    #[serde(skip)]
+    pub message_format: MessageFormat,
+}
+
+impl CompletionRequest {
+    /// JSON body for the provider, with messages written in `message_format`
+    pub fn body(&self) -> Value {
+        let mut body = serde_json::to_value(self).unwrap_or_default();
+        body["messages"] = self
+            .messages
+            .iter()
+            .map(|message| message.to_provider_json(self.message_format))
+            .collect();
+        body
+    }
+}
+
+/// Reply produced by an `LLMBackend`
+#[derive(Debug, Clone, Default)]
+pub struct Completion {
+    pub content: String,
+    /// Total tokens reported for the call, zero when unknown
+    pub tokens_used: u64,
+    /// Function calls requested through the provider's native tool calling
+    pub tool_calls: Vec<ToolCall>,
+}
+
+/// Body sent to a provider's embeddings endpoint
+#[derive(Debug, Clone, Serialize)]
+pub struct EmbeddingRequest {
+    pub model: String,
+    pub input: Vec<String>,
+}
+
+/// Vectors produced by an `LLMBackend`, in the same order as the inputs
+#[derive(Debug, Clone, Default)]
+pub struct Embeddings {
+    pub vectors: Vec<Vec<f32>>,
+    /// Total tokens reported for the call, zero when unknown
+    pub tokens_used: u64,
+}
+
+/// Failure reported while calling a provider
+///
+/// Carries the HTTP status when the provider answered, so callers can tell
+/// transient outages from requests that would fail anywhere.
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("{message}")]
+pub struct ProviderError {
+    pub status: Option<u16>,
+    pub message: String,
+}
+
+impl ProviderError {
+    pub fn new(status: Option<u16>, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+
+    /// Connection failures, timeouts, rate limits and server errors
+    pub fn is_transient(&self) -> bool {
+        match self.status {
+            Some(status) => status == 429 || status >= 500,
+            None => true,
+        }
+    }
+}
+
+/// Whether another provider in the chain might serve a request that failed this way
+///
+/// Only failures known to be passing are transient: a transient `ProviderError`,
+/// an HTTP timeout or connection failure, an elapsed timer, or an interrupted
+/// connection. Anything else, such as a bad request built by a custom backend,
+/// would fail on every provider.
+fn is_transient(error: &anyhow::Error) -> bool {
+    error
+        .chain()
+        .find_map(|cause| {
+            if let Some(e) = cause.downcast_ref::<ProviderError>() {
+                return Some(e.is_transient());
+            }
+            if let Some(e) = cause.downcast_ref::<reqwest::Error>() {
+                let retryable_status = e
+                    .status()
+                    .is_some_and(|s| s.as_u16() == 429 || s.is_server_error());
+                return Some(e.is_timeout() || e.is_connect() || retryable_status);
+            }
+            if cause.is::<tokio::time::error::Elapsed>() {
+                return Some(true);
+            }
+            cause.downcast_ref::<std::io::Error>().map(|e| {
+                matches!(
+                    e.kind(),
+                    std::io::ErrorKind::TimedOut
+                        | std::io::ErrorKind::ConnectionReset
+                        | std::io::ErrorKind::ConnectionAborted
+                        | std::io::ErrorKind::ConnectionRefused
+                        | std::io::ErrorKind::Interrupted
+                        | std::io::ErrorKind::UnexpectedEof
+                )
+            })
+        })
+        .unwrap_or(false)
+}
+
+/// Transport that turns a completion request into a reply
+///
+/// `LLMClient` builds requests from settings and per-call overrides and
+/// delegates the actual call to a backend. The default backend talks to the
+/// provider over HTTP; `MockLLM` replays scripted replies for tests.
+#[async_trait]
+pub trait LLMBackend: Send + Sync {
+    async fn complete(&self, request: &CompletionRequest) -> Result<Completion>;
+
+    /// Stream reply content to `tx`
+    ///
+    /// Backends without native streaming send the whole reply as one chunk.
+    async fn stream(&self, request: &CompletionRequest, tx: mpsc::Sender<String>) -> Result<()> {
+        let completion = self.complete(request).await?;
+        tx.send(completion.content).await?;
+        Ok(())
+    }
+
+    /// Embed each input text
+    ///
+    /// Backends without an embeddings endpoint report an error.
+    async fn embed(&self, _request: &EmbeddingRequest) -> Result<Embeddings> {
+        anyhow::bail!("This LLM backend does not support embeddings")
+    }
 }
 
 #[derive(Debug, Deserialize)]
 struct ChatResponse {
     choices: Vec<Choice>,
+    #[serde(default)]
+    usage: Option<Usage>,
+}
+
+/// Token usage reported by the provider for one call
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Usage {
+    pub total_tokens: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbeddingResponse {
+    data: Vec<EmbeddingData>,
+    #[serde(default)]
+    usage: Option<Usage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbeddingData {
+    embedding: Vec<f32>,
+    #[serde(default)]
+    index: usize,
 }
 
 #[derive(Debug, Deserialize)]
@@ -70,39 +636,95 @@ struct Delta {
     content: Option<String>,
 }
 
-pub struct LLMClient {
+/// Incremental parser for a streamed chat completion
+///
+/// Network chunks may end mid-line or in the middle of a multibyte
+/// character, so bytes are held back until a whole line has arrived.
+/// A line always ends on a character boundary, so it decodes cleanly.
+#[derive(Debug, Default)]
+struct SseDecoder {
+    pending: Vec<u8>,
+    done: bool,
+}
+
+impl SseDecoder {
+    /// Content of every event completed by `chunk`
+    fn push(&mut self, chunk: &[u8]) -> Vec<String> {
+        self.pending.extend_from_slice(chunk);
+        let mut tokens = Vec::new();
+        while let Some(end) = self.pending.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.pending.drain(..=end).collect();
+            tokens.extend(self.parse_line(&line));
+        }
+        tokens
+    }
+
+    /// Content of a final event not followed by a newline
+    fn finish(&mut self) -> Vec<String> {
+        let line = std::mem::take(&mut self.pending);
+        self.parse_line(&line).into_iter().collect()
+    }
+
+    /// Whether the provider has signalled the end of the stream
+    fn is_done(&self) -> bool {
+        self.done
+    }
+
+    fn parse_line(&mut self, line: &[u8]) -> Option<String> {
+        if self.done {
+            return None;
+        }
+        let line = String::from_utf8_lossy(line);
+        let data = line.trim_end().strip_prefix("data: ")?;
+        if data == "[DONE]" {
+            self.done = true;
+            return None;
+        }
+        serde_json::from_str::<StreamChunk>(data)
+            .ok()?
+            .choices
+            .into_iter()
+            .next()?
+            .delta
+            .content
+    }
+}
+
+/// Backend calling an OpenAI-compatible chat completions endpoint
+pub struct HttpBackend {
     client: Client,
     api_key: String,
-    settings: Settings,
+    endpoint: String,
+    embeddings_endpoint: String,
 }
 
-impl LLMClient {
-    pub fn new(api_key: String, settings: Settings) -> Self {
+impl HttpBackend {
+    pub fn new(api_key: String) -> Self {
         Self {
             client: Client::new(),
             api_key,
-            settings,
+            endpoint: DEFAULT_CHAT_COMPLETIONS_URL.to_string(),
+            embeddings_endpoint: DEFAULT_EMBEDDINGS_URL.to_string(),
         }
     }
 
-    pub async fn chat(&self, messages: Vec<ChatMessage>) -> Result<String> {
-        self.chat_with_format(messages, None).await
+    /// Point the backend at a different chat completions endpoint
+    pub fn with_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = endpoint.into();
+        self
     }
 
-    pub async fn chat_with_format(
-        &self,
-        messages: Vec<ChatMessage>,
-        response_format: Option<ResponseFormat>,
-    ) -> Result<String> {
-        let request = ChatRequest {
-            model: self.settings.llm.model.clone(),
-            messages,
-            max_tokens: self.settings.llm.max_tokens,
-            temperature: self.settings.llm.temperature,
-            stream: false,
-            response_format,
-        };
+    /// Use an OpenAI-compatible API root such as `http://localhost:11434/v1`
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        let base_url = base_url.trim_end_matches('/');
+        self.embeddings_endpoint = format!("{}/embeddings", base_url);
+        self.with_endpoint(format!("{}/chat/completions", base_url))
+    }
+}
 
+#[async_trait]
+impl LLMBackend for HttpBackend {
+    async fn complete(&self, request: &CompletionRequest) -> Result<Completion> {
         const MAX_RETRIES: u32 = 3;
         const BASE_DELAY_MS: u64 = 1000;
 
@@ -122,10 +744,10 @@ impl LLMClient {
 
             let response_result = self
                 .client
-                .post("https://api.openai.com/v1/chat/completions")
+                .post(&self.endpoint)
                 .header("Authorization", format!("Bearer {}", self.api_key))
                 .header("Content-Type", "application/json")
-                .json(&request)
+                .json(&request.body())
                 .send()
                 .await;
 
@@ -133,7 +755,10 @@ impl LLMClient {
                 Ok(resp) => resp,
                 Err(e) => {
                     tracing::warn!("[LLMClient] HTTP request failed: {}", e);
-                    last_error = Some(anyhow::anyhow!("HTTP request failed: {}", e));
+                    last_error = Some(ProviderError::new(
+                        None,
+                        format!("HTTP request failed: {}", e),
+                    ));
                     continue;
                 }
             };
@@ -149,7 +774,10 @@ impl LLMClient {
                     status,
                     error_text
                 );
-                last_error = Some(anyhow::anyhow!("API error {}: {}", status, error_text));
+                last_error = Some(ProviderError::new(
+                    Some(status.as_u16()),
+                    format!("API error {}: {}", status, error_text),
+                ));
                 continue;
             }
 
@@ -157,69 +785,1081 @@ impl LLMClient {
                 Ok(cr) => cr,
                 Err(e) => {
                     tracing::warn!("[LLMClient] Failed to decode response body: {}", e);
-                    last_error = Some(anyhow::anyhow!("Response decode error: {}", e));
+                    last_error = Some(ProviderError::new(
+                        None,
+                        format!("Response decode error: {}", e),
+                    ));
                     continue;
                 }
             };
 
-            return Ok(chat_response
-                .choices
-                .first()
-                .map(|c| c.message.content.clone())
-                .unwrap_or_default());
+            let message = chat_response.choices.into_iter().next().map(|c| c.message);
+            return Ok(Completion {
+                content: message
+                    .as_ref()
+                    .map(|m| m.content.clone())
+                    .unwrap_or_default(),
+                tokens_used: chat_response.usage.map_or(0, |u| u.total_tokens),
+                tool_calls: message.and_then(|m| m.tool_calls).unwrap_or_default(),
+            });
         }
 
-        Err(last_error.unwrap_or_else(|| anyhow::anyhow!("All retry attempts failed")))
+        Err(last_error
+            .unwrap_or_else(|| ProviderError::new(None, "All retry attempts failed"))
+            .into())
     }
 
-    pub async fn stream_chat(
-        &self,
-        messages: Vec<ChatMessage>,
-        tx: mpsc::Sender<String>,
-    ) -> Result<()> {
-        let request = ChatRequest {
-            model: self.settings.llm.model.clone(),
-            messages,
-            max_tokens: self.settings.llm.max_tokens,
-            temperature: self.settings.llm.temperature,
-            stream: true,
-            response_format: None,
-        };
-
+    async fn stream(&self, request: &CompletionRequest, tx: mpsc::Sender<String>) -> Result<()> {
         let response = self
             .client
-            .post("https://api.openai.com/v1/chat/completions")
+            .post(&self.endpoint)
             .header("Authorization", format!("Bearer {}", self.api_key))
             .header("Content-Type", "application/json")
-            .json(&request)
+            .json(&request.body())
             .send()
             .await?;
 
         let mut stream = response.bytes_stream();
+        let mut decoder = SseDecoder::default();
 
         while let Some(chunk) = stream.next().await {
             if let Ok(bytes) = chunk {
-                let text = String::from_utf8_lossy(&bytes);
+                for token in decoder.push(&bytes) {
+                    tx.send(token).await?;
+                }
+                if decoder.is_done() {
+                    break;
+                }
+            }
+        }
+        for token in decoder.finish() {
+            tx.send(token).await?;
+        }
 
-                for line in text.lines() {
-                    if line.starts_with("data: ") {
-                        let json_str = &line[6..];
-                        if json_str == "[DONE]" {
-                            break;
-                        }
+        Ok(())
+    }
 
-                        if let Ok(chunk) = serde_json::from_str::<StreamChunk>(json_str) {
-                            if let Some(content) =
-                                chunk.choices.first().and_then(|c| c.delta.content.as_ref())
-                            {
-                                tx.send(content.clone()).await?;
-                            }
-                        }
+    async fn embed(&self, request: &EmbeddingRequest) -> Result<Embeddings> {
+        let response = self
+            .client
+            .post(&self.embeddings_endpoint)
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .header("Content-Type", "application/json")
+            .json(request)
+            .send()
+            .await
+            .map_err(|e| ProviderError::new(None, format!("HTTP request failed: {}", e)))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(ProviderError::new(
+                Some(status.as_u16()),
+                format!("API error {}: {}", status, error_text),
+            )
+            .into());
+        }
+
+        let mut body = response
+            .json::<EmbeddingResponse>()
+            .await
+            .map_err(|e| ProviderError::new(None, format!("Response decode error: {}", e)))?;
+        body.data.sort_by_key(|d| d.index);
+
+        Ok(Embeddings {
+            vectors: body.data.into_iter().map(|d| d.embedding).collect(),
+            tokens_used: body.usage.map_or(0, |u| u.total_tokens),
+        })
+    }
+}
+
+/// Structured, opt-in hook on every chat call made by an `LLMClient`
+///
+/// Unlike tracing output, observers receive the exact messages sent and the
+/// reply received, e.g. to record transcripts to a file or database for
+/// replay and evaluation. Both methods default to doing nothing.
+pub trait LLMObserver: Send + Sync {
+    /// Called with the full conversation before it is sent
+    fn on_request(&self, _messages: &[ChatMessage]) {}
+
+    /// Called with the reply content and usage after a successful call
+    ///
+    /// Streamed replies are reported once the stream ends, with the chunks
+    /// joined; their usage is zero since streams carry no token counts.
+    fn on_response(&self, _content: &str, _usage: &Usage) {}
+}
+
+/// Rough upper bound on the tokens a request would use
+///
+/// Assumes about four characters per prompt token and a reply that uses the
+/// full `max_tokens` budget.
+fn estimate_tokens(request: &CompletionRequest) -> u64 {
+    let prompt_chars: usize = request.messages.iter().map(|m| m.content.len()).sum();
+    (prompt_chars / 4) as u64 + u64::from(request.max_tokens)
+}
+
+/// One entry in the client's provider chain
+struct Provider {
+    name: String,
+    model: String,
+    backend: Arc<dyn LLMBackend>,
+    breaker: CircuitBreaker,
+}
+
+impl Provider {
+    /// Count transient failures against the breaker; any other outcome means the provider is up
+    fn track(&self, error: Option<&anyhow::Error>) {
+        match error {
+            Some(e) if is_transient(e) => self.breaker.record_failure(),
+            _ => self.breaker.record_success(),
+        }
+    }
+
+    fn circuit_open_error(&self) -> anyhow::Error {
+        ProviderError::new(
+            None,
+            format!("Circuit breaker open for provider '{}'", self.name),
+        )
+        .into()
+    }
+}
+
+pub struct LLMClient {
+    providers: Vec<Provider>,
+    settings: Settings,
+    tokens_used: AtomicU64,
+    last_provider: Mutex<Option<String>>,
+    observer: Option<Arc<dyn LLMObserver>>,
+    planned: Mutex<Vec<CompletionRequest>>,
+}
+
+impl LLMClient {
+    /// Create a client for the configured model, falling back through
+    /// `settings.llm.fallbacks` in order on transient failures
+    pub fn new(api_key: String, settings: Settings) -> Self {
+        let fallbacks = settings.llm.fallbacks.clone();
+        let primary = HttpBackend::new(api_key.clone()).with_base_url(&settings.llm.base_url);
+        let mut client = Self::with_backend(Arc::new(primary), settings);
+
+        for fallback in fallbacks {
+            let key = match &fallback.api_key_env {
+                Some(var) => std::env::var(var).unwrap_or_else(|_| {
+                    tracing::warn!(
+                        "[LLMClient] {} not set for fallback '{}', using primary API key",
+                        var,
+                        fallback.model
+                    );
+                    api_key.clone()
+                }),
+                None => api_key.clone(),
+            };
+            let mut backend = HttpBackend::new(key);
+            if let Some(endpoint) = &fallback.endpoint {
+                backend = backend.with_endpoint(endpoint);
+            }
+            let name = fallback.name.unwrap_or_else(|| fallback.model.clone());
+            client = client.with_fallback(name, fallback.model, Arc::new(backend));
+        }
+
+        client
+    }
+
+    /// Create a client that sends its requests through the given backend
+    pub fn with_backend(backend: Arc<dyn LLMBackend>, settings: Settings) -> Self {
+        let model = settings.llm.model.clone();
+        Self {
+            providers: vec![Provider {
+                name: model.clone(),
+                model,
+                backend,
+                breaker: CircuitBreaker::new(settings.circuit_breaker.clone()),
+            }],
+            settings,
+            tokens_used: AtomicU64::new(0),
+            last_provider: Mutex::new(None),
+            observer: None,
+            planned: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Report every request and response to the given observer
+    pub fn with_observer(mut self, observer: Arc<dyn LLMObserver>) -> Self {
+        self.observer = Some(observer);
+        self
+    }
+
+    /// Append a provider tried, in order, when the previous ones fail transiently
+    pub fn with_fallback(
+        mut self,
+        name: impl Into<String>,
+        model: impl Into<String>,
+        backend: Arc<dyn LLMBackend>,
+    ) -> Self {
+        self.providers.push(Provider {
+            name: name.into(),
+            model: model.into(),
+            backend,
+            breaker: CircuitBreaker::new(self.settings.circuit_breaker.clone()),
+        });
+        self
+    }
+
+    /// Settings this client was built from
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    /// Name of the provider that served the most recent successful call
+    pub fn last_provider(&self) -> Option<String> {
+        self.last_provider.lock().unwrap().clone()
+    }
+
+    /// Circuit breaker state of each provider, in fallback order
+    pub fn breaker_states(&self) -> Vec<(String, BreakerState)> {
+        self.providers
+            .iter()
+            .map(|provider| (provider.name.clone(), provider.breaker.state()))
+            .collect()
+    }
+
+    /// Total tokens reported by the provider across all calls made by this client
+    ///
+    /// In dry-run mode this is the estimate for the calls that were skipped.
+    pub fn tokens_used(&self) -> u64 {
+        self.tokens_used.load(Ordering::Relaxed)
+    }
+
+    /// Requests skipped because `settings.dry_run` is set, in order
+    pub fn planned_requests(&self) -> Vec<CompletionRequest> {
+        self.planned.lock().unwrap().clone()
+    }
+
+    /// Record `request` instead of sending it, returning the placeholder reply
+    fn plan(&self, request: &CompletionRequest) -> String {
+        let estimate = estimate_tokens(request);
+        tracing::info!(
+            "[LLMClient] Dry run: skipping call with {} messages (~{} tokens)",
+            request.messages.len(),
+            estimate
+        );
+        self.tokens_used.fetch_add(estimate, Ordering::Relaxed);
+        self.planned.lock().unwrap().push(request.clone());
+        if let Some(observer) = &self.observer {
+            observer.on_response(
+                DRY_RUN_REPLY,
+                &Usage {
+                    total_tokens: estimate,
+                },
+            );
+        }
+        DRY_RUN_REPLY.to_string()
+    }
+
+    pub async fn chat(&self, messages: Vec<ChatMessage>) -> Result<String> {
+        self.chat_with_format(messages, None).await
+    }
+
+    /// Chat with sampling parameters overriding the configured defaults
+    pub async fn chat_with_params(
+        &self,
+        messages: Vec<ChatMessage>,
+        params: &LLMParams,
+    ) -> Result<String> {
+        let request = self.build_request(messages, params, false, None);
+        self.send_chat(&request).await
+    }
+
+    /// Chat offering `tools` through the provider's native function calling
+    ///
+    /// Returns the requested calls when the model picks tools, or its text
+    /// otherwise. Feed results back with `ChatMessage::assistant_tool_calls`
+    /// followed by one `ChatMessage::tool` per call.
+    pub async fn chat_with_tools(
+        &self,
+        messages: Vec<ChatMessage>,
+        tools: Vec<ToolMetadata>,
+    ) -> Result<ChatOutcome> {
+        let mut request = self.build_request(messages, &LLMParams::default(), false, None);
+        if !tools.is_empty() {
+            request.tools = Some(tools.iter().map(function_definition).collect());
+        }
+
+        let completion = self.send_completion(&request).await?;
+        if completion.tool_calls.is_empty() {
+            Ok(ChatOutcome::Text(completion.content))
+        } else {
+            Ok(ChatOutcome::ToolCalls(completion.tool_calls))
+        }
+    }
+
+    pub async fn chat_with_format(
+        &self,
+        messages: Vec<ChatMessage>,
+        response_format: Option<ResponseFormat>,
+    ) -> Result<String> {
+        let request = self.build_request(messages, &LLMParams::default(), false, response_format);
+        self.send_chat(&request).await
+    }
+
+    fn build_request(
+        &self,
+        messages: Vec<ChatMessage>,
+        params: &LLMParams,
+        stream: bool,
+        response_format: Option<ResponseFormat>,
+    ) -> CompletionRequest {
+        CompletionRequest {
+            model: self.settings.llm.model.clone(),
+            messages,
+            max_tokens: params.max_tokens.unwrap_or(self.settings.llm.max_tokens),
+            temperature: params.temperature.unwrap_or(self.settings.llm.temperature),
+            top_p: params.top_p,
+            stop: params.stop.clone(),
+            seed: params.seed,
+            stream,
+            response_format,
+            tools: None,
+            message_format: self.settings.llm.message_format,
+        }
+    }
+
+    async fn send_chat(&self, request: &CompletionRequest) -> Result<String> {
+        Ok(self.send_completion(request).await?.content)
+    }
+
+    async fn send_completion(&self, request: &CompletionRequest) -> Result<Completion> {
+        if let Some(observer) = &self.observer {
+            observer.on_request(&request.messages);
+        }
+        if self.settings.dry_run {
+            return Ok(Completion {
+                content: self.plan(request),
+                ..Completion::default()
+            });
+        }
+
+        let mut last_error = None;
+
+        for (index, provider) in self.providers.iter().enumerate() {
+            if !provider.breaker.allow() {
+                tracing::warn!(
+                    "[LLMClient] Circuit open for provider '{}', skipping",
+                    provider.name
+                );
+                last_error = Some(provider.circuit_open_error());
+                continue;
+            }
+
+            let mut request = request.clone();
+            request.model = provider.model.clone();
+
+            let result = provider.backend.complete(&request).await;
+            provider.track(result.as_ref().err());
+            match result {
+                Ok(completion) => {
+                    self.tokens_used
+                        .fetch_add(completion.tokens_used, Ordering::Relaxed);
+                    *self.last_provider.lock().unwrap() = Some(provider.name.clone());
+                    if let Some(observer) = &self.observer {
+                        observer.on_response(
+                            &completion.content,
+                            &Usage {
+                                total_tokens: completion.tokens_used,
+                            },
+                        );
                     }
+                    return Ok(completion);
+                }
+                Err(e) if is_transient(&e) && index + 1 < self.providers.len() => {
+                    tracing::warn!(
+                        "[LLMClient] Provider '{}' failed, falling back: {}",
+                        provider.name,
+                        e
+                    );
+                    last_error = Some(e);
                 }
+                Err(e) => return Err(e),
             }
         }
 
-        Ok(())
+        Err(last_error.unwrap_or_else(|| anyhow::anyhow!("No LLM provider configured")))
+    }
+
+    /// Embed texts with the configured embedding model
+    ///
+    /// Uses the primary provider only; the returned vectors are in input order.
+    pub async fn embed(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+        if self.settings.dry_run {
+            anyhow::bail!("Embeddings are not available in dry-run mode");
+        }
+        let expected = texts.len();
+        let provider = self
+            .providers
+            .first()
+            .ok_or_else(|| anyhow::anyhow!("No LLM provider configured"))?;
+
+        let request = EmbeddingRequest {
+            model: self.settings.llm.embedding_model.clone(),
+            input: texts,
+        };
+        let embeddings = provider.backend.embed(&request).await?;
+        if embeddings.vectors.len() != expected {
+            anyhow::bail!(
+                "Provider returned {} embeddings for {} inputs",
+                embeddings.vectors.len(),
+                expected
+            );
+        }
+
+        self.tokens_used
+            .fetch_add(embeddings.tokens_used, Ordering::Relaxed);
+        Ok(embeddings.vectors)
+    }
+
+    /// Stream the reply to `tx`, falling back to the next provider only if
+    /// the failing one had not sent any chunk yet
+    pub async fn stream_chat(
+        &self,
+        messages: Vec<ChatMessage>,
+        tx: mpsc::Sender<String>,
+    ) -> Result<()> {
+        let request = self.build_request(messages, &LLMParams::default(), true, None);
+        if let Some(observer) = &self.observer {
+            observer.on_request(&request.messages);
+        }
+        if self.settings.dry_run {
+            tx.send(self.plan(&request)).await?;
+            return Ok(());
+        }
+
+        let mut last_error = None;
+        for (index, provider) in self.providers.iter().enumerate() {
+            if !provider.breaker.allow() {
+                tracing::warn!(
+                    "[LLMClient] Circuit open for provider '{}', skipping",
+                    provider.name
+                );
+                last_error = Some(provider.circuit_open_error());
+                continue;
+            }
+
+            let mut request = request.clone();
+            request.model = provider.model.clone();
+
+            // Chunks pass through a relay so a failure after the first chunk
+            // does not hand the consumer a second, overlapping reply. The
+            // relay takes a chunk only once `tx` has room, so it buffers at
+            // most one chunk beyond the consumer's channel.
+            let (relay_tx, mut relay_rx) = mpsc::channel::<String>(1);
+            let relay = async {
+                let mut delivered = false;
+                let mut content = String::new();
+                while let Ok(permit) = tx.reserve().await {
+                    let Some(chunk) = relay_rx.recv().await else {
+                        break;
+                    };
+                    delivered = true;
+                    content.push_str(&chunk);
+                    permit.send(chunk);
+                }
+                (delivered, content)
+            };
+            let (result, (delivered, content)) =
+                tokio::join!(provider.backend.stream(&request, relay_tx), relay);
+            provider.track(result.as_ref().err());
+            match result {
+                Ok(()) => {
+                    *self.last_provider.lock().unwrap() = Some(provider.name.clone());
+                    if let Some(observer) = &self.observer {
+                        observer.on_response(&content, &Usage { total_tokens: 0 });
+                    }
+                    return Ok(());
+                }
+                Err(e) if !delivered && is_transient(&e) && index + 1 < self.providers.len() => {
+                    tracing::warn!(
+                        "[LLMClient] Provider '{}' failed to stream, falling back: {}",
+                        provider.name,
+                        e
+                    );
+                    last_error = Some(e);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| anyhow::anyhow!("No LLM provider configured")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sse_decoder_joins_character_split_across_chunks() {
+        let event = "data: {\"choices\":[{\"delta\":{\"content\":\"hi 😀\"}}]}\n\n";
+        let bytes = event.as_bytes();
+        // Split inside the four-byte emoji
+        let split = event.find('😀').unwrap() + 2;
+
+        let mut decoder = SseDecoder::default();
+        assert!(decoder.push(&bytes[..split]).is_empty());
+        assert_eq!(decoder.push(&bytes[split..]), vec!["hi 😀"]);
+
+        assert!(decoder.push(b"data: [DONE]\n").is_empty());
+        assert!(decoder.is_done());
+        assert!(decoder.finish().is_empty());
+    }
+
+    #[test]
+    fn test_role_rejects_unknown_names() {
+        let err = Role::try_from("assisstant").unwrap_err();
+        assert_eq!(err, UnknownRole("assisstant".to_string()));
+        assert!("System".parse::<Role>().is_err());
+    }
+
+    #[test]
+    fn test_role_round_trips() {
+        for name in ["system", "user", "assistant", "tool"] {
+            let role = Role::try_from(name).unwrap();
+            assert_eq!(role.to_string(), name);
+            assert_eq!(String::from(role), name);
+        }
+
+        let message = ChatMessage::assistant("Hi");
+        let json = serde_json::to_string(&message).unwrap();
+        assert_eq!(json, r#"{"role":"assistant","content":"Hi"}"#);
+
+        let parsed: ChatMessage = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.role, Role::Assistant);
+        assert!(serde_json::from_str::<ChatMessage>(r#"{"role":"bot","content":"x"}"#).is_err());
+    }
+
+    #[test]
+    fn test_optional_message_fields_and_content_blocks() {
+        let plain = ChatMessage::user("hi");
+        assert_eq!(
+            serde_json::to_value(&plain).unwrap(),
+            serde_json::json!({"role": "user", "content": "hi"})
+        );
+        assert_eq!(
+            plain.to_provider_json(MessageFormat::ContentBlocks),
+            serde_json::json!({"role": "user", "content": [{"type": "text", "text": "hi"}]})
+        );
+
+        let image = ChatMessage::user("What is this?")
+            .with_name("alice")
+            .with_parts(vec![ContentPart::ImageUrl {
+                image_url: ImageUrl {
+                    url: "https://example.com/cat.png".to_string(),
+                    detail: None,
+                },
+            }]);
+        assert_eq!(
+            image.to_provider_json(MessageFormat::ContentBlocks),
+            serde_json::json!({
+                "role": "user",
+                "name": "alice",
+                "content": [
+                    {"type": "text", "text": "What is this?"},
+                    {"type": "image_url", "image_url": {"url": "https://example.com/cat.png"}}
+                ]
+            })
+        );
+        // A plain string cannot carry the image, so it stays in block form
+        assert_eq!(
+            image.to_provider_json(MessageFormat::Text),
+            image.to_provider_json(MessageFormat::ContentBlocks)
+        );
+
+        // Stored form keeps the parts
+        let stored = serde_json::to_string(&image).unwrap();
+        assert_eq!(serde_json::from_str::<ChatMessage>(&stored).unwrap(), image);
+    }
+
+    #[test]
+    fn test_trimming_drops_tool_results_with_their_call() {
+        let call = ToolCall::function("call_1", "list_files", &serde_json::json!({}));
+        let mut history = vec![
+            ChatMessage::system("You are helpful"),
+            ChatMessage::user("List the files"),
+            ChatMessage::assistant_tool_calls(vec![call]),
+            ChatMessage::tool("call_1", "a.txt"),
+            ChatMessage::user("Anything else?"),
+        ];
+
+        // Only two messages need to go, but the answered call takes its result along
+        let dropped = trim_history(&mut history, 3);
+        let roles: Vec<Role> = dropped.iter().map(|m| m.role).collect();
+        assert_eq!(roles, [Role::User, Role::Assistant, Role::Tool]);
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[1].content, "Anything else?");
+    }
+
+    #[test]
+    fn test_trimming_keeps_pinned_and_system_messages() {
+        let mut history = vec![
+            ChatMessage::system("You are helpful"),
+            ChatMessage::user("My name is Ada")
+                .with_metadata(serde_json::json!({"agent": "intake"}))
+                .pinned(),
+            ChatMessage::assistant("Hello Ada"),
+            ChatMessage::user("Tell me a joke"),
+            ChatMessage::assistant("Why did the actor cross the channel?"),
+            ChatMessage::user("Another one"),
+        ];
+
+        let dropped = trim_history(&mut history, 4);
+
+        let contents: Vec<&str> = dropped.iter().map(|m| m.content.as_str()).collect();
+        assert_eq!(contents, ["Hello Ada", "Tell me a joke"]);
+        let contents: Vec<&str> = history.iter().map(|m| m.content.as_str()).collect();
+        assert_eq!(
+            contents,
+            [
+                "You are helpful",
+                "My name is Ada",
+                "Why did the actor cross the channel?",
+                "Another one"
+            ]
+        );
+
+        // Only system and pinned messages are left to give up, so nothing more goes
+        trim_history(&mut history, 1);
+        assert_eq!(history.len(), 2);
+        assert!(history[1].pinned);
+
+        // Tags are persisted but never reach the provider
+        let stored = serde_json::to_string(&history[1]).unwrap();
+        assert_eq!(
+            serde_json::from_str::<ChatMessage>(&stored).unwrap(),
+            history[1]
+        );
+        assert_eq!(
+            history[1].to_provider_json(MessageFormat::Text),
+            serde_json::json!({"role": "user", "content": "My name is Ada"})
+        );
+    }
+
+    #[test]
+    fn test_tool_transcript_matches_provider_schema() {
+        let request = CompletionRequest {
+            model: "gpt-4o".to_string(),
+            messages: vec![
+                ChatMessage::user("What is in /tmp?"),
+                ChatMessage::assistant_tool_calls(vec![ToolCall::function(
+                    "call_1",
+                    "execute_shell",
+                    &serde_json::json!({"command": "ls /tmp"}),
+                )]),
+                ChatMessage::tool("call_1", "a.txt\nb.txt"),
+            ],
+            max_tokens: 100,
+            temperature: 0.0,
+            top_p: None,
+            stop: None,
+            seed: None,
+            stream: false,
+            response_format: None,
+            tools: None,
+            message_format: MessageFormat::Text,
+        };
+
+        let body = request.body();
+        assert_eq!(
+            body["messages"],
+            serde_json::json!([
+                {"role": "user", "content": "What is in /tmp?"},
+                {
+                    "role": "assistant",
+                    "content": "",
+                    "tool_calls": [{
+                        "id": "call_1",
+                        "type": "function",
+                        "function": {
+                            "name": "execute_shell",
+                            "arguments": "{\"command\":\"ls /tmp\"}"
+                        }
+                    }]
+                },
+                {"role": "tool", "content": "a.txt\nb.txt", "tool_call_id": "call_1"}
+            ])
+        );
+
+        // Assistant turns that only call tools arrive with null content
+        let reply: ChatMessage = serde_json::from_value(serde_json::json!({
+            "role": "assistant",
+            "content": null,
+            "tool_calls": [{
+                "id": "call_2",
+                "type": "function",
+                "function": {"name": "read_file", "arguments": "{}"}
+            }]
+        }))
+        .unwrap();
+        assert_eq!(reply.content, "");
+        assert_eq!(reply.tool_calls.unwrap()[0].function.name, "read_file");
+    }
+
+    #[test]
+    fn test_params_override_settings_in_request_body() {
+        let settings = Settings::new().unwrap();
+        let client = LLMClient::new("test-key".to_string(), settings.clone());
+
+        let defaults = client.build_request(
+            vec![ChatMessage::user("Hi")],
+            &LLMParams::default(),
+            false,
+            None,
+        );
+        let body = serde_json::to_value(&defaults).unwrap();
+        assert_eq!(body["max_tokens"], settings.llm.max_tokens);
+        assert!(body.get("top_p").is_none());
+        assert!(body.get("stop").is_none());
+
+        let params = LLMParams::default()
+            .temperature(0.0)
+            .top_p(0.5)
+            .max_tokens(64)
+            .stop(vec!["\n\n".to_string()]);
+        let request = client.build_request(vec![ChatMessage::user("Hi")], &params, false, None);
+
+        let body = serde_json::to_value(&request).unwrap();
+        assert_eq!(body["temperature"], 0.0);
+        assert_eq!(body["top_p"], 0.5);
+        assert_eq!(body["max_tokens"], 64);
+        assert_eq!(body["stop"], serde_json::json!(["\n\n"]));
+        assert_eq!(body["model"], settings.llm.model);
+    }
+
+    /// Backend whose provider always answers with the given HTTP status
+    struct FailingBackend(u16);
+
+    #[async_trait]
+    impl LLMBackend for FailingBackend {
+        async fn complete(&self, _request: &CompletionRequest) -> Result<Completion> {
+            Err(ProviderError::new(Some(self.0), format!("API error {}", self.0)).into())
+        }
+    }
+
+    /// Always answers 503, counting how often it was reached
+    #[derive(Default)]
+    struct CountingBackend {
+        calls: AtomicU64,
+    }
+
+    #[async_trait]
+    impl LLMBackend for CountingBackend {
+        async fn complete(&self, _request: &CompletionRequest) -> Result<Completion> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Err(ProviderError::new(Some(503), "API error 503").into())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_requests_target_configured_base_url() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/chat/completions"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "choices": [{"message": {"role": "assistant", "content": "local reply"}}],
+                "usage": {"total_tokens": 7}
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut settings = Settings::new().unwrap();
+        settings.llm.base_url = format!("{}/v1/", server.uri());
+        let client = LLMClient::new("test-key".to_string(), settings);
+
+        let reply = client.chat(vec![ChatMessage::user("Hi")]).await.unwrap();
+
+        assert_eq!(reply, "local reply");
+        assert_eq!(client.tokens_used(), 7);
+    }
+
+    #[tokio::test]
+    async fn test_chat_with_tools_parses_native_tool_calls() {
+        use wiremock::matchers::{body_partial_json, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/chat/completions"))
+            .and(body_partial_json(serde_json::json!({
+                "tools": [{"type": "function", "function": {"name": "get_weather"}}]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "choices": [{"message": {
+                    "role": "assistant",
+                    "content": null,
+                    "tool_calls": [{
+                        "id": "call_7",
+                        "type": "function",
+                        "function": {
+                            "name": "get_weather",
+                            "arguments": "{\"city\": \"Oslo\", \"days\": 3}"
+                        }
+                    }]
+                }}],
+                "usage": {"total_tokens": 21}
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut settings = Settings::new().unwrap();
+        settings.llm.base_url = format!("{}/v1", server.uri());
+        let client = LLMClient::new("test-key".to_string(), settings);
+        let weather = ToolMetadata {
+            name: "get_weather".to_string(),
+            description: "Forecast for a city".to_string(),
+            parameters: Vec::new(),
+        };
+
+        let outcome = client
+            .chat_with_tools(vec![ChatMessage::user("Weather in Oslo?")], vec![weather])
+            .await
+            .unwrap();
+
+        let ChatOutcome::ToolCalls(calls) = outcome else {
+            panic!("expected tool calls, got {:?}", outcome);
+        };
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].id, "call_7");
+        assert_eq!(calls[0].function.name, "get_weather");
+        assert_eq!(
+            calls[0].parsed_arguments().unwrap(),
+            serde_json::json!({"city": "Oslo", "days": 3})
+        );
+        assert_eq!(client.tokens_used(), 21);
+    }
+
+    #[tokio::test]
+    async fn test_embed_returns_vectors_in_input_order() {
+        use wiremock::matchers::{body_partial_json, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/embeddings"))
+            .and(body_partial_json(serde_json::json!({
+                "model": "test-embedder",
+                "input": ["first", "second"]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": [
+                    {"index": 1, "embedding": [0.0, 1.0]},
+                    {"index": 0, "embedding": [1.0, 0.0]}
+                ],
+                "usage": {"total_tokens": 4}
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut settings = Settings::new().unwrap();
+        settings.llm.base_url = format!("{}/v1", server.uri());
+        settings.llm.embedding_model = "test-embedder".to_string();
+        let client = LLMClient::new("test-key".to_string(), settings);
+
+        let vectors = client
+            .embed(vec!["first".to_string(), "second".to_string()])
+            .await
+            .unwrap();
+
+        assert_eq!(vectors, vec![vec![1.0, 0.0], vec![0.0, 1.0]]);
+        assert_eq!(client.tokens_used(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_transient_failure_falls_back_to_next_provider() {
+        let fallback = Arc::new(crate::core::mock_llm::MockLLM::new(["from fallback"]));
+        let client =
+            LLMClient::with_backend(Arc::new(FailingBackend(503)), Settings::new().unwrap())
+                .with_fallback("backup", "backup-model", fallback.clone());
+
+        let reply = client.chat(vec![ChatMessage::user("Hi")]).await.unwrap();
+
+        assert_eq!(reply, "from fallback");
+        assert_eq!(client.last_provider().as_deref(), Some("backup"));
+        assert_eq!(fallback.requests()[0].model, "backup-model");
+    }
+
+    /// Streams one chunk, then fails with a 503
+    struct BrokenStreamBackend;
+
+    #[async_trait]
+    impl LLMBackend for BrokenStreamBackend {
+        async fn complete(&self, _request: &CompletionRequest) -> Result<Completion> {
+            unreachable!("only streamed in tests")
+        }
+
+        async fn stream(
+            &self,
+            _request: &CompletionRequest,
+            tx: mpsc::Sender<String>,
+        ) -> Result<()> {
+            tx.send("partial ".to_string()).await?;
+            Err(ProviderError::new(Some(503), "API error 503").into())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stream_falls_back_only_before_first_chunk() {
+        let fallback = Arc::new(crate::core::mock_llm::MockLLM::new(["from fallback"]));
+        let client =
+            LLMClient::with_backend(Arc::new(FailingBackend(503)), Settings::new().unwrap())
+                .with_fallback("backup", "backup-model", fallback.clone());
+        let (tx, mut rx) = mpsc::channel(8);
+        client
+            .stream_chat(vec![ChatMessage::user("Hi")], tx)
+            .await
+            .unwrap();
+        assert_eq!(rx.recv().await.as_deref(), Some("from fallback"));
+        assert_eq!(client.last_provider().as_deref(), Some("backup"));
+
+        let fallback = Arc::new(crate::core::mock_llm::MockLLM::new(["from fallback"]));
+        let client =
+            LLMClient::with_backend(Arc::new(BrokenStreamBackend), Settings::new().unwrap())
+                .with_fallback("backup", "backup-model", fallback.clone());
+        let (tx, mut rx) = mpsc::channel(8);
+        let err = client
+            .stream_chat(vec![ChatMessage::user("Hi")], tx)
+            .await
+            .unwrap_err();
+
+        assert!(err.to_string().contains("503"));
+        assert_eq!(rx.recv().await.as_deref(), Some("partial "));
+        assert!(rx.recv().await.is_none());
+        assert_eq!(fallback.remaining(), 1);
+    }
+
+    #[test]
+    fn test_only_known_passing_failures_are_transient() {
+        assert!(is_transient(&ProviderError::new(Some(503), "down").into()));
+        assert!(is_transient(&ProviderError::new(None, "reset").into()));
+        assert!(!is_transient(
+            &ProviderError::new(Some(401), "bad key").into()
+        ));
+
+        let timed_out = std::io::Error::new(std::io::ErrorKind::TimedOut, "timed out");
+        assert!(is_transient(
+            &anyhow::Error::new(timed_out).context("reading reply")
+        ));
+        let missing = std::io::Error::new(std::io::ErrorKind::NotFound, "no such file");
+        assert!(!is_transient(&missing.into()));
+
+        assert!(!is_transient(&anyhow::anyhow!(
+            "custom backend rejected the request"
+        )));
+    }
+
+    #[tokio::test]
+    async fn test_client_errors_do_not_fall_back() {
+        let fallback = Arc::new(crate::core::mock_llm::MockLLM::new(["from fallback"]));
+        let client =
+            LLMClient::with_backend(Arc::new(FailingBackend(400)), Settings::new().unwrap())
+                .with_fallback("backup", "backup-model", fallback.clone());
+
+        let err = client
+            .chat(vec![ChatMessage::user("Hi")])
+            .await
+            .unwrap_err();
+
+        assert!(err.to_string().contains("400"));
+        assert_eq!(fallback.remaining(), 1);
+        assert!(client.last_provider().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_open_breaker_short_circuits_provider() {
+        let mut settings = Settings::new().unwrap();
+        settings.circuit_breaker.failure_threshold = 2;
+        let failing = Arc::new(CountingBackend::default());
+        let client = LLMClient::with_backend(failing.clone(), settings);
+
+        for _ in 0..2 {
+            assert!(client.chat(vec![ChatMessage::user("Hi")]).await.is_err());
+        }
+        assert_eq!(client.breaker_states()[0].1, BreakerState::Open);
+
+        let err = client
+            .chat(vec![ChatMessage::user("Hi")])
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Circuit breaker open"));
+        assert_eq!(failing.calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[derive(Default)]
+    struct RecordingObserver {
+        requests: Mutex<Vec<Vec<ChatMessage>>>,
+        responses: Mutex<Vec<(String, Usage)>>,
+    }
+
+    impl LLMObserver for RecordingObserver {
+        fn on_request(&self, messages: &[ChatMessage]) {
+            self.requests.lock().unwrap().push(messages.to_vec());
+        }
+
+        fn on_response(&self, content: &str, usage: &Usage) {
+            self.responses
+                .lock()
+                .unwrap()
+                .push((content.to_string(), *usage));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_observer_records_exact_messages() {
+        let backend =
+            Arc::new(crate::core::mock_llm::MockLLM::new(["Hello!"]).with_tokens_per_reply(12));
+        let observer = Arc::new(RecordingObserver::default());
+        let client = LLMClient::with_backend(backend, Settings::new().unwrap())
+            .with_observer(observer.clone());
+
+        let messages = vec![
+            ChatMessage::system("You are terse"),
+            ChatMessage::user("Say hello"),
+        ];
+        client.chat(messages.clone()).await.unwrap();
+
+        assert_eq!(*observer.requests.lock().unwrap(), vec![messages]);
+        assert_eq!(
+            *observer.responses.lock().unwrap(),
+            vec![("Hello!".to_string(), Usage { total_tokens: 12 })]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_observer_sees_streamed_reply_once_it_ends() {
+        let backend = Arc::new(crate::core::mock_llm::MockLLM::new(["Streamed hello"]));
+        let observer = Arc::new(RecordingObserver::default());
+        let client = LLMClient::with_backend(backend, Settings::new().unwrap())
+            .with_observer(observer.clone());
+
+        let (tx, mut rx) = mpsc::channel(8);
+        client
+            .stream_chat(vec![ChatMessage::user("Say hello")], tx)
+            .await
+            .unwrap();
+        assert_eq!(rx.recv().await.as_deref(), Some("Streamed hello"));
+
+        assert_eq!(observer.requests.lock().unwrap().len(), 1);
+        assert_eq!(
+            *observer.responses.lock().unwrap(),
+            vec![("Streamed hello".to_string(), Usage { total_tokens: 0 })]
+        );
     }
 }
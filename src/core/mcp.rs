@@ -23,6 +23,20 @@ fn default_input_schema() -> serde_json::Value {
     })
 }
 
+/// Element types from `additionalProperties` (maps) or `prefixItems` (tuples)
+fn item_types(schema: &serde_json::Value) -> Option<Vec<String>> {
+    let type_of = |s: &serde_json::Value| s.get("type")?.as_str().map(str::to_string);
+    if let Some(value_type) = schema.get("additionalProperties").and_then(type_of) {
+        return Some(vec![value_type]);
+    }
+    schema
+        .get("prefixItems")?
+        .as_array()?
+        .iter()
+        .map(type_of)
+        .collect()
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct MCPResponse {
     jsonrpc: String,
@@ -205,11 +219,30 @@ impl Tool for MCPToolWrapper {
                             .map(|arr| arr.iter().any(|v| v.as_str() == Some(name)))
                             .unwrap_or(false);
 
+                        let allowed_values =
+                            schema.get("enum").and_then(|e| e.as_array()).map(|values| {
+                                values
+                                    .iter()
+                                    .map(|v| match v.as_str() {
+                                        Some(s) => s.to_string(),
+                                        None => v.to_string(),
+                                    })
+                                    .collect()
+                            });
+
                         ToolParameter {
                             name: name.clone(),
                             description,
                             param_type,
                             required,
+                            allowed_values,
+                            minimum: schema.get("minimum").and_then(|m| m.as_f64()),
+                            maximum: schema.get("maximum").and_then(|m| m.as_f64()),
+                            pattern: schema
+                                .get("pattern")
+                                .and_then(|p| p.as_str())
+                                .map(str::to_string),
+                            item_types: item_types(schema),
                         }
                     })
                     .collect()
@@ -0,0 +1,102 @@
+//! Mock LLM - Scripted backend for deterministic agent runs
+//!
+//! Information Hiding:
+//! - Hides the reply queue and request log behind a thread-safe interface
+//! - Replaces network calls so agent loops can be exercised offline
+//! - Exposes recorded requests for assertions on what was sent
+
+use crate::core::llm::{Completion, CompletionRequest, EmbeddingRequest, Embeddings, LLMBackend};
+use anyhow::Result;
+use async_trait::async_trait;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+type Embedder = Box<dyn Fn(&str) -> Vec<f32> + Send + Sync>;
+
+/// Backend that replays predetermined replies in order
+///
+/// Each call pops the next reply; calling past the end of the script is an
+/// error so tests notice unexpected extra LLM round trips.
+///
+/// # Example
+/// ```ignore
+/// let mock = Arc::new(MockLLM::new([r#"{"thought": "done", "is_final": true}"#]));
+/// let client = LLMClient::with_backend(mock.clone(), settings);
+/// ```
+pub struct MockLLM {
+    replies: Mutex<VecDeque<String>>,
+    requests: Mutex<Vec<CompletionRequest>>,
+    tokens_per_reply: u64,
+    embedder: Option<Embedder>,
+}
+
+impl MockLLM {
+    pub fn new<I, S>(replies: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            replies: Mutex::new(replies.into_iter().map(Into::into).collect()),
+            requests: Mutex::new(Vec::new()),
+            tokens_per_reply: 0,
+            embedder: None,
+        }
+    }
+
+    /// Report a fixed token usage for every reply
+    pub fn with_tokens_per_reply(mut self, tokens: u64) -> Self {
+        self.tokens_per_reply = tokens;
+        self
+    }
+
+    /// Answer embedding requests by applying `embedder` to each input
+    pub fn with_embedder(
+        mut self,
+        embedder: impl Fn(&str) -> Vec<f32> + Send + Sync + 'static,
+    ) -> Self {
+        self.embedder = Some(Box::new(embedder));
+        self
+    }
+
+    /// Requests received so far, in call order
+    pub fn requests(&self) -> Vec<CompletionRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// Number of scripted replies not yet consumed
+    pub fn remaining(&self) -> usize {
+        self.replies.lock().unwrap().len()
+    }
+}
+
+#[async_trait]
+impl LLMBackend for MockLLM {
+    async fn complete(&self, request: &CompletionRequest) -> Result<Completion> {
+        self.requests.lock().unwrap().push(request.clone());
+
+        let content = self
+            .replies
+            .lock()
+            .unwrap()
+            .pop_front()
+            .ok_or_else(|| anyhow::anyhow!("MockLLM called more times than scripted"))?;
+
+        Ok(Completion {
+            content,
+            tokens_used: self.tokens_per_reply,
+            tool_calls: Vec::new(),
+        })
+    }
+
+    async fn embed(&self, request: &EmbeddingRequest) -> Result<Embeddings> {
+        let embedder = self
+            .embedder
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("MockLLM has no embedder configured"))?;
+        Ok(Embeddings {
+            vectors: request.input.iter().map(|text| embedder(text)).collect(),
+            tokens_used: 0,
+        })
+    }
+}
@@ -1,2 +1,5 @@
+pub mod circuit_breaker;
 pub mod llm;
 pub mod mcp;
+pub mod mock_llm;
+pub mod replay;
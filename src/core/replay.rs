@@ -0,0 +1,179 @@
+//! Replay Backend - Golden-transcript playback for regression tests
+//!
+//! Information Hiding:
+//! - Hides the transcript file format behind load/save helpers
+//! - Hides request matching and cursor management
+//! - Exposes an LLMBackend that fails loudly when prompts drift
+
+use crate::core::llm::{ChatMessage, Completion, CompletionRequest, LLMBackend, ToolCall};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::Mutex;
+
+/// One recorded request and the reply the provider gave to it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Exchange {
+    pub messages: Vec<ChatMessage>,
+    pub response: String,
+    #[serde(default)]
+    pub tokens_used: u64,
+    /// Native function calls the provider returned alongside `response`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<ToolCall>,
+}
+
+/// Ordered list of recorded exchanges
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Transcript {
+    pub exchanges: Vec<Exchange>,
+}
+
+impl Transcript {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read transcript {}", path.display()))?;
+        serde_json::from_str(&json)
+            .with_context(|| format!("Invalid transcript {}", path.display()))
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// Backend serving recorded replies in order
+///
+/// Every incoming request must carry exactly the recorded messages, so a
+/// change to a prompt shows up as a test failure rather than silently
+/// replaying a reply to a different question.
+pub struct ReplayBackend {
+    exchanges: Vec<Exchange>,
+    cursor: Mutex<usize>,
+}
+
+impl ReplayBackend {
+    pub fn new(transcript: Transcript) -> Self {
+        Self {
+            exchanges: transcript.exchanges,
+            cursor: Mutex::new(0),
+        }
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        Ok(Self::new(Transcript::load(path)?))
+    }
+
+    /// Whether every recorded exchange has been served
+    pub fn is_exhausted(&self) -> bool {
+        *self.cursor.lock().unwrap() >= self.exchanges.len()
+    }
+}
+
+#[async_trait]
+impl LLMBackend for ReplayBackend {
+    async fn complete(&self, request: &CompletionRequest) -> Result<Completion> {
+        let mut cursor = self.cursor.lock().unwrap();
+        let index = *cursor;
+        let exchange = self.exchanges.get(index).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Replay transcript has {} exchanges but request #{} was made",
+                self.exchanges.len(),
+                index + 1
+            )
+        })?;
+
+        if request.messages != exchange.messages {
+            let position = request
+                .messages
+                .iter()
+                .zip(&exchange.messages)
+                .position(|(sent, recorded)| sent != recorded)
+                .unwrap_or_else(|| request.messages.len().min(exchange.messages.len()));
+            anyhow::bail!(
+                "Request #{} does not match the recorded prompt at message {}:\n\
+                 sent:     {:?}\n\
+                 recorded: {:?}",
+                index + 1,
+                position,
+                request.messages.get(position),
+                exchange.messages.get(position)
+            );
+        }
+
+        *cursor += 1;
+        Ok(Completion {
+            content: exchange.response.clone(),
+            tokens_used: exchange.tokens_used,
+            tool_calls: exchange.tool_calls.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Settings;
+    use crate::core::llm::{ChatOutcome, LLMClient};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_replay_rejects_changed_prompt() {
+        let backend = Arc::new(ReplayBackend::new(Transcript {
+            exchanges: vec![Exchange {
+                messages: vec![ChatMessage::user("What is 2 + 2?")],
+                response: "4".to_string(),
+                tokens_used: 0,
+                tool_calls: Vec::new(),
+            }],
+        }));
+        let client = LLMClient::with_backend(backend.clone(), Settings::new().unwrap());
+
+        let err = client
+            .chat(vec![ChatMessage::user("What is 3 + 3?")])
+            .await
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("does not match the recorded prompt"));
+
+        let reply = client
+            .chat(vec![ChatMessage::user("What is 2 + 2?")])
+            .await
+            .unwrap();
+        assert_eq!(reply, "4");
+        assert!(backend.is_exhausted());
+    }
+
+    #[tokio::test]
+    async fn test_replay_returns_recorded_tool_calls() {
+        let call = ToolCall::function(
+            "call_1",
+            "get_weather",
+            &serde_json::json!({"city": "Oslo"}),
+        );
+        let transcript = Transcript {
+            exchanges: vec![Exchange {
+                messages: vec![ChatMessage::user("Weather in Oslo?")],
+                response: String::new(),
+                tokens_used: 12,
+                tool_calls: vec![call.clone()],
+            }],
+        };
+        let json = serde_json::to_string(&transcript).unwrap();
+        let backend = Arc::new(ReplayBackend::new(serde_json::from_str(&json).unwrap()));
+        let client = LLMClient::with_backend(backend, Settings::new().unwrap());
+
+        let outcome = client
+            .chat_with_tools(vec![ChatMessage::user("Weather in Oslo?")], Vec::new())
+            .await
+            .unwrap();
+        let ChatOutcome::ToolCalls(calls) = outcome else {
+            panic!("expected tool calls, got {:?}", outcome);
+        };
+        assert_eq!(calls, vec![call]);
+    }
+}
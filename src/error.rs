@@ -0,0 +1,111 @@
+//! Error Types - Typed failures at the public API boundary
+//!
+//! Information Hiding:
+//! - Internal code keeps using anyhow; conversion happens at the boundary
+//! - Recognized internal errors are mapped onto matchable variants
+//! - Anything else is preserved unchanged in `ActorusError::Other`
+
+use crate::actors::AgentBuildError;
+use crate::core::llm::ProviderError;
+use tokio::sync::oneshot::error::RecvError;
+
+/// Result type returned by the public API
+pub type Result<T, E = ActorusError> = std::result::Result<T, E>;
+
+/// Failure modes callers can match on
+#[derive(Debug, thiserror::Error)]
+pub enum ActorusError {
+    #[error("system not initialized; call init() first")]
+    NotInitialized,
+    #[error("system already initialized")]
+    AlreadyInitialized,
+    #[error("configuration error: {0}")]
+    Config(String),
+    #[error("rate limited by LLM provider: {0}")]
+    RateLimited(String),
+    #[error("LLM provider error: {message}")]
+    Provider {
+        /// HTTP status, when the provider answered at all
+        status: Option<u16>,
+        message: String,
+    },
+    #[error("validation failed: {0}")]
+    Validation(String),
+    /// An actor stopped before replying, typically during shutdown
+    #[error("actor unavailable: {0}")]
+    ActorUnavailable(String),
+    #[error(transparent)]
+    Other(anyhow::Error),
+}
+
+impl From<ProviderError> for ActorusError {
+    fn from(e: ProviderError) -> Self {
+        match e.status {
+            Some(429) => ActorusError::RateLimited(e.message),
+            status => ActorusError::Provider {
+                status,
+                message: e.message,
+            },
+        }
+    }
+}
+
+impl From<config::ConfigError> for ActorusError {
+    fn from(e: config::ConfigError) -> Self {
+        ActorusError::Config(e.to_string())
+    }
+}
+
+impl From<AgentBuildError> for ActorusError {
+    fn from(e: AgentBuildError) -> Self {
+        ActorusError::Validation(e.to_string())
+    }
+}
+
+impl From<RecvError> for ActorusError {
+    fn from(_: RecvError) -> Self {
+        ActorusError::ActorUnavailable("request dropped before a reply was sent".to_string())
+    }
+}
+
+impl From<anyhow::Error> for ActorusError {
+    fn from(e: anyhow::Error) -> Self {
+        let e = match e.downcast::<ProviderError>() {
+            Ok(provider) => return provider.into(),
+            Err(e) => e,
+        };
+        let e = match e.downcast::<config::ConfigError>() {
+            Ok(config) => return config.into(),
+            Err(e) => e,
+        };
+        match e.downcast::<AgentBuildError>() {
+            Ok(build) => build.into(),
+            Err(e) => ActorusError::Other(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_anyhow_errors_map_to_typed_variants() {
+        let rate_limited: ActorusError =
+            anyhow::Error::new(ProviderError::new(Some(429), "slow down")).into();
+        assert!(matches!(rate_limited, ActorusError::RateLimited(ref m) if m == "slow down"));
+
+        let server: ActorusError =
+            anyhow::Error::new(ProviderError::new(Some(503), "unavailable")).into();
+        assert!(matches!(
+            server,
+            ActorusError::Provider {
+                status: Some(503),
+                ..
+            }
+        ));
+
+        let other: ActorusError = anyhow::anyhow!("boom").into();
+        assert!(matches!(other, ActorusError::Other(_)));
+    }
+}
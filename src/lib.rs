@@ -4,82 +4,146 @@
 //! fault-tolerant multi-agent LLM systems with MCP integration.
 
 // Re-export procedural macros
-pub use actorus_macros::{tool, tool_fn};
+pub use actorus_macros::{tool, tool_fn, Tool, ToolEnum};
 
 pub mod actors;
 mod config;
 pub mod core; // Make core public for MCP access
+pub mod error;
 pub mod storage;
 pub mod tools;
 pub mod utils;
 
 pub mod api;
 pub mod cli;
+#[cfg(feature = "server")]
+pub mod server;
 
 pub use api::*;
-pub use config::Settings;
+pub use config::{
+    AgentConfig, GuardrailConfig, LLMConfig, LoggingConfig, PromptConfig, Settings,
+    SettingsBuilder, SystemConfig, ToolsConfig, ValidationConfig,
+};
+pub use error::ActorusError;
 
 // ✅ Re-export StateSnapshot for public use
 pub use actors::messages::StateSnapshot;
 
 // ✅ Re-export AgentBuilder for easy agent creation
-pub use actors::{AgentBuilder, AgentCollection};
+pub use actors::{AgentBuildError, AgentBuilder, AgentCollection, ShutdownSummary};
 
 // ✅ Re-export ResponseFormat for structured outputs
-pub use core::llm::{JsonSchemaFormat, ResponseFormat};
+pub use core::llm::{
+    ChatOutcome, ContentPart, ImageSource, ImageUrl, JsonSchemaFormat, LLMBackend, LLMObserver,
+    LLMParams, MessageFormat, ResponseFormat, Role,
+};
+pub use core::mock_llm::MockLLM;
+pub use core::replay::{ReplayBackend, Transcript};
+
+// Process-wide default tool set, configured before init()
+pub use tools::registry::{builtin_tools, set_default_tools};
+
+// Caller-scoped values for tools, outside the LLM-chosen arguments
+pub use tools::context::ExecutionContext;
+
+// Durable outputs tools can leave behind through the execution context
+pub use storage::artifacts::{
+    Artifact, ArtifactStore, FileSystemArtifactStore, InMemoryArtifactStore,
+};
 
 use actors::MessageRouterHandle;
 use once_cell::sync::OnceCell;
-use tokio::sync::oneshot;
+use tokio::sync::{oneshot, watch};
 
 static SYSTEM: OnceCell<System> = OnceCell::new();
 
 pub struct System {
     router: MessageRouterHandle,
+    api_key: String,
 }
 
 impl System {
     fn new(settings: Settings, api_key: String) -> Self {
         Self {
-            router: MessageRouterHandle::new(settings, api_key),
+            router: MessageRouterHandle::new(settings, api_key.clone()),
+            api_key,
         }
     }
 
-    fn global() -> &'static System {
-        SYSTEM
-            .get()
-            .expect("System not initialized. Call init() first")
+    fn global() -> error::Result<&'static System> {
+        SYSTEM.get().ok_or(ActorusError::NotInitialized)
+    }
+
+    /// API key for clients built outside the actor system
+    ///
+    /// Uses the key the system was initialized with, so a key passed to
+    /// `init_with_api_key` reaches every entry point. Before `init()` the
+    /// key is resolved from `settings`.
+    fn api_key(settings: &Settings) -> error::Result<String> {
+        match SYSTEM.get() {
+            Some(system) => Ok(system.api_key.clone()),
+            None => Ok(settings.resolve_api_key(None)?),
+        }
     }
 }
 
 /// Initialize the system
 /// Must be called before using any API functions
-pub async fn init() -> anyhow::Result<()> {
+pub async fn init() -> error::Result<()> {
+    init_with(None)
+}
+
+/// Initialize the actor system with an explicit API key
+///
+/// The key takes precedence over environment variables and key files.
+pub async fn init_with_api_key(api_key: impl Into<String>) -> error::Result<()> {
+    init_with(Some(api_key.into()))
+}
+
+fn init_with(api_key: Option<String>) -> error::Result<()> {
     let settings = Settings::new()?;
-    let api_key = Settings::api_key()?;
+    settings.validate()?;
+    let api_key = settings.resolve_api_key(api_key.as_deref())?;
 
     let system = System::new(settings, api_key);
     SYSTEM
         .set(system)
-        .map_err(|_| anyhow::anyhow!("System already initialized"))?;
+        .map_err(|_| ActorusError::AlreadyInitialized)?;
 
     tracing::info!("Actorus system initialized");
     Ok(())
 }
 
 /// Shutdown the system gracefully
-pub async fn shutdown() -> anyhow::Result<()> {
-    if let Some(system) = SYSTEM.get() {
-        system.router.shutdown().await?;
-    }
-    tracing::info!("Actorus system shutdown complete");
-    Ok(())
+///
+/// In-flight requests are given `system.shutdown_timeout_ms` to finish
+/// before the actors stop; the summary reports how many made it.
+pub async fn shutdown() -> error::Result<ShutdownSummary> {
+    let summary = match SYSTEM.get() {
+        Some(system) => system.router.shutdown().await?,
+        None => ShutdownSummary::default(),
+    };
+    tracing::info!(
+        "Actorus system shutdown complete ({} drained, {} aborted)",
+        summary.drained,
+        summary.aborted
+    );
+    Ok(summary)
+}
+
+/// Subscribe to actor health as it changes
+///
+/// The receiver is updated on every heartbeat and whenever an actor goes
+/// silent, so dashboards can react immediately instead of polling
+/// `get_system_state`.
+pub fn subscribe_health() -> error::Result<watch::Receiver<StateSnapshot>> {
+    Ok(System::global()?.router.subscribe_health())
 }
 
 /// Get the current state of the actor system
 /// Returns a snapshot showing which actors are active and their last heartbeat times
-pub async fn get_system_state() -> anyhow::Result<StateSnapshot> {
-    let system = System::global();
+pub async fn get_system_state() -> error::Result<StateSnapshot> {
+    let system = System::global()?;
 
     let (response_tx, response_rx) = oneshot::channel();
 
@@ -88,7 +152,5 @@ pub async fn get_system_state() -> anyhow::Result<StateSnapshot> {
         .send_message(actors::messages::RoutingMessage::GetState(response_tx))
         .await?;
 
-    response_rx
-        .await
-        .map_err(|e| anyhow::anyhow!("Failed to receive system state: {}", e))
+    Ok(response_rx.await?)
 }
@@ -1,7 +1,12 @@
-use actorus::cli::{Cli, Commands};
+use actorus::batch::BatchRecord;
+use actorus::cli::batch::{self, BatchOutputFormat};
+use actorus::cli::{health, Cli, Commands};
+use actorus::health::LLMHealth;
 use actorus::{init, shutdown, utils};
 use anyhow::Result;
 use clap::Parser;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
 use tokio::fs::File;
 use tokio::io::{self, AsyncBufReadExt, BufReader};
 
@@ -23,8 +28,17 @@ async fn main() -> Result<()> {
             session_id,
             storage_dir,
         } => handle_interactive(system, memory, session_id, storage_dir).await,
-        Commands::Batch { file, concurrency } => handle_batch(file, concurrency).await,
-        Commands::Health { watch } => handle_health(watch).await,
+        Commands::Batch {
+            file,
+            concurrency,
+            output,
+            format,
+        } => handle_batch(file, concurrency, output, format).await,
+        Commands::Health {
+            watch,
+            json,
+            ping_llm,
+        } => handle_health(watch, json, ping_llm).await,
     };
 
     // Shutdown gracefully
@@ -113,6 +127,15 @@ async fn handle_interactive_with_memory(
     )
     .await?;
 
+    // Show shell output and download progress while tools are still running
+    let (tool_output, mut partial_output) = tokio::sync::mpsc::channel::<String>(64);
+    tokio::spawn(async move {
+        while let Some(line) = partial_output.recv().await {
+            utils::print_tool_output(&line);
+        }
+    });
+    session = session.with_tool_output(tool_output);
+
     // Show message count if resuming existing session
     let msg_count = session.message_count();
     if msg_count > 0 {
@@ -124,15 +147,9 @@ async fn handle_interactive_with_memory(
         utils::print_success("New session created");
     }
 
-    // Set system prompt if provided (only for new sessions)
-    if msg_count == 0 {
-        if let Some(sys) = system {
-            // For sessions, we add system message through the first interaction
-            utils::print_info(&format!("System prompt: {}\n", sys));
-            let _ = session
-                .send_message(&format!("System context: {}", sys))
-                .await?;
-        }
+    if let Some(sys) = system {
+        utils::print_info(&format!("System prompt: {}\n", sys));
+        session = session.with_system_prompt(sys).await?;
     }
 
     let stdin = io::stdin();
@@ -156,6 +173,20 @@ async fn handle_interactive_with_memory(
             continue;
         }
 
+        if input == "/undo" {
+            let removed = session.undo_last_interaction().await?;
+            if removed.is_empty() {
+                utils::print_info("Nothing to undo");
+            } else {
+                utils::print_success(&format!(
+                    "Removed last exchange ({} messages)",
+                    removed.len()
+                ));
+            }
+            println!();
+            continue;
+        }
+
         if input == "/count" {
             let count = session.message_count();
             utils::print_info(&format!("Messages in session: {}", count));
@@ -166,6 +197,7 @@ async fn handle_interactive_with_memory(
         if input == "/help" {
             println!("Special commands:");
             println!("  /clear  - Clear session history");
+            println!("  /undo   - Remove the last exchange");
             println!("  /count  - Show message count");
             println!("  /help   - Show this help");
             println!("  Ctrl+C  - Exit\n");
@@ -178,23 +210,45 @@ async fn handle_interactive_with_memory(
     }
 }
 
-async fn handle_batch(file: String, concurrency: usize) -> Result<()> {
+async fn handle_batch(
+    file: String,
+    concurrency: usize,
+    output: Option<PathBuf>,
+    format: Option<BatchOutputFormat>,
+) -> Result<()> {
     utils::print_info(&format!(
         "Processing prompts from {} with concurrency {}",
         file, concurrency
     ));
 
-    let file = File::open(file).await?;
-    let reader = BufReader::new(file);
-    let mut lines = reader.lines();
-
-    let mut prompts = vec![];
-    while let Some(line) = lines.next_line().await? {
-        if !line.trim().is_empty() {
-            prompts.push(line);
+    let structured = file.ends_with(".jsonl") || file.ends_with(".csv");
+    if structured || output.is_some() {
+        let records = read_batch_records(&file).await?;
+        let outcomes = actorus::batch::process_records(records, concurrency).await?;
+
+        if let Some(path) = output {
+            let format = format.unwrap_or_else(|| BatchOutputFormat::for_path(&path));
+            batch::write_outcomes(&outcomes, format, &path).await?;
+            let failed = outcomes.iter().filter(|o| o.result.is_err()).count();
+            utils::print_success(&format!(
+                "Wrote {} results ({} failed) to {}",
+                outcomes.len(),
+                failed,
+                path.display()
+            ));
+        } else {
+            for outcome in outcomes {
+                let line = match outcome.result {
+                    Ok(response) => serde_json::json!({ "id": outcome.id, "result": response }),
+                    Err(e) => serde_json::json!({ "id": outcome.id, "error": e.to_string() }),
+                };
+                println!("{}", line);
+            }
         }
+        return Ok(());
     }
 
+    let prompts = read_prompt_lines(&file).await?;
     let results = actorus::batch::process_prompts(prompts, concurrency).await;
 
     for (i, result) in results.iter().enumerate() {
@@ -212,10 +266,78 @@ async fn handle_batch(file: String, concurrency: usize) -> Result<()> {
     Ok(())
 }
 
-async fn handle_health(watch: Option<u64>) -> Result<()> {
+/// Non-empty lines of a plain prompt file
+async fn read_prompt_lines(file: &str) -> Result<Vec<String>> {
+    let file = File::open(file).await?;
+    let reader = BufReader::new(file);
+    let mut lines = reader.lines();
+
+    let mut prompts = vec![];
+    while let Some(line) = lines.next_line().await? {
+        if !line.trim().is_empty() {
+            prompts.push(line);
+        }
+    }
+    Ok(prompts)
+}
+
+/// Records from a JSONL/CSV file; plain prompts are numbered from 1
+async fn read_batch_records(file: &str) -> Result<Vec<BatchRecord>> {
+    if file.ends_with(".jsonl") || file.ends_with(".csv") {
+        let input = tokio::fs::read_to_string(file).await?;
+        return Ok(if file.ends_with(".csv") {
+            actorus::batch::parse_csv(&input)?
+        } else {
+            actorus::batch::parse_jsonl(&input)?
+        });
+    }
+
+    Ok(read_prompt_lines(file)
+        .await?
+        .into_iter()
+        .enumerate()
+        .map(|(i, prompt)| BatchRecord {
+            id: (i + 1).to_string(),
+            prompt,
+            system: None,
+        })
+        .collect())
+}
+
+/// Shortest time between billed LLM pings while `health --watch` refreshes
+const LLM_PING_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Latest LLM provider ping, reused until it is `LLM_PING_INTERVAL` old
+#[derive(Default)]
+struct LlmPing {
+    last: Option<(Instant, Result<LLMHealth, String>)>,
+}
+
+impl LlmPing {
+    async fn current(&mut self) -> &Result<LLMHealth, String> {
+        let stale = self
+            .last
+            .as_ref()
+            .is_none_or(|(at, _)| at.elapsed() >= LLM_PING_INTERVAL);
+        if stale {
+            let health = actorus::health::check_llm()
+                .await
+                .map_err(|e| e.to_string());
+            self.last = Some((Instant::now(), health));
+        }
+        &self.last.as_ref().expect("pinged above").1
+    }
+}
+
+async fn handle_health(watch: Option<u64>, json: bool, ping_llm: bool) -> Result<()> {
     // Give the system a moment to start up and send initial heartbeats
     tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
 
+    let mut ping = ping_llm.then(LlmPing::default);
+    if json {
+        return handle_health_json(watch, ping).await;
+    }
+
     loop {
         match actorus::get_system_state().await {
             Ok(state) => {
@@ -230,8 +352,8 @@ async fn handle_health(watch: Option<u64>) -> Result<()> {
                         let last_seen = state
                             .last_heartbeat
                             .get(actor_type)
-                            .map(|instant| {
-                                let elapsed = instant.elapsed();
+                            .map(|time| {
+                                let elapsed = time.elapsed().unwrap_or_default();
                                 if elapsed.as_secs() < 1 {
                                     format!("{}ms ago", elapsed.as_millis())
                                 } else {
@@ -243,13 +365,30 @@ async fn handle_health(watch: Option<u64>) -> Result<()> {
                         println!("  {:?}: {} (last seen: {})", actor_type, status, last_seen);
                     }
                 }
-                println!();
             }
             Err(e) => {
                 eprintln!("Failed to get system state: {}", e);
             }
         }
 
+        // The provider ping is billed, so it only runs when asked for
+        if let Some(ping) = ping.as_mut() {
+            match ping.current().await {
+                Ok(llm) if llm.reachable => println!(
+                    "  LLM provider: Reachable via {} ({}ms)",
+                    llm.provider.as_deref().unwrap_or("unknown"),
+                    llm.latency.as_millis()
+                ),
+                Ok(llm) => println!(
+                    "  LLM provider: Unreachable after {}ms ({})",
+                    llm.latency.as_millis(),
+                    llm.error.as_deref().unwrap_or("unknown error")
+                ),
+                Err(e) => eprintln!("Failed to check LLM provider: {}", e),
+            }
+        }
+        println!();
+
         // If watch mode enabled, wait and refresh
         if let Some(interval) = watch {
             tokio::time::sleep(tokio::time::Duration::from_secs(interval)).await;
@@ -262,3 +401,22 @@ async fn handle_health(watch: Option<u64>) -> Result<()> {
 
     Ok(())
 }
+
+/// One JSON document per line, so `--watch` output can be streamed into jq
+async fn handle_health_json(watch: Option<u64>, mut ping: Option<LlmPing>) -> Result<()> {
+    loop {
+        let state = actorus::get_system_state().await?;
+        let llm = match ping.as_mut() {
+            Some(ping) => ping.current().await.as_ref().ok(),
+            None => None,
+        };
+        println!("{}", health::health_json(&state, llm));
+
+        match watch {
+            Some(interval) => tokio::time::sleep(tokio::time::Duration::from_secs(interval)).await,
+            None => break,
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,153 @@
+//! HTTP Server - Serves the actor system over the web (feature `server`)
+//!
+//! Information Hiding:
+//! - Routing, SSE framing and WebSocket handling hidden behind `app`
+//! - Requests reuse the running actor system rather than their own LLM clients
+//! - Wire format of agent steps and results hidden from the agent API
+
+use crate::actors::messages::{AgentStep, ChatMessageData};
+use crate::actors::MessageRouterHandle;
+use crate::api::agent::{send_task, AgentResult};
+use crate::api::stream_tokens;
+use crate::error::Result;
+use crate::System;
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::State;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::Response;
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use futures::stream::{self, Stream, StreamExt};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::convert::Infallible;
+use tokio::sync::mpsc;
+
+/// Iterations allowed for each task received over WebSocket
+const MAX_ITERATIONS: usize = 10;
+
+/// Body of `POST /chat/stream`
+#[derive(Debug, Deserialize)]
+pub struct ChatStreamRequest {
+    pub prompt: String,
+    #[serde(default)]
+    pub system: Option<String>,
+}
+
+/// Routes served by the system started with `init()`
+pub fn app_for_system() -> Result<Router> {
+    Ok(app(&System::global()?.router))
+}
+
+/// Routes backed by the actor system behind `router`
+///
+/// - `POST /chat/stream`: the reply as SSE `data` events, one per token,
+///   followed by a `done` event (or a single `error` event)
+/// - `GET /agent/ws`: each text message is run as an agent task; its steps
+///   and then its result come back as JSON messages
+pub fn app(router: &'static MessageRouterHandle) -> Router {
+    Router::new()
+        .route("/chat/stream", post(chat_stream))
+        .route("/agent/ws", get(agent_ws))
+        .with_state(router)
+}
+
+async fn chat_stream(
+    State(router): State<&'static MessageRouterHandle>,
+    Json(request): Json<ChatStreamRequest>,
+) -> Sse<impl Stream<Item = std::result::Result<Event, Infallible>>> {
+    let mut messages = vec![];
+    if let Some(system) = request.system {
+        messages.push(ChatMessageData::system(system));
+    }
+    messages.push(ChatMessageData::user(request.prompt));
+
+    let events = match stream_tokens(router, messages).await {
+        Ok(tokens) => stream::unfold(tokens, |mut tokens| async move {
+            let token = tokens.recv().await?;
+            Some((Event::default().data(token), tokens))
+        })
+        .chain(stream::once(async {
+            Event::default().event("done").data("")
+        }))
+        .boxed(),
+        Err(e) => {
+            tracing::warn!("[Server] Chat stream failed: {}", e);
+            stream::once(async move { Event::default().event("error").data(e.to_string()) }).boxed()
+        }
+    };
+
+    Sse::new(events.map(Ok)).keep_alive(KeepAlive::default())
+}
+
+async fn agent_ws(
+    State(router): State<&'static MessageRouterHandle>,
+    ws: WebSocketUpgrade,
+) -> Response {
+    ws.on_upgrade(move |socket| run_agent_socket(router, socket))
+}
+
+/// Run each text message as a task until the client disconnects
+///
+/// Each step is sent as soon as the agent takes it; the result follows
+/// the task's last step.
+async fn run_agent_socket(router: &'static MessageRouterHandle, mut socket: WebSocket) {
+    while let Some(Ok(message)) = socket.recv().await {
+        let Message::Text(task) = message else {
+            continue;
+        };
+
+        let (step_tx, mut step_rx) = mpsc::channel(MAX_ITERATIONS);
+        let run = send_task(router, task.to_string(), MAX_ITERATIONS, Some(step_tx));
+        tokio::pin!(run);
+
+        let outcome = loop {
+            tokio::select! {
+                biased;
+                Some(step) = step_rx.recv() => {
+                    if send_json(&mut socket, step_message(&step)).await.is_err() {
+                        return;
+                    }
+                }
+                outcome = &mut run => break outcome,
+            }
+        };
+        // Steps are sent before the response, so any left are already queued
+        while let Ok(step) = step_rx.try_recv() {
+            if send_json(&mut socket, step_message(&step)).await.is_err() {
+                return;
+            }
+        }
+
+        let reply = match outcome {
+            Ok(response) => result_message(AgentResult::from_response(response)),
+            Err(e) => json!({ "type": "error", "error": e.to_string() }),
+        };
+        if send_json(&mut socket, reply).await.is_err() {
+            return;
+        }
+    }
+}
+
+async fn send_json(socket: &mut WebSocket, value: Value) -> std::result::Result<(), axum::Error> {
+    socket.send(Message::Text(value.to_string().into())).await
+}
+
+fn step_message(step: &AgentStep) -> Value {
+    json!({
+        "type": "step",
+        "iteration": step.iteration,
+        "thought": step.thought,
+        "action": step.action,
+        "observation": step.observation,
+    })
+}
+
+fn result_message(result: AgentResult) -> Value {
+    json!({
+        "type": "result",
+        "success": result.success,
+        "result": result.result,
+        "error": result.error,
+    })
+}
@@ -0,0 +1,228 @@
+//! Artifact Storage - Durable outputs produced during agent runs
+//!
+//! Information Hiding:
+//! - Byte storage and content-type bookkeeping hidden behind the ArtifactStore trait
+//! - File layout and index format of the filesystem store hidden from tools
+//! - Artifact name validation internalized
+
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::PathBuf;
+use tokio::fs;
+use tokio::sync::{Mutex, RwLock};
+
+/// Index file of the filesystem store, mapping artifact names to MIME types
+const INDEX_FILE: &str = ".artifacts.json";
+
+/// Named output of a run, such as a report or a JSON export
+#[derive(Debug, Clone, PartialEq)]
+pub struct Artifact {
+    pub name: String,
+    pub mime: String,
+    pub bytes: Vec<u8>,
+}
+
+impl Artifact {
+    /// Contents as text, if they are valid UTF-8
+    pub fn as_text(&self) -> Option<&str> {
+        std::str::from_utf8(&self.bytes).ok()
+    }
+}
+
+/// Place for agents and tools to leave outputs that outlive the run
+///
+/// Tools reach the store of the current call through
+/// `ExecutionContext::artifact_store`. Writing an existing name replaces it.
+#[async_trait]
+pub trait ArtifactStore: Send + Sync {
+    async fn put(&self, name: &str, bytes: Vec<u8>, mime: &str) -> Result<()>;
+
+    /// Artifact stored under `name`, or None if absent
+    async fn get(&self, name: &str) -> Result<Option<Artifact>>;
+
+    /// All artifact names, in sorted order
+    async fn list(&self) -> Result<Vec<String>>;
+}
+
+impl fmt::Debug for dyn ArtifactStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ArtifactStore")
+    }
+}
+
+/// Reject names that are empty or could escape the store's directory
+fn validate_name(name: &str) -> Result<()> {
+    if name.is_empty() || name.starts_with('.') || name.contains(['/', '\\']) {
+        bail!("Invalid artifact name '{}'", name);
+    }
+    Ok(())
+}
+
+/// Process-local artifact store (lost on process termination)
+#[derive(Default)]
+pub struct InMemoryArtifactStore {
+    artifacts: RwLock<BTreeMap<String, Artifact>>,
+}
+
+impl InMemoryArtifactStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl ArtifactStore for InMemoryArtifactStore {
+    async fn put(&self, name: &str, bytes: Vec<u8>, mime: &str) -> Result<()> {
+        validate_name(name)?;
+        self.artifacts.write().await.insert(
+            name.to_string(),
+            Artifact {
+                name: name.to_string(),
+                mime: mime.to_string(),
+                bytes,
+            },
+        );
+        Ok(())
+    }
+
+    async fn get(&self, name: &str) -> Result<Option<Artifact>> {
+        Ok(self.artifacts.read().await.get(name).cloned())
+    }
+
+    async fn list(&self) -> Result<Vec<String>> {
+        Ok(self.artifacts.read().await.keys().cloned().collect())
+    }
+}
+
+/// File system artifact store - each artifact is a file
+/// Files are stored as {base_path}/{name}, with MIME types in an index file
+pub struct FileSystemArtifactStore {
+    base_path: PathBuf,
+    lock: Mutex<()>,
+}
+
+impl FileSystemArtifactStore {
+    pub async fn new(base_path: PathBuf) -> Result<Self> {
+        fs::create_dir_all(&base_path)
+            .await
+            .context("Failed to create artifact directory")?;
+
+        Ok(Self {
+            base_path,
+            lock: Mutex::new(()),
+        })
+    }
+
+    async fn read_index(&self) -> Result<BTreeMap<String, String>> {
+        let path = self.base_path.join(INDEX_FILE);
+        if !path.exists() {
+            return Ok(BTreeMap::new());
+        }
+        let json = fs::read_to_string(&path)
+            .await
+            .context(format!("Failed to read artifact index: {:?}", path))?;
+        serde_json::from_str(&json).context("Failed to deserialize artifact index")
+    }
+
+    async fn write_index(&self, index: &BTreeMap<String, String>) -> Result<()> {
+        let path = self.base_path.join(INDEX_FILE);
+        let json =
+            serde_json::to_string_pretty(index).context("Failed to serialize artifact index")?;
+        fs::write(&path, json)
+            .await
+            .context(format!("Failed to write artifact index: {:?}", path))
+    }
+}
+
+#[async_trait]
+impl ArtifactStore for FileSystemArtifactStore {
+    async fn put(&self, name: &str, bytes: Vec<u8>, mime: &str) -> Result<()> {
+        validate_name(name)?;
+        let _guard = self.lock.lock().await;
+
+        let path = self.base_path.join(name);
+        fs::write(&path, &bytes)
+            .await
+            .context(format!("Failed to write artifact: {:?}", path))?;
+
+        let mut index = self.read_index().await?;
+        index.insert(name.to_string(), mime.to_string());
+        self.write_index(&index).await?;
+
+        tracing::debug!(
+            "[FileSystemArtifactStore] Saved artifact '{}' ({} bytes) to {:?}",
+            name,
+            bytes.len(),
+            path
+        );
+        Ok(())
+    }
+
+    async fn get(&self, name: &str) -> Result<Option<Artifact>> {
+        let _guard = self.lock.lock().await;
+        let Some(mime) = self.read_index().await?.remove(name) else {
+            return Ok(None);
+        };
+
+        let path = self.base_path.join(name);
+        let bytes = fs::read(&path)
+            .await
+            .context(format!("Failed to read artifact: {:?}", path))?;
+        Ok(Some(Artifact {
+            name: name.to_string(),
+            mime,
+            bytes,
+        }))
+    }
+
+    async fn list(&self) -> Result<Vec<String>> {
+        let _guard = self.lock.lock().await;
+        Ok(self.read_index().await?.into_keys().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_put_get_list_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let stores: Vec<Arc<dyn ArtifactStore>> = vec![
+            Arc::new(InMemoryArtifactStore::new()),
+            Arc::new(
+                FileSystemArtifactStore::new(temp_dir.path().join("artifacts"))
+                    .await
+                    .unwrap(),
+            ),
+        ];
+
+        for store in stores {
+            store
+                .put("report.md", b"# Report".to_vec(), "text/markdown")
+                .await
+                .unwrap();
+            store
+                .put("export.json", b"{\"rows\":2}".to_vec(), "application/json")
+                .await
+                .unwrap();
+
+            let report = store.get("report.md").await.unwrap().unwrap();
+            assert_eq!(report.mime, "text/markdown");
+            assert_eq!(report.as_text(), Some("# Report"));
+            assert_eq!(
+                store.list().await.unwrap(),
+                vec!["export.json", "report.md"]
+            );
+            assert_eq!(store.get("missing.txt").await.unwrap(), None);
+            assert!(store
+                .put("../escape", Vec::new(), "text/plain")
+                .await
+                .is_err());
+        }
+    }
+}
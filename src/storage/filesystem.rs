@@ -4,18 +4,106 @@
 //! - File paths and JSON serialization format hidden from users
 //! - Directory structure management hidden behind interface
 //! - Persistence mechanism independent of storage trait users
+//! - File format versioning and migration of older files hidden behind `load`
 
-use super::ConversationStorage;
+use super::kv::{FileKvStore, MemoryStore};
+use super::{trim_to_bytes, ConversationStorage, TrimReport};
 use crate::core::llm::ChatMessage;
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::path::PathBuf;
+use std::sync::Arc;
 use tokio::fs;
 
+/// Format version written by `save`
+///
+/// Bump it together with a new step in `migrate` whenever a `ChatMessage`
+/// change would stop older files from deserializing.
+const CURRENT_VERSION: u32 = 2;
+
+/// On-disk layout of a session file
+#[derive(Serialize)]
+struct SessionFile<'a> {
+    version: u32,
+    messages: &'a [ChatMessage],
+}
+
+/// Session file read back before migration
+#[derive(Deserialize)]
+struct StoredSessionFile {
+    version: u32,
+    messages: Value,
+}
+
+/// Parse a session file of any known version into current messages
+///
+/// Version 1 files are a bare array of messages; later versions wrap the
+/// messages in a `{version, messages}` envelope.
+fn parse_session_file(json: &str) -> Result<(u32, Vec<ChatMessage>)> {
+    let value: Value = serde_json::from_str(json).context("Session file is not valid JSON")?;
+    let (version, messages) = match value {
+        Value::Array(_) => (1, value),
+        Value::Object(_) => {
+            let file: StoredSessionFile =
+                serde_json::from_value(value).context("Malformed session file envelope")?;
+            (file.version, file.messages)
+        }
+        _ => bail!("Session file must hold an array or a versioned object"),
+    };
+    if version == 0 || version > CURRENT_VERSION {
+        bail!(
+            "Unsupported session file version {} (this build reads up to {})",
+            version,
+            CURRENT_VERSION
+        );
+    }
+
+    let messages = migrate(version, messages)?;
+    let messages =
+        serde_json::from_value(messages).context("Failed to deserialize conversation history")?;
+    Ok((version, messages))
+}
+
+/// Upgrade raw messages stored in format `version` to `CURRENT_VERSION`
+fn migrate(version: u32, mut messages: Value) -> Result<Value> {
+    for from in version..CURRENT_VERSION {
+        messages = match from {
+            1 => migrate_v1(messages)?,
+            _ => unreachable!("no migration registered from version {}", from),
+        };
+    }
+    Ok(messages)
+}
+
+/// Version 1 wrote roles as given by callers (e.g. "User") and allowed
+/// messages without content
+fn migrate_v1(messages: Value) -> Result<Value> {
+    let Value::Array(mut messages) = messages else {
+        bail!("Version 1 session file must be an array of messages");
+    };
+    for message in &mut messages {
+        let Some(message) = message.as_object_mut() else {
+            bail!("Version 1 session file holds a non-object message");
+        };
+        if let Some(Value::String(role)) = message.get_mut("role") {
+            *role = role.to_lowercase();
+        }
+        if message.get("content").is_none_or(Value::is_null) {
+            message.insert("content".to_string(), Value::String(String::new()));
+        }
+    }
+    Ok(Value::Array(messages))
+}
+
 /// File system storage - each session is a JSON file
 /// Files are stored as {base_path}/{session_id}.json
 pub struct FileSystemStorage {
     base_path: PathBuf,
+    max_bytes: Option<usize>,
+    /// Session memory, kept in `memory.kv` next to the session files
+    memory: Arc<FileKvStore>,
 }
 
 impl FileSystemStorage {
@@ -24,8 +112,20 @@ impl FileSystemStorage {
         fs::create_dir_all(&base_path)
             .await
             .context("Failed to create storage directory")?;
+        let memory = Arc::new(FileKvStore::new(base_path.join("memory.kv")).await?);
+
+        Ok(Self {
+            base_path,
+            max_bytes: None,
+            memory,
+        })
+    }
 
-        Ok(Self { base_path })
+    /// Keep each session file under `max_bytes` by dropping the oldest
+    /// messages before saving; system and pinned messages are kept
+    pub fn with_max_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self
     }
 
     fn session_path(&self, session_id: &str) -> PathBuf {
@@ -36,8 +136,37 @@ impl FileSystemStorage {
 #[async_trait]
 impl ConversationStorage for FileSystemStorage {
     async fn save(&self, session_id: &str, history: &[ChatMessage]) -> Result<()> {
+        self.save_trimmed(session_id, history).await.map(|_| ())
+    }
+
+    async fn save_trimmed(&self, session_id: &str, history: &[ChatMessage]) -> Result<TrimReport> {
+        let mut history = history.to_vec();
+        let report = match self.max_bytes {
+            Some(max_bytes) => trim_to_bytes(&mut history, max_bytes, |messages| {
+                let file = SessionFile {
+                    version: CURRENT_VERSION,
+                    messages,
+                };
+                serde_json::to_vec_pretty(&file).map_or(0, |json| json.len())
+            }),
+            None => TrimReport::default(),
+        };
+        if !report.is_empty() {
+            tracing::warn!(
+                "[FileSystemStorage] Session '{}' exceeds {} bytes, dropped {} oldest messages ({} bytes)",
+                session_id,
+                self.max_bytes.unwrap_or_default(),
+                report.removed_messages,
+                report.removed_bytes
+            );
+        }
+
         let path = self.session_path(session_id);
-        let json = serde_json::to_string_pretty(history)
+        let file = SessionFile {
+            version: CURRENT_VERSION,
+            messages: &history,
+        };
+        let json = serde_json::to_string_pretty(&file)
             .context("Failed to serialize conversation history")?;
 
         fs::write(&path, json)
@@ -50,7 +179,7 @@ impl ConversationStorage for FileSystemStorage {
             session_id,
             path
         );
-        Ok(())
+        Ok(report)
     }
 
     async fn load(&self, session_id: &str) -> Result<Vec<ChatMessage>> {
@@ -68,8 +197,16 @@ impl ConversationStorage for FileSystemStorage {
             .await
             .context(format!("Failed to read session file: {:?}", path))?;
 
-        let history: Vec<ChatMessage> =
-            serde_json::from_str(&json).context("Failed to deserialize conversation history")?;
+        let (version, history) = parse_session_file(&json)
+            .context(format!("Failed to load session file: {:?}", path))?;
+        if version < CURRENT_VERSION {
+            tracing::info!(
+                "[FileSystemStorage] Upgraded session '{}' from format v{} to v{}; it is rewritten on next save",
+                session_id,
+                version,
+                CURRENT_VERSION
+            );
+        }
 
         tracing::debug!(
             "[FileSystemStorage] Loaded {} messages for session '{}' from {:?}",
@@ -129,11 +266,16 @@ impl ConversationStorage for FileSystemStorage {
         let path = self.session_path(session_id);
         Ok(path.exists())
     }
+
+    fn memory_store(&self) -> Option<Arc<dyn MemoryStore>> {
+        Some(self.memory.clone())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::core::llm::Role;
     use tempfile::TempDir;
 
     #[tokio::test]
@@ -144,14 +286,8 @@ mod tests {
             .unwrap();
 
         let messages = vec![
-            ChatMessage {
-                role: "user".to_string(),
-                content: "Hello".to_string(),
-            },
-            ChatMessage {
-                role: "assistant".to_string(),
-                content: "Hi there".to_string(),
-            },
+            ChatMessage::user("Hello".to_string()),
+            ChatMessage::assistant("Hi there".to_string()),
         ];
 
         storage.save("test-session", &messages).await.unwrap();
@@ -180,10 +316,7 @@ mod tests {
             .await
             .unwrap();
 
-        let messages = vec![ChatMessage {
-            role: "user".to_string(),
-            content: "Test".to_string(),
-        }];
+        let messages = vec![ChatMessage::user("Test".to_string())];
 
         storage.save("test-session", &messages).await.unwrap();
         assert!(storage.exists("test-session").await.unwrap());
@@ -199,10 +332,7 @@ mod tests {
             .await
             .unwrap();
 
-        let msg = vec![ChatMessage {
-            role: "user".to_string(),
-            content: "Test".to_string(),
-        }];
+        let msg = vec![ChatMessage::user("Test".to_string())];
 
         storage.save("session-1", &msg).await.unwrap();
         storage.save("session-2", &msg).await.unwrap();
@@ -213,6 +343,76 @@ mod tests {
         assert!(sessions.contains(&"session-2".to_string()));
     }
 
+    #[tokio::test]
+    async fn test_max_bytes_trims_oldest_messages() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = FileSystemStorage::new(temp_dir.path().to_path_buf())
+            .await
+            .unwrap()
+            .with_max_bytes(2_000);
+
+        let mut history = vec![
+            ChatMessage::system("You are helpful".to_string()),
+            ChatMessage::user("Remember: my name is Ada".to_string()).pinned(),
+        ];
+        history.extend(
+            (0..40).map(|i| ChatMessage::user(format!("Message {} {}", i, "x".repeat(80)))),
+        );
+
+        let report = storage.save_trimmed("big", &history).await.unwrap();
+
+        let file_size = std::fs::metadata(temp_dir.path().join("big.json"))
+            .unwrap()
+            .len();
+        assert!(file_size <= 2_000, "stored {} bytes", file_size);
+        assert!(report.removed_messages > 0);
+
+        let loaded = storage.load("big").await.unwrap();
+        assert_eq!(loaded.len(), history.len() - report.removed_messages);
+        assert_eq!(loaded[0].content, "You are helpful");
+        assert_eq!(loaded[1].content, "Remember: my name is Ada");
+        assert!(loaded.last().unwrap().content.starts_with("Message 39"));
+    }
+
+    #[tokio::test]
+    async fn test_v1_file_is_upgraded_on_load() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = FileSystemStorage::new(temp_dir.path().to_path_buf())
+            .await
+            .unwrap();
+
+        // Files written before versioning: a bare array with loose roles
+        let v1 = r#"[
+            {"role": "System", "content": "You are helpful"},
+            {"role": "user", "content": "What is 2 + 2?"},
+            {"role": "Assistant", "content": null, "tool_calls": null}
+        ]"#;
+        std::fs::write(temp_dir.path().join("old.json"), v1).unwrap();
+
+        let loaded = storage.load("old").await.unwrap();
+        assert_eq!(loaded.len(), 3);
+        assert_eq!(loaded[0].role, Role::System);
+        assert_eq!(loaded[1].content, "What is 2 + 2?");
+        assert_eq!(loaded[2].role, Role::Assistant);
+        assert_eq!(loaded[2].content, "");
+        assert!(!loaded[1].pinned);
+
+        storage.save("old", &loaded).await.unwrap();
+        let saved: Value = serde_json::from_str(
+            &std::fs::read_to_string(temp_dir.path().join("old.json")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(saved["version"], CURRENT_VERSION);
+        assert_eq!(storage.load("old").await.unwrap(), loaded);
+
+        std::fs::write(
+            temp_dir.path().join("future.json"),
+            r#"{"version": 99, "messages": []}"#,
+        )
+        .unwrap();
+        assert!(storage.load("future").await.is_err());
+    }
+
     #[tokio::test]
     async fn test_persistence_across_instances() {
         let temp_dir = TempDir::new().unwrap();
@@ -221,10 +421,7 @@ mod tests {
         // Create first storage instance and save data
         {
             let storage = FileSystemStorage::new(path.clone()).await.unwrap();
-            let messages = vec![ChatMessage {
-                role: "user".to_string(),
-                content: "Persistent message".to_string(),
-            }];
+            let messages = vec![ChatMessage::user("Persistent message".to_string())];
             storage.save("persist-test", &messages).await.unwrap();
         }
 
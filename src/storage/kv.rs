@@ -0,0 +1,273 @@
+//! Long-Term Memory Store
+//!
+//! Information Hiding:
+//! - Key/value persistence hidden behind the MemoryStore trait
+//! - File layout and serialization format hidden from agents and tools
+//! - Session scoping implemented as a key namespace wrapper
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::fs;
+use tokio::sync::{Mutex, RwLock};
+
+/// Scratchpad that outlives a single task, e.g. for user preferences
+///
+/// Unlike `ConversationStorage` this holds facts, not chat transcripts.
+#[async_trait]
+pub trait MemoryStore: Send + Sync {
+    async fn put(&self, key: &str, value: &str) -> Result<()>;
+
+    /// Value stored under `key`, or None if absent
+    async fn get(&self, key: &str) -> Result<Option<String>>;
+
+    /// Remove `key`, returning whether it was present
+    async fn delete(&self, key: &str) -> Result<bool>;
+
+    /// All keys, in sorted order
+    async fn keys(&self) -> Result<Vec<String>>;
+}
+
+/// Process-local memory store (lost on process termination)
+#[derive(Default)]
+pub struct InMemoryKvStore {
+    entries: RwLock<BTreeMap<String, String>>,
+}
+
+impl InMemoryKvStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl MemoryStore for InMemoryKvStore {
+    async fn put(&self, key: &str, value: &str) -> Result<()> {
+        self.entries
+            .write()
+            .await
+            .insert(key.to_string(), value.to_string());
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<String>> {
+        Ok(self.entries.read().await.get(key).cloned())
+    }
+
+    async fn delete(&self, key: &str) -> Result<bool> {
+        Ok(self.entries.write().await.remove(key).is_some())
+    }
+
+    async fn keys(&self) -> Result<Vec<String>> {
+        Ok(self.entries.read().await.keys().cloned().collect())
+    }
+}
+
+/// File-backed memory store - all entries in one JSON object
+pub struct FileKvStore {
+    path: PathBuf,
+    lock: Mutex<()>,
+}
+
+impl FileKvStore {
+    pub async fn new(path: PathBuf) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .await
+                .context("Failed to create memory store directory")?;
+        }
+        Ok(Self {
+            path,
+            lock: Mutex::new(()),
+        })
+    }
+
+    async fn read_all(&self) -> Result<BTreeMap<String, String>> {
+        if !self.path.exists() {
+            return Ok(BTreeMap::new());
+        }
+        let json = fs::read_to_string(&self.path)
+            .await
+            .context(format!("Failed to read memory file: {:?}", self.path))?;
+        serde_json::from_str(&json).context("Failed to deserialize memory store")
+    }
+
+    async fn write_all(&self, entries: &BTreeMap<String, String>) -> Result<()> {
+        let json = serde_json::to_string_pretty(entries).context("Failed to serialize memory")?;
+        fs::write(&self.path, json)
+            .await
+            .context(format!("Failed to write memory file: {:?}", self.path))
+    }
+}
+
+#[async_trait]
+impl MemoryStore for FileKvStore {
+    async fn put(&self, key: &str, value: &str) -> Result<()> {
+        let _guard = self.lock.lock().await;
+        let mut entries = self.read_all().await?;
+        entries.insert(key.to_string(), value.to_string());
+        self.write_all(&entries).await
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<String>> {
+        let _guard = self.lock.lock().await;
+        Ok(self.read_all().await?.remove(key))
+    }
+
+    async fn delete(&self, key: &str) -> Result<bool> {
+        let _guard = self.lock.lock().await;
+        let mut entries = self.read_all().await?;
+        let removed = entries.remove(key).is_some();
+        if removed {
+            self.write_all(&entries).await?;
+        }
+        Ok(removed)
+    }
+
+    async fn keys(&self) -> Result<Vec<String>> {
+        let _guard = self.lock.lock().await;
+        Ok(self.read_all().await?.into_keys().collect())
+    }
+}
+
+/// View of a shared store restricted to one scope, such as a session id
+///
+/// Keys are stored as `{scope}/{key}` with `%` and `/` in the scope
+/// percent-encoded, so scopes sharing a backing store never see each
+/// other's entries even when a scope contains `/`.
+pub struct ScopedMemoryStore {
+    inner: Arc<dyn MemoryStore>,
+    prefix: String,
+}
+
+impl ScopedMemoryStore {
+    pub fn new(inner: Arc<dyn MemoryStore>, scope: &str) -> Self {
+        Self {
+            inner,
+            prefix: format!("{}/", scope.replace('%', "%25").replace('/', "%2F")),
+        }
+    }
+
+    fn scoped(&self, key: &str) -> String {
+        format!("{}{}", self.prefix, key)
+    }
+
+    /// Remove every entry in this scope, returning how many there were
+    pub async fn clear(&self) -> Result<usize> {
+        let keys = self.keys().await?;
+        for key in &keys {
+            self.delete(key).await?;
+        }
+        Ok(keys.len())
+    }
+}
+
+#[async_trait]
+impl MemoryStore for ScopedMemoryStore {
+    async fn put(&self, key: &str, value: &str) -> Result<()> {
+        self.inner.put(&self.scoped(key), value).await
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<String>> {
+        self.inner.get(&self.scoped(key)).await
+    }
+
+    async fn delete(&self, key: &str) -> Result<bool> {
+        self.inner.delete(&self.scoped(key)).await
+    }
+
+    async fn keys(&self) -> Result<Vec<String>> {
+        Ok(self
+            .inner
+            .keys()
+            .await?
+            .into_iter()
+            .filter_map(|key| key.strip_prefix(&self.prefix).map(str::to_string))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_put_get_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let stores: Vec<Arc<dyn MemoryStore>> = vec![
+            Arc::new(InMemoryKvStore::new()),
+            Arc::new(
+                FileKvStore::new(temp_dir.path().join("memory.kv"))
+                    .await
+                    .unwrap(),
+            ),
+        ];
+
+        for store in stores {
+            store.put("color", "blue").await.unwrap();
+            store.put("city", "Lagos").await.unwrap();
+
+            assert_eq!(store.get("color").await.unwrap().as_deref(), Some("blue"));
+            assert_eq!(store.keys().await.unwrap(), vec!["city", "color"]);
+            assert!(store.delete("city").await.unwrap());
+            assert!(!store.delete("city").await.unwrap());
+            assert_eq!(store.get("city").await.unwrap(), None);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_file_store_persists_across_instances() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("memory.kv");
+
+        FileKvStore::new(path.clone())
+            .await
+            .unwrap()
+            .put("name", "Ada")
+            .await
+            .unwrap();
+
+        let reopened = FileKvStore::new(path).await.unwrap();
+        assert_eq!(reopened.get("name").await.unwrap().as_deref(), Some("Ada"));
+    }
+
+    #[tokio::test]
+    async fn test_scopes_are_isolated() {
+        let shared: Arc<dyn MemoryStore> = Arc::new(InMemoryKvStore::new());
+        let alice = ScopedMemoryStore::new(Arc::clone(&shared), "alice");
+        let bob = ScopedMemoryStore::new(Arc::clone(&shared), "bob");
+
+        alice.put("color", "blue").await.unwrap();
+        bob.put("color", "green").await.unwrap();
+
+        assert_eq!(alice.get("color").await.unwrap().as_deref(), Some("blue"));
+        assert_eq!(bob.get("color").await.unwrap().as_deref(), Some("green"));
+        assert_eq!(alice.keys().await.unwrap(), vec!["color"]);
+
+        assert!(alice.delete("color").await.unwrap());
+        assert_eq!(bob.get("color").await.unwrap().as_deref(), Some("green"));
+
+        alice.put("city", "Lagos").await.unwrap();
+        assert_eq!(alice.clear().await.unwrap(), 1);
+        assert!(alice.keys().await.unwrap().is_empty());
+        assert_eq!(bob.keys().await.unwrap(), vec!["color"]);
+    }
+
+    #[tokio::test]
+    async fn test_scopes_containing_slash_do_not_collide() {
+        let shared: Arc<dyn MemoryStore> = Arc::new(InMemoryKvStore::new());
+        let outer = ScopedMemoryStore::new(Arc::clone(&shared), "a");
+        let nested = ScopedMemoryStore::new(Arc::clone(&shared), "a/b");
+
+        outer.put("b/c", "outer").await.unwrap();
+        nested.put("c", "nested").await.unwrap();
+
+        assert_eq!(outer.get("b/c").await.unwrap().as_deref(), Some("outer"));
+        assert_eq!(nested.get("c").await.unwrap().as_deref(), Some("nested"));
+        assert_eq!(outer.keys().await.unwrap(), vec!["b/c"]);
+        assert_eq!(nested.keys().await.unwrap(), vec!["c"]);
+    }
+}
@@ -3,9 +3,11 @@
 //! Information Hiding:
 //! - HashMap storage structure hidden from users
 //! - Thread-safe access via RwLock hidden behind async interface
+//! - LRU eviction bookkeeping hidden behind the storage trait
 //! - Suitable for testing and ephemeral sessions
 
-use super::ConversationStorage;
+use super::kv::{InMemoryKvStore, MemoryStore, ScopedMemoryStore};
+use super::{trim_to_bytes, ConversationStorage, TrimReport};
 use crate::core::llm::ChatMessage;
 use anyhow::Result;
 use async_trait::async_trait;
@@ -13,18 +15,93 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
+/// Stored conversation plus the logical time it was last touched
+struct SessionEntry {
+    history: Vec<ChatMessage>,
+    last_access: u64,
+}
+
+/// Session map with a logical clock for least-recently-used eviction
+#[derive(Default)]
+struct SessionTable {
+    entries: HashMap<String, SessionEntry>,
+    clock: u64,
+}
+
+impl SessionTable {
+    fn tick(&mut self) -> u64 {
+        self.clock += 1;
+        self.clock
+    }
+
+    /// Drop least-recently-accessed sessions until at most `max` remain,
+    /// returning the ids of the dropped sessions
+    fn evict_to(&mut self, max: usize) -> Vec<String> {
+        let mut evicted = Vec::new();
+        while self.entries.len() > max {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_access)
+                .map(|(id, _)| id.clone());
+
+            match oldest {
+                Some(id) => {
+                    self.entries.remove(&id);
+                    tracing::debug!(
+                        "[InMemoryStorage] Evicted least recently used session '{}'",
+                        id
+                    );
+                    evicted.push(id);
+                }
+                None => break,
+            }
+        }
+        evicted
+    }
+}
+
 /// In-memory storage using HashMap
 /// Data is lost when process terminates
+///
+/// By default the number of sessions is unbounded. Use [`InMemoryStorage::with_max_sessions`]
+/// in long-running processes so the least-recently-used session is evicted once the cap is hit.
 pub struct InMemoryStorage {
-    sessions: Arc<RwLock<HashMap<String, Vec<ChatMessage>>>>,
+    sessions: Arc<RwLock<SessionTable>>,
+    memory: Arc<InMemoryKvStore>,
+    max_sessions: Option<usize>,
+    max_bytes: Option<usize>,
 }
 
 impl InMemoryStorage {
     pub fn new() -> Self {
         Self {
-            sessions: Arc::new(RwLock::new(HashMap::new())),
+            sessions: Arc::new(RwLock::new(SessionTable::default())),
+            memory: Arc::new(InMemoryKvStore::new()),
+            max_sessions: None,
+            max_bytes: None,
+        }
+    }
+
+    /// Create storage that keeps at most `max_sessions` sessions
+    ///
+    /// Saving or loading a session marks it as recently used. The session
+    /// being saved is always kept, so a cap of 0 behaves like 1. An evicted
+    /// session's memory is cleared along with its history.
+    pub fn with_max_sessions(max_sessions: usize) -> Self {
+        Self {
+            max_sessions: Some(max_sessions.max(1)),
+            ..Self::new()
         }
     }
+
+    /// Keep each session's history, measured as JSON, under `max_bytes` by
+    /// dropping the oldest messages before saving; system and pinned
+    /// messages are kept
+    pub fn with_max_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self
+    }
 }
 
 impl Default for InMemoryStorage {
@@ -36,19 +113,66 @@ impl Default for InMemoryStorage {
 #[async_trait]
 impl ConversationStorage for InMemoryStorage {
     async fn save(&self, session_id: &str, history: &[ChatMessage]) -> Result<()> {
+        self.save_trimmed(session_id, history).await.map(|_| ())
+    }
+
+    async fn save_trimmed(&self, session_id: &str, history: &[ChatMessage]) -> Result<TrimReport> {
+        let mut history = history.to_vec();
+        let report = match self.max_bytes {
+            Some(max_bytes) => trim_to_bytes(&mut history, max_bytes, |messages| {
+                serde_json::to_vec(messages).map_or(0, |json| json.len())
+            }),
+            None => TrimReport::default(),
+        };
+        if !report.is_empty() {
+            tracing::warn!(
+                "[InMemoryStorage] Session '{}' exceeds {} bytes, dropped {} oldest messages ({} bytes)",
+                session_id,
+                self.max_bytes.unwrap_or_default(),
+                report.removed_messages,
+                report.removed_bytes
+            );
+        }
+
         let mut sessions = self.sessions.write().await;
-        sessions.insert(session_id.to_string(), history.to_vec());
+        let last_access = sessions.tick();
+        let saved = history.len();
+        sessions.entries.insert(
+            session_id.to_string(),
+            SessionEntry {
+                history,
+                last_access,
+            },
+        );
+        let evicted = match self.max_sessions {
+            Some(max) => sessions.evict_to(max),
+            None => Vec::new(),
+        };
+        drop(sessions);
         tracing::debug!(
             "[InMemoryStorage] Saved {} messages for session '{}'",
-            history.len(),
+            saved,
             session_id
         );
-        Ok(())
+
+        for id in evicted {
+            ScopedMemoryStore::new(self.memory.clone(), &id)
+                .clear()
+                .await?;
+        }
+        Ok(report)
     }
 
     async fn load(&self, session_id: &str) -> Result<Vec<ChatMessage>> {
-        let sessions = self.sessions.read().await;
-        let history = sessions.get(session_id).cloned().unwrap_or_default();
+        let mut sessions = self.sessions.write().await;
+        let now = sessions.tick();
+        let history = match sessions.entries.get_mut(session_id) {
+            Some(entry) => {
+                entry.last_access = now;
+                entry.history.clone()
+            }
+            None => Vec::new(),
+        };
         tracing::debug!(
             "[InMemoryStorage] Loaded {} messages for session '{}'",
             history.len(),
@@ -59,21 +183,25 @@ impl ConversationStorage for InMemoryStorage {
 
     async fn delete(&self, session_id: &str) -> Result<()> {
         let mut sessions = self.sessions.write().await;
-        sessions.remove(session_id);
+        sessions.entries.remove(session_id);
         tracing::debug!("[InMemoryStorage] Deleted session '{}'", session_id);
         Ok(())
     }
 
     async fn list_sessions(&self) -> Result<Vec<String>> {
         let sessions = self.sessions.read().await;
-        let session_ids: Vec<String> = sessions.keys().cloned().collect();
+        let session_ids: Vec<String> = sessions.entries.keys().cloned().collect();
         tracing::debug!("[InMemoryStorage] Listed {} sessions", session_ids.len());
         Ok(session_ids)
     }
 
     async fn exists(&self, session_id: &str) -> Result<bool> {
         let sessions = self.sessions.read().await;
-        Ok(sessions.contains_key(session_id))
+        Ok(sessions.entries.contains_key(session_id))
+    }
+
+    fn memory_store(&self) -> Option<Arc<dyn MemoryStore>> {
+        Some(self.memory.clone())
     }
 }
 
@@ -85,14 +213,8 @@ mod tests {
     async fn test_save_and_load() {
         let storage = InMemoryStorage::new();
         let messages = vec![
-            ChatMessage {
-                role: "user".to_string(),
-                content: "Hello".to_string(),
-            },
-            ChatMessage {
-                role: "assistant".to_string(),
-                content: "Hi there".to_string(),
-            },
+            ChatMessage::user("Hello".to_string()),
+            ChatMessage::assistant("Hi there".to_string()),
         ];
 
         storage.save("test-session", &messages).await.unwrap();
@@ -113,10 +235,7 @@ mod tests {
     #[tokio::test]
     async fn test_delete_session() {
         let storage = InMemoryStorage::new();
-        let messages = vec![ChatMessage {
-            role: "user".to_string(),
-            content: "Test".to_string(),
-        }];
+        let messages = vec![ChatMessage::user("Test".to_string())];
 
         storage.save("test-session", &messages).await.unwrap();
         assert!(storage.exists("test-session").await.unwrap());
@@ -128,10 +247,7 @@ mod tests {
     #[tokio::test]
     async fn test_list_sessions() {
         let storage = InMemoryStorage::new();
-        let msg = vec![ChatMessage {
-            role: "user".to_string(),
-            content: "Test".to_string(),
-        }];
+        let msg = vec![ChatMessage::user("Test".to_string())];
 
         storage.save("session-1", &msg).await.unwrap();
         storage.save("session-2", &msg).await.unwrap();
@@ -141,4 +257,79 @@ mod tests {
         assert!(sessions.contains(&"session-1".to_string()));
         assert!(sessions.contains(&"session-2".to_string()));
     }
+
+    #[tokio::test]
+    async fn test_max_sessions_evicts_least_recently_used() {
+        let storage = InMemoryStorage::with_max_sessions(2);
+        let msg = vec![ChatMessage::user("Test".to_string())];
+
+        storage.save("session-1", &msg).await.unwrap();
+        storage.save("session-2", &msg).await.unwrap();
+
+        // Touch session-1 so session-2 becomes the least recently used
+        storage.load("session-1").await.unwrap();
+        storage.save("session-3", &msg).await.unwrap();
+
+        let sessions = storage.list_sessions().await.unwrap();
+        assert_eq!(sessions.len(), 2);
+        assert!(storage.exists("session-1").await.unwrap());
+        assert!(!storage.exists("session-2").await.unwrap());
+        assert!(storage.exists("session-3").await.unwrap());
+
+        // Evicted sessions take their memory with them
+        let storage = InMemoryStorage::with_max_sessions(1);
+        let memory = storage.memory_store().unwrap();
+        storage.save("old", &msg).await.unwrap();
+        ScopedMemoryStore::new(Arc::clone(&memory), "old")
+            .put("color", "blue")
+            .await
+            .unwrap();
+        ScopedMemoryStore::new(Arc::clone(&memory), "new")
+            .put("color", "green")
+            .await
+            .unwrap();
+        storage.save("new", &msg).await.unwrap();
+        assert_eq!(memory.keys().await.unwrap(), vec!["new/color"]);
+
+        // A zero cap still keeps the session just saved
+        let storage = InMemoryStorage::with_max_sessions(0);
+        storage.save("only", &msg).await.unwrap();
+        assert_eq!(storage.load("only").await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_fork_session() {
+        let storage = InMemoryStorage::new();
+        let messages = vec![
+            ChatMessage::user("Hello".to_string()),
+            ChatMessage::assistant("Hi there".to_string()),
+        ];
+
+        storage.save("original", &messages).await.unwrap();
+        storage.fork("original", "branch").await.unwrap();
+
+        // Diverging the branch leaves the original untouched
+        let mut branch = storage.load("branch").await.unwrap();
+        branch.push(ChatMessage::user("Another path".to_string()));
+        storage.save("branch", &branch).await.unwrap();
+
+        assert_eq!(storage.load("original").await.unwrap().len(), 2);
+        assert_eq!(storage.load("branch").await.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_truncate_session() {
+        let storage = InMemoryStorage::new();
+        let messages: Vec<ChatMessage> = (0..5)
+            .map(|i| ChatMessage::user(format!("Message {}", i)))
+            .collect();
+
+        storage.save("test-session", &messages).await.unwrap();
+        storage.truncate("test-session", 2).await.unwrap();
+
+        let loaded = storage.load("test-session").await.unwrap();
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded[0].content, "Message 0");
+        assert_eq!(loaded[1].content, "Message 1");
+    }
 }
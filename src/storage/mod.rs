@@ -5,13 +5,75 @@
 //! - Allows swapping between memory, filesystem, SQLite, Redis without API changes
 //! - Each storage implementation encapsulates its own data structures and protocols
 
-use crate::core::llm::ChatMessage;
+use crate::core::llm::{ChatMessage, Role};
 use anyhow::Result;
 use async_trait::async_trait;
+use kv::MemoryStore;
+use std::sync::Arc;
 
+pub mod artifacts;
 pub mod filesystem;
+pub mod kv;
 pub mod memory;
 
+/// What a storage's byte cap removed from a history before saving it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TrimReport {
+    pub removed_messages: usize,
+    /// Serialized size of the removed messages
+    pub removed_bytes: usize,
+}
+
+impl TrimReport {
+    pub fn is_empty(&self) -> bool {
+        self.removed_messages == 0
+    }
+}
+
+/// Drop the oldest messages until `history` fits in `max_bytes`
+///
+/// `size_of` is the storage's serialized size of a history; each message is
+/// measured on its own and the sizes summed, which over-estimates the whole,
+/// so the saved history fits. System and pinned messages are always kept, so
+/// a history made only of them can stay over the cap.
+pub(crate) fn trim_to_bytes(
+    history: &mut Vec<ChatMessage>,
+    max_bytes: usize,
+    size_of: impl Fn(&[ChatMessage]) -> usize,
+) -> TrimReport {
+    let sizes: Vec<usize> = history
+        .iter()
+        .map(|m| size_of(std::slice::from_ref(m)))
+        .collect();
+    let mut total: usize = sizes.iter().sum();
+    let mut report = TrimReport::default();
+    let mut keep = vec![true; history.len()];
+    let mut dropped_calls = Vec::new();
+
+    for (index, message) in history.iter().enumerate() {
+        // A tool result cannot outlive the call it answers
+        let orphaned = message.role == Role::Tool
+            && message
+                .tool_call_id
+                .as_ref()
+                .is_some_and(|id| dropped_calls.contains(id));
+        if !orphaned && total <= max_bytes {
+            break;
+        }
+        if orphaned || (message.role != Role::System && !message.pinned) {
+            dropped_calls.extend(message.tool_calls.iter().flatten().map(|c| c.id.clone()));
+            keep[index] = false;
+            total -= sizes[index];
+            report.removed_messages += 1;
+            report.removed_bytes += sizes[index];
+        }
+    }
+
+    let mut keep = keep.into_iter();
+    history.retain(|_| keep.next().unwrap_or(true));
+    report
+}
+
 /// Trait defining conversation storage interface
 /// Implementations can use different backends (memory, file, database, cache)
 #[async_trait]
@@ -19,6 +81,13 @@ pub trait ConversationStorage: Send + Sync {
     /// Save conversation history for a session
     async fn save(&self, session_id: &str, history: &[ChatMessage]) -> Result<()>;
 
+    /// Save conversation history, reporting what a size cap removed first
+    /// Storages without a cap save the history unchanged
+    async fn save_trimmed(&self, session_id: &str, history: &[ChatMessage]) -> Result<TrimReport> {
+        self.save(session_id, history).await?;
+        Ok(TrimReport::default())
+    }
+
     /// Load conversation history for a session
     /// Returns empty vector if session doesn't exist
     async fn load(&self, session_id: &str) -> Result<Vec<ChatMessage>>;
@@ -33,4 +102,26 @@ pub trait ConversationStorage: Send + Sync {
     async fn exists(&self, session_id: &str) -> Result<bool> {
         Ok(self.load(session_id).await?.is_empty() == false)
     }
+
+    /// Copy the history of session `from` into session `to`
+    /// Any existing history under `to` is replaced
+    async fn fork(&self, from: &str, to: &str) -> Result<()> {
+        let history = self.load(from).await?;
+        self.save(to, &history).await
+    }
+
+    /// Long-term memory kept alongside the histories, if the storage has one
+    ///
+    /// Sessions built on this storage scope it by session id for their
+    /// `remember` and `recall` tools.
+    fn memory_store(&self) -> Option<Arc<dyn MemoryStore>> {
+        None
+    }
+
+    /// Cut a session's history down to its first `keep` messages
+    async fn truncate(&self, session_id: &str, keep: usize) -> Result<()> {
+        let mut history = self.load(session_id).await?;
+        history.truncate(keep);
+        self.save(session_id, &history).await
+    }
 }
@@ -0,0 +1,66 @@
+//! Clock Tool - Tells agents the current date and time
+//!
+//! Information Hiding:
+//! - Clock source and timestamp formatting hidden from callers
+
+use super::{Tool, ToolMetadata, ToolResult};
+use crate::{tool_metadata, tool_result};
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{SecondsFormat, Utc};
+use serde_json::{json, Value};
+
+/// Report the current UTC time, so agents need not guess today's date
+pub struct CurrentTimeTool;
+
+impl CurrentTimeTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for CurrentTimeTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Tool for CurrentTimeTool {
+    fn metadata(&self) -> ToolMetadata {
+        tool_metadata! {
+            name: "current_time",
+            description: "Get the current date and time in UTC. Use this instead of assuming today's date.",
+            parameters: []
+        }
+    }
+
+    async fn execute(&self, _args: Value) -> Result<ToolResult> {
+        let now = Utc::now();
+        tool_result!(success: json!({
+            "utc": now.to_rfc3339_opts(SecondsFormat::Secs, true),
+            "date": now.format("%Y-%m-%d").to_string(),
+            "weekday": now.format("%A").to_string(),
+            "unix_seconds": now.timestamp(),
+            "timezone": "UTC",
+        })
+        .to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_reports_parseable_current_time() {
+        let before = Utc::now().timestamp();
+        let result = CurrentTimeTool::new().execute(json!({})).await.unwrap();
+        let report: Value = serde_json::from_str(&result.output).unwrap();
+
+        let utc = chrono::DateTime::parse_from_rfc3339(report["utc"].as_str().unwrap()).unwrap();
+        assert!(utc.timestamp() >= before);
+        assert_eq!(report["unix_seconds"], utc.timestamp());
+        assert_eq!(report["timezone"], "UTC");
+    }
+}
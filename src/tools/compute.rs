@@ -0,0 +1,429 @@
+//! Compute Tool - Exact arithmetic over structured data
+//!
+//! Information Hiding:
+//! - Value extraction from rows and fields hidden from callers
+//! - Aggregation and grouping logic internalized
+//! - Result formatting (integers stay integers) hidden
+
+use super::{Tool, ToolErrorKind, ToolMetadata, ToolResult};
+use crate::{tool_metadata, tool_result, validate_required_string};
+use anyhow::{anyhow, bail, Result};
+use async_trait::async_trait;
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+
+const OPERATIONS: [&str; 6] = ["sum", "avg", "min", "max", "count", "group_by"];
+const AGGREGATES: [&str; 5] = ["sum", "avg", "min", "max", "count"];
+
+/// Aggregate numbers locally instead of asking the model to do arithmetic
+///
+/// Takes either a list of numbers or a list of objects plus the numeric
+/// `field` to read, and returns the exact result. `group_by` buckets rows by
+/// the `by` field and applies `aggregate` (default sum) to each bucket.
+pub struct ComputeTool;
+
+/// A row lacked the field being read; reported as `NotFound` rather than bad input
+#[derive(Debug)]
+struct MissingField {
+    index: usize,
+    field: String,
+}
+
+impl std::fmt::Display for MissingField {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Row {} has no field '{}'", self.index, self.field)
+    }
+}
+
+impl std::error::Error for MissingField {}
+
+fn missing_field(index: usize, field: &str) -> anyhow::Error {
+    MissingField {
+        index,
+        field: field.to_string(),
+    }
+    .into()
+}
+
+/// Input number, kept as an integer when JSON wrote it as one
+#[derive(Debug, Clone, Copy)]
+enum Number {
+    Int(i128),
+    Float(f64),
+}
+
+impl Number {
+    fn as_f64(self) -> f64 {
+        match self {
+            Number::Int(n) => n as f64,
+            Number::Float(n) => n,
+        }
+    }
+}
+
+impl ComputeTool {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Numeric value of one row, either the row itself or its `field`
+    fn value_of(row: &Value, field: Option<&str>, index: usize) -> Result<Number> {
+        let value = match field {
+            Some(field) => row.get(field).ok_or_else(|| missing_field(index, field))?,
+            None => row,
+        };
+        if let Some(n) = value.as_i64() {
+            return Ok(Number::Int(n as i128));
+        }
+        if let Some(n) = value.as_u64() {
+            return Ok(Number::Int(n as i128));
+        }
+        value.as_f64().map(Number::Float).ok_or_else(|| {
+            anyhow!(
+                "Row {} is not numeric: {}{}",
+                index,
+                value,
+                if field.is_none() && row.is_object() {
+                    " (pass 'field' for rows that are objects)"
+                } else {
+                    ""
+                }
+            )
+        })
+    }
+
+    fn aggregate(operation: &str, rows: &[&Value], field: Option<&str>) -> Result<Value> {
+        if operation == "count" {
+            return Ok(json!(rows.len()));
+        }
+
+        let values = rows
+            .iter()
+            .enumerate()
+            .map(|(index, row)| Self::value_of(row, field, index))
+            .collect::<Result<Vec<Number>>>()?;
+        if values.is_empty() && operation != "sum" {
+            bail!("Cannot compute {} of an empty dataset", operation);
+        }
+
+        // Integers stay exact; i128 cannot overflow summing i64/u64 inputs
+        let integers: Option<Vec<i128>> = values
+            .iter()
+            .map(|value| match value {
+                Number::Int(n) => Some(*n),
+                Number::Float(_) => None,
+            })
+            .collect();
+        if let Some(integers) = integers {
+            return Self::aggregate_integers(operation, &integers);
+        }
+
+        let values: Vec<f64> = values.into_iter().map(Number::as_f64).collect();
+        let result = match operation {
+            "sum" => values.iter().sum(),
+            "avg" => values.iter().sum::<f64>() / values.len() as f64,
+            "min" => values.iter().copied().fold(f64::INFINITY, f64::min),
+            "max" => values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            other => bail!(
+                "Unknown operation '{}'; expected one of: {}",
+                other,
+                AGGREGATES.join(", ")
+            ),
+        };
+        Ok(Self::number(result))
+    }
+
+    fn aggregate_integers(operation: &str, values: &[i128]) -> Result<Value> {
+        let sum: i128 = values.iter().sum();
+        let result = match operation {
+            "sum" => sum,
+            "avg" if sum % values.len() as i128 == 0 => sum / values.len() as i128,
+            "avg" => return Ok(json!(sum as f64 / values.len() as f64)),
+            "min" => *values.iter().min().unwrap_or(&0),
+            "max" => *values.iter().max().unwrap_or(&0),
+            other => bail!(
+                "Unknown operation '{}'; expected one of: {}",
+                other,
+                AGGREGATES.join(", ")
+            ),
+        };
+        Ok(Self::integer(result))
+    }
+
+    /// JSON integer, falling back to a float only beyond the u64/i64 range
+    fn integer(value: i128) -> Value {
+        if let Ok(n) = i64::try_from(value) {
+            json!(n)
+        } else if let Ok(n) = u64::try_from(value) {
+            json!(n)
+        } else {
+            json!(value as f64)
+        }
+    }
+
+    /// JSON number, written as an integer when the value is whole
+    fn number(value: f64) -> Value {
+        if value.fract() == 0.0 && value.abs() < i64::MAX as f64 {
+            json!(value as i64)
+        } else {
+            json!(value)
+        }
+    }
+
+    /// Group key for a row; strings are used as-is, other values as JSON text
+    fn group_key(row: &Value, by: &str, index: usize) -> Result<String> {
+        match row.get(by) {
+            Some(Value::String(key)) => Ok(key.clone()),
+            Some(other) => Ok(other.to_string()),
+            None => Err(missing_field(index, by)),
+        }
+    }
+
+    /// Run `operation` over `data`, returning the result value
+    pub fn compute(
+        data: &[Value],
+        operation: &str,
+        field: Option<&str>,
+        by: Option<&str>,
+        aggregate: &str,
+    ) -> Result<Value> {
+        if operation != "group_by" {
+            let rows: Vec<&Value> = data.iter().collect();
+            return Self::aggregate(operation, &rows, field);
+        }
+
+        let by = by.ok_or_else(|| anyhow!("'by' is required for group_by"))?;
+        let mut groups: BTreeMap<String, Vec<&Value>> = BTreeMap::new();
+        for (index, row) in data.iter().enumerate() {
+            groups
+                .entry(Self::group_key(row, by, index)?)
+                .or_default()
+                .push(row);
+        }
+
+        let mut result = Map::new();
+        for (key, rows) in groups {
+            result.insert(key, Self::aggregate(aggregate, &rows, field)?);
+        }
+        Ok(Value::Object(result))
+    }
+}
+
+impl Default for ComputeTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Tool for ComputeTool {
+    fn metadata(&self) -> ToolMetadata {
+        tool_metadata! {
+            name: "compute",
+            description: "Compute exact sums, averages, minimums, maximums and counts over a list of numbers or records, optionally grouped by a field. Use this instead of doing arithmetic yourself.",
+            parameters: [
+                {
+                    name: "data",
+                    type: "array",
+                    description: "List of numbers, or list of objects (JSON text is also accepted)",
+                    required: true
+                },
+                {
+                    name: "operation",
+                    type: "string",
+                    description: "What to compute",
+                    required: true,
+                    allowed_values: OPERATIONS
+                },
+                {
+                    name: "field",
+                    type: "string",
+                    description: "Numeric field to read when data holds objects",
+                    required: false
+                },
+                {
+                    name: "by",
+                    type: "string",
+                    description: "Field to group rows by (group_by only)",
+                    required: false
+                },
+                {
+                    name: "aggregate",
+                    type: "string",
+                    description: "Aggregate applied to each group (group_by only, default sum)",
+                    required: false,
+                    allowed_values: AGGREGATES
+                }
+            ]
+        }
+    }
+
+    fn validate(&self, args: &Value) -> Result<()> {
+        if args["data"].is_null() {
+            bail!("'data' parameter is required");
+        }
+        let operation = validate_required_string!(args, "operation");
+        if !OPERATIONS.contains(&operation) {
+            bail!(
+                "Unknown operation '{}'; expected one of: {}",
+                operation,
+                OPERATIONS.join(", ")
+            );
+        }
+        Ok(())
+    }
+
+    fn cacheable(&self) -> bool {
+        true
+    }
+
+    async fn execute(&self, args: Value) -> Result<ToolResult> {
+        if let Err(e) = self.validate(&args) {
+            return Ok(ToolResult::failure_with_kind(
+                ToolErrorKind::Validation,
+                e.to_string(),
+            ));
+        }
+        let operation = validate_required_string!(args, "operation");
+
+        // Models sometimes pass the dataset as JSON text
+        let data = match &args["data"] {
+            Value::String(text) => match serde_json::from_str(text) {
+                Ok(parsed) => parsed,
+                Err(e) => {
+                    return Ok(ToolResult::failure_with_kind(
+                        ToolErrorKind::Validation,
+                        format!("'data' is not valid JSON: {}", e),
+                    ))
+                }
+            },
+            other => other.clone(),
+        };
+        let Value::Array(rows) = data else {
+            return Ok(ToolResult::failure_with_kind(
+                ToolErrorKind::Validation,
+                "'data' must be an array",
+            ));
+        };
+
+        match Self::compute(
+            &rows,
+            operation,
+            args["field"].as_str(),
+            args["by"].as_str(),
+            args["aggregate"].as_str().unwrap_or("sum"),
+        ) {
+            Ok(result) => tool_result!(success: json!({
+                "operation": operation,
+                "rows": rows.len(),
+                "result": result,
+            })
+            .to_string()),
+            Err(e) => {
+                let kind = if e.downcast_ref::<MissingField>().is_some() {
+                    ToolErrorKind::NotFound
+                } else {
+                    ToolErrorKind::Validation
+                };
+                Ok(ToolResult::failure_with_kind(kind, e.to_string()))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sales() -> Value {
+        json!([
+            {"region": "east", "amount": 120.5},
+            {"region": "west", "amount": 80},
+            {"region": "east", "amount": 99.5},
+            {"region": "north", "amount": 42}
+        ])
+    }
+
+    async fn run(args: Value) -> Value {
+        let result = ComputeTool::new().execute(args).await.unwrap();
+        assert!(result.success, "{:?}", result.error);
+        serde_json::from_str(&result.output).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_sum_over_numbers_and_fields() {
+        let output = run(json!({"data": [1, 2, 3.5], "operation": "sum"})).await;
+        assert_eq!(output["result"], json!(6.5));
+
+        let output = run(json!({"data": sales(), "operation": "sum", "field": "amount"})).await;
+        assert_eq!(output["result"], json!(342));
+        assert_eq!(output["rows"], 4);
+
+        let output = run(json!({
+            "data": sales().to_string(),
+            "operation": "max",
+            "field": "amount"
+        }))
+        .await;
+        assert_eq!(output["result"], json!(120.5));
+
+        let missing = ComputeTool::new()
+            .execute(json!({"data": sales(), "operation": "avg"}))
+            .await
+            .unwrap();
+        assert_eq!(missing.error_kind, Some(ToolErrorKind::Validation));
+        assert!(missing.error.unwrap().contains("pass 'field'"));
+
+        let no_field = ComputeTool::new()
+            .execute(json!({"data": sales(), "operation": "sum", "field": "price"}))
+            .await
+            .unwrap();
+        assert_eq!(no_field.error_kind, Some(ToolErrorKind::NotFound));
+
+        let bad_op = ComputeTool::new()
+            .execute(json!({"data": [1], "operation": "median"}))
+            .await
+            .unwrap();
+        assert_eq!(bad_op.error_kind, Some(ToolErrorKind::Validation));
+    }
+
+    #[tokio::test]
+    async fn test_integer_inputs_stay_exact() {
+        let big = 9_007_199_254_740_993_i64; // 2^53 + 1, not representable as f64
+        let output = run(json!({"data": [big, 1, -1], "operation": "sum"})).await;
+        assert_eq!(output["result"], json!(big));
+
+        let output = run(json!({"data": [big, big], "operation": "avg"})).await;
+        assert_eq!(output["result"], json!(big));
+
+        let output = run(json!({"data": [1, 2], "operation": "avg"})).await;
+        assert_eq!(output["result"], json!(1.5));
+
+        let output = run(json!({"data": [u64::MAX, 1], "operation": "max"})).await;
+        assert_eq!(output["result"], json!(u64::MAX));
+    }
+
+    #[tokio::test]
+    async fn test_group_by_aggregates_each_group() {
+        let output = run(json!({
+            "data": sales(),
+            "operation": "group_by",
+            "by": "region",
+            "field": "amount"
+        }))
+        .await;
+        assert_eq!(
+            output["result"],
+            json!({"east": 220, "north": 42, "west": 80})
+        );
+
+        let output = run(json!({
+            "data": sales(),
+            "operation": "group_by",
+            "by": "region",
+            "aggregate": "count"
+        }))
+        .await;
+        assert_eq!(output["result"], json!({"east": 2, "north": 1, "west": 1}));
+    }
+}
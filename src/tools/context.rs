@@ -0,0 +1,145 @@
+//! Execution Context - Caller-scoped values visible to tools
+//!
+//! Information Hiding:
+//! - Task-local storage carrying the context hidden behind `scope`/`current`
+//! - Value map layout hidden behind typed accessors
+
+use crate::storage::artifacts::{Artifact, ArtifactStore};
+use crate::utils::canonicalize;
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+tokio::task_local! {
+    static CURRENT: ExecutionContext;
+}
+
+/// Values set by the caller of an API function, such as a user or tenant id
+///
+/// Unlike tool arguments, these never pass through the LLM, so a tool can
+/// trust them, e.g. to scope a database query to the current user. Run an
+/// API call inside [`ExecutionContext::scope`] and read the values from a
+/// tool with [`ExecutionContext::current`]. The context follows the call into
+/// the agent actor as well.
+///
+/// Results of `Tool::cacheable` tools are only shared between calls made in
+/// contexts holding the same values.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExecutionContext {
+    values: HashMap<String, Value>,
+    /// Where tools leave durable outputs; not serialized
+    #[serde(skip)]
+    artifacts: Option<Arc<RecordingStore>>,
+}
+
+/// Caller's store, remembering the names written through this context
+#[derive(Debug)]
+struct RecordingStore {
+    inner: Arc<dyn ArtifactStore>,
+    written: Mutex<Vec<String>>,
+}
+
+#[async_trait]
+impl ArtifactStore for RecordingStore {
+    async fn put(&self, name: &str, bytes: Vec<u8>, mime: &str) -> Result<()> {
+        self.inner.put(name, bytes, mime).await?;
+        let mut written = self.written.lock().unwrap_or_else(|e| e.into_inner());
+        if !written.iter().any(|n| n == name) {
+            written.push(name.to_string());
+        }
+        Ok(())
+    }
+
+    async fn get(&self, name: &str) -> Result<Option<Artifact>> {
+        self.inner.get(name).await
+    }
+
+    async fn list(&self) -> Result<Vec<String>> {
+        self.inner.list().await
+    }
+}
+
+impl PartialEq for ExecutionContext {
+    fn eq(&self, other: &Self) -> bool {
+        let same_store = match (&self.artifacts, &other.artifacts) {
+            (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+            (None, None) => true,
+            _ => false,
+        };
+        same_store && self.values == other.values
+    }
+}
+
+impl ExecutionContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(mut self, key: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.values.insert(key.into(), value.into());
+        self
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.values.get(key)
+    }
+
+    /// String value of `key`, if it is set to a string
+    pub fn get_str(&self, key: &str) -> Option<&str> {
+        self.get(key).and_then(Value::as_str)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// The values as canonical JSON, equal for contexts with equal values
+    pub(crate) fn canonical_values(&self) -> String {
+        canonicalize(&serde_json::to_value(&self.values).unwrap_or_default())
+    }
+
+    /// Let tools run in this context write artifacts to `store`
+    ///
+    /// Results of API calls made in the context list what was written
+    /// through `AgentResult::artifacts`.
+    pub fn with_artifact_store(mut self, store: Arc<dyn ArtifactStore>) -> Self {
+        self.artifacts = Some(Arc::new(RecordingStore {
+            inner: store,
+            written: Mutex::new(Vec::new()),
+        }));
+        self
+    }
+
+    pub fn artifact_store(&self) -> Option<Arc<dyn ArtifactStore>> {
+        self.artifacts
+            .clone()
+            .map(|store| store as Arc<dyn ArtifactStore>)
+    }
+
+    /// Names written through this context since the last call, in write order
+    ///
+    /// Each API result takes the names written during its run, so a context
+    /// reused for several calls reports each artifact only once.
+    pub(crate) fn take_written_artifacts(&self) -> Vec<String> {
+        self.artifacts
+            .as_ref()
+            .map(|store| {
+                std::mem::take(&mut *store.written.lock().unwrap_or_else(|e| e.into_inner()))
+            })
+            .unwrap_or_default()
+    }
+
+    /// Run `future` with this context as the current one
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        CURRENT.scope(self, future).await
+    }
+
+    /// Context of the enclosing `scope`, if any
+    pub fn current() -> Option<ExecutionContext> {
+        CURRENT.try_with(ExecutionContext::clone).ok()
+    }
+}
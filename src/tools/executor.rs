@@ -4,33 +4,307 @@
 //! - Retry strategy implementation hidden
 //! - Backoff algorithm hidden
 //! - Error classification logic hidden
+//! - Keyed locks serializing non-concurrent tools hidden
+//! - Per-tool circuit breakers hidden; only their state is exposed
+//! - Token buckets enforcing rate limits hidden; callers just wait
+//! - LRU result cache hidden
 
-use super::{Tool, ToolConfig, ToolResult};
+use super::context::ExecutionContext;
+use super::{CacheConfig, RateLimit, Tool, ToolConfig, ToolErrorKind, ToolResult};
+use crate::core::circuit_breaker::{BreakerState, CircuitBreaker};
+use crate::utils::canonicalize;
 use anyhow::Result;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::sync::Arc;
-use tokio::time::{sleep, Duration};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use tokio::sync::mpsc;
+use tokio::time::{sleep, Duration, Instant};
+
+/// Lock shared by every executor for tools with the given serialization key
+fn serialization_lock(key: &str) -> Arc<tokio::sync::Mutex<()>> {
+    static LOCKS: OnceLock<Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>> = OnceLock::new();
+    let mut locks = LOCKS.get_or_init(Default::default).lock().unwrap();
+    Arc::clone(locks.entry(key.to_string()).or_default())
+}
+
+/// Bucket shared by every executor for the tool with the given name
+///
+/// The limit seen on the first call for a name is the one kept.
+fn rate_limiter(tool_name: &str, limit: RateLimit) -> Arc<TokenBucket> {
+    static BUCKETS: OnceLock<Mutex<HashMap<String, Arc<TokenBucket>>>> = OnceLock::new();
+    let mut buckets = BUCKETS.get_or_init(Default::default).lock().unwrap();
+    Arc::clone(
+        buckets
+            .entry(tool_name.to_string())
+            .or_insert_with(|| Arc::new(TokenBucket::new(limit))),
+    )
+}
+
+/// Holds up to `max` tokens, refilled evenly over each `per` window
+struct TokenBucket {
+    capacity: f64,
+    per_second: f64,
+    /// Tokens available and when they were last topped up
+    state: Mutex<(f64, Instant)>,
+}
+
+impl TokenBucket {
+    fn new(limit: RateLimit) -> Self {
+        let capacity = f64::from(limit.max);
+        Self {
+            capacity,
+            per_second: capacity / limit.per.as_secs_f64(),
+            state: Mutex::new((capacity, Instant::now())),
+        }
+    }
+
+    /// Wait until a token is available, then take it
+    async fn acquire(&self) {
+        loop {
+            let wait = {
+                let mut state = self.state.lock().unwrap();
+                let (tokens, last) = *state;
+                let now = Instant::now();
+                let tokens = (tokens + now.duration_since(last).as_secs_f64() * self.per_second)
+                    .min(self.capacity);
+                if tokens >= 1.0 {
+                    *state = (tokens - 1.0, now);
+                    return;
+                }
+                *state = (tokens, now);
+                Duration::from_secs_f64((1.0 - tokens) / self.per_second)
+            };
+            sleep(wait).await;
+        }
+    }
+}
+
+/// Tool name, canonical context values and canonical input of a call
+type CacheKey = (String, String, String);
+
+/// Least-recently-used results keyed by tool, execution context and input
+struct ResultCache {
+    config: CacheConfig,
+    entries: HashMap<CacheKey, CacheEntry>,
+    /// Bumped on every access; the entry with the lowest stamp is evicted first
+    clock: u64,
+}
+
+struct CacheEntry {
+    result: ToolResult,
+    stored_at: Instant,
+    last_used: u64,
+}
+
+impl ResultCache {
+    fn new(config: CacheConfig) -> Self {
+        Self {
+            config,
+            entries: HashMap::new(),
+            clock: 0,
+        }
+    }
+
+    fn get(&mut self, key: &CacheKey) -> Option<ToolResult> {
+        self.clock += 1;
+        let entry = self.entries.get_mut(key)?;
+        if entry.stored_at.elapsed() > self.config.ttl {
+            self.entries.remove(key);
+            return None;
+        }
+        entry.last_used = self.clock;
+        Some(entry.result.clone())
+    }
+
+    fn insert(&mut self, key: CacheKey, result: ToolResult) {
+        if self.config.capacity == 0 {
+            return;
+        }
+        self.clock += 1;
+        if !self.entries.contains_key(&key) && self.entries.len() >= self.config.capacity {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                self.entries.remove(&oldest);
+            }
+        }
+        self.entries.insert(
+            key,
+            CacheEntry {
+                result,
+                stored_at: Instant::now(),
+                last_used: self.clock,
+            },
+        );
+    }
+}
+
+/// Tool call skipped because `ToolConfig.dry_run` is set
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlannedToolCall {
+    pub tool: String,
+    pub args: Value,
+}
 
 /// Tool executor with retry and timeout support
 pub struct ToolExecutor {
     config: ToolConfig,
+    breakers: Mutex<HashMap<String, Arc<CircuitBreaker>>>,
+    cache: Option<Mutex<ResultCache>>,
+    planned: Mutex<Vec<PlannedToolCall>>,
 }
 
 impl ToolExecutor {
     pub fn new(config: ToolConfig) -> Self {
-        Self { config }
+        Self {
+            cache: config
+                .cache
+                .map(|cache| Mutex::new(ResultCache::new(cache))),
+            config,
+            breakers: Mutex::new(HashMap::new()),
+            planned: Mutex::new(Vec::new()),
+        }
     }
 
     pub fn default() -> Self {
-        Self {
-            config: ToolConfig::default(),
+        Self::new(ToolConfig::default())
+    }
+
+    /// Circuit breaker state for a tool; closed for tools never called
+    pub fn breaker_state(&self, tool_name: &str) -> BreakerState {
+        self.breakers
+            .lock()
+            .unwrap()
+            .get(tool_name)
+            .map(|breaker| breaker.state())
+            .unwrap_or(BreakerState::Closed)
+    }
+
+    /// Tool calls skipped because `dry_run` is set, in order
+    pub fn planned_tool_calls(&self) -> Vec<PlannedToolCall> {
+        self.planned.lock().unwrap().clone()
+    }
+
+    fn breaker(&self, tool_name: &str) -> Arc<CircuitBreaker> {
+        let mut breakers = self.breakers.lock().unwrap();
+        Arc::clone(
+            breakers.entry(tool_name.to_string()).or_insert_with(|| {
+                Arc::new(CircuitBreaker::new(self.config.circuit_breaker.clone()))
+            }),
+        )
+    }
+
+    /// Execute a tool, answering repeated calls to cacheable tools from the cache
+    pub async fn execute(&self, tool: Arc<dyn Tool>, args: Value) -> Result<ToolResult> {
+        self.run(tool, args, None).await
+    }
+
+    /// Execute a tool through `Tool::execute_streaming`, forwarding its
+    /// partial output to `output`
+    ///
+    /// Retried attempts stream again; cached and dry-run results send nothing.
+    pub async fn execute_streaming(
+        &self,
+        tool: Arc<dyn Tool>,
+        args: Value,
+        output: mpsc::Sender<String>,
+    ) -> Result<ToolResult> {
+        self.run(tool, args, Some(&output)).await
+    }
+
+    async fn run(
+        &self,
+        tool: Arc<dyn Tool>,
+        args: Value,
+        output: Option<&mpsc::Sender<String>>,
+    ) -> Result<ToolResult> {
+        let tool_name = tool.metadata().name.clone();
+
+        if self.config.dry_run {
+            tracing::info!("[dry run] Skipping tool '{}'", tool_name);
+            let description = format!("[dry run] Would call tool '{}' with {}", tool_name, args);
+            self.planned.lock().unwrap().push(PlannedToolCall {
+                tool: tool_name,
+                args,
+            });
+            return Ok(ToolResult::success(description));
+        }
+
+        if self.config.sandbox {
+            if let Err(e) = tool.check_sandbox(&args) {
+                tracing::warn!("[Sandbox] Blocked call to tool '{}': {}", tool_name, e);
+                return Ok(ToolResult::failure_with_kind(
+                    ToolErrorKind::Permission,
+                    format!("Blocked by sandbox: {}", e),
+                ));
+            }
+        }
+
+        let cache = self.cache.as_ref().filter(|_| tool.cacheable());
+        let key = cache.map(|_| {
+            let context = ExecutionContext::current().unwrap_or_default();
+            (
+                tool_name.clone(),
+                context.canonical_values(),
+                canonicalize(&args),
+            )
+        });
+        if let (Some(cache), Some(key)) = (cache, &key) {
+            if let Some(result) = cache.lock().unwrap().get(key) {
+                tracing::debug!("Cache hit for tool '{}'", tool_name);
+                return Ok(result);
+            }
+        }
+
+        let result = self
+            .execute_with_retry(tool, &tool_name, args, output)
+            .await?;
+
+        if let (Some(cache), Some(key)) = (cache, key) {
+            if result.success {
+                cache.lock().unwrap().insert(key, result.clone());
+            }
         }
+        Ok(result)
     }
 
     /// Execute a tool with retry logic
-    pub async fn execute(&self, tool: Arc<dyn Tool>, args: Value) -> Result<ToolResult> {
+    async fn execute_with_retry(
+        &self,
+        tool: Arc<dyn Tool>,
+        tool_name: &str,
+        args: Value,
+        output: Option<&mpsc::Sender<String>>,
+    ) -> Result<ToolResult> {
         let mut last_error = None;
-        let tool_name = tool.metadata().name.clone();
+
+        let breaker = self.breaker(tool_name);
+        if !breaker.allow() {
+            tracing::warn!("Circuit open for tool '{}', skipping call", tool_name);
+            return Ok(ToolResult::failure(format!(
+                "Tool '{}' is unavailable after repeated failures (circuit breaker open)",
+                tool_name
+            )));
+        }
+
+        // Held across retries so a serialized tool never overlaps itself
+        let _serialized = match tool.serialization_key() {
+            Some(key) => {
+                tracing::debug!("Tool '{}' waiting for lock '{}'", tool_name, key);
+                Some(serialization_lock(&key).lock_owned().await)
+            }
+            None => None,
+        };
+
+        let limiter = tool
+            .rate_limit()
+            .or(self.config.rate_limit)
+            .filter(|limit| limit.max > 0 && !limit.per.is_zero())
+            .map(|limit| rate_limiter(tool_name, limit));
 
         for attempt in 0..self.config.max_retries {
             if attempt > 0 {
@@ -46,12 +320,23 @@ impl ToolExecutor {
                 sleep(Duration::from_millis(backoff_ms)).await;
             }
 
-            match tool.execute(args.clone()).await {
+            if let Some(limiter) = &limiter {
+                limiter.acquire().await;
+            }
+
+            let attempt_result = match output {
+                Some(output) => tool.execute_streaming(args.clone(), output.clone()).await,
+                None => tool.execute(args.clone()).await,
+            };
+            match attempt_result {
                 Ok(result) => {
                     if result.success {
+                        breaker.record_success();
                         return Ok(result);
                     } else if !self.should_retry(&result) {
-                        // Don't retry on certain types of failures (e.g., validation errors)
+                        // Don't retry on certain types of failures (e.g., validation errors);
+                        // the tool itself is working, so they don't count against the breaker
+                        breaker.record_success();
                         return Ok(result);
                     }
                     last_error = result.error;
@@ -63,6 +348,7 @@ impl ToolExecutor {
         }
 
         // All retries exhausted
+        breaker.record_failure();
         Ok(ToolResult::failure(format!(
             "Tool '{}' failed after {} attempts. Last error: {}",
             tool_name,
@@ -82,6 +368,10 @@ impl ToolExecutor {
 
     /// Determine if error is retryable (internal logic)
     fn should_retry(&self, result: &ToolResult) -> bool {
+        if let Some(kind) = result.error_kind {
+            return kind.is_retryable();
+        }
+
         if let Some(ref error) = result.error {
             let error_lower = error.to_lowercase();
 
@@ -111,7 +401,8 @@ impl ToolExecutor {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::tools::{Tool, ToolMetadata, ToolResult};
+    use crate::core::circuit_breaker::CircuitBreakerConfig;
+    use crate::tools::{Tool, ToolErrorKind, ToolMetadata, ToolResult};
     use async_trait::async_trait;
 
     struct MockTool {
@@ -156,6 +447,7 @@ mod tests {
             timeout_secs: 30,
             max_retries: 3,
             sandbox: false,
+            ..ToolConfig::default()
         });
 
         let tool = Arc::new(MockTool::new(2)); // Fail twice, then succeed
@@ -165,12 +457,41 @@ mod tests {
         assert!(result.output.contains("Success after retries"));
     }
 
+    #[tokio::test]
+    async fn test_dry_run_describes_call_without_running_tool() {
+        let executor = ToolExecutor::new(ToolConfig {
+            dry_run: true,
+            ..ToolConfig::default()
+        });
+
+        let tool = Arc::new(MockTool::new(0));
+        let result = executor
+            .execute(tool.clone(), serde_json::json!({"path": "out.txt"}))
+            .await
+            .unwrap();
+
+        assert!(result.success);
+        assert_eq!(
+            result.output,
+            r#"[dry run] Would call tool 'mock_tool' with {"path":"out.txt"}"#
+        );
+        assert_eq!(*tool.fail_count.lock().unwrap(), 0);
+        assert_eq!(
+            executor.planned_tool_calls(),
+            vec![PlannedToolCall {
+                tool: "mock_tool".to_string(),
+                args: serde_json::json!({"path": "out.txt"}),
+            }]
+        );
+    }
+
     #[tokio::test]
     async fn test_executor_retry_exhausted() {
         let executor = ToolExecutor::new(ToolConfig {
             timeout_secs: 30,
             max_retries: 2,
             sandbox: false,
+            ..ToolConfig::default()
         });
 
         let tool = Arc::new(MockTool::new(5)); // Will keep failing
@@ -179,4 +500,396 @@ mod tests {
         assert!(!result.success);
         assert!(result.error.unwrap().contains("failed after"));
     }
+
+    /// Fails with a fixed kind, counting attempts
+    struct FailingTool {
+        kind: ToolErrorKind,
+        attempts: std::sync::atomic::AtomicU32,
+    }
+
+    #[async_trait]
+    impl Tool for FailingTool {
+        fn metadata(&self) -> ToolMetadata {
+            ToolMetadata {
+                name: "failing_tool".to_string(),
+                description: "Always fails".to_string(),
+                parameters: vec![],
+            }
+        }
+
+        async fn execute(&self, _args: Value) -> Result<ToolResult> {
+            self.attempts
+                .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(ToolResult::failure_with_kind(
+                self.kind,
+                "no such file: ./data.csv",
+            ))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_only_retryable_error_kinds_are_retried() {
+        for (kind, expected_attempts) in [
+            (ToolErrorKind::Validation, 1),
+            (ToolErrorKind::NotFound, 1),
+            (ToolErrorKind::Network, 2),
+        ] {
+            let executor = ToolExecutor::new(ToolConfig {
+                timeout_secs: 30,
+                max_retries: 2,
+                sandbox: false,
+                ..ToolConfig::default()
+            });
+            let tool = Arc::new(FailingTool {
+                kind,
+                attempts: Default::default(),
+            });
+
+            let result = executor
+                .execute(tool.clone(), serde_json::json!({}))
+                .await
+                .unwrap();
+
+            assert!(!result.success);
+            assert_eq!(
+                tool.attempts.load(std::sync::atomic::Ordering::SeqCst),
+                expected_attempts,
+                "{:?}",
+                kind
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_breaker_opens_after_consecutive_failed_calls() {
+        let executor = ToolExecutor::new(ToolConfig {
+            max_retries: 1,
+            circuit_breaker: CircuitBreakerConfig {
+                failure_threshold: 2,
+                cooldown_ms: 60_000,
+            },
+            ..ToolConfig::default()
+        });
+        let tool = Arc::new(FailingTool {
+            kind: ToolErrorKind::Network,
+            attempts: Default::default(),
+        });
+
+        for _ in 0..2 {
+            let result = executor
+                .execute(tool.clone(), serde_json::json!({}))
+                .await
+                .unwrap();
+            assert!(result.error.unwrap().contains("failed after"));
+        }
+        assert_eq!(executor.breaker_state("failing_tool"), BreakerState::Open);
+
+        let result = executor
+            .execute(tool.clone(), serde_json::json!({}))
+            .await
+            .unwrap();
+        assert!(result.error.unwrap().contains("circuit breaker open"));
+        assert_eq!(tool.attempts.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+
+    /// Sleeps while recording how many calls overlap
+    struct SlowTool {
+        key: Option<&'static str>,
+        active: std::sync::atomic::AtomicUsize,
+        peak: std::sync::atomic::AtomicUsize,
+    }
+
+    impl SlowTool {
+        fn new(key: Option<&'static str>) -> Self {
+            Self {
+                key,
+                active: Default::default(),
+                peak: Default::default(),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl Tool for SlowTool {
+        fn metadata(&self) -> ToolMetadata {
+            ToolMetadata {
+                name: "slow_tool".to_string(),
+                description: "Sleeps briefly".to_string(),
+                parameters: vec![],
+            }
+        }
+
+        async fn execute(&self, _args: Value) -> Result<ToolResult> {
+            use std::sync::atomic::Ordering;
+
+            let now = self.active.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak.fetch_max(now, Ordering::SeqCst);
+            sleep(Duration::from_millis(30)).await;
+            self.active.fetch_sub(1, Ordering::SeqCst);
+            Ok(ToolResult::success("done"))
+        }
+
+        fn serialization_key(&self) -> Option<String> {
+            self.key.map(str::to_string)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_serialized_tool_calls_never_overlap() {
+        use std::sync::atomic::Ordering;
+
+        // Separate executors, as when two agents share a tool
+        let first = ToolExecutor::default();
+        let second = ToolExecutor::default();
+
+        let serialized = Arc::new(SlowTool::new(Some("executor_test_db")));
+        let (a, b) = tokio::join!(
+            first.execute(serialized.clone(), serde_json::json!({})),
+            second.execute(serialized.clone(), serde_json::json!({}))
+        );
+        assert!(a.unwrap().success && b.unwrap().success);
+        assert_eq!(serialized.peak.load(Ordering::SeqCst), 1);
+
+        let parallel = Arc::new(SlowTool::new(None));
+        let _ = tokio::join!(
+            first.execute(parallel.clone(), serde_json::json!({})),
+            second.execute(parallel.clone(), serde_json::json!({}))
+        );
+        assert_eq!(parallel.peak.load(Ordering::SeqCst), 2);
+    }
+
+    /// Records when each call started
+    struct StampTool {
+        calls: Mutex<Vec<Instant>>,
+    }
+
+    #[async_trait]
+    impl Tool for StampTool {
+        fn metadata(&self) -> ToolMetadata {
+            ToolMetadata {
+                name: "executor_test_rate_limited".to_string(),
+                description: "Records call times".to_string(),
+                parameters: vec![],
+            }
+        }
+
+        async fn execute(&self, _args: Value) -> Result<ToolResult> {
+            self.calls.lock().unwrap().push(Instant::now());
+            Ok(ToolResult::success("done"))
+        }
+
+        fn rate_limit(&self) -> Option<RateLimit> {
+            Some(RateLimit::new(2, Duration::from_millis(200)))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_calls_wait_for_their_turn() {
+        let executor = ToolExecutor::default();
+        let tool = Arc::new(StampTool {
+            calls: Mutex::new(Vec::new()),
+        });
+
+        let results = futures::future::join_all(
+            (0..5).map(|_| executor.execute(tool.clone(), serde_json::json!({}))),
+        )
+        .await;
+        assert!(results.into_iter().all(|r| r.unwrap().success));
+
+        let mut calls = tool.calls.lock().unwrap().clone();
+        calls.sort();
+        // Two calls fit the burst; the other three wait 100ms each for a token
+        assert!(calls[1] - calls[0] < Duration::from_millis(50));
+        for pair in calls[2..].windows(2) {
+            assert!(pair[1] - pair[0] >= Duration::from_millis(90));
+        }
+        assert!(calls[4] - calls[0] >= Duration::from_millis(280));
+    }
+
+    /// Counts its runs; output depends only on the input
+    struct PureTool {
+        runs: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait]
+    impl Tool for PureTool {
+        fn metadata(&self) -> ToolMetadata {
+            ToolMetadata {
+                name: "pure".to_string(),
+                description: "Echoes its input".to_string(),
+                parameters: vec![],
+            }
+        }
+
+        async fn execute(&self, args: Value) -> Result<ToolResult> {
+            self.runs.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(ToolResult::success(args.to_string()))
+        }
+
+        fn cacheable(&self) -> bool {
+            true
+        }
+    }
+
+    #[tokio::test]
+    async fn test_identical_calls_to_cacheable_tool_hit_the_cache() {
+        let executor = ToolExecutor::new(ToolConfig {
+            cache: Some(CacheConfig::new(2, Duration::from_millis(100))),
+            ..ToolConfig::default()
+        });
+        let tool = Arc::new(PureTool {
+            runs: std::sync::atomic::AtomicUsize::new(0),
+        });
+        let call = |args: Value| executor.execute(tool.clone(), args);
+
+        let first = call(serde_json::json!({"a": 1, "b": [1, 2]}))
+            .await
+            .unwrap();
+        // Same input with keys in another order
+        let second = call(serde_json::json!({"b": [1, 2], "a": 1}))
+            .await
+            .unwrap();
+        assert_eq!(first.output, second.output);
+        assert_eq!(tool.runs.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        call(serde_json::json!({"a": 2})).await.unwrap();
+        assert_eq!(tool.runs.load(std::sync::atomic::Ordering::SeqCst), 2);
+
+        // Touching the first input leaves {"a": 2} least recently used,
+        // so a third input evicts it
+        call(serde_json::json!({"a": 1, "b": [1, 2]}))
+            .await
+            .unwrap();
+        call(serde_json::json!({"a": 3})).await.unwrap();
+        assert_eq!(tool.runs.load(std::sync::atomic::Ordering::SeqCst), 3);
+        call(serde_json::json!({"a": 1, "b": [1, 2]}))
+            .await
+            .unwrap();
+        assert_eq!(tool.runs.load(std::sync::atomic::Ordering::SeqCst), 3);
+        call(serde_json::json!({"a": 2})).await.unwrap();
+        assert_eq!(tool.runs.load(std::sync::atomic::Ordering::SeqCst), 4);
+
+        // Entries expire after the TTL
+        sleep(Duration::from_millis(120)).await;
+        call(serde_json::json!({"a": 2})).await.unwrap();
+        assert_eq!(tool.runs.load(std::sync::atomic::Ordering::SeqCst), 5);
+    }
+
+    #[tokio::test]
+    async fn test_cached_results_are_not_shared_across_contexts() {
+        let executor = ToolExecutor::new(ToolConfig {
+            cache: Some(CacheConfig::new(8, Duration::from_secs(60))),
+            ..ToolConfig::default()
+        });
+        let tool = Arc::new(PureTool {
+            runs: std::sync::atomic::AtomicUsize::new(0),
+        });
+        let call_as = |user: &str| {
+            ExecutionContext::new()
+                .with("user_id", user)
+                .scope(executor.execute(tool.clone(), serde_json::json!({"q": 1})))
+        };
+
+        call_as("alice").await.unwrap();
+        call_as("bob").await.unwrap();
+        assert_eq!(tool.runs.load(std::sync::atomic::Ordering::SeqCst), 2);
+
+        call_as("alice").await.unwrap();
+        assert_eq!(tool.runs.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_sandbox_keeps_file_writes_in_working_directory() {
+        use crate::tools::filesystem::WriteFileTool;
+
+        let outside = tempfile::tempdir().unwrap();
+        let target = outside.path().join("escaped.txt");
+        let args = serde_json::json!({
+            "path": target.to_str().unwrap(),
+            "content": "hello"
+        });
+        let tool: Arc<dyn Tool> = Arc::new(WriteFileTool::new(1024));
+
+        let sandboxed = ToolExecutor::new(ToolConfig::default());
+        let result = sandboxed
+            .execute(Arc::clone(&tool), args.clone())
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert_eq!(result.error_kind, Some(ToolErrorKind::Permission));
+        assert!(result
+            .error
+            .unwrap()
+            .contains("outside the working directory"));
+        assert!(!target.exists());
+
+        // Escaping through '..' is caught as well
+        let climb = serde_json::json!({"path": "../escaped.txt", "content": "hello"});
+        let result = sandboxed.execute(Arc::clone(&tool), climb).await.unwrap();
+        assert_eq!(result.error_kind, Some(ToolErrorKind::Permission));
+
+        let unrestricted = ToolExecutor::new(ToolConfig {
+            sandbox: false,
+            ..ToolConfig::default()
+        });
+        let result = unrestricted.execute(tool, args).await.unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(std::fs::read_to_string(&target).unwrap(), "hello");
+    }
+
+    struct ProgressTool;
+
+    #[async_trait]
+    impl Tool for ProgressTool {
+        fn metadata(&self) -> ToolMetadata {
+            ToolMetadata {
+                name: "progress_tool".to_string(),
+                description: "Reports progress while working".to_string(),
+                parameters: vec![],
+            }
+        }
+
+        async fn execute(&self, _args: Value) -> Result<ToolResult> {
+            Ok(ToolResult::success("downloaded 3 parts"))
+        }
+
+        async fn execute_streaming(
+            &self,
+            args: Value,
+            output: mpsc::Sender<String>,
+        ) -> Result<ToolResult> {
+            for part in 1..=3 {
+                let _ = output.send(format!("part {}/3", part)).await;
+            }
+            self.execute(args).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_streamed_chunks_arrive_before_final_result() {
+        let executor = ToolExecutor::default();
+        let (tx, mut rx) = mpsc::channel(8);
+
+        let result = executor
+            .execute_streaming(Arc::new(ProgressTool), serde_json::json!({}), tx.clone())
+            .await
+            .unwrap();
+
+        // Every chunk was queued by the time the result came back
+        let mut chunks = Vec::new();
+        while let Ok(chunk) = rx.try_recv() {
+            chunks.push(chunk);
+        }
+        assert_eq!(chunks, vec!["part 1/3", "part 2/3", "part 3/3"]);
+        assert!(result.success);
+        assert_eq!(result.output, "downloaded 3 parts");
+
+        // Tools without streaming support still run, sending nothing
+        let result = executor
+            .execute_streaming(Arc::new(MockTool::new(0)), serde_json::json!({}), tx)
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert!(rx.try_recv().is_err());
+    }
 }
@@ -5,17 +5,187 @@
 //! - Path validation and security checks hidden
 //! - Error handling for file operations abstracted
 
-use super::{Tool, ToolMetadata, ToolResult};
+use super::{expand_env_vars, Tool, ToolErrorKind, ToolMetadata, ToolResult};
 use crate::{tool_metadata, tool_result, validate_required_string};
 use anyhow::Result;
 use async_trait::async_trait;
-use serde_json::Value;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::borrow::Cow;
 use std::path::{Path, PathBuf};
 use tokio::fs;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncSeekExt, BufReader};
+
+/// Path argument as the tool should use it, with environment variables
+/// expanded only when the tool opts in
+fn input_path(raw: &str, expand_env: bool) -> Cow<'_, str> {
+    if expand_env {
+        Cow::Owned(expand_env_vars(raw))
+    } else {
+        Cow::Borrowed(raw)
+    }
+}
+
+/// What a file tool replaced, so the change can be taken back
+///
+/// Write, append and edit results carry this in `ToolResult::metadata`
+/// under `"undo"`. `previous_contents` is `None` when the tool created the
+/// file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileUndo {
+    pub path: PathBuf,
+    pub previous_contents: Option<String>,
+}
+
+impl FileUndo {
+    /// Record the current state of `path` before a tool changes it
+    ///
+    /// Returns `None` when the file exists but cannot be read as text, since
+    /// it could not be put back.
+    async fn capture(path: &Path) -> Option<Self> {
+        let previous_contents = match fs::read_to_string(path).await {
+            Ok(contents) => Some(contents),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(_) => return None,
+        };
+        Some(Self {
+            path: path.to_path_buf(),
+            previous_contents,
+        })
+    }
+
+    /// The undo record attached to `result`, if the tool left one
+    pub fn from_result(result: &ToolResult) -> Option<Self> {
+        let undo = result.metadata.as_ref()?.get("undo")?;
+        serde_json::from_value(undo.clone()).ok()
+    }
+
+    /// Put the file back the way it was, removing it if the tool created it
+    pub async fn restore(&self) -> std::io::Result<()> {
+        match &self.previous_contents {
+            Some(contents) => fs::write(&self.path, contents).await,
+            None => match fs::remove_file(&self.path).await {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+                _ => Ok(()),
+            },
+        }
+    }
+
+    /// Attach this record to a successful `result`
+    fn attach(undo: Option<Self>, result: ToolResult) -> ToolResult {
+        match undo {
+            Some(undo) => result.with_metadata(json!({ "undo": undo })),
+            None => result,
+        }
+    }
+}
+
+/// Whether `path` stays inside the current working directory
+///
+/// `..` components are resolved lexically and the part of the path that
+/// already exists is canonicalized, so neither can be used to escape.
+fn within_working_dir(path: &Path) -> bool {
+    use std::path::Component;
+
+    let Ok(cwd) = std::env::current_dir().and_then(|cwd| cwd.canonicalize()) else {
+        return false;
+    };
+
+    let mut normalized = PathBuf::new();
+    for component in cwd.join(path).components() {
+        match component {
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            Component::CurDir => {}
+            other => normalized.push(other),
+        }
+    }
+
+    let mut existing = normalized.as_path();
+    let mut missing = Vec::new();
+    while !existing.exists() {
+        match (existing.file_name(), existing.parent()) {
+            (Some(name), Some(parent)) => {
+                missing.push(name);
+                existing = parent;
+            }
+            _ => return false,
+        }
+    }
+    let Ok(resolved) = existing.canonicalize() else {
+        return false;
+    };
+    missing
+        .iter()
+        .rev()
+        .fold(resolved, |path, name| path.join(name))
+        .starts_with(&cwd)
+}
+
+/// Sandbox rule shared by the file tools: without explicit `allowed_paths`,
+/// the `param` path must stay inside the working directory
+fn check_sandbox_path(
+    allowed_paths: &Option<Vec<PathBuf>>,
+    args: &Value,
+    param: &str,
+    expand_env: bool,
+) -> Result<()> {
+    if allowed_paths.is_some() {
+        return Ok(());
+    }
+    let Some(raw) = args[param].as_str() else {
+        return Ok(());
+    };
+    let path = input_path(raw, expand_env);
+    if !within_working_dir(Path::new(path.as_ref())) {
+        anyhow::bail!(
+            "path '{}' is outside the working directory; configure allowed_paths to permit it",
+            path
+        );
+    }
+    Ok(())
+}
+
+/// Whether `path` lies under one of `allowed_paths`; any path is allowed
+/// when none are configured
+///
+/// The path is checked as given and canonicalized. A path that does not
+/// exist yet, such as a file about to be written, is checked through its
+/// canonicalized parent.
+fn is_path_allowed(allowed_paths: &Option<Vec<PathBuf>>, path: &Path) -> bool {
+    let Some(allowed) = allowed_paths else {
+        return true;
+    };
+    let resolved = path.canonicalize().ok().or_else(|| {
+        path.parent()
+            .and_then(|parent| parent.canonicalize().ok())
+            .zip(path.file_name())
+            .map(|(parent, name)| parent.join(name))
+    });
+    allowed.iter().any(|allowed_path| {
+        path.starts_with(allowed_path)
+            || resolved
+                .as_ref()
+                .is_some_and(|resolved| resolved.starts_with(allowed_path))
+    })
+}
+
+/// Read an optional non-negative integer parameter
+fn optional_index(args: &Value, param: &str) -> Result<Option<u64>> {
+    match &args[param] {
+        Value::Null => Ok(None),
+        value => value
+            .as_u64()
+            .map(Some)
+            .ok_or_else(|| anyhow::anyhow!("'{}' parameter must be a non-negative integer", param)),
+    }
+}
 
 /// Read file tool
 pub struct ReadFileTool {
     allowed_paths: Option<Vec<PathBuf>>,
+    expand_env: bool,
     max_size_bytes: usize,
 }
 
@@ -23,6 +193,7 @@ impl ReadFileTool {
     pub fn new(max_size_bytes: usize) -> Self {
         Self {
             allowed_paths: None,
+            expand_env: false,
             max_size_bytes,
         }
     }
@@ -32,20 +203,10 @@ impl ReadFileTool {
         self
     }
 
-    /// Check if path is allowed (internal security check)
-    fn is_path_allowed(&self, path: &Path) -> bool {
-        if let Some(ref allowed) = self.allowed_paths {
-            allowed.iter().any(|allowed_path| {
-                path.starts_with(allowed_path)
-                    || path
-                        .canonicalize()
-                        .ok()
-                        .map(|p| p.starts_with(allowed_path))
-                        .unwrap_or(false)
-            })
-        } else {
-            true
-        }
+    /// Apply [`expand_env_vars`] to the path argument
+    pub fn with_expand_env(mut self, enabled: bool) -> Self {
+        self.expand_env = enabled;
+        self
     }
 }
 
@@ -54,50 +215,129 @@ impl Tool for ReadFileTool {
     fn metadata(&self) -> ToolMetadata {
         tool_metadata! {
             name: "read_file",
-            description: "Read the contents of a file from the filesystem.",
+            description: "Read the contents of a file from the filesystem. \
+                          For large files, read a byte range (offset/length) or a line range (start_line/end_line).",
             parameters: [
                 {
                     name: "path",
                     type: "string",
                     description: "The file path to read",
                     required: true
+                },
+                {
+                    name: "offset",
+                    type: "number",
+                    description: "Byte offset to start reading from",
+                    required: false
+                },
+                {
+                    name: "length",
+                    type: "number",
+                    description: "Number of bytes to read from offset",
+                    required: false
+                },
+                {
+                    name: "start_line",
+                    type: "number",
+                    description: "First line to read (1-based, inclusive)",
+                    required: false
+                },
+                {
+                    name: "end_line",
+                    type: "number",
+                    description: "Last line to read (1-based, inclusive); defaults to the end of the file",
+                    required: false
                 }
             ]
         }
     }
 
     fn validate(&self, args: &Value) -> Result<()> {
-        let path_str = validate_required_string!(args, "path");
+        let path_str: &str = &input_path(validate_required_string!(args, "path"), self.expand_env);
 
         if path_str.is_empty() {
             return Err(anyhow::anyhow!("Path cannot be empty"));
         }
 
         let path = Path::new(path_str);
-        if !self.is_path_allowed(path) {
+        if !is_path_allowed(&self.allowed_paths, path) {
             return Err(anyhow::anyhow!(
                 "Access to path '{}' is not allowed",
                 path_str
             ));
         }
 
+        let byte_range =
+            optional_index(args, "offset")?.is_some() || optional_index(args, "length")?.is_some();
+        let start_line = optional_index(args, "start_line")?;
+        let end_line = optional_index(args, "end_line")?;
+
+        if byte_range && (start_line.is_some() || end_line.is_some()) {
+            return Err(anyhow::anyhow!(
+                "Use either offset/length or start_line/end_line, not both"
+            ));
+        }
+        if start_line == Some(0) || end_line == Some(0) {
+            return Err(anyhow::anyhow!("Line numbers start at 1"));
+        }
+        if let (Some(start), Some(end)) = (start_line, end_line) {
+            if end < start {
+                return Err(anyhow::anyhow!(
+                    "end_line ({}) is before start_line ({})",
+                    end,
+                    start
+                ));
+            }
+        }
+        if let Some(length) = optional_index(args, "length")? {
+            if length as usize > self.max_size_bytes {
+                return Err(anyhow::anyhow!(
+                    "Requested length too large: {} bytes (max: {} bytes)",
+                    length,
+                    self.max_size_bytes
+                ));
+            }
+        }
+
         Ok(())
     }
 
+    fn check_sandbox(&self, args: &Value) -> Result<()> {
+        check_sandbox_path(&self.allowed_paths, args, "path", self.expand_env)
+    }
+
     async fn execute(&self, args: Value) -> Result<ToolResult> {
         self.validate(&args)?;
 
-        let path_str = validate_required_string!(args, "path");
+        let path_str: &str = &input_path(validate_required_string!(args, "path"), self.expand_env);
         let path = Path::new(path_str);
 
+        let offset = optional_index(&args, "offset")?;
+        let length = optional_index(&args, "length")?;
+        let start_line = optional_index(&args, "start_line")?;
+        let end_line = optional_index(&args, "end_line")?;
+
+        if offset.is_some() || length.is_some() {
+            tracing::info!("Reading byte range of file: {}", path_str);
+            return self
+                .read_byte_range(path, offset.unwrap_or(0), length)
+                .await;
+        }
+        if start_line.is_some() || end_line.is_some() {
+            tracing::info!("Reading line range of file: {}", path_str);
+            return self
+                .read_line_range(path, start_line.unwrap_or(1), end_line)
+                .await;
+        }
+
         tracing::info!("Reading file: {}", path_str);
 
         // Check file exists
         if !path.exists() {
-            return Ok(ToolResult::failure(format!(
-                "File does not exist: {}",
-                path_str
-            )));
+            return Ok(ToolResult::failure_with_kind(
+                ToolErrorKind::NotFound,
+                format!("File does not exist: {}", path_str),
+            ));
         }
 
         // Check file size
@@ -105,10 +345,13 @@ impl Tool for ReadFileTool {
             Ok(metadata) => {
                 let size = metadata.len() as usize;
                 if size > self.max_size_bytes {
-                    return Ok(ToolResult::failure(format!(
-                        "File too large: {} bytes (max: {} bytes)",
-                        size, self.max_size_bytes
-                    )));
+                    return Ok(ToolResult::failure_with_kind(
+                        ToolErrorKind::Validation,
+                        format!(
+                            "File too large: {} bytes (max: {} bytes)",
+                            size, self.max_size_bytes
+                        ),
+                    ));
                 }
             }
             Err(e) => {
@@ -127,9 +370,80 @@ impl Tool for ReadFileTool {
     }
 }
 
+impl ReadFileTool {
+    /// Read up to `length` bytes starting at `offset`, bounded by the size budget
+    async fn read_byte_range(
+        &self,
+        path: &Path,
+        offset: u64,
+        length: Option<u64>,
+    ) -> Result<ToolResult> {
+        let length = length.unwrap_or(self.max_size_bytes as u64);
+
+        let mut file = match fs::File::open(path).await {
+            Ok(file) => file,
+            Err(e) => return tool_result!(failure: format!("Failed to open file: {}", e)),
+        };
+        if let Err(e) = file.seek(std::io::SeekFrom::Start(offset)).await {
+            return tool_result!(failure: format!("Failed to seek to offset {}: {}", offset, e));
+        }
+
+        let mut buffer = Vec::new();
+        match file.take(length).read_to_end(&mut buffer).await {
+            Ok(_) => tool_result!(success: String::from_utf8_lossy(&buffer).into_owned()),
+            Err(e) => tool_result!(failure: format!("Failed to read file: {}", e)),
+        }
+    }
+
+    /// Read lines `start_line..=end_line` (1-based), bounded by the size budget
+    async fn read_line_range(
+        &self,
+        path: &Path,
+        start_line: u64,
+        end_line: Option<u64>,
+    ) -> Result<ToolResult> {
+        let file = match fs::File::open(path).await {
+            Ok(file) => file,
+            Err(e) => return tool_result!(failure: format!("Failed to open file: {}", e)),
+        };
+
+        let mut lines = BufReader::new(file).lines();
+        let mut output = String::new();
+        let mut line_number = 0u64;
+
+        loop {
+            let line = match lines.next_line().await {
+                Ok(Some(line)) => line,
+                Ok(None) => break,
+                Err(e) => return tool_result!(failure: format!("Failed to read file: {}", e)),
+            };
+            line_number += 1;
+
+            if line_number < start_line {
+                continue;
+            }
+            if end_line.is_some_and(|end| line_number > end) {
+                break;
+            }
+
+            if output.len() + line.len() + 1 > self.max_size_bytes {
+                return tool_result!(failure: format!(
+                    "Line range too large: exceeds {} bytes at line {}",
+                    self.max_size_bytes, line_number
+                ));
+            }
+            output.push_str(&line);
+            output.push('\n');
+        }
+
+        tool_result!(success: output)
+    }
+}
+
 /// Write file tool
 pub struct WriteFileTool {
     allowed_paths: Option<Vec<PathBuf>>,
+    expand_env: bool,
     max_size_bytes: usize,
 }
 
@@ -137,6 +451,7 @@ impl WriteFileTool {
     pub fn new(max_size_bytes: usize) -> Self {
         Self {
             allowed_paths: None,
+            expand_env: false,
             max_size_bytes,
         }
     }
@@ -146,19 +461,10 @@ impl WriteFileTool {
         self
     }
 
-    fn is_path_allowed(&self, path: &Path) -> bool {
-        if let Some(ref allowed) = self.allowed_paths {
-            allowed.iter().any(|allowed_path| {
-                path.starts_with(allowed_path)
-                    || path
-                        .parent()
-                        .and_then(|p| p.canonicalize().ok())
-                        .map(|p| p.starts_with(allowed_path))
-                        .unwrap_or(false)
-            })
-        } else {
-            true
-        }
+    /// Apply [`expand_env_vars`] to the path argument
+    pub fn with_expand_env(mut self, enabled: bool) -> Self {
+        self.expand_env = enabled;
+        self
     }
 }
 
@@ -186,7 +492,7 @@ impl Tool for WriteFileTool {
     }
 
     fn validate(&self, args: &Value) -> Result<()> {
-        let path_str = validate_required_string!(args, "path");
+        let path_str: &str = &input_path(validate_required_string!(args, "path"), self.expand_env);
         let content = validate_required_string!(args, "content");
 
         if path_str.is_empty() {
@@ -202,7 +508,7 @@ impl Tool for WriteFileTool {
         }
 
         let path = Path::new(path_str);
-        if !self.is_path_allowed(path) {
+        if !is_path_allowed(&self.allowed_paths, path) {
             return Err(anyhow::anyhow!(
                 "Access to path '{}' is not allowed",
                 path_str
@@ -212,10 +518,14 @@ impl Tool for WriteFileTool {
         Ok(())
     }
 
+    fn check_sandbox(&self, args: &Value) -> Result<()> {
+        check_sandbox_path(&self.allowed_paths, args, "path", self.expand_env)
+    }
+
     async fn execute(&self, args: Value) -> Result<ToolResult> {
         self.validate(&args)?;
 
-        let path_str = validate_required_string!(args, "path");
+        let path_str: &str = &input_path(validate_required_string!(args, "path"), self.expand_env);
         let content = validate_required_string!(args, "content");
         let path = Path::new(path_str);
 
@@ -234,10 +544,16 @@ impl Tool for WriteFileTool {
         }
 
         // Write file
+        let undo = FileUndo::capture(path).await;
         match fs::write(path, content).await {
-            Ok(_) => {
-                tool_result!(success: format!("Successfully wrote {} bytes to {}", content.len(), path_str))
-            }
+            Ok(_) => Ok(FileUndo::attach(
+                undo,
+                ToolResult::success(format!(
+                    "Successfully wrote {} bytes to {}",
+                    content.len(),
+                    path_str
+                )),
+            )),
             Err(e) => tool_result!(failure: format!("Failed to write file: {}", e)),
         }
     }
@@ -246,6 +562,7 @@ impl Tool for WriteFileTool {
 /// Append to file tool
 pub struct AppendFileTool {
     allowed_paths: Option<Vec<PathBuf>>,
+    expand_env: bool,
     max_size_bytes: usize,
 }
 
@@ -253,6 +570,7 @@ impl AppendFileTool {
     pub fn new(max_size_bytes: usize) -> Self {
         Self {
             allowed_paths: None,
+            expand_env: false,
             max_size_bytes,
         }
     }
@@ -262,19 +580,10 @@ impl AppendFileTool {
         self
     }
 
-    fn is_path_allowed(&self, path: &Path) -> bool {
-        if let Some(ref allowed) = self.allowed_paths {
-            allowed.iter().any(|allowed_path| {
-                path.starts_with(allowed_path)
-                    || path
-                        .parent()
-                        .and_then(|p| p.canonicalize().ok())
-                        .map(|p| p.starts_with(allowed_path))
-                        .unwrap_or(false)
-            })
-        } else {
-            true
-        }
+    /// Apply [`expand_env_vars`] to the path argument
+    pub fn with_expand_env(mut self, enabled: bool) -> Self {
+        self.expand_env = enabled;
+        self
     }
 }
 
@@ -302,7 +611,7 @@ impl Tool for AppendFileTool {
     }
 
     fn validate(&self, args: &Value) -> Result<()> {
-        let path_str = validate_required_string!(args, "path");
+        let path_str: &str = &input_path(validate_required_string!(args, "path"), self.expand_env);
         let content = validate_required_string!(args, "content");
 
         if path_str.is_empty() {
@@ -318,7 +627,7 @@ impl Tool for AppendFileTool {
         }
 
         let path = Path::new(path_str);
-        if !self.is_path_allowed(path) {
+        if !is_path_allowed(&self.allowed_paths, path) {
             return Err(anyhow::anyhow!(
                 "Access to path '{}' is not allowed",
                 path_str
@@ -328,10 +637,14 @@ impl Tool for AppendFileTool {
         Ok(())
     }
 
+    fn check_sandbox(&self, args: &Value) -> Result<()> {
+        check_sandbox_path(&self.allowed_paths, args, "path", self.expand_env)
+    }
+
     async fn execute(&self, args: Value) -> Result<ToolResult> {
         self.validate(&args)?;
 
-        let path_str = validate_required_string!(args, "path");
+        let path_str: &str = &input_path(validate_required_string!(args, "path"), self.expand_env);
         let content = validate_required_string!(args, "content");
         let path = Path::new(path_str);
 
@@ -351,6 +664,7 @@ impl Tool for AppendFileTool {
 
         // Append to file using OpenOptions
         use tokio::io::AsyncWriteExt;
+        let undo = FileUndo::capture(path).await;
         let result = tokio::fs::OpenOptions::new()
             .create(true)
             .append(true)
@@ -359,10 +673,13 @@ impl Tool for AppendFileTool {
 
         match result {
             Ok(mut file) => match file.write_all(content.as_bytes()).await {
-                Ok(_) => tool_result!(success: format!(
-                    "Successfully appended {} bytes to {}",
-                    content.len(),
-                    path_str
+                Ok(_) => Ok(FileUndo::attach(
+                    undo,
+                    ToolResult::success(format!(
+                        "Successfully appended {} bytes to {}",
+                        content.len(),
+                        path_str
+                    )),
                 )),
                 Err(e) => tool_result!(failure: format!("Failed to write to file: {}", e)),
             },
@@ -371,6 +688,327 @@ impl Tool for AppendFileTool {
     }
 }
 
+/// Edit file tool
+///
+/// Applies exact find/replace edits instead of rewriting the whole file.
+/// Every `find` text must occur exactly once; otherwise nothing is written.
+pub struct EditFileTool {
+    allowed_paths: Option<Vec<PathBuf>>,
+    expand_env: bool,
+    max_size_bytes: usize,
+}
+
+impl EditFileTool {
+    pub fn new(max_size_bytes: usize) -> Self {
+        Self {
+            allowed_paths: None,
+            expand_env: false,
+            max_size_bytes,
+        }
+    }
+
+    pub fn with_allowed_paths(mut self, paths: Vec<PathBuf>) -> Self {
+        self.allowed_paths = Some(paths);
+        self
+    }
+
+    /// Apply [`expand_env_vars`] to the path argument
+    pub fn with_expand_env(mut self, enabled: bool) -> Self {
+        self.expand_env = enabled;
+        self
+    }
+
+    /// Apply edits in order, failing on the first missing or ambiguous match
+    fn apply_edits(content: &str, edits: &[Value]) -> std::result::Result<String, String> {
+        let mut content = content.to_string();
+        for (index, edit) in edits.iter().enumerate() {
+            let find = edit["find"].as_str().unwrap_or_default();
+            let replace = edit["replace"].as_str().unwrap_or_default();
+
+            match content.matches(find).count() {
+                1 => content = content.replacen(find, replace, 1),
+                0 => {
+                    return Err(format!(
+                        "Edit {}: find text not found: {:?}",
+                        index + 1,
+                        find
+                    ))
+                }
+                n => {
+                    return Err(format!(
+                        "Edit {}: find text is ambiguous ({} matches), include more context: {:?}",
+                        index + 1,
+                        n,
+                        find
+                    ))
+                }
+            }
+        }
+        Ok(content)
+    }
+}
+
+#[async_trait]
+impl Tool for EditFileTool {
+    fn metadata(&self) -> ToolMetadata {
+        tool_metadata! {
+            name: "edit_file",
+            description: "Edit an existing file by replacing exact text. Each edit's 'find' text must appear exactly once in the file; \
+                          if any edit fails, the file is left unchanged. Returns the edited content.",
+            parameters: [
+                {
+                    name: "path",
+                    type: "string",
+                    description: "The file path to edit",
+                    required: true
+                },
+                {
+                    name: "edits",
+                    type: "array",
+                    description: "List of {\"find\": \"exact text\", \"replace\": \"new text\"} operations applied in order",
+                    required: true
+                }
+            ]
+        }
+    }
+
+    fn validate(&self, args: &Value) -> Result<()> {
+        let path_str: &str = &input_path(validate_required_string!(args, "path"), self.expand_env);
+
+        if path_str.is_empty() {
+            return Err(anyhow::anyhow!("Path cannot be empty"));
+        }
+
+        let edits = args["edits"]
+            .as_array()
+            .ok_or_else(|| anyhow::anyhow!("'edits' parameter is required and must be an array"))?;
+        if edits.is_empty() {
+            return Err(anyhow::anyhow!("'edits' must contain at least one edit"));
+        }
+        for (index, edit) in edits.iter().enumerate() {
+            let find = edit["find"].as_str();
+            if matches!(find, None | Some("")) || edit["replace"].as_str().is_none() {
+                return Err(anyhow::anyhow!(
+                    "Edit {} needs a non-empty 'find' string and a 'replace' string",
+                    index + 1
+                ));
+            }
+        }
+
+        let path = Path::new(path_str);
+        if !is_path_allowed(&self.allowed_paths, path) {
+            return Err(anyhow::anyhow!(
+                "Access to path '{}' is not allowed",
+                path_str
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn check_sandbox(&self, args: &Value) -> Result<()> {
+        check_sandbox_path(&self.allowed_paths, args, "path", self.expand_env)
+    }
+
+    async fn execute(&self, args: Value) -> Result<ToolResult> {
+        self.validate(&args)?;
+
+        let path_str: &str = &input_path(validate_required_string!(args, "path"), self.expand_env);
+        let path = Path::new(path_str);
+        let edits = args["edits"].as_array().cloned().unwrap_or_default();
+
+        tracing::info!("Editing file: {} ({} edits)", path_str, edits.len());
+
+        let original = match fs::metadata(path).await {
+            Ok(metadata) if metadata.len() as usize > self.max_size_bytes => {
+                return tool_result!(failure: format!(
+                    "File too large: {} bytes (max: {} bytes)",
+                    metadata.len(),
+                    self.max_size_bytes
+                ));
+            }
+            Ok(_) => match fs::read_to_string(path).await {
+                Ok(contents) => contents,
+                Err(e) => return tool_result!(failure: format!("Failed to read file: {}", e)),
+            },
+            Err(e) => {
+                return tool_result!(failure: format!("Failed to read file {}: {}", path_str, e))
+            }
+        };
+
+        let edited = match Self::apply_edits(&original, &edits) {
+            Ok(edited) => edited,
+            Err(conflict) => return tool_result!(failure: conflict),
+        };
+
+        if edited.len() > self.max_size_bytes {
+            return tool_result!(failure: format!(
+                "Edited content too large: {} bytes (max: {} bytes)",
+                edited.len(),
+                self.max_size_bytes
+            ));
+        }
+
+        let undo = FileUndo {
+            path: path.to_path_buf(),
+            previous_contents: Some(original),
+        };
+        match fs::write(path, &edited).await {
+            Ok(_) => Ok(FileUndo::attach(Some(undo), ToolResult::success(edited))),
+            Err(e) => tool_result!(failure: format!("Failed to write file: {}", e)),
+        }
+    }
+}
+
+/// Find files tool
+///
+/// Matches a glob pattern relative to a root directory, so agents can locate
+/// files without falling back to shell commands.
+pub struct FindFilesTool {
+    allowed_paths: Option<Vec<PathBuf>>,
+    expand_env: bool,
+    max_results: usize,
+}
+
+impl FindFilesTool {
+    pub fn new(max_results: usize) -> Self {
+        Self {
+            allowed_paths: None,
+            expand_env: false,
+            max_results,
+        }
+    }
+
+    pub fn with_allowed_paths(mut self, paths: Vec<PathBuf>) -> Self {
+        self.allowed_paths = Some(paths);
+        self
+    }
+
+    /// Apply [`expand_env_vars`] to the path argument
+    pub fn with_expand_env(mut self, enabled: bool) -> Self {
+        self.expand_env = enabled;
+        self
+    }
+}
+
+#[async_trait]
+impl Tool for FindFilesTool {
+    fn metadata(&self) -> ToolMetadata {
+        tool_metadata! {
+            name: "find_files",
+            description: "Find files under a directory whose paths match a glob pattern (e.g. '**/*.rs'). Returns one path per line.",
+            parameters: [
+                {
+                    name: "root",
+                    type: "string",
+                    description: "The directory to search in",
+                    required: true
+                },
+                {
+                    name: "pattern",
+                    type: "string",
+                    description: "Glob pattern relative to root, e.g. '*.txt' or '**/*.rs'",
+                    required: true
+                },
+                {
+                    name: "max_results",
+                    type: "number",
+                    description: "Maximum number of paths to return",
+                    required: false
+                }
+            ]
+        }
+    }
+
+    fn validate(&self, args: &Value) -> Result<()> {
+        let root: &str = &input_path(validate_required_string!(args, "root"), self.expand_env);
+        let pattern = validate_required_string!(args, "pattern");
+
+        if root.is_empty() || pattern.is_empty() {
+            return Err(anyhow::anyhow!("Root and pattern cannot be empty"));
+        }
+
+        let pattern_path = Path::new(pattern);
+        if pattern_path.is_absolute()
+            || pattern_path
+                .components()
+                .any(|c| matches!(c, std::path::Component::ParentDir))
+        {
+            return Err(anyhow::anyhow!(
+                "Pattern '{}' must be relative to root and cannot contain '..'",
+                pattern
+            ));
+        }
+
+        if !is_path_allowed(&self.allowed_paths, Path::new(root)) {
+            return Err(anyhow::anyhow!("Access to path '{}' is not allowed", root));
+        }
+
+        glob::Pattern::new(pattern)
+            .map_err(|e| anyhow::anyhow!("Invalid glob pattern '{}': {}", pattern, e))?;
+
+        Ok(())
+    }
+
+    fn check_sandbox(&self, args: &Value) -> Result<()> {
+        check_sandbox_path(&self.allowed_paths, args, "root", self.expand_env)
+    }
+
+    async fn execute(&self, args: Value) -> Result<ToolResult> {
+        self.validate(&args)?;
+
+        let root: &str = &input_path(validate_required_string!(args, "root"), self.expand_env);
+        let pattern = validate_required_string!(args, "pattern");
+        let max_results = optional_index(&args, "max_results")?
+            .map(|n| (n as usize).min(self.max_results))
+            .unwrap_or(self.max_results);
+
+        if !Path::new(root).is_dir() {
+            return Ok(ToolResult::failure_with_kind(
+                ToolErrorKind::NotFound,
+                format!("Directory does not exist: {}", root),
+            ));
+        }
+
+        tracing::info!("Finding files in {} matching {}", root, pattern);
+
+        // Escaped so brackets or wildcards in the directory name match literally
+        let full_pattern = Path::new(&glob::Pattern::escape(root)).join(pattern);
+        let entries = match glob::glob(&full_pattern.to_string_lossy()) {
+            Ok(entries) => entries,
+            Err(e) => {
+                return Ok(ToolResult::failure_with_kind(
+                    ToolErrorKind::Validation,
+                    format!("Invalid glob pattern: {}", e),
+                ))
+            }
+        };
+
+        let mut matches = Vec::new();
+        let mut truncated = false;
+        for path in entries.flatten() {
+            if !path.is_file() {
+                continue;
+            }
+            if matches.len() == max_results {
+                truncated = true;
+                break;
+            }
+            matches.push(path.to_string_lossy().into_owned());
+        }
+
+        if matches.is_empty() {
+            return tool_result!(success: format!("No files match '{}' in {}", pattern, root));
+        }
+
+        let mut output = matches.join("\n");
+        if truncated {
+            output.push_str(&format!("\n...[results limited to {} paths]", max_results));
+        }
+        tool_result!(success: output)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -409,6 +1047,30 @@ mod tests {
         assert_eq!(contents, "Test content");
     }
 
+    #[tokio::test]
+    async fn test_write_file_records_what_it_replaced() {
+        let dir = tempdir().unwrap();
+        let existing = dir.path().join("existing.txt");
+        let created = dir.path().join("created.txt");
+        fs::write(&existing, "before").await.unwrap();
+
+        let tool = WriteFileTool::new(1024);
+        let mut undos = Vec::new();
+        for path in [&existing, &created] {
+            let args = json!({"path": path.to_str().unwrap(), "content": "after"});
+            let result = tool.execute(args).await.unwrap();
+            undos.push(FileUndo::from_result(&result).unwrap());
+        }
+        assert_eq!(undos[0].previous_contents.as_deref(), Some("before"));
+        assert_eq!(undos[1].previous_contents, None);
+
+        for undo in &undos {
+            undo.restore().await.unwrap();
+        }
+        assert_eq!(fs::read_to_string(&existing).await.unwrap(), "before");
+        assert!(!created.exists());
+    }
+
     #[tokio::test]
     async fn test_file_size_limit() {
         let tool = ReadFileTool::new(10); // 10 bytes max
@@ -480,4 +1142,167 @@ mod tests {
         let contents = fs::read_to_string(&file_path).await.unwrap();
         assert_eq!(contents, "Created by append\n");
     }
+
+    #[tokio::test]
+    async fn test_read_line_range() {
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("log.txt");
+        let contents: String = (1..=100).map(|i| format!("line {}\n", i)).collect();
+        fs::write(&file_path, &contents).await.unwrap();
+
+        // The whole file exceeds the budget, but a few lines fit
+        let tool = ReadFileTool::new(64);
+        let args = json!({
+            "path": file_path.to_str().unwrap(),
+            "start_line": 98,
+            "end_line": 100
+        });
+
+        let result = tool.execute(args).await.unwrap();
+        assert!(result.success);
+        assert_eq!(result.output, "line 98\nline 99\nline 100\n");
+    }
+
+    #[tokio::test]
+    async fn test_read_byte_range() {
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("data.bin");
+        fs::write(&file_path, "0123456789abcdefghij").await.unwrap();
+
+        let tool = ReadFileTool::new(8);
+        let args = json!({"path": file_path.to_str().unwrap(), "offset": 10, "length": 6});
+
+        let result = tool.execute(args).await.unwrap();
+        assert!(result.success);
+        assert_eq!(result.output, "abcdef");
+
+        let too_long = json!({"path": file_path.to_str().unwrap(), "offset": 0, "length": 9});
+        assert!(tool.execute(too_long).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_find_files_matches_glob() {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("nested")).await.unwrap();
+        fs::write(dir.path().join("a.txt"), "a").await.unwrap();
+        fs::write(dir.path().join("b.log"), "b").await.unwrap();
+        fs::write(dir.path().join("nested/c.txt"), "c")
+            .await
+            .unwrap();
+
+        let tool = FindFilesTool::new(100).with_allowed_paths(vec![dir.path().to_path_buf()]);
+        let root = dir.path().to_str().unwrap();
+
+        let result = tool
+            .execute(json!({"root": root, "pattern": "*.txt"}))
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert_eq!(result.output.lines().count(), 1);
+        assert!(result.output.ends_with("a.txt"));
+
+        let result = tool
+            .execute(json!({"root": root, "pattern": "**/*.txt", "max_results": 1}))
+            .await
+            .unwrap();
+        assert!(result.output.contains("results limited to 1 paths"));
+    }
+
+    #[tokio::test]
+    async fn test_find_files_treats_root_literally() {
+        let dir = tempdir().unwrap();
+        let root = dir.path().join("reports[2024]");
+        fs::create_dir_all(&root).await.unwrap();
+        fs::write(root.join("q1.txt"), "q1").await.unwrap();
+
+        let tool = FindFilesTool::new(100).with_allowed_paths(vec![dir.path().to_path_buf()]);
+        let result = tool
+            .execute(json!({"root": root.to_str().unwrap(), "pattern": "*.txt"}))
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert!(result.output.ends_with("q1.txt"), "{}", result.output);
+    }
+
+    #[tokio::test]
+    async fn test_find_files_enforces_sandbox() {
+        let allowed = tempdir().unwrap();
+        let other = tempdir().unwrap();
+        let tool = FindFilesTool::new(100).with_allowed_paths(vec![allowed.path().to_path_buf()]);
+
+        let outside = json!({"root": other.path().to_str().unwrap(), "pattern": "*"});
+        assert!(tool.execute(outside).await.is_err());
+
+        let escape = json!({"root": allowed.path().to_str().unwrap(), "pattern": "../*"});
+        assert!(tool.execute(escape).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_edit_file_single_replacement() {
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("main.rs");
+        fs::write(&file_path, "fn main() {\n    println!(\"hi\");\n}\n")
+            .await
+            .unwrap();
+
+        let tool = EditFileTool::new(1024);
+        let args = json!({
+            "path": file_path.to_str().unwrap(),
+            "edits": [{"find": "\"hi\"", "replace": "\"hello\""}]
+        });
+
+        let result = tool.execute(args).await.unwrap();
+        assert!(result.success);
+        let expected = "fn main() {\n    println!(\"hello\");\n}\n";
+        assert_eq!(result.output, expected);
+        assert_eq!(fs::read_to_string(&file_path).await.unwrap(), expected);
+    }
+
+    #[tokio::test]
+    async fn test_edit_file_rejects_ambiguous_match() {
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("config.toml");
+        let original = "a = 1\nb = 1\nc = 0\n";
+        fs::write(&file_path, original).await.unwrap();
+
+        let tool = EditFileTool::new(1024);
+        let args = json!({
+            "path": file_path.to_str().unwrap(),
+            "edits": [
+                {"find": "c = 0", "replace": "c = 2"},
+                {"find": "= 1", "replace": "= 3"}
+            ]
+        });
+
+        let result = tool.execute(args).await.unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("ambiguous"));
+        assert_eq!(fs::read_to_string(&file_path).await.unwrap(), original);
+    }
+
+    #[tokio::test]
+    async fn test_env_vars_in_paths_expand_only_when_enabled() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("notes.txt"), "expanded")
+            .await
+            .unwrap();
+        std::env::set_var("ACTORUS_TEST_READ_DIR", dir.path());
+
+        let args = json!({"path": "$ACTORUS_TEST_READ_DIR/notes.txt"});
+
+        let literal = ReadFileTool::new(1024).execute(args.clone()).await.unwrap();
+        assert!(!literal.success);
+        assert!(literal
+            .error
+            .unwrap()
+            .contains("$ACTORUS_TEST_READ_DIR/notes.txt"));
+
+        let expanded = ReadFileTool::new(1024)
+            .with_expand_env(true)
+            .execute(args)
+            .await
+            .unwrap();
+        assert!(expanded.success);
+        assert_eq!(expanded.output, "expanded");
+    }
 }
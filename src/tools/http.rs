@@ -4,12 +4,19 @@
 //! - HTTP client implementation details hidden
 //! - Request/response handling abstracted
 //! - Error handling and retries hidden
+//! - Downloads streamed straight to disk, so file bytes never reach the LLM
 
-use super::{Tool, ToolMetadata, ToolParameter, ToolResult};
+use super::{Tool, ToolErrorKind, ToolMetadata, ToolParameter, ToolResult};
+use crate::{tool_metadata, validate_required_string};
 use anyhow::Result;
 use async_trait::async_trait;
-use reqwest::Client;
-use serde_json::Value;
+use futures::StreamExt;
+use reqwest::{Client, StatusCode};
+use serde_json::{json, Value};
+use std::path::{Component, Path, PathBuf};
+use tokio::fs;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::mpsc;
 use tokio::time::{timeout, Duration};
 
 /// HTTP request tool
@@ -55,18 +62,22 @@ impl Tool for HttpTool {
                     param_type: "string".to_string(),
                     description: "The URL to request".to_string(),
                     required: true,
+                    ..Default::default()
                 },
                 ToolParameter {
                     name: "method".to_string(),
                     param_type: "string".to_string(),
                     description: "HTTP method (GET or POST), default is GET".to_string(),
                     required: false,
+                    allowed_values: Some(vec!["GET".to_string(), "POST".to_string()]),
+                    ..Default::default()
                 },
                 ToolParameter {
                     name: "body".to_string(),
                     param_type: "string".to_string(),
                     description: "Request body for POST requests".to_string(),
                     required: false,
+                    ..Default::default()
                 },
             ],
         }
@@ -145,19 +156,317 @@ impl Tool for HttpTool {
                     )))
                 }
             }
-            Ok(Err(e)) => Ok(ToolResult::failure(format!("Request failed: {}", e))),
-            Err(_) => Ok(ToolResult::failure(format!(
-                "Request timed out after {} seconds",
-                self.timeout_secs
-            ))),
+            Ok(Err(e)) => Ok(ToolResult::failure_with_kind(
+                ToolErrorKind::Network,
+                format!("Request failed: {}", e),
+            )),
+            Err(_) => Ok(ToolResult::failure_with_kind(
+                ToolErrorKind::Timeout,
+                format!("Request timed out after {} seconds", self.timeout_secs),
+            )),
         }
     }
 }
 
+/// Bytes between the progress reports of a streamed download
+const DOWNLOAD_PROGRESS_STEP: u64 = 1024 * 1024;
+
+/// Download a URL into a sandbox directory
+///
+/// The body is written to disk as it arrives and only metadata is returned,
+/// so "download X and save it to Y" needs one call and no file content in
+/// the conversation.
+pub struct DownloadFileTool {
+    client: Client,
+    download_dir: PathBuf,
+    max_size_bytes: u64,
+    timeout_secs: u64,
+    allowed_domains: Option<Vec<String>>,
+}
+
+impl DownloadFileTool {
+    /// Save downloads under `download_dir`, refusing bodies over `max_size_bytes`
+    pub fn new(download_dir: impl Into<PathBuf>, max_size_bytes: u64) -> Self {
+        Self {
+            client: Client::new(),
+            download_dir: download_dir.into(),
+            max_size_bytes,
+            timeout_secs: 60,
+            allowed_domains: None,
+        }
+    }
+
+    pub fn with_timeout_secs(mut self, timeout_secs: u64) -> Self {
+        self.timeout_secs = timeout_secs;
+        self
+    }
+
+    pub fn with_allowed_domains(mut self, domains: Vec<String>) -> Self {
+        self.allowed_domains = Some(domains);
+        self
+    }
+
+    fn is_domain_allowed(&self, url: &str) -> bool {
+        if let Some(ref allowed) = self.allowed_domains {
+            allowed.iter().any(|domain| url.contains(domain))
+        } else {
+            true
+        }
+    }
+
+    /// Destination inside the download directory, or None if `path` would escape it
+    fn sandboxed_path(&self, path: &str) -> Option<PathBuf> {
+        let relative = Path::new(path);
+        let stays_inside = relative
+            .components()
+            .all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+        (stays_inside && relative.file_name().is_some()).then(|| self.download_dir.join(relative))
+    }
+
+    /// Failure for a body of `size` (e.g. "2048 bytes") over the cap
+    fn too_large(&self, size: String) -> ToolResult {
+        ToolResult::failure_with_kind(
+            ToolErrorKind::Validation,
+            format!(
+                "Download too large: {} (max: {} bytes)",
+                size, self.max_size_bytes
+            ),
+        )
+    }
+
+    /// Stream the response body into `partial`, then move it to `destination`
+    ///
+    /// `progress` hears the byte count after every `DOWNLOAD_PROGRESS_STEP`
+    /// and once the body is complete.
+    async fn save(
+        &self,
+        response: reqwest::Response,
+        partial: &mut PartialFile,
+        destination: &Path,
+        progress: Option<&mpsc::Sender<String>>,
+    ) -> Result<ToolResult, DownloadError> {
+        if let Some(length) = response.content_length() {
+            if length > self.max_size_bytes {
+                return Ok(self.too_large(format!("{} bytes", length)));
+            }
+        }
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+
+        if let Some(parent) = destination.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        let mut file = fs::File::create(&partial.path).await?;
+        let total = response.content_length();
+        let mut written: u64 = 0;
+        let mut body = response.bytes_stream();
+        let report = |written: u64| async move {
+            if let Some(progress) = progress {
+                let message = match total {
+                    Some(total) => format!("Downloaded {} of {} bytes", written, total),
+                    None => format!("Downloaded {} bytes", written),
+                };
+                let _ = progress.send(message).await;
+            }
+        };
+
+        let mut reported = None;
+        while let Some(chunk) = body.next().await {
+            let chunk = chunk?;
+            written += chunk.len() as u64;
+            if written > self.max_size_bytes {
+                return Ok(self.too_large(format!("at least {} bytes", written)));
+            }
+            file.write_all(&chunk).await?;
+            if written - reported.unwrap_or(0) >= DOWNLOAD_PROGRESS_STEP {
+                report(written).await;
+                reported = Some(written);
+            }
+        }
+        file.flush().await?;
+        if reported != Some(written) {
+            report(written).await;
+        }
+        drop(file);
+        partial.persist(destination).await?;
+
+        Ok(ToolResult::success(
+            json!({
+                "path": destination.display().to_string(),
+                "bytes": written,
+                "content_type": content_type,
+            })
+            .to_string(),
+        ))
+    }
+
+    /// Validate and run the download, reporting progress if asked to
+    async fn download(
+        &self,
+        args: Value,
+        progress: Option<&mpsc::Sender<String>>,
+    ) -> Result<ToolResult> {
+        self.validate(&args)?;
+
+        let url = validate_required_string!(args, "url");
+        let path = validate_required_string!(args, "path");
+        let destination = self.sandboxed_path(path).unwrap();
+
+        tracing::info!("Downloading {} to {}", url, destination.display());
+
+        let mut partial = PartialFile::for_destination(&destination);
+        let download = async {
+            let response = self.client.get(url).send().await?;
+            let status = response.status();
+            if !status.is_success() {
+                let kind = if status == StatusCode::NOT_FOUND {
+                    ToolErrorKind::NotFound
+                } else {
+                    ToolErrorKind::Network
+                };
+                return Ok(ToolResult::failure_with_kind(
+                    kind,
+                    format!("HTTP error: {}", status),
+                ));
+            }
+            self.save(response, &mut partial, &destination, progress)
+                .await
+        };
+
+        match timeout(Duration::from_secs(self.timeout_secs), download).await {
+            Ok(Ok(result)) => Ok(result),
+            Ok(Err(e)) => Ok(e.into_result()),
+            Err(_) => Ok(ToolResult::failure_with_kind(
+                ToolErrorKind::Timeout,
+                format!("Download timed out after {} seconds", self.timeout_secs),
+            )),
+        }
+    }
+}
+
+/// Why a download stopped before the file was in place
+#[derive(Debug, thiserror::Error)]
+enum DownloadError {
+    #[error("Download failed: {0}")]
+    Network(#[from] reqwest::Error),
+    #[error("Failed to save download: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+impl DownloadError {
+    fn into_result(self) -> ToolResult {
+        let kind = match &self {
+            DownloadError::Network(_) => ToolErrorKind::Network,
+            DownloadError::Io(e) => ToolErrorKind::from_io(e),
+        };
+        ToolResult::failure_with_kind(kind, self.to_string())
+    }
+}
+
+/// Temporary file a download is streamed into
+///
+/// Removed when dropped unless `persist` moved it into place, so errors,
+/// size overruns and timeouts all leave nothing behind.
+struct PartialFile {
+    path: PathBuf,
+    persisted: bool,
+}
+
+impl PartialFile {
+    fn for_destination(destination: &Path) -> Self {
+        Self {
+            path: partial_path(destination),
+            persisted: false,
+        }
+    }
+
+    async fn persist(&mut self, destination: &Path) -> std::io::Result<()> {
+        fs::rename(&self.path, destination).await?;
+        self.persisted = true;
+        Ok(())
+    }
+}
+
+impl Drop for PartialFile {
+    fn drop(&mut self) {
+        if !self.persisted {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// Temporary file a download to `destination` is streamed into
+///
+/// `.part` is appended to the whole file name so `x.csv` and `x.pdf` never
+/// share one.
+fn partial_path(destination: &Path) -> PathBuf {
+    let mut name = destination.file_name().unwrap_or_default().to_os_string();
+    name.push(".part");
+    destination.with_file_name(name)
+}
+
+#[async_trait]
+impl Tool for DownloadFileTool {
+    fn metadata(&self) -> ToolMetadata {
+        tool_metadata! {
+            name: "download_file",
+            description: "Download a URL and save it to a file without reading its content. Returns the saved path, size in bytes and content type.",
+            parameters: [
+                {
+                    name: "url",
+                    type: "string",
+                    description: "The URL to download",
+                    required: true
+                },
+                {
+                    name: "path",
+                    type: "string",
+                    description: "Relative path to save the file under, e.g. 'reports/q3.pdf'",
+                    required: true
+                }
+            ]
+        }
+    }
+
+    fn validate(&self, args: &Value) -> Result<()> {
+        let url = validate_required_string!(args, "url");
+        let path = validate_required_string!(args, "path");
+
+        if !self.is_domain_allowed(url) {
+            return Err(anyhow::anyhow!(
+                "Access to domain in '{}' is not allowed",
+                url
+            ));
+        }
+        if self.sandboxed_path(path).is_none() {
+            return Err(anyhow::anyhow!(
+                "Path '{}' is not allowed: use a relative file path without '..'",
+                path
+            ));
+        }
+        Ok(())
+    }
+
+    async fn execute(&self, args: Value) -> Result<ToolResult> {
+        self.download(args, None).await
+    }
+
+    /// Sends the number of bytes received as the download proceeds
+    async fn execute_streaming(
+        &self,
+        args: Value,
+        output: mpsc::Sender<String>,
+    ) -> Result<ToolResult> {
+        self.download(args, Some(&output)).await
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use serde_json::json;
 
     #[tokio::test]
     async fn test_http_get_request() {
@@ -208,4 +517,149 @@ mod tests {
         assert!(!metadata.description.is_empty());
         assert!(!metadata.parameters.is_empty());
     }
+
+    #[tokio::test]
+    async fn test_download_streams_to_sandboxed_file() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/report.csv"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw("a,b\n1,2\n", "text/csv"))
+            .mount(&mock_server)
+            .await;
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let tool = DownloadFileTool::new(temp_dir.path(), 1024);
+        let url = format!("{}/report.csv", mock_server.uri());
+
+        let result = tool
+            .execute(json!({"url": url, "path": "data/report.csv"}))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        let metadata: Value = serde_json::from_str(&result.output).unwrap();
+        assert_eq!(metadata["bytes"], 8);
+        assert_eq!(metadata["content_type"], "text/csv");
+        let saved = temp_dir.path().join("data/report.csv");
+        assert_eq!(metadata["path"], saved.display().to_string());
+        assert_eq!(std::fs::read_to_string(saved).unwrap(), "a,b\n1,2\n");
+
+        assert!(tool
+            .validate(&json!({"url": url, "path": "../escape.csv"}))
+            .is_err());
+        assert!(tool
+            .validate(&json!({"url": url, "path": "/etc/passwd"}))
+            .is_err());
+    }
+
+    #[test]
+    fn test_partial_path_keeps_the_full_file_name() {
+        assert_eq!(
+            partial_path(Path::new("out/x.csv")),
+            Path::new("out/x.csv.part")
+        );
+        assert_ne!(
+            partial_path(Path::new("x.csv")),
+            partial_path(Path::new("x.pdf"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_streamed_download_reports_bytes_received() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(vec![0u8; 2048]))
+            .mount(&mock_server)
+            .await;
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let tool = DownloadFileTool::new(temp_dir.path(), 4096);
+        let (tx, mut rx) = mpsc::channel(8);
+        let args = json!({"url": mock_server.uri(), "path": "blob.bin"});
+
+        let result = tool.execute_streaming(args, tx).await.unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(rx.recv().await.unwrap(), "Downloaded 2048 of 2048 bytes");
+        assert!(rx.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_download_over_size_cap_fails_without_leaving_a_file() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(vec![0u8; 2048]))
+            .mount(&mock_server)
+            .await;
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let tool = DownloadFileTool::new(temp_dir.path(), 1000);
+
+        let result = tool
+            .execute(json!({"url": mock_server.uri(), "path": "big.bin"}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert_eq!(result.error_kind, Some(ToolErrorKind::Validation));
+        assert_eq!(
+            result.error.as_deref(),
+            Some("Download too large: 2048 bytes (max: 1000 bytes)")
+        );
+        assert_eq!(std::fs::read_dir(temp_dir.path()).unwrap().count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_partial_file_is_removed_unless_persisted() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let destination = temp_dir.path().join("x.csv");
+
+        let partial = PartialFile::for_destination(&destination);
+        std::fs::write(&partial.path, "half").unwrap();
+        drop(partial);
+        assert_eq!(std::fs::read_dir(temp_dir.path()).unwrap().count(), 0);
+
+        let mut partial = PartialFile::for_destination(&destination);
+        std::fs::write(&partial.path, "whole").unwrap();
+        partial.persist(&destination).await.unwrap();
+        drop(partial);
+        assert_eq!(std::fs::read_to_string(&destination).unwrap(), "whole");
+    }
+
+    #[tokio::test]
+    async fn test_download_save_failure_is_not_retried_as_network_error() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("data"))
+            .mount(&mock_server)
+            .await;
+
+        // The download directory is a plain file, so nothing can be saved under it
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let not_a_dir = temp_dir.path().join("downloads");
+        std::fs::write(&not_a_dir, "").unwrap();
+        let tool = DownloadFileTool::new(&not_a_dir, 1024);
+
+        let result = tool
+            .execute(json!({"url": mock_server.uri(), "path": "sub/data.txt"}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result
+            .error
+            .as_deref()
+            .unwrap()
+            .starts_with("Failed to save download"));
+        assert_eq!(result.error_kind, Some(ToolErrorKind::Io));
+        assert!(!result.error_kind.unwrap().is_retryable());
+    }
 }
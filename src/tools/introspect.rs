@@ -0,0 +1,133 @@
+//! Introspection Tool - Lets an agent look up the roster of agents and tools
+//!
+//! Information Hiding:
+//! - Roster captured as plain profiles, so tools never depend on live agents
+//! - JSON layout of the report hidden from callers
+
+use super::{Tool, ToolErrorKind, ToolMetadata, ToolResult};
+use crate::{tool_metadata, tool_result};
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::Serialize;
+use serde_json::Value;
+
+/// What one agent is for and which tools it can use
+#[derive(Debug, Clone, Serialize)]
+pub struct AgentProfile {
+    pub name: String,
+    pub description: String,
+    pub tools: Vec<ToolMetadata>,
+}
+
+/// Report the registered agents and their tools as JSON
+///
+/// Gives a planning LLM more detail than the one-line agent descriptions
+/// in its prompt, e.g. which parameters each agent's tools accept.
+pub struct IntrospectTool {
+    agents: Vec<AgentProfile>,
+}
+
+impl IntrospectTool {
+    pub fn new(mut agents: Vec<AgentProfile>) -> Self {
+        // Sorted so the output is identical across runs
+        agents.sort_by(|a, b| a.name.cmp(&b.name));
+        for agent in &mut agents {
+            agent.tools.sort_by(|a, b| a.name.cmp(&b.name));
+        }
+        Self { agents }
+    }
+}
+
+#[async_trait]
+impl Tool for IntrospectTool {
+    fn metadata(&self) -> ToolMetadata {
+        tool_metadata! {
+            name: "list_agents",
+            description: "List the available agents with their descriptions and the tools each one can use. Pass an agent name to see only that agent.",
+            parameters: [
+                {
+                    name: "agent",
+                    type: "string",
+                    description: "Name of a single agent to describe",
+                    required: false
+                }
+            ]
+        }
+    }
+
+    async fn execute(&self, args: Value) -> Result<ToolResult> {
+        if !args["agent"].is_null() && !args["agent"].is_string() {
+            return Ok(ToolResult::failure_with_kind(
+                ToolErrorKind::Validation,
+                "'agent' must be a string",
+            ));
+        }
+
+        let selected: Vec<&AgentProfile> = match args["agent"].as_str().map(str::trim) {
+            Some(name) => self.agents.iter().filter(|a| a.name == name).collect(),
+            None => self.agents.iter().collect(),
+        };
+
+        if selected.is_empty() && !self.agents.is_empty() {
+            let known: Vec<&str> = self.agents.iter().map(|a| a.name.as_str()).collect();
+            return Ok(ToolResult::failure_with_kind(
+                ToolErrorKind::NotFound,
+                format!(
+                    "Unknown agent '{}'. Available agents: {}",
+                    args["agent"].as_str().unwrap_or_default(),
+                    known.join(", ")
+                ),
+            ));
+        }
+
+        tool_result!(success: serde_json::to_string_pretty(&selected)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn profile(name: &str, tools: &[&str]) -> AgentProfile {
+        AgentProfile {
+            name: name.to_string(),
+            description: format!("{} agent", name),
+            tools: tools
+                .iter()
+                .map(|tool| ToolMetadata {
+                    name: tool.to_string(),
+                    description: String::new(),
+                    parameters: vec![],
+                })
+                .collect(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_lists_configured_agents_and_their_tools() {
+        let tool = IntrospectTool::new(vec![
+            profile("web", &["http_get"]),
+            profile("files", &["write_file", "read_file"]),
+        ]);
+
+        let result = tool.execute(json!({})).await.unwrap();
+        let roster: Value = serde_json::from_str(&result.output).unwrap();
+        assert_eq!(roster[0]["name"], "files");
+        assert_eq!(roster[0]["description"], "files agent");
+        assert_eq!(roster[0]["tools"][0]["name"], "read_file");
+        assert_eq!(roster[1]["name"], "web");
+
+        let single = tool.execute(json!({"agent": "web"})).await.unwrap();
+        let roster: Value = serde_json::from_str(&single.output).unwrap();
+        assert_eq!(roster.as_array().unwrap().len(), 1);
+
+        let unknown = tool.execute(json!({"agent": "db"})).await.unwrap();
+        assert!(!unknown.success);
+        assert_eq!(unknown.error_kind, Some(ToolErrorKind::NotFound));
+        assert!(unknown.error.unwrap().contains("files, web"));
+
+        let invalid = tool.execute(json!({"agent": 3})).await.unwrap();
+        assert_eq!(invalid.error_kind, Some(ToolErrorKind::Validation));
+    }
+}
@@ -0,0 +1,242 @@
+//! JSON Extraction Tool - Trims large JSON outputs down to the part that matters
+//!
+//! Information Hiding:
+//! - Path syntax (JSON Pointer or a JSONPath subset) parsed internally
+//! - Traversal of nested objects and arrays hidden from callers
+
+use super::{Tool, ToolErrorKind, ToolMetadata, ToolResult};
+use crate::{tool_metadata, tool_result, validate_required_string};
+use anyhow::{anyhow, bail, Result};
+use async_trait::async_trait;
+use serde_json::Value;
+
+/// One step of a parsed JSONPath expression
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Key(String),
+    Index(usize),
+    Wildcard,
+}
+
+/// Select a subset of a JSON document
+///
+/// Lets an agent pass a compact slice of a big API response or database
+/// dump downstream instead of the whole document. Accepts either a JSON
+/// Pointer (`/items/0/name`) or a JSONPath subset (`$.items[*].name`)
+/// supporting keys, `['quoted keys']`, indices and `*` wildcards.
+pub struct ExtractJsonTool;
+
+impl ExtractJsonTool {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn parse_path(path: &str) -> Result<Vec<Segment>> {
+        let rest = path
+            .strip_prefix('$')
+            .ok_or_else(|| anyhow!("JSONPath must start with '$' or '/': {}", path))?;
+        let chars: Vec<char> = rest.chars().collect();
+        let mut segments = Vec::new();
+        let mut i = 0;
+
+        while i < chars.len() {
+            match chars[i] {
+                '.' => {
+                    let start = i + 1;
+                    i = start;
+                    while i < chars.len() && chars[i] != '.' && chars[i] != '[' {
+                        i += 1;
+                    }
+                    let key: String = chars[start..i].iter().collect();
+                    match key.as_str() {
+                        "" => bail!("Empty key in JSONPath '{}'", path),
+                        "*" => segments.push(Segment::Wildcard),
+                        _ => segments.push(Segment::Key(key)),
+                    }
+                }
+                '[' => {
+                    let close = chars[i..]
+                        .iter()
+                        .position(|&c| c == ']')
+                        .map(|offset| i + offset)
+                        .ok_or_else(|| anyhow!("Unclosed '[' in JSONPath '{}'", path))?;
+                    let inner: String = chars[i + 1..close].iter().collect();
+                    let inner = inner.trim();
+                    let quoted = inner
+                        .strip_prefix('\'')
+                        .and_then(|s| s.strip_suffix('\''))
+                        .or_else(|| inner.strip_prefix('"').and_then(|s| s.strip_suffix('"')));
+                    segments.push(match quoted {
+                        Some(key) => Segment::Key(key.to_string()),
+                        None if inner == "*" => Segment::Wildcard,
+                        None => Segment::Index(inner.parse().map_err(|_| {
+                            anyhow!("Invalid index '{}' in JSONPath '{}'", inner, path)
+                        })?),
+                    });
+                    i = close + 1;
+                }
+                other => bail!("Unexpected '{}' in JSONPath '{}'", other, path),
+            }
+        }
+
+        Ok(segments)
+    }
+
+    /// Values reached by following `segments` from `value`
+    fn select<'a>(value: &'a Value, segments: &[Segment]) -> Vec<&'a Value> {
+        let Some((segment, rest)) = segments.split_first() else {
+            return vec![value];
+        };
+        let children: Vec<&Value> = match (segment, value) {
+            (Segment::Key(key), Value::Object(map)) => map.get(key).into_iter().collect(),
+            (Segment::Index(index), Value::Array(items)) => items.get(*index).into_iter().collect(),
+            (Segment::Wildcard, Value::Array(items)) => items.iter().collect(),
+            (Segment::Wildcard, Value::Object(map)) => map.values().collect(),
+            _ => Vec::new(),
+        };
+        children
+            .into_iter()
+            .flat_map(|child| Self::select(child, rest))
+            .collect()
+    }
+
+    /// Apply `path` to `data`, or None if nothing matches
+    ///
+    /// Paths with a wildcard always yield an array of matches; other paths
+    /// yield the single value they point at.
+    pub fn extract(data: &Value, path: &str) -> Result<Option<Value>> {
+        let path = path.trim();
+        if path.is_empty() || path.starts_with('/') {
+            return Ok(data.pointer(path).cloned());
+        }
+
+        let segments = Self::parse_path(path)?;
+        let matches = Self::select(data, &segments);
+        if segments.contains(&Segment::Wildcard) {
+            Ok(Some(Value::Array(matches.into_iter().cloned().collect())))
+        } else {
+            Ok(matches.first().map(|&value| value.clone()))
+        }
+    }
+}
+
+impl Default for ExtractJsonTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Tool for ExtractJsonTool {
+    fn metadata(&self) -> ToolMetadata {
+        tool_metadata! {
+            name: "extract_json",
+            description: "Select part of a JSON document. Use a JSON Pointer like '/items/0' or a JSONPath like '$.items[*].name'. Returns only the selected JSON.",
+            parameters: [
+                {
+                    name: "data",
+                    type: "string",
+                    description: "The JSON document to extract from",
+                    required: true
+                },
+                {
+                    name: "path",
+                    type: "string",
+                    description: "JSON Pointer ('/a/0/b') or JSONPath ('$.a[0].b', '$.a[*].b')",
+                    required: true
+                }
+            ]
+        }
+    }
+
+    fn validate(&self, args: &Value) -> Result<()> {
+        if args["data"].is_null() {
+            bail!("'data' parameter is required");
+        }
+        validate_required_string!(args, "path");
+        Ok(())
+    }
+
+    fn cacheable(&self) -> bool {
+        true
+    }
+
+    async fn execute(&self, args: Value) -> Result<ToolResult> {
+        self.validate(&args)?;
+        let path = validate_required_string!(args, "path");
+
+        // Models usually pass JSON as text, but accept an inline value too
+        let data = match &args["data"] {
+            Value::String(text) => match serde_json::from_str(text) {
+                Ok(parsed) => parsed,
+                Err(e) => {
+                    return Ok(ToolResult::failure_with_kind(
+                        ToolErrorKind::Validation,
+                        format!("'data' is not valid JSON: {}", e),
+                    ))
+                }
+            },
+            other => other.clone(),
+        };
+
+        match Self::extract(&data, path) {
+            Ok(Some(selected)) => tool_result!(success: serde_json::to_string(&selected)?),
+            Ok(None) => Ok(ToolResult::failure_with_kind(
+                ToolErrorKind::NotFound,
+                format!("Path '{}' matched nothing", path),
+            )),
+            Err(e) => Ok(ToolResult::failure_with_kind(
+                ToolErrorKind::Validation,
+                e.to_string(),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_extracts_nested_array_slices() {
+        let document = json!({
+            "response": {
+                "orders": [
+                    {"id": 1, "items": [{"sku": "a"}, {"sku": "b"}]},
+                    {"id": 2, "items": [{"sku": "c"}]}
+                ],
+                "meta": {"total": 2}
+            }
+        });
+        let tool = ExtractJsonTool::new();
+        let run = |path: &str| tool.execute(json!({"data": document.to_string(), "path": path}));
+
+        let result = run("$.response.orders[0].items").await.unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(result.output, r#"[{"sku":"a"},{"sku":"b"}]"#);
+
+        let result = run("/response/orders/1/items").await.unwrap();
+        assert_eq!(result.output, r#"[{"sku":"c"}]"#);
+
+        let result = run("$.response.orders[*].items[*].sku").await.unwrap();
+        assert_eq!(result.output, r#"["a","b","c"]"#);
+
+        let result = run("$['response']['meta'].total").await.unwrap();
+        assert_eq!(result.output, "2");
+
+        let missing = run("$.response.customers").await.unwrap();
+        assert!(!missing.success);
+        assert_eq!(missing.error_kind, Some(ToolErrorKind::NotFound));
+
+        let bad_path = run("$.response[").await.unwrap();
+        assert_eq!(bad_path.error_kind, Some(ToolErrorKind::Validation));
+
+        let invalid = tool
+            .execute(json!({"data": "{not json", "path": "$.a"}))
+            .await
+            .unwrap();
+        assert_eq!(invalid.error_kind, Some(ToolErrorKind::Validation));
+        assert!(invalid.error.unwrap().contains("not valid JSON"));
+    }
+}
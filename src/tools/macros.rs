@@ -25,8 +25,28 @@
 ///     ]
 /// }
 /// ```
+///
+/// A parameter may end with optional constraints, in any order:
+/// `allowed_values: ["a", "b"]`, `minimum: 1`, `maximum: 65535` and
+/// `pattern: r"^\d{4}-\d{2}-\d{2}$"`, plus `item_types: ["number"]` to
+/// describe the elements of an object map or tuple-like array.
 #[macro_export]
 macro_rules! tool_metadata {
+    (@set $param:ident allowed_values $value:expr) => {
+        $param.allowed_values = Some($value.iter().map(|v| v.to_string()).collect());
+    };
+    (@set $param:ident minimum $value:expr) => {
+        $param.minimum = Some($value as f64);
+    };
+    (@set $param:ident maximum $value:expr) => {
+        $param.maximum = Some($value as f64);
+    };
+    (@set $param:ident pattern $value:expr) => {
+        $param.pattern = Some($value.to_string());
+    };
+    (@set $param:ident item_types $value:expr) => {
+        $param.item_types = Some($value.iter().map(|v| v.to_string()).collect());
+    };
     (
         name: $name:expr,
         description: $description:expr,
@@ -37,6 +57,8 @@ macro_rules! tool_metadata {
                     type: $param_type:expr,
                     description: $param_desc:expr,
                     required: $param_required:expr
+                    $(, $constraint:ident: $constraint_value:expr)*
+                    $(,)?
                 }
             ),* $(,)?
         ]
@@ -46,11 +68,17 @@ macro_rules! tool_metadata {
             description: $description.to_string(),
             parameters: vec![
                 $(
-                    $crate::tools::ToolParameter {
-                        name: $param_name.to_string(),
-                        param_type: $param_type.to_string(),
-                        description: $param_desc.to_string(),
-                        required: $param_required,
+                    {
+                        #[allow(unused_mut)]
+                        let mut param = $crate::tools::ToolParameter {
+                            name: $param_name.to_string(),
+                            param_type: $param_type.to_string(),
+                            description: $param_desc.to_string(),
+                            required: $param_required,
+                            ..Default::default()
+                        };
+                        $($crate::tool_metadata!(@set param $constraint $constraint_value);)*
+                        param
                     }
                 ),*
             ],
@@ -128,4 +156,40 @@ mod tests {
         assert_eq!(metadata.parameters[1].name, "param2");
         assert_eq!(metadata.parameters[1].required, false);
     }
+
+    #[test]
+    fn test_tool_metadata_macro_constraints() {
+        let metadata = tool_metadata! {
+            name: "serve",
+            description: "Start a server",
+            parameters: [
+                {
+                    name: "port",
+                    type: "number",
+                    description: "Port to listen on",
+                    required: true,
+                    minimum: 1,
+                    maximum: 65535
+                },
+                {
+                    name: "mode",
+                    type: "string",
+                    description: "Server mode",
+                    required: false,
+                    allowed_values: ["dev", "prod"],
+                    pattern: "^[a-z]+$",
+                }
+            ]
+        };
+
+        let port = &metadata.parameters[0];
+        assert_eq!((port.minimum, port.maximum), (Some(1.0), Some(65535.0)));
+        assert!(port.check(&serde_json::json!(80)).is_ok());
+        assert!(port.check(&serde_json::json!(0)).is_err());
+
+        let mode = &metadata.parameters[1];
+        assert_eq!(mode.pattern.as_deref(), Some("^[a-z]+$"));
+        assert!(mode.check(&serde_json::json!("prod")).is_ok());
+        assert!(mode.check(&serde_json::json!("staging")).is_err());
+    }
 }
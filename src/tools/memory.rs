@@ -0,0 +1,246 @@
+//! Vector Memory Tool
+//!
+//! Information Hiding:
+//! - Embedding calls hidden behind the LLM client
+//! - Storage layout hidden behind the VectorStore trait
+//! - Similarity scoring and ranking hidden from agents
+
+use super::{Tool, ToolMetadata, ToolResult};
+use crate::core::llm::LLMClient;
+use crate::{tool_metadata, tool_result, validate_required_string};
+use anyhow::Result;
+use async_trait::async_trait;
+use serde_json::Value;
+use std::sync::{Arc, Mutex};
+
+/// Storage for embedded text chunks
+pub trait VectorStore: Send + Sync {
+    fn add(&self, text: String, embedding: Vec<f32>);
+
+    /// Up to `top_k` stored texts ranked by similarity to `query`, best first
+    fn search(&self, query: &[f32], top_k: usize) -> Vec<(String, f32)>;
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Process-local vector store with brute-force search
+#[derive(Default)]
+pub struct InMemoryVectorStore {
+    entries: Mutex<Vec<(String, Vec<f32>)>>,
+}
+
+impl InMemoryVectorStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl VectorStore for InMemoryVectorStore {
+    fn add(&self, text: String, embedding: Vec<f32>) {
+        self.entries.lock().unwrap().push((text, embedding));
+    }
+
+    fn search(&self, query: &[f32], top_k: usize) -> Vec<(String, f32)> {
+        let entries = self.entries.lock().unwrap();
+        let mut scored: Vec<(String, f32)> = entries
+            .iter()
+            .map(|(text, embedding)| (text.clone(), cosine_similarity(query, embedding)))
+            .collect();
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        scored.truncate(top_k);
+        scored
+    }
+
+    fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+}
+
+/// Cosine similarity, zero when either vector has no magnitude
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a * norm_b)
+    }
+}
+
+/// Semantic memory tool: remember text and recall the closest matches
+pub struct MemoryTool {
+    client: Arc<LLMClient>,
+    store: Arc<dyn VectorStore>,
+    max_top_k: usize,
+}
+
+impl MemoryTool {
+    pub fn new(client: Arc<LLMClient>) -> Self {
+        Self::with_store(client, Arc::new(InMemoryVectorStore::new()))
+    }
+
+    /// Use a custom store, e.g. one shared between several agents
+    pub fn with_store(client: Arc<LLMClient>, store: Arc<dyn VectorStore>) -> Self {
+        Self {
+            client,
+            store,
+            max_top_k: 10,
+        }
+    }
+
+    async fn embed_one(&self, text: &str) -> Result<Vec<f32>> {
+        self.client
+            .embed(vec![text.to_string()])
+            .await?
+            .pop()
+            .ok_or_else(|| anyhow::anyhow!("Provider returned no embedding"))
+    }
+}
+
+#[async_trait]
+impl Tool for MemoryTool {
+    fn metadata(&self) -> ToolMetadata {
+        tool_metadata! {
+            name: "memory",
+            description: "Store facts and retrieve them later by meaning. Use action 'remember' with 'text' to store a fact, or action 'recall' with 'query' to find the most similar stored facts.",
+            parameters: [
+                {
+                    name: "action",
+                    type: "string",
+                    description: "Either 'remember' or 'recall'",
+                    required: true
+                },
+                {
+                    name: "text",
+                    type: "string",
+                    description: "The fact to store (for 'remember')",
+                    required: false
+                },
+                {
+                    name: "query",
+                    type: "string",
+                    description: "What to look for (for 'recall')",
+                    required: false
+                },
+                {
+                    name: "top_k",
+                    type: "number",
+                    description: "Number of facts to return for 'recall', default is 3",
+                    required: false
+                }
+            ]
+        }
+    }
+
+    fn validate(&self, args: &Value) -> Result<()> {
+        match validate_required_string!(args, "action") {
+            "remember" => {
+                if validate_required_string!(args, "text").trim().is_empty() {
+                    return Err(anyhow::anyhow!("Text to remember cannot be empty"));
+                }
+            }
+            "recall" => {
+                validate_required_string!(args, "query");
+            }
+            other => {
+                return Err(anyhow::anyhow!(
+                    "Unknown action '{}', expected 'remember' or 'recall'",
+                    other
+                ))
+            }
+        }
+        Ok(())
+    }
+
+    async fn execute(&self, args: Value) -> Result<ToolResult> {
+        self.validate(&args)?;
+
+        if validate_required_string!(args, "action") == "remember" {
+            let text = validate_required_string!(args, "text");
+            let embedding = match self.embed_one(text).await {
+                Ok(embedding) => embedding,
+                Err(e) => return tool_result!(failure: format!("Failed to embed text: {}", e)),
+            };
+            self.store.add(text.to_string(), embedding);
+            tracing::info!("[MemoryTool] Stored fact ({} total)", self.store.len());
+            return tool_result!(success: format!("Remembered: {}", text));
+        }
+
+        let query = validate_required_string!(args, "query");
+        let top_k = args["top_k"]
+            .as_u64()
+            .map(|n| (n as usize).clamp(1, self.max_top_k))
+            .unwrap_or(3);
+
+        if self.store.is_empty() {
+            return tool_result!(success: "No facts have been remembered yet");
+        }
+
+        let embedding = match self.embed_one(query).await {
+            Ok(embedding) => embedding,
+            Err(e) => return tool_result!(failure: format!("Failed to embed query: {}", e)),
+        };
+
+        let matches = self.store.search(&embedding, top_k);
+        let output = matches
+            .iter()
+            .map(|(text, score)| format!("[{:.3}] {}", score, text))
+            .collect::<Vec<_>>()
+            .join("\n");
+        tool_result!(success: output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Settings;
+    use crate::core::mock_llm::MockLLM;
+    use serde_json::json;
+
+    /// Bag-of-words embedding over a tiny fixed vocabulary
+    fn keyword_embedding(text: &str) -> Vec<f32> {
+        let text = text.to_lowercase();
+        [
+            "cat", "sofa", "rust", "compiler", "paris", "france", "capital",
+        ]
+        .iter()
+        .map(|word| text.matches(word).count() as f32)
+        .collect()
+    }
+
+    #[tokio::test]
+    async fn test_recall_returns_most_similar_fact() {
+        let llm = Arc::new(MockLLM::new(Vec::<String>::new()).with_embedder(keyword_embedding));
+        let client = Arc::new(LLMClient::with_backend(llm, Settings::new().unwrap()));
+        let tool = MemoryTool::new(client);
+
+        for fact in [
+            "The cat sleeps on the sofa",
+            "Rust has a strict compiler",
+            "Paris is the capital of France",
+        ] {
+            let stored = tool
+                .execute(json!({"action": "remember", "text": fact}))
+                .await
+                .unwrap();
+            assert!(stored.success);
+        }
+
+        let recalled = tool
+            .execute(
+                json!({"action": "recall", "query": "What is the capital of France?", "top_k": 1}),
+            )
+            .await
+            .unwrap();
+
+        assert!(recalled.success);
+        assert_eq!(recalled.output.lines().count(), 1);
+        assert!(recalled.output.ends_with("Paris is the capital of France"));
+    }
+}
@@ -6,26 +6,109 @@
 //! - Registry implementation details hidden from consumers
 //! - Error handling internalized per tool
 
+pub mod clock;
+pub mod compute;
+pub mod context;
 pub mod executor;
 pub mod filesystem;
 pub mod http;
+pub mod introspect;
+pub mod json;
 pub mod macros;
+pub mod memory;
 pub mod registry;
+pub mod scratchpad;
 pub mod shell;
 
+use crate::config::Settings;
+use crate::core::circuit_breaker::CircuitBreakerConfig;
 use anyhow::Result;
 use async_trait::async_trait;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::fmt;
+use std::sync::OnceLock;
+use tokio::sync::mpsc;
 
 /// Tool parameter schema definition
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ToolParameter {
     pub name: String,
     pub param_type: String,
     pub description: String,
     pub required: bool,
+    /// Fixed set of accepted values, if the parameter is an enumeration
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed_values: Option<Vec<String>>,
+    /// Inclusive lower bound for numeric parameters
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub minimum: Option<f64>,
+    /// Inclusive upper bound for numeric parameters
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub maximum: Option<f64>,
+    /// Regex string parameters must match
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    /// Element types: the value type of an "object" map, or the type at each
+    /// position of a fixed-length "array" such as a tuple
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub item_types: Option<Vec<String>>,
+}
+
+impl ToolParameter {
+    /// Type as shown to the model, including element types when known
+    pub fn type_hint(&self) -> String {
+        match (&self.item_types, self.param_type.as_str()) {
+            (Some(types), "object") if !types.is_empty() => {
+                format!("object with {} values", types[0])
+            }
+            (Some(types), "array") => format!("array [{}]", types.join(", ")),
+            _ => self.param_type.clone(),
+        }
+    }
+
+    /// Check a supplied argument against this parameter's constraints
+    ///
+    /// Only constraints that apply to the value's JSON type are checked;
+    /// presence and type are left to the tool's own validation.
+    pub fn check(&self, value: &Value) -> Result<()> {
+        if let Some(n) = value.as_f64() {
+            if let Some(min) = self.minimum.filter(|min| n < *min) {
+                anyhow::bail!("'{}' must be at least {}, got {}", self.name, min, n);
+            }
+            if let Some(max) = self.maximum.filter(|max| n > *max) {
+                anyhow::bail!("'{}' must be at most {}, got {}", self.name, max, n);
+            }
+        }
+
+        if let Some(s) = value.as_str() {
+            if let Some(allowed) = &self.allowed_values {
+                if !allowed.iter().any(|v| v == s) {
+                    anyhow::bail!(
+                        "'{}' must be one of: {}, got '{}'",
+                        self.name,
+                        allowed.join(", "),
+                        s
+                    );
+                }
+            }
+            if let Some(pattern) = &self.pattern {
+                let regex = Regex::new(pattern)
+                    .map_err(|e| anyhow::anyhow!("Invalid pattern for '{}': {}", self.name, e))?;
+                if !regex.is_match(s) {
+                    anyhow::bail!(
+                        "'{}' must match pattern {}, got '{}'",
+                        self.name,
+                        pattern,
+                        s
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
 }
 
 /// Tool metadata - describes what the tool does and how to use it
@@ -36,18 +119,174 @@ pub struct ToolMetadata {
     pub parameters: Vec<ToolParameter>,
 }
 
+impl ToolMetadata {
+    /// JSON Schema describing the tool's arguments object
+    pub fn to_json_schema(&self) -> Value {
+        let mut properties = serde_json::Map::new();
+        for param in &self.parameters {
+            let mut property = serde_json::json!({
+                "type": param.param_type,
+                "description": param.description,
+            });
+            if let Some(values) = &param.allowed_values {
+                property["enum"] = serde_json::json!(values);
+            }
+            if let Some(min) = param.minimum {
+                property["minimum"] = serde_json::json!(min);
+            }
+            if let Some(max) = param.maximum {
+                property["maximum"] = serde_json::json!(max);
+            }
+            if let Some(pattern) = &param.pattern {
+                property["pattern"] = serde_json::json!(pattern);
+            }
+            match (&param.item_types, param.param_type.as_str()) {
+                (Some(types), "object") if !types.is_empty() => {
+                    property["additionalProperties"] = serde_json::json!({ "type": types[0] });
+                }
+                (Some(types), "array") => {
+                    let items: Vec<Value> = types
+                        .iter()
+                        .map(|t| serde_json::json!({ "type": t }))
+                        .collect();
+                    property["prefixItems"] = serde_json::json!(items);
+                    property["minItems"] = serde_json::json!(types.len());
+                    property["maxItems"] = serde_json::json!(types.len());
+                }
+                _ => {}
+            }
+            properties.insert(param.name.clone(), property);
+        }
+
+        let required: Vec<&str> = self
+            .parameters
+            .iter()
+            .filter(|p| p.required)
+            .map(|p| p.name.as_str())
+            .collect();
+
+        serde_json::json!({
+            "type": "object",
+            "properties": properties,
+            "required": required,
+        })
+    }
+}
+
+/// Check every supplied argument against its parameter's constraints
+pub fn validate_constraints(parameters: &[ToolParameter], args: &Value) -> Result<()> {
+    for param in parameters {
+        if let Some(value) = args.get(&param.name).filter(|v| !v.is_null()) {
+            param.check(value)?;
+        }
+    }
+    Ok(())
+}
+
+/// Parameter type with a fixed set of values, e.g. a unit-only enum
+///
+/// Derive it with `#[derive(actorus::ToolEnum)]`; the tool macros then fill
+/// `ToolParameter::allowed_values` for parameters of the type.
+pub trait ToolEnum {
+    /// Every accepted value, as the model should write it
+    fn allowed_values() -> Vec<String>;
+}
+
+/// Support for macro-generated code; not public API
+#[doc(hidden)]
+pub mod __private {
+    use super::ToolEnum;
+    use std::marker::PhantomData;
+
+    /// Asks a parameter type for its `ToolEnum` values without requiring
+    /// the trait: method lookup prefers `ViaToolEnum` and falls back to
+    /// `ViaAnyType` through auto-ref
+    pub struct EnumProbe<T>(PhantomData<T>);
+
+    impl<T> EnumProbe<T> {
+        #[allow(clippy::new_without_default)]
+        pub fn new() -> Self {
+            Self(PhantomData)
+        }
+    }
+
+    pub trait ViaToolEnum {
+        fn allowed_values(&self) -> Option<Vec<String>>;
+    }
+
+    impl<T: ToolEnum> ViaToolEnum for EnumProbe<T> {
+        fn allowed_values(&self) -> Option<Vec<String>> {
+            Some(T::allowed_values())
+        }
+    }
+
+    pub trait ViaAnyType {
+        fn allowed_values(&self) -> Option<Vec<String>>;
+    }
+
+    impl<T> ViaAnyType for &EnumProbe<T> {
+        fn allowed_values(&self) -> Option<Vec<String>> {
+            None
+        }
+    }
+}
+
 impl fmt::Display for ToolMetadata {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}: {}", self.name, self.description)
     }
 }
 
+/// Why a tool failed, deciding whether the executor retries it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolErrorKind {
+    /// Bad arguments, e.g. a malformed path
+    Validation,
+    Permission,
+    NotFound,
+    Timeout,
+    Network,
+    /// Local filesystem failure, e.g. a full disk
+    Io,
+    /// Unexpected failure inside the tool
+    Internal,
+}
+
+impl ToolErrorKind {
+    /// Kind of a failed local file operation
+    pub fn from_io(error: &std::io::Error) -> Self {
+        match error.kind() {
+            std::io::ErrorKind::PermissionDenied => ToolErrorKind::Permission,
+            std::io::ErrorKind::NotFound => ToolErrorKind::NotFound,
+            _ => ToolErrorKind::Io,
+        }
+    }
+
+    /// Transient failures worth another attempt
+    pub fn is_retryable(self) -> bool {
+        matches!(
+            self,
+            ToolErrorKind::Timeout | ToolErrorKind::Network | ToolErrorKind::Internal
+        )
+    }
+}
+
 /// Result of a tool execution
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolResult {
     pub success: bool,
     pub output: String,
     pub error: Option<String>,
+    /// Classification of the failure; unclassified failures are judged by their message
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_kind: Option<ToolErrorKind>,
+    /// The tool did nothing yet and is waiting for the user to approve the call
+    #[serde(default)]
+    pub requires_confirmation: bool,
+    /// Structured details for the caller, e.g. what a file tool overwrote
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Value>,
 }
 
 impl ToolResult {
@@ -56,6 +295,9 @@ impl ToolResult {
             success: true,
             output: output.into(),
             error: None,
+            error_kind: None,
+            requires_confirmation: false,
+            metadata: None,
         }
     }
 
@@ -64,6 +306,35 @@ impl ToolResult {
             success: false,
             output: String::new(),
             error: Some(error.into()),
+            error_kind: None,
+            requires_confirmation: false,
+            metadata: None,
+        }
+    }
+
+    /// Failure tagged with its kind, e.g. `ToolErrorKind::Validation` to skip retries
+    pub fn failure_with_kind(kind: ToolErrorKind, error: impl Into<String>) -> Self {
+        Self {
+            error_kind: Some(kind),
+            ..Self::failure(error)
+        }
+    }
+
+    /// Attach structured details alongside the output
+    pub fn with_metadata(mut self, metadata: Value) -> Self {
+        self.metadata = Some(metadata);
+        self
+    }
+
+    /// Ask the user to approve the call before doing anything, e.g. before a delete
+    ///
+    /// A `SpecializedAgent` pauses with `AgentResponse::NeedsInput` carrying
+    /// `prompt`; when resumed it repeats the call with the user's answer in a
+    /// `confirmation` argument. Elsewhere this reads as a non-retryable failure.
+    pub fn needs_confirmation(prompt: impl Into<String>) -> Self {
+        Self {
+            requires_confirmation: true,
+            ..Self::failure_with_kind(ToolErrorKind::Permission, prompt)
         }
     }
 }
@@ -86,10 +357,107 @@ pub trait Tool: Send + Sync {
     /// * `ToolResult` - Success or failure with output/error
     async fn execute(&self, args: Value) -> Result<ToolResult>;
 
+    /// Execute the tool, sending partial output to `output` as it is produced
+    ///
+    /// Long-running tools, such as shell commands or downloads, override this
+    /// so callers can show progress. Chunks are sent before the final result
+    /// is returned; the result still carries the complete output. The default
+    /// runs `execute` and sends nothing.
+    async fn execute_streaming(
+        &self,
+        args: Value,
+        output: mpsc::Sender<String>,
+    ) -> Result<ToolResult> {
+        let _ = output;
+        self.execute(args).await
+    }
+
     /// Validate arguments before execution (optional)
     fn validate(&self, _args: &Value) -> Result<()> {
         Ok(())
     }
+
+    /// Lock name for tools that must not run concurrently (optional)
+    ///
+    /// Calls to tools sharing a key run one at a time, even across agents,
+    /// e.g. every tool using one database connection returns `Some("db")`.
+    /// Tools without a key run in parallel.
+    fn serialization_key(&self) -> Option<String> {
+        None
+    }
+
+    /// Cap on how often this tool may be called (optional)
+    ///
+    /// Overrides `ToolConfig::rate_limit`. Callers over the limit wait for
+    /// their turn instead of failing.
+    fn rate_limit(&self) -> Option<RateLimit> {
+        None
+    }
+
+    /// Whether identical calls may share a result (optional)
+    ///
+    /// Only for pure tools, whose output depends on nothing but the input
+    /// and the `ExecutionContext` values; not for ones touching files,
+    /// shells or changing remote state. Calls share a result only when both
+    /// match. Takes effect when `ToolConfig::cache` is set.
+    fn cacheable(&self) -> bool {
+        false
+    }
+
+    /// Restrictions applied while `ToolConfig::sandbox` is on (optional)
+    ///
+    /// Tools that reach outside the process, such as the filesystem and
+    /// shell tools, reject risky calls here unless they were given an
+    /// explicit allowlist of their own.
+    fn check_sandbox(&self, _args: &Value) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// At most `max` calls in any `per` window, e.g. 5 per second for a search API
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    pub max: u32,
+    pub per: std::time::Duration,
+}
+
+impl RateLimit {
+    pub fn new(max: u32, per: std::time::Duration) -> Self {
+        Self { max, per }
+    }
+}
+
+/// Keep up to `capacity` results of cacheable tools, each for `ttl`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CacheConfig {
+    pub capacity: usize,
+    pub ttl: std::time::Duration,
+}
+
+impl CacheConfig {
+    pub fn new(capacity: usize, ttl: std::time::Duration) -> Self {
+        Self { capacity, ttl }
+    }
+}
+
+/// Expand `$VAR` and `${VAR}` references from the process environment
+///
+/// References to unset variables are left as written. The filesystem tools
+/// apply this to their path arguments only after `with_expand_env(true)`;
+/// expansion is off by default so a path such as `$HOME` names a literal
+/// file unless the caller opts in.
+pub fn expand_env_vars(input: &str) -> String {
+    static ENV_REF: OnceLock<Regex> = OnceLock::new();
+    let pattern = ENV_REF.get_or_init(|| {
+        Regex::new(r"\$(?:\{([A-Za-z_][A-Za-z0-9_]*)\}|([A-Za-z_][A-Za-z0-9_]*))").unwrap()
+    });
+
+    pattern
+        .replace_all(input, |caps: &regex::Captures| {
+            let name = caps.get(1).or_else(|| caps.get(2)).unwrap().as_str();
+            std::env::var(name).unwrap_or_else(|_| caps[0].to_string())
+        })
+        .into_owned()
 }
 
 /// Tool execution configuration
@@ -97,7 +465,17 @@ pub trait Tool: Send + Sync {
 pub struct ToolConfig {
     pub timeout_secs: u64,
     pub max_retries: u32,
+    /// Run `Tool::check_sandbox` before each call, e.g. keeping file tools
+    /// without `allowed_paths` inside the working directory
     pub sandbox: bool,
+    /// Per-tool breaker, opened by calls that still fail after all retries
+    pub circuit_breaker: CircuitBreakerConfig,
+    /// Report what each call would do instead of running the tool
+    pub dry_run: bool,
+    /// Applied to tools that don't declare their own `Tool::rate_limit`
+    pub rate_limit: Option<RateLimit>,
+    /// Reuse successful results of `Tool::cacheable` tools called with the same input
+    pub cache: Option<CacheConfig>,
 }
 
 impl Default for ToolConfig {
@@ -106,6 +484,22 @@ impl Default for ToolConfig {
             timeout_secs: 30,
             max_retries: 3,
             sandbox: true,
+            circuit_breaker: CircuitBreakerConfig::default(),
+            dry_run: false,
+            rate_limit: None,
+            cache: None,
+        }
+    }
+}
+
+impl ToolConfig {
+    /// Executor configuration for agents and sessions running on `settings`
+    pub fn for_settings(settings: &Settings) -> Self {
+        Self {
+            circuit_breaker: settings.circuit_breaker.clone(),
+            dry_run: settings.tools_dry_run(),
+            cache: settings.tools.cache(),
+            ..Self::default()
         }
     }
 }
@@ -4,25 +4,103 @@
 //! - Tool storage and lookup implementation hidden
 //! - Tool lifecycle management hidden
 //! - Registration and discovery mechanisms abstracted
+//! - Process-wide default tool set kept behind `set_default_tools`
+//! - Name normalization and edit distance behind fuzzy lookup hidden
 
 use super::{Tool, ToolMetadata};
+use crate::config::ToolsConfig;
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
+
+/// Tool set installed by `set_default_tools`, used instead of the built-ins
+static DEFAULT_TOOLS: RwLock<Option<Vec<Arc<dyn Tool>>>> = RwLock::new(None);
+
+/// Replace the tools every agent gets from `ToolRegistry::with_defaults`
+///
+/// Call before `init()` so the actors pick the set up when they start.
+/// Start from [`builtin_tools`] to keep some of the built-ins, e.g. to drop
+/// shell access or add organisation-specific tools everywhere.
+pub fn set_default_tools(tools: Vec<Arc<dyn Tool>>) {
+    replace_default_tools(Some(tools));
+}
+
+/// Install `tools` as the default set (`None` for the built-ins), returning
+/// the previous setting so it can be put back
+pub(crate) fn replace_default_tools(
+    tools: Option<Vec<Arc<dyn Tool>>>,
+) -> Option<Vec<Arc<dyn Tool>>> {
+    std::mem::replace(
+        &mut *DEFAULT_TOOLS.write().unwrap_or_else(|e| e.into_inner()),
+        tools,
+    )
+}
+
+/// The tools the crate registers by default
+pub fn builtin_tools() -> Vec<Arc<dyn Tool>> {
+    #[allow(unused_mut)]
+    let mut tools: Vec<Arc<dyn Tool>> = vec![
+        Arc::new(crate::tools::shell::ShellTool::new(30)),
+        Arc::new(crate::tools::filesystem::ReadFileTool::new(1024 * 1024)), // 1MB max
+        Arc::new(crate::tools::filesystem::WriteFileTool::new(1024 * 1024)), // 1MB max
+        Arc::new(crate::tools::filesystem::AppendFileTool::new(1024 * 1024)), // 1MB max
+        Arc::new(crate::tools::http::HttpTool::new(30)),
+        Arc::new(crate::tools::json::ExtractJsonTool::new()),
+        Arc::new(crate::tools::compute::ComputeTool::new()),
+        Arc::new(crate::tools::clock::CurrentTimeTool::new()),
+    ];
+
+    #[cfg(feature = "memory")]
+    tools.extend(ToolRegistry::memory_tool());
+
+    tools
+}
 
 /// Tool registry for managing available tools
 ///
 /// Provides centralized tool management with dynamic registration
 pub struct ToolRegistry {
     tools: HashMap<String, Arc<dyn Tool>>,
+    fuzzy_names: bool,
+}
+
+/// Lowercased name without separators, so `read-file`, `readFile` and
+/// `read_file` compare equal
+fn normalize_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| !matches!(c, '_' | '-' | ' ' | '.'))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Levenshtein distance between two strings, counted in characters
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
 }
 
 impl ToolRegistry {
     pub fn new() -> Self {
         Self {
             tools: HashMap::new(),
+            fuzzy_names: false,
         }
     }
 
+    /// Let `resolve`, `get` and `has_tool` accept near misses of registered names
+    pub fn with_fuzzy_names(mut self, enabled: bool) -> Self {
+        self.fuzzy_names = enabled;
+        self
+    }
+
     /// Register a new tool
     pub fn register(&mut self, tool: Arc<dyn Tool>) {
         let name = tool.metadata().name.clone();
@@ -30,14 +108,77 @@ impl ToolRegistry {
         self.tools.insert(name, tool);
     }
 
-    /// Get a tool by name
+    /// Get a tool by name, accepting the same names as `resolve`
+    ///
+    /// Agent loops resolve the name first so they record the registered one.
     pub fn get(&self, name: &str) -> Option<Arc<dyn Tool>> {
-        self.tools.get(name).cloned()
+        match self.tools.get(name) {
+            Some(tool) => Some(Arc::clone(tool)),
+            None => self.tools.get(&self.resolve(name)?).cloned(),
+        }
     }
 
-    /// Check if a tool exists
+    /// Registered name `name` refers to
+    ///
+    /// Exact names always resolve. With fuzzy names enabled, a near miss
+    /// resolves to the one tool it is closest to: case and separators are
+    /// ignored, and names of five or more characters may be off by one
+    /// further edit. Returns None when nothing is close or several tools are
+    /// equally close.
+    pub fn resolve(&self, name: &str) -> Option<String> {
+        if self.tools.contains_key(name) {
+            return Some(name.to_string());
+        }
+        if !self.fuzzy_names {
+            return None;
+        }
+
+        let query = normalize_name(name);
+        let max_distance = if query.chars().count() >= 5 { 1 } else { 0 };
+        let mut best: Option<(usize, Vec<&String>)> = None;
+        for candidate in self.tools.keys() {
+            let distance = edit_distance(&query, &normalize_name(candidate));
+            if distance > max_distance {
+                continue;
+            }
+            match &mut best {
+                Some((closest, names)) if distance == *closest => names.push(candidate),
+                Some((closest, _)) if distance > *closest => {}
+                _ => best = Some((distance, vec![candidate])),
+            }
+        }
+
+        match best {
+            Some((_, names)) if names.len() == 1 => Some(names[0].clone()),
+            Some((_, names)) => {
+                tracing::debug!(
+                    "[ToolRegistry] Tool name '{}' is ambiguous between {:?}",
+                    name,
+                    names
+                );
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Whether `name` is registered and `offered` lets the current run use it
+    pub fn is_offered(&self, name: &str, offered: impl Fn(&ToolMetadata) -> bool) -> bool {
+        self.get(name).is_some_and(|tool| offered(&tool.metadata()))
+    }
+
+    /// Names of the registered tools `offered` lets the current run use
+    pub fn offered_names(&self, offered: impl Fn(&ToolMetadata) -> bool) -> Vec<String> {
+        self.list_tools()
+            .into_iter()
+            .filter(|metadata| offered(metadata))
+            .map(|metadata| metadata.name)
+            .collect()
+    }
+
+    /// Check if a tool exists, accepting the same names as `get`
     pub fn has_tool(&self, name: &str) -> bool {
-        self.tools.contains_key(name)
+        self.resolve(name).is_some()
     }
 
     /// Get all tool names
@@ -52,17 +193,33 @@ impl ToolRegistry {
 
     /// Get tool metadata as formatted string for LLM prompts
     pub fn tools_description(&self) -> String {
+        self.tools_description_filtered(|_| true)
+    }
+
+    /// Like `tools_description`, but only for tools `keep` accepts
+    pub fn tools_description_filtered(&self, keep: impl Fn(&ToolMetadata) -> bool) -> String {
         let mut descriptions = Vec::new();
         for tool in self.tools.values() {
             let metadata = tool.metadata();
+            if !keep(&metadata) {
+                continue;
+            }
             let params = metadata
                 .parameters
                 .iter()
                 .map(|p| {
                     let required = if p.required { "required" } else { "optional" };
+                    let allowed = match &p.allowed_values {
+                        Some(values) => format!(" (one of: {})", values.join(", ")),
+                        None => String::new(),
+                    };
                     format!(
-                        "  - {} ({}): {} [{}]",
-                        p.name, p.param_type, p.description, required
+                        "  - {} ({}): {}{} [{}]",
+                        p.name,
+                        p.type_hint(),
+                        p.description,
+                        allowed,
+                        required
                     )
                 })
                 .collect::<Vec<_>>()
@@ -77,24 +234,54 @@ impl ToolRegistry {
     }
 
     /// Create a default registry with common tools
+    ///
+    /// Holds the built-in tools unless `set_default_tools` installed another
+    /// set, minus any tool disabled in the `[tools]` section of the loaded
+    /// settings.
     pub fn with_defaults() -> Self {
-        let mut registry = Self::new();
-
-        // Register default tools
-        registry.register(Arc::new(crate::tools::shell::ShellTool::new(30)));
-        registry.register(Arc::new(crate::tools::filesystem::ReadFileTool::new(
-            1024 * 1024,
-        ))); // 1MB max
-        registry.register(Arc::new(crate::tools::filesystem::WriteFileTool::new(
-            1024 * 1024,
-        ))); // 1MB max
-        registry.register(Arc::new(crate::tools::filesystem::AppendFileTool::new(
-            1024 * 1024,
-        ))); // 1MB max
-        registry.register(Arc::new(crate::tools::http::HttpTool::new(30)));
+        let policy = crate::config::Settings::new()
+            .map(|settings| settings.tools)
+            .unwrap_or_default();
+        Self::with_defaults_for(&policy)
+    }
+
+    /// Like `with_defaults`, but leaving out the tools `policy` disables
+    pub fn with_defaults_for(policy: &ToolsConfig) -> Self {
+        let mut registry = Self::new().with_fuzzy_names(policy.fuzzy_names);
+
+        let configured = DEFAULT_TOOLS
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+        for tool in configured.unwrap_or_else(builtin_tools) {
+            let name = tool.metadata().name;
+            if policy.allows(&name) {
+                registry.register(tool);
+            } else {
+                tracing::debug!("[ToolRegistry] Tool '{}' disabled by configuration", name);
+            }
+        }
 
         registry
     }
+
+    /// `MemoryTool` embedding through the configured provider, if one is set up
+    #[cfg(feature = "memory")]
+    fn memory_tool() -> Option<Arc<dyn Tool>> {
+        use crate::config::Settings;
+        use crate::core::llm::LLMClient;
+
+        match (Settings::new(), Settings::api_key()) {
+            (Ok(settings), Ok(api_key)) => {
+                let client = Arc::new(LLMClient::new(api_key, settings));
+                Some(Arc::new(crate::tools::memory::MemoryTool::new(client)))
+            }
+            _ => {
+                tracing::warn!("[ToolRegistry] LLM not configured, skipping memory tool");
+                None
+            }
+        }
+    }
 }
 
 impl Default for ToolRegistry {
@@ -107,6 +294,25 @@ impl Default for ToolRegistry {
 mod tests {
     use super::*;
     use crate::tools::shell::ShellTool;
+    use crate::tools::ToolResult;
+    use async_trait::async_trait;
+
+    struct NamedTool(&'static str);
+
+    #[async_trait]
+    impl Tool for NamedTool {
+        fn metadata(&self) -> ToolMetadata {
+            ToolMetadata {
+                name: self.0.to_string(),
+                description: "Test tool".to_string(),
+                parameters: vec![],
+            }
+        }
+
+        async fn execute(&self, _args: serde_json::Value) -> anyhow::Result<ToolResult> {
+            Ok(ToolResult::success(self.0))
+        }
+    }
 
     #[test]
     fn test_registry_register_and_get() {
@@ -120,6 +326,56 @@ mod tests {
         assert!(registry.get("nonexistent").is_none());
     }
 
+    #[test]
+    fn test_fuzzy_names_resolve_near_misses_only_when_unambiguous() {
+        let registry = ToolRegistry::with_defaults_for(&ToolsConfig {
+            fuzzy_names: true,
+            ..ToolsConfig::default()
+        });
+
+        assert_eq!(registry.resolve("read-file").as_deref(), Some("read_file"));
+        assert_eq!(registry.resolve("readFile").as_deref(), Some("read_file"));
+        assert_eq!(registry.resolve("read_fle").as_deref(), Some("read_file"));
+        assert_eq!(
+            registry.get("read-file").unwrap().metadata().name,
+            "read_file"
+        );
+        assert!(registry.resolve("delete_everything").is_none());
+
+        // One edit from write_file, further from read_file
+        assert_eq!(registry.resolve("rite_file").as_deref(), Some("write_file"));
+
+        let mut registry = ToolRegistry::new().with_fuzzy_names(true);
+        for name in ["fetch_page", "fetch_pages"] {
+            registry.register(Arc::new(NamedTool(name)));
+        }
+        assert!(registry.resolve("fetch_pagez").is_none());
+        assert!(registry.get("fetch_pagez").is_none());
+
+        // Exact lookups only unless enabled
+        let strict = ToolRegistry::with_defaults();
+        assert!(strict.resolve("read-file").is_none());
+        assert!(strict.get("read-file").is_none());
+        assert!(!strict.has_tool("read-file"));
+    }
+
+    #[test]
+    fn test_offered_tools_respect_the_filter() {
+        let mut registry = ToolRegistry::new();
+        for name in ["search", "delete"] {
+            registry.register(Arc::new(NamedTool(name)));
+        }
+        let read_only = |metadata: &ToolMetadata| metadata.name != "delete";
+
+        assert!(registry.is_offered("search", read_only));
+        assert!(!registry.is_offered("delete", read_only));
+        assert!(!registry.is_offered("missing", |_| true));
+        assert_eq!(
+            registry.offered_names(read_only),
+            vec!["search".to_string()]
+        );
+    }
+
     #[test]
     fn test_registry_list_tools() {
         let registry = ToolRegistry::with_defaults();
@@ -132,6 +388,37 @@ mod tests {
         assert!(registry.has_tool("http_request"));
     }
 
+    #[test]
+    fn test_disabled_tools_are_left_out() {
+        let registry = ToolRegistry::with_defaults_for(&ToolsConfig {
+            enable_shell: false,
+            ..ToolsConfig::default()
+        });
+
+        assert!(registry.get("execute_shell").is_none());
+        assert!(registry.get("read_file").is_some());
+        assert!(registry.get("write_file").is_some());
+
+        let registry = ToolRegistry::with_defaults_for(&ToolsConfig {
+            enable_shell: true,
+            enable_write: false,
+            enable_http: false,
+            ..ToolsConfig::default()
+        });
+        assert!(registry.has_tool("execute_shell"));
+        assert!(!registry.has_tool("write_file"));
+        assert!(!registry.has_tool("append_file"));
+        assert!(!registry.has_tool("http_request"));
+
+        let policy = ToolsConfig {
+            enable_write: false,
+            enable_http: false,
+            ..ToolsConfig::default()
+        };
+        assert!(!policy.allows("edit_file"));
+        assert!(!policy.allows("download_file"));
+    }
+
     #[test]
     fn test_tools_description() {
         let registry = ToolRegistry::with_defaults();
@@ -142,4 +429,22 @@ mod tests {
         assert!(description.contains("Description:"));
         assert!(description.contains("Parameters:"));
     }
+
+    #[test]
+    fn test_allowed_values_in_description_and_schema() {
+        let registry = ToolRegistry::with_defaults();
+        let description = registry.tools_description();
+        assert!(description.contains(
+            "  - method (string): HTTP method (GET or POST), default is GET (one of: GET, POST) [optional]"
+        ));
+
+        let http = registry.get("http_request").unwrap().metadata();
+        let schema = http.to_json_schema();
+        assert_eq!(
+            schema["properties"]["method"]["enum"],
+            serde_json::json!(["GET", "POST"])
+        );
+        assert!(schema["properties"]["url"].get("enum").is_none());
+        assert_eq!(schema["required"], serde_json::json!(["url"]));
+    }
 }
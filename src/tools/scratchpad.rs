@@ -0,0 +1,145 @@
+//! Scratchpad Tools - Key/value memory exposed to agents
+//!
+//! Information Hiding:
+//! - Backing store hidden behind the MemoryStore trait
+//! - Scoping (per session or shared) decided by whoever builds the tools
+
+use super::{Tool, ToolMetadata, ToolResult};
+use crate::storage::kv::MemoryStore;
+use crate::{tool_metadata, tool_result, validate_required_string};
+use anyhow::Result;
+use async_trait::async_trait;
+use serde_json::Value;
+use std::sync::Arc;
+
+/// Store a value under a key for later tasks
+pub struct RememberTool {
+    store: Arc<dyn MemoryStore>,
+}
+
+impl RememberTool {
+    pub fn new(store: Arc<dyn MemoryStore>) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait]
+impl Tool for RememberTool {
+    fn metadata(&self) -> ToolMetadata {
+        tool_metadata! {
+            name: "remember",
+            description: "Save a fact under a short key so it can be recalled in later tasks (e.g. key 'favorite_color', value 'blue'). Overwrites any existing value.",
+            parameters: [
+                {
+                    name: "key",
+                    type: "string",
+                    description: "Short identifier for the fact",
+                    required: true
+                },
+                {
+                    name: "value",
+                    type: "string",
+                    description: "The fact to remember",
+                    required: true
+                }
+            ]
+        }
+    }
+
+    fn validate(&self, args: &Value) -> Result<()> {
+        if validate_required_string!(args, "key").trim().is_empty() {
+            return Err(anyhow::anyhow!("Key cannot be empty"));
+        }
+        validate_required_string!(args, "value");
+        Ok(())
+    }
+
+    async fn execute(&self, args: Value) -> Result<ToolResult> {
+        self.validate(&args)?;
+
+        let key = validate_required_string!(args, "key").trim();
+        let value = validate_required_string!(args, "value");
+
+        match self.store.put(key, value).await {
+            Ok(()) => tool_result!(success: format!("Remembered '{}'", key)),
+            Err(e) => tool_result!(failure: format!("Failed to remember '{}': {}", key, e)),
+        }
+    }
+}
+
+/// Look up a remembered value, or list what is remembered
+pub struct RecallTool {
+    store: Arc<dyn MemoryStore>,
+}
+
+impl RecallTool {
+    pub fn new(store: Arc<dyn MemoryStore>) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait]
+impl Tool for RecallTool {
+    fn metadata(&self) -> ToolMetadata {
+        tool_metadata! {
+            name: "recall",
+            description: "Recall a fact saved earlier with 'remember'. Omit the key to list all remembered keys.",
+            parameters: [
+                {
+                    name: "key",
+                    type: "string",
+                    description: "Key of the fact to recall",
+                    required: false
+                }
+            ]
+        }
+    }
+
+    async fn execute(&self, args: Value) -> Result<ToolResult> {
+        let Some(key) = args["key"].as_str().map(str::trim) else {
+            return match self.store.keys().await {
+                Ok(keys) if keys.is_empty() => tool_result!(success: "Nothing remembered yet"),
+                Ok(keys) => tool_result!(success: keys.join("\n")),
+                Err(e) => tool_result!(failure: format!("Failed to list memory: {}", e)),
+            };
+        };
+
+        match self.store.get(key).await {
+            Ok(Some(value)) => tool_result!(success: value),
+            Ok(None) => tool_result!(failure: format!("Nothing remembered under '{}'", key)),
+            Err(e) => tool_result!(failure: format!("Failed to recall '{}': {}", key, e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::kv::InMemoryKvStore;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_remember_then_recall() {
+        let store: Arc<dyn MemoryStore> = Arc::new(InMemoryKvStore::new());
+        let remember = RememberTool::new(Arc::clone(&store));
+        let recall = RecallTool::new(store);
+
+        let saved = remember
+            .execute(json!({"key": "favorite_color", "value": "blue"}))
+            .await
+            .unwrap();
+        assert!(saved.success);
+
+        let value = recall
+            .execute(json!({"key": "favorite_color"}))
+            .await
+            .unwrap();
+        assert_eq!(value.output, "blue");
+
+        let listed = recall.execute(json!({})).await.unwrap();
+        assert_eq!(listed.output, "favorite_color");
+
+        let missing = recall.execute(json!({"key": "pet"})).await.unwrap();
+        assert!(!missing.success);
+    }
+}
@@ -9,15 +9,25 @@ use super::{Tool, ToolMetadata, ToolParameter, ToolResult};
 use anyhow::Result;
 use async_trait::async_trait;
 use serde_json::Value;
+use std::process::{ExitStatus, Stdio};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
 use tokio::process::Command;
+use tokio::sync::mpsc;
 use tokio::time::{timeout, Duration};
 
+/// Commands refused in sandbox mode when no whitelist is configured
+const DESTRUCTIVE_COMMANDS: [&str; 14] = [
+    "rm", "rmdir", "dd", "mkfs", "shred", "shutdown", "reboot", "halt", "poweroff", "sudo", "su",
+    "chmod", "chown", "kill",
+];
+
 /// Shell command executor tool
 ///
 /// Executes shell commands in a controlled environment with timeout protection
 pub struct ShellTool {
     timeout_secs: u64,
     allowed_commands: Option<Vec<String>>,
+    expand_env: bool,
 }
 
 impl ShellTool {
@@ -25,6 +35,7 @@ impl ShellTool {
         Self {
             timeout_secs,
             allowed_commands: None,
+            expand_env: false,
         }
     }
 
@@ -33,6 +44,16 @@ impl ShellTool {
         self
     }
 
+    /// Let the shell expand `$VAR` references in commands (disabled by default)
+    ///
+    /// When disabled, variable references are escaped so they reach the
+    /// command literally; command substitution and special parameters such
+    /// as `$?` are unaffected.
+    pub fn with_expand_env(mut self, enabled: bool) -> Self {
+        self.expand_env = enabled;
+        self
+    }
+
     /// Check if command is allowed (internal implementation detail)
     fn is_command_allowed(&self, command: &str) -> bool {
         if let Some(ref allowed) = self.allowed_commands {
@@ -43,6 +64,71 @@ impl ShellTool {
             true // No whitelist means all commands allowed
         }
     }
+
+    /// First destructive program named anywhere in a pipeline or command list
+    fn destructive_command(command: &str) -> Option<&str> {
+        command
+            .split([';', '|', '&', '\n', '(', ')', '`'])
+            .filter_map(|segment| segment.split_whitespace().next())
+            .map(|program| program.rsplit('/').next().unwrap_or(program))
+            .find(|program| DESTRUCTIVE_COMMANDS.contains(program) || program.starts_with("mkfs."))
+    }
+
+    /// Validate and run the command, sending output lines to `progress` if given
+    async fn run(
+        &self,
+        args: Value,
+        progress: Option<&mpsc::Sender<String>>,
+    ) -> Result<ToolResult> {
+        self.validate(&args)?;
+
+        let command = args["command"].as_str().unwrap();
+        let command = if self.expand_env {
+            command.to_string()
+        } else {
+            escape_env_refs(command)
+        };
+
+        tracing::info!("Executing shell command: {}", command);
+
+        // Execute with timeout protection
+        let result = timeout(
+            Duration::from_secs(self.timeout_secs),
+            run_command(&command, progress),
+        )
+        .await;
+
+        match result {
+            Ok(Ok((status, stdout, stderr))) => {
+                let stdout = String::from_utf8_lossy(&stdout);
+                let stderr = String::from_utf8_lossy(&stderr);
+
+                if status.success() {
+                    let combined = if stderr.is_empty() {
+                        stdout.to_string()
+                    } else {
+                        format!("stdout:\n{}\nstderr:\n{}", stdout, stderr)
+                    };
+                    Ok(ToolResult::success(combined))
+                } else {
+                    Ok(ToolResult::failure(format!(
+                        "Command failed with exit code {:?}\nstdout: {}\nstderr: {}",
+                        status.code(),
+                        stdout,
+                        stderr
+                    )))
+                }
+            }
+            Ok(Err(e)) => Ok(ToolResult::failure(format!(
+                "Failed to execute command: {}",
+                e
+            ))),
+            Err(_) => Ok(ToolResult::failure(format!(
+                "Command timed out after {} seconds",
+                self.timeout_secs
+            ))),
+        }
+    }
 }
 
 #[async_trait]
@@ -57,6 +143,7 @@ impl Tool for ShellTool {
                     param_type: "string".to_string(),
                     description: "The shell command to execute".to_string(),
                     required: true,
+                    ..Default::default()
                 },
             ],
         }
@@ -81,51 +168,116 @@ impl Tool for ShellTool {
         Ok(())
     }
 
+    fn check_sandbox(&self, args: &Value) -> Result<()> {
+        if self.allowed_commands.is_some() {
+            return Ok(());
+        }
+        if let Some(program) = args["command"].as_str().and_then(Self::destructive_command) {
+            anyhow::bail!(
+                "'{}' is not allowed without a command whitelist; configure one with with_whitelist",
+                program
+            );
+        }
+        Ok(())
+    }
+
     async fn execute(&self, args: Value) -> Result<ToolResult> {
-        self.validate(&args)?;
+        self.run(args, None).await
+    }
 
-        let command = args["command"].as_str().unwrap();
+    /// Sends each line the command prints, from stdout or stderr, as it appears
+    async fn execute_streaming(
+        &self,
+        args: Value,
+        output: mpsc::Sender<String>,
+    ) -> Result<ToolResult> {
+        self.run(args, Some(&output)).await
+    }
+}
 
-        tracing::info!("Executing shell command: {}", command);
+/// Run `command` under `sh`, returning its exit status, stdout and stderr
+///
+/// The child is killed if the returned future is dropped, e.g. on timeout.
+async fn run_command(
+    command: &str,
+    progress: Option<&mpsc::Sender<String>>,
+) -> std::io::Result<(ExitStatus, Vec<u8>, Vec<u8>)> {
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()?;
+    let stdout = child.stdout.take().expect("stdout is piped");
+    let stderr = child.stderr.take().expect("stderr is piped");
 
-        // Execute with timeout protection
-        let result = timeout(
-            Duration::from_secs(self.timeout_secs),
-            Command::new("sh").arg("-c").arg(command).output(),
-        )
-        .await;
+    let (stdout, stderr, status) = tokio::try_join!(
+        read_lines(stdout, progress),
+        read_lines(stderr, progress),
+        child.wait()
+    )?;
+    Ok((status, stdout, stderr))
+}
 
-        match result {
-            Ok(Ok(output)) => {
-                let stdout = String::from_utf8_lossy(&output.stdout);
-                let stderr = String::from_utf8_lossy(&output.stderr);
+/// Everything `reader` produces, sending each line to `progress` on the way
+///
+/// A closed progress channel is ignored; the output is still collected.
+async fn read_lines(
+    reader: impl AsyncRead + Unpin,
+    progress: Option<&mpsc::Sender<String>>,
+) -> std::io::Result<Vec<u8>> {
+    let mut reader = BufReader::new(reader);
+    let mut collected = Vec::new();
+    loop {
+        let start = collected.len();
+        if reader.read_until(b'\n', &mut collected).await? == 0 {
+            return Ok(collected);
+        }
+        if let Some(progress) = progress {
+            let line = String::from_utf8_lossy(&collected[start..]);
+            let _ = progress
+                .send(line.trim_end_matches(['\r', '\n']).to_string())
+                .await;
+        }
+    }
+}
 
-                if output.status.success() {
-                    let combined = if stderr.is_empty() {
-                        stdout.to_string()
-                    } else {
-                        format!("stdout:\n{}\nstderr:\n{}", stdout, stderr)
-                    };
-                    Ok(ToolResult::success(combined))
-                } else {
-                    Ok(ToolResult::failure(format!(
-                        "Command failed with exit code {:?}\nstdout: {}\nstderr: {}",
-                        output.status.code(),
-                        stdout,
-                        stderr
-                    )))
+/// Escape `$NAME` and `${NAME}` so `sh` passes them through literally
+///
+/// Single-quoted text is already literal and left untouched, as are
+/// characters escaped with a backslash. An apostrophe inside double quotes
+/// does not open a single-quoted span.
+fn escape_env_refs(command: &str) -> String {
+    let mut escaped = String::with_capacity(command.len());
+    let mut chars = command.chars().peekable();
+    let mut in_single_quotes = false;
+    let mut in_double_quotes = false;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' if !in_double_quotes => in_single_quotes = !in_single_quotes,
+            '"' if !in_single_quotes => in_double_quotes = !in_double_quotes,
+            '\\' if !in_single_quotes => {
+                escaped.push(c);
+                if let Some(next) = chars.next() {
+                    escaped.push(next);
                 }
+                continue;
             }
-            Ok(Err(e)) => Ok(ToolResult::failure(format!(
-                "Failed to execute command: {}",
-                e
-            ))),
-            Err(_) => Ok(ToolResult::failure(format!(
-                "Command timed out after {} seconds",
-                self.timeout_secs
-            ))),
+            '$' if !in_single_quotes
+                && chars
+                    .peek()
+                    .is_some_and(|&n| n == '{' || n == '_' || n.is_ascii_alphabetic()) =>
+            {
+                escaped.push('\\');
+            }
+            _ => {}
         }
+        escaped.push(c);
     }
+
+    escaped
 }
 
 #[cfg(test)]
@@ -133,6 +285,42 @@ mod tests {
     use super::*;
     use serde_json::json;
 
+    #[test]
+    fn test_sandbox_refuses_destructive_commands_without_whitelist() {
+        let tool = ShellTool::new(5);
+        assert!(tool.check_sandbox(&json!({"command": "ls -la"})).is_ok());
+        for command in [
+            "rm -rf build",
+            "echo hi && /bin/rm x",
+            "cat f | sudo tee /etc/x",
+        ] {
+            assert!(tool.check_sandbox(&json!({"command": command})).is_err());
+        }
+
+        let trusted = ShellTool::new(5).with_whitelist(vec!["rm".to_string()]);
+        assert!(trusted
+            .check_sandbox(&json!({"command": "rm -rf build"}))
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_streaming_sends_lines_as_they_are_printed() {
+        let tool = ShellTool::new(5);
+        let (tx, mut rx) = mpsc::channel(8);
+        let args = json!({"command": "echo one; echo two >&2; echo three"});
+
+        let result = tool.execute_streaming(args, tx).await.unwrap();
+        assert!(result.success);
+        assert!(result.output.contains("one\nthree\n"));
+
+        let mut lines = Vec::new();
+        while let Some(line) = rx.recv().await {
+            lines.push(line);
+        }
+        lines.sort();
+        assert_eq!(lines, vec!["one", "three", "two"]);
+    }
+
     #[tokio::test]
     async fn test_shell_tool_success() {
         let tool = ShellTool::new(5);
@@ -166,4 +354,43 @@ mod tests {
         let result = tool.execute(args).await;
         assert!(result.is_err());
     }
+
+    #[tokio::test]
+    async fn test_env_vars_expand_only_when_enabled() {
+        std::env::set_var("ACTORUS_TEST_SHELL_VAR", "expanded");
+        let args = json!({"command": "echo \"$ACTORUS_TEST_SHELL_VAR ${ACTORUS_TEST_SHELL_VAR}\""});
+
+        let literal = ShellTool::new(5).execute(args.clone()).await.unwrap();
+        assert_eq!(
+            literal.output.trim(),
+            "$ACTORUS_TEST_SHELL_VAR ${ACTORUS_TEST_SHELL_VAR}"
+        );
+
+        let expanded = ShellTool::new(5)
+            .with_expand_env(true)
+            .execute(args)
+            .await
+            .unwrap();
+        assert_eq!(expanded.output.trim(), "expanded expanded");
+    }
+
+    #[test]
+    fn test_escape_env_refs_leaves_other_syntax_alone() {
+        assert_eq!(escape_env_refs("echo $HOME"), "echo \\$HOME");
+        assert_eq!(escape_env_refs("echo '$HOME'"), "echo '$HOME'");
+        assert_eq!(escape_env_refs("echo $(pwd) $?"), "echo $(pwd) $?");
+        assert_eq!(escape_env_refs("echo \\$HOME"), "echo \\$HOME");
+    }
+
+    #[test]
+    fn test_escape_env_refs_apostrophe_inside_double_quotes() {
+        assert_eq!(
+            escape_env_refs("echo \"it's $HOME\""),
+            "echo \"it's \\$HOME\""
+        );
+        assert_eq!(
+            escape_env_refs("echo \"it's\" '$HOME'"),
+            "echo \"it's\" '$HOME'"
+        );
+    }
 }
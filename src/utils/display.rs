@@ -20,3 +20,7 @@ pub fn print_info(text: &str) {
 pub fn print_prompt(text: &str) {
     print!("{}", text.yellow().bold());
 }
+
+pub fn print_tool_output(text: &str) {
+    println!("  {}", text.dimmed());
+}
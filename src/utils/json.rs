@@ -0,0 +1,63 @@
+//! Canonical JSON - One spelling for logically equal values
+//!
+//! Information Hiding:
+//! - Key ordering and number normalization rules hidden behind `canonicalize`
+
+use serde_json::{Number, Value};
+
+/// Serialize `value` so logically identical JSON yields the same string
+///
+/// Object keys are sorted at every level and whole-number floats are
+/// written as integers (`1.0` and `1` match, as do `-0.0` and `0`), so the
+/// result can key caches and compare tool inputs.
+pub fn canonicalize(value: &Value) -> String {
+    normalize(value).to_string()
+}
+
+fn normalize(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key.clone(), normalize(value)))
+                    .collect(),
+            )
+        }
+        Value::Array(items) => Value::Array(items.iter().map(normalize).collect()),
+        Value::Number(number) => Value::Number(normalize_number(number)),
+        other => other.clone(),
+    }
+}
+
+fn normalize_number(number: &Number) -> Number {
+    match number.as_f64() {
+        Some(float) if number.is_f64() && float.fract() == 0.0 && float.abs() < i64::MAX as f64 => {
+            Number::from(float as i64)
+        }
+        _ => number.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_key_order_and_number_spelling_do_not_matter() {
+        let a = json!({"query": "rust", "filters": {"year": 2024, "lang": "en"}, "limit": 10});
+        let b = json!({"limit": 10.0, "filters": {"lang": "en", "year": 2024}, "query": "rust"});
+        assert_eq!(canonicalize(&a), canonicalize(&b));
+        assert_eq!(
+            canonicalize(&a),
+            r#"{"filters":{"lang":"en","year":2024},"limit":10,"query":"rust"}"#
+        );
+
+        assert_eq!(canonicalize(&json!(-0.0)), canonicalize(&json!(0)));
+        assert_ne!(canonicalize(&json!([1, 2])), canonicalize(&json!([2, 1])));
+        assert_ne!(canonicalize(&json!(1.5)), canonicalize(&json!(1)));
+    }
+}
@@ -1,2 +1,4 @@
 pub mod display;
+pub mod json;
 pub use display::*;
+pub use json::canonicalize;
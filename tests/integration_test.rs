@@ -81,6 +81,7 @@ async fn test_tool_executor_retry() {
         timeout_secs: 30,
         max_retries: 3,
         sandbox: false,
+        ..ToolConfig::default()
     });
 
     let tool = Arc::new(ShellTool::new(5));
@@ -152,6 +153,269 @@ async fn test_tool_metadata() {
     assert!(param.required);
 }
 
+#[actorus::tool_fn(name = "convert_temperature", description = "Convert a temperature")]
+async fn convert_temperature(
+    degrees: f64,
+    #[param(description = "Target unit", allowed_values = ["celsius", "fahrenheit"])] unit: String,
+) -> anyhow::Result<String> {
+    Ok(format!("{} {}", degrees, unit))
+}
+
+#[tokio::test]
+async fn test_tool_fn_allowed_values() {
+    let metadata = ConvertTemperatureTool::tool_metadata();
+
+    let unit = &metadata.parameters[1];
+    assert_eq!(unit.description, "Target unit");
+    assert_eq!(
+        unit.allowed_values,
+        Some(vec!["celsius".to_string(), "fahrenheit".to_string()])
+    );
+    assert_eq!(metadata.parameters[0].allowed_values, None);
+    assert_eq!(
+        metadata.to_json_schema()["properties"]["unit"]["enum"],
+        json!(["celsius", "fahrenheit"])
+    );
+}
+
+#[derive(serde::Deserialize, actorus::ToolEnum)]
+#[serde(rename_all = "snake_case")]
+enum Precision {
+    Whole,
+    TwoDecimals,
+}
+
+#[actorus::tool_fn(name = "round_number", description = "Round a number")]
+async fn round_number(value: f64, precision: Precision) -> anyhow::Result<String> {
+    Ok(match precision {
+        Precision::Whole => format!("{:.0}", value),
+        Precision::TwoDecimals => format!("{:.2}", value),
+    })
+}
+
+#[tokio::test]
+async fn test_tool_fn_enum_parameter_lists_its_values() {
+    let metadata = RoundNumberTool::tool_metadata();
+
+    let precision = &metadata.parameters[1];
+    assert_eq!(precision.param_type, "string");
+    assert_eq!(
+        precision.allowed_values,
+        Some(vec!["whole".to_string(), "two_decimals".to_string()])
+    );
+
+    let result = RoundNumberTool::new()
+        .execute(json!({"value": 2.345, "precision": "two_decimals"}))
+        .await
+        .unwrap();
+    assert_eq!(result.output, "2.35");
+    assert!(RoundNumberTool::new()
+        .execute(json!({"value": 2.0, "precision": "tenths"}))
+        .await
+        .is_err());
+}
+
+#[actorus::tool_fn(name = "open_port", description = "Open a network port")]
+async fn open_port(
+    #[param(description = "Port number", minimum = 1, maximum = 65535)] port: i64,
+    #[param(pattern = r"^\d{4}-\d{2}-\d{2}$")] until: Option<String>,
+) -> anyhow::Result<String> {
+    Ok(format!("Opened {} until {:?}", port, until))
+}
+
+#[tokio::test]
+async fn test_tool_fn_rejects_out_of_range_arguments() {
+    let tool = OpenPortTool::new();
+
+    assert!(tool.validate(&json!({"port": 8080})).is_ok());
+    assert!(tool
+        .validate(&json!({"port": 443, "until": "2025-01-31"}))
+        .is_ok());
+
+    let err = tool.validate(&json!({"port": 70000})).unwrap_err();
+    assert!(err.to_string().contains("'port' must be at most 65535"));
+    assert!(tool.validate(&json!({"port": 0})).is_err());
+    assert!(tool
+        .validate(&json!({"port": 22, "until": "next week"}))
+        .is_err());
+
+    let result = tool.execute(json!({"port": 70000})).await;
+    assert!(result.is_err());
+
+    let schema = OpenPortTool::tool_metadata().to_json_schema();
+    assert_eq!(schema["properties"]["port"]["maximum"], json!(65535.0));
+}
+
+#[actorus::tool_fn(name = "resize_image", description = "Resize an image")]
+async fn resize_image(
+    width: u32,
+    quality: u8,
+    offset: std::primitive::i64,
+    scale: Option<f32>,
+) -> anyhow::Result<String> {
+    Ok(format!("{} {} {} {:?}", width, quality, offset, scale))
+}
+
+#[tokio::test]
+async fn test_tool_fn_numeric_types() {
+    let metadata = ResizeImageTool::tool_metadata();
+    let types: Vec<&str> = metadata
+        .parameters
+        .iter()
+        .map(|p| p.param_type.as_str())
+        .collect();
+    assert_eq!(types, vec!["number", "number", "number", "number"]);
+
+    let tool = ResizeImageTool::new();
+    let result = tool
+        .execute(json!({"width": 3_000_000_000u64, "quality": 90, "offset": -5, "scale": 0.5}))
+        .await
+        .unwrap();
+    assert_eq!(result.output, "3000000000 90 -5 Some(0.5)");
+
+    // u8 overflow and negative unsigned values are rejected, not truncated
+    assert!(tool
+        .validate(&json!({"width": 10, "quality": 300, "offset": 0}))
+        .is_err());
+    assert!(tool
+        .validate(&json!({"width": -1, "quality": 1, "offset": 0}))
+        .is_err());
+}
+
+#[actorus::tool_fn(name = "adjust_scores", description = "Add a bonus to every score")]
+async fn adjust_scores(
+    scores: std::collections::HashMap<String, i64>,
+    bonus: Option<(String, i64)>,
+) -> anyhow::Result<String> {
+    let mut scores = scores;
+    if let Some((name, points)) = bonus {
+        *scores.entry(name).or_default() += points;
+    }
+    Ok(serde_json::to_string(&scores)?)
+}
+
+#[tokio::test]
+async fn test_tool_fn_map_and_tuple_parameters() {
+    let metadata = AdjustScoresTool::tool_metadata();
+    let scores = &metadata.parameters[0];
+    assert_eq!(scores.param_type, "object");
+    assert_eq!(scores.item_types, Some(vec!["number".to_string()]));
+    let bonus = &metadata.parameters[1];
+    assert_eq!(bonus.param_type, "array");
+    assert_eq!(
+        bonus.item_types,
+        Some(vec!["string".to_string(), "number".to_string()])
+    );
+
+    let schema = metadata.to_json_schema();
+    assert_eq!(
+        schema["properties"]["scores"]["additionalProperties"],
+        json!({"type": "number"})
+    );
+    assert_eq!(schema["properties"]["bonus"]["maxItems"], json!(2));
+
+    let result = AdjustScoresTool::new()
+        .execute(json!({"scores": {"ada": 3, "bob": 5}, "bonus": ["ada", 10]}))
+        .await
+        .unwrap();
+    let round_trip: serde_json::Value = serde_json::from_str(&result.output).unwrap();
+    assert_eq!(round_trip, json!({"ada": 13, "bob": 5}));
+}
+
+#[actorus::tool_fn(name = "lookup", description = "Look up a term")]
+async fn lookup(_query: String, _limit: Option<i64>) -> anyhow::Result<String> {
+    Ok("no results".to_string())
+}
+
+#[tokio::test]
+async fn test_tool_fn_strips_leading_underscore_from_parameter_names() {
+    let metadata = LookupTool::tool_metadata();
+    let names: Vec<&str> = metadata
+        .parameters
+        .iter()
+        .map(|p| p.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["query", "limit"]);
+    assert!(metadata.to_json_schema()["properties"]["query"].is_object());
+
+    let result = LookupTool::new()
+        .execute(json!({"query": "actors"}))
+        .await
+        .unwrap();
+    assert_eq!(result.output, "no results");
+}
+
+#[derive(Clone, Default, actorus::Tool)]
+#[tool_meta(name = "greet", description = "Greet a person")]
+struct GreetTool {
+    #[param(description = "Who to greet")]
+    name: String,
+    #[param(description = "How many times", minimum = 1, maximum = 3)]
+    times: Option<u8>,
+    /// Configuration carried over from the registered instance
+    greeting: String,
+}
+
+impl GreetTool {
+    async fn run(&self) -> anyhow::Result<String> {
+        let greeting = format!("{}, {}!", self.greeting, self.name);
+        Ok(vec![greeting; self.times.unwrap_or(1) as usize].join(" "))
+    }
+}
+
+#[tokio::test]
+async fn test_derived_tool_runs_through_agent() {
+    use actorus::actors::messages::AgentResponse;
+    use actorus::actors::specialized_agent::{SpecializedAgent, SpecializedAgentConfig};
+    use actorus::core::llm::LLMClient;
+    use actorus::{MockLLM, Settings};
+
+    let tool = GreetTool {
+        greeting: "Hello".to_string(),
+        ..Default::default()
+    };
+    let metadata = tool.metadata();
+    assert_eq!(metadata.name, "greet");
+    assert_eq!(metadata.parameters.len(), 2);
+    assert!(!metadata.parameters[1].required);
+    assert!(tool.validate(&json!({"name": "Ada", "times": 5})).is_err());
+
+    let llm = Arc::new(MockLLM::new([
+        json!({
+            "thought": "greet Ada twice",
+            "action": {"tool": "greet", "input": {"name": "Ada", "times": 2}},
+            "is_final": false,
+            "final_answer": null
+        })
+        .to_string(),
+        json!({
+            "thought": "done",
+            "action": null,
+            "is_final": true,
+            "final_answer": "greeted"
+        })
+        .to_string(),
+    ]));
+    let agent = SpecializedAgent::with_llm_client(
+        SpecializedAgentConfig {
+            name: "greeter".to_string(),
+            description: "Greets people".to_string(),
+            system_prompt: "You greet people".to_string(),
+            tools: vec![Arc::new(tool)],
+            return_tool_output: true,
+            ..Default::default()
+        },
+        LLMClient::with_backend(llm, Settings::new().unwrap()),
+    );
+
+    match agent.execute_task("Greet Ada twice", 3).await {
+        AgentResponse::Success { result, .. } => {
+            assert_eq!(result, "Hello, Ada! Hello, Ada!");
+        }
+        other => panic!("expected success, got {:?}", other),
+    }
+}
+
 #[tokio::test]
 async fn test_tool_executor_backoff() {
     use std::time::Instant;
@@ -160,6 +424,7 @@ async fn test_tool_executor_backoff() {
         timeout_secs: 5,
         max_retries: 3,
         sandbox: false,
+        ..ToolConfig::default()
     });
 
     // This will fail and should retry with backoff
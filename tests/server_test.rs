@@ -0,0 +1,139 @@
+//! End-to-end tests for the HTTP server (run with `--features server`)
+
+#![cfg(feature = "server")]
+
+use actorus::actors::MessageRouterHandle;
+use actorus::{LLMConfig, Settings};
+use futures::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use std::time::{Duration, Instant};
+use tokio_tungstenite::tungstenite::Message;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn delta(content: &str) -> String {
+    format!(
+        "data: {}\n\n",
+        serde_json::json!({ "choices": [{ "delta": { "content": content } }] })
+    )
+}
+
+/// Serve `app` over the actor system whose LLM provider is `provider`
+async fn serve(provider: &MockServer) -> std::net::SocketAddr {
+    let settings = Settings::builder()
+        .llm(LLMConfig {
+            base_url: provider.uri(),
+            ..Settings::defaults().llm
+        })
+        .build()
+        .unwrap();
+    let router = Box::leak(Box::new(MessageRouterHandle::new(
+        settings,
+        "test-key".to_string(),
+    )));
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, actorus::server::app(router))
+            .await
+            .unwrap()
+    });
+    address
+}
+
+fn completion(decision: Value) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_json(json!({
+        "choices": [{ "message": { "role": "assistant", "content": decision.to_string() } }],
+        "usage": { "total_tokens": 1 }
+    }))
+}
+
+#[tokio::test]
+async fn test_sse_endpoint_streams_tokens() {
+    let provider = MockServer::start().await;
+    let body = format!("{}{}data: [DONE]\n\n", delta("Hel"), delta("lo!"));
+    Mock::given(method("POST"))
+        .and(path("/chat/completions"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(body, "text/event-stream"))
+        .mount(&provider)
+        .await;
+
+    let address = serve(&provider).await;
+
+    let response = reqwest::Client::new()
+        .post(format!("http://{}/chat/stream", address))
+        .json(&serde_json::json!({ "prompt": "Say hello" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.headers()["content-type"], "text/event-stream");
+
+    let events = response.text().await.unwrap();
+    let data: Vec<&str> = events
+        .lines()
+        .filter_map(|line| line.strip_prefix("data: "))
+        .collect();
+    assert_eq!(data, vec!["Hel", "lo!"]);
+    assert!(events.contains("event: done"));
+}
+
+#[tokio::test]
+async fn test_websocket_streams_steps_before_result() {
+    let provider = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/chat/completions"))
+        .respond_with(completion(json!({
+            "thought": "check the file",
+            "action": { "tool": "read_file", "input": { "path": "missing.txt" } },
+            "is_final": false,
+            "final_answer": null
+        })))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(&provider)
+        .await;
+    // The answer is slow, so a batched reply would hold the step back
+    Mock::given(method("POST"))
+        .and(path("/chat/completions"))
+        .respond_with(
+            completion(json!({
+                "thought": "done",
+                "action": null,
+                "is_final": true,
+                "final_answer": "No such file"
+            }))
+            .set_delay(Duration::from_millis(500)),
+        )
+        .mount(&provider)
+        .await;
+    let address = serve(&provider).await;
+
+    let (mut socket, _) = tokio_tungstenite::connect_async(format!("ws://{}/agent/ws", address))
+        .await
+        .unwrap();
+    socket
+        .send(Message::text("Read missing.txt"))
+        .await
+        .unwrap();
+
+    let mut received = Vec::new();
+    while let Some(message) = socket.next().await {
+        let reply: Value = serde_json::from_str(message.unwrap().to_text().unwrap()).unwrap();
+        let is_result = reply["type"] == "result";
+        received.push((Instant::now(), reply));
+        if is_result {
+            break;
+        }
+    }
+
+    let (first_at, first) = &received[0];
+    let (result_at, result) = received.last().unwrap();
+    assert_eq!(first["type"], "step");
+    assert_eq!(first["action"], "read_file");
+    assert_eq!(result["result"], "No such file");
+    assert!(
+        result_at.duration_since(*first_at) >= Duration::from_millis(300),
+        "the first step should arrive while the agent is still thinking"
+    );
+}